@@ -1,5 +1,7 @@
 // src/actions.rs
 use actix_web::{HttpRequest, web, HttpResponse};
+use futures::future::LocalBoxFuture;
+use serde::Serialize;
 use serde_json::Value;
 use std::future::Future;
 use std::pin::Pin;
@@ -13,3 +15,62 @@ pub struct CustomAction {
     pub method: &'static str, // "GET", "POST"
     pub handler: DynHandler,
 }
+
+/// Handler for a row action, called with the request and the document id.
+/// The caller's `Claims` are in the request extensions.
+pub type RowActionHandler = fn(HttpRequest, String) -> LocalBoxFuture<'static, HttpResponse>;
+
+/// A per-document button on the list view (e.g. "Approve"), served at
+/// `/{base_path}/{id}/{name}`. Set with `AdmixResource::row_actions`.
+#[derive(Clone, Serialize)]
+pub struct RowAction {
+    /// URL segment; must not clash with `delete` or `restore`
+    pub name: &'static str,
+    pub label: &'static str,
+    /// SVG path data for a 24×24 outline icon; the label is shown when unset
+    pub icon: Option<&'static str>,
+    /// `POST` renders a form button, `GET` a link
+    pub method: &'static str,
+    /// Ask for confirmation with this text before running the action
+    pub confirm: Option<&'static str>,
+    /// Roles that may see and run the action; empty falls back to the
+    /// resource's `edit` permission
+    #[serde(skip)]
+    pub roles: Vec<&'static str>,
+    #[serde(skip)]
+    pub handler: RowActionHandler,
+}
+
+impl RowAction {
+    pub fn new(name: &'static str, label: &'static str, handler: RowActionHandler) -> Self {
+        Self {
+            name,
+            label,
+            icon: None,
+            method: "POST",
+            confirm: None,
+            roles: vec![],
+            handler,
+        }
+    }
+
+    pub fn icon(mut self, path: &'static str) -> Self {
+        self.icon = Some(path);
+        self
+    }
+
+    pub fn method(mut self, method: &'static str) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn confirm(mut self, message: &'static str) -> Self {
+        self.confirm = Some(message);
+        self
+    }
+
+    pub fn roles(mut self, roles: Vec<&'static str>) -> Self {
+        self.roles = roles;
+        self
+    }
+}
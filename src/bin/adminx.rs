@@ -3,16 +3,23 @@
 use clap::{Parser, Subcommand};
 use std::io::{self, Write};
 use std::env;
+use std::collections::HashSet;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use adminx::{
-    models::adminx_model::{AdminxUser, get_admin_by_email, get_all_admins},
+    models::adminx_model::{AdminListQuery, AdminxUser, AdminxUserPublic, count_active_admins, find_admins, get_admin_by_email, get_all_admins, send_email_verification},
     utils::{
     	auth::{
     		AdminxStatus,
     	},
+    	mailer::{Email, Mailer},
+    	password::{check_password, hash_password},
     	database::{
     		initiate_mongo_client,
     		initiate_database,
     		get_adminx_database,
+    		check_database_health,
     	},
 	}
 };
@@ -54,6 +61,9 @@ enum Commands {
         /// Skip confirmation prompt
         #[arg(short = 'y', long)]
         yes: bool,
+        /// Require the user to confirm their email before signing in (prints the verification token)
+        #[arg(long)]
+        unverified: bool,
     },
     /// List all admin users
     List {
@@ -63,6 +73,24 @@ enum Commands {
         /// Output format (table, json)
         #[arg(short, long, default_value = "table")]
         format: String,
+        /// Only users whose email or username contains this term
+        #[arg(long)]
+        search: Option<String>,
+        /// Only users with this status (active, inactive, suspended)
+        #[arg(long)]
+        status: Option<String>,
+        /// Maximum number of users to show
+        #[arg(long)]
+        limit: Option<i64>,
+        /// Number of users to skip
+        #[arg(long)]
+        offset: Option<u64>,
+        /// Sort field, prefix with '-' for descending (e.g. -created_at)
+        #[arg(long)]
+        sort: Option<String>,
+        /// Filters in the web list's query syntax (e.g. "status=active&sort=-created_at&per_page=50")
+        #[arg(long)]
+        query: Option<String>,
     },
     /// Show details of a specific admin user
     Show {
@@ -84,6 +112,11 @@ enum Commands {
         /// New status (active, inactive, suspended)
         status: String,
     },
+    /// Mark a user's email unverified and print a new verification token
+    SendVerification {
+        /// User email or ID
+        identifier: String,
+    },
     /// Reset admin user password
     ResetPassword {
         /// User email or ID
@@ -92,6 +125,33 @@ enum Commands {
         #[arg(short, long)]
         password: Option<String>,
     },
+    /// Bulk import admin users from a CSV file (username,email,password,status[,roles])
+    Import {
+        /// Path to the CSV file
+        #[arg(short, long)]
+        file: PathBuf,
+        /// Validate rows without creating any users
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Export admin users to a CSV or JSON file (passwords are never included)
+    Export {
+        /// Output format (csv, json)
+        #[arg(short, long, default_value = "csv")]
+        format: String,
+        /// Output file path
+        #[arg(short, long)]
+        out: PathBuf,
+        /// Comma-separated columns to export (id, username, email, status, deleted, created_at, updated_at)
+        #[arg(long)]
+        fields: Option<String>,
+        /// Include deleted users
+        #[arg(short, long)]
+        deleted: bool,
+    },
+    /// Check database connectivity and basic AdminX health
+    #[command(alias = "health")]
+    Doctor,
 }
 
 #[tokio::main]
@@ -110,11 +170,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Connected to MongoDB: {} (database: {})", mongodb_url, db_name);
     
     match cli.command {
-        Commands::Create { username, email, password, status, yes } => {
-            create_user(username, email, password, status, yes).await?;
+        Commands::Create { username, email, password, status, yes, unverified } => {
+            create_user(username, email, password, status, yes, unverified).await?;
         }
-        Commands::List { deleted, format } => {
-            list_users(deleted, format).await?;
+        Commands::List { deleted, format, search, status, limit, offset, sort, query } => {
+            let status = match status {
+                Some(status) => Some(
+                    parse_status(&status).ok_or("Invalid status. Must be one of: active, inactive, suspended")?,
+                ),
+                None => None,
+            };
+            let query = list_query(query.as_deref(), AdminListQuery {
+                search,
+                status,
+                include_deleted: deleted,
+                limit,
+                offset,
+                sort,
+                filters: None,
+            }).map_err(|e| format!("Invalid query: {}", e))?;
+            list_users(query, format).await?;
         }
         Commands::Show { identifier } => {
             show_user(identifier).await?;
@@ -125,9 +200,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Commands::Status { identifier, status } => {
             update_status(identifier, status).await?;
         }
+        Commands::SendVerification { identifier } => {
+            send_verification(identifier).await?;
+        }
         Commands::ResetPassword { identifier, password } => {
             reset_password(identifier, password).await?;
         }
+        Commands::Import { file, dry_run } => {
+            import_users(file, dry_run).await?;
+        }
+        Commands::Export { format, out, fields, deleted } => {
+            export_users(format, out, fields, deleted).await?;
+        }
+        Commands::Doctor => {
+            let report = run_doctor(
+                || async { check_database_health().await.map_err(|e| e.to_string()) },
+                || async { count_active_admins().await.map_err(|e| e.to_string()) },
+            ).await;
+            report.print();
+            std::process::exit(report.exit_code());
+        }
     }
     
     Ok(())
@@ -190,6 +282,7 @@ async fn create_user(
     password: Option<String>,
     status_str: String,
     skip_confirm: bool,
+    unverified: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Parse status
     let status = match status_str.to_lowercase().as_str() {
@@ -208,14 +301,13 @@ async fn create_user(
         None => {
             print!("Enter password: ");
             io::stdout().flush()?;
-            let password = rpassword::read_password()?;
-            if password.len() < 8 {
-                eprintln!("Password must be at least 8 characters long");
-                return Ok(());
-            }
-            password
+            rpassword::read_password()?
         }
     };
+    if let Err(reason) = check_password(&password) {
+        eprintln!("{}", reason);
+        return Ok(());
+    }
     
     // Check if user already exists
     if let Some(_) = get_admin_by_email(&email).await {
@@ -229,6 +321,7 @@ async fn create_user(
         println!("  Username: {}", username);
         println!("  Email: {}", email);
         println!("  Status: {:?}", status);
+        println!("  Email verified: {}", if unverified { "no" } else { "yes" });
         print!("Continue? (y/N): ");
         io::stdout().flush()?;
         
@@ -242,7 +335,12 @@ async fn create_user(
     }
     
     // Create user
-    match AdminxUser::create_new_user_with_status(username, email.clone(), password, status).await {
+    let created = if unverified {
+        AdminxUser::create_unverified_user(username, email.clone(), password, status, &PrintMailer).await
+    } else {
+        AdminxUser::create_new_user_with_status(username, email.clone(), password, status).await
+    };
+    match created {
         Ok(user_id) => {
             println!("✓ Successfully created admin user");
             println!("  ID: {}", user_id);
@@ -256,8 +354,16 @@ async fn create_user(
     Ok(())
 }
 
-async fn list_users(include_deleted: bool, format: String) -> Result<(), Box<dyn std::error::Error>> {
-    let users = get_all_admins(include_deleted).await?;
+/// Apply a `--query` string to the options of the other list flags
+fn list_query(query: Option<&str>, options: AdminListQuery) -> anyhow::Result<AdminListQuery> {
+    match query {
+        Some(query) => options.with_query(query),
+        None => Ok(options),
+    }
+}
+
+async fn list_users(query: AdminListQuery, format: String) -> Result<(), Box<dyn std::error::Error>> {
+    let users = find_admins(&query).await?;
     
     if users.is_empty() {
         println!("No users found");
@@ -402,17 +508,16 @@ async fn reset_password(identifier: String, password: Option<String>) -> Result<
         None => {
             print!("Enter new password: ");
             io::stdout().flush()?;
-            let password = rpassword::read_password()?;
-            if password.len() < 8 {
-                eprintln!("Password must be at least 8 characters long");
-                return Ok(());
-            }
-            password
+            rpassword::read_password()?
         }
     };
+    if let Err(reason) = check_password(&new_password) {
+        eprintln!("{}", reason);
+        return Ok(());
+    }
     
     // For password reset, we'll directly hash and update (bypass current password check)
-    let hashed_password = bcrypt::hash(new_password, bcrypt::DEFAULT_COST)
+    let hashed_password = hash_password(&new_password)
         .map_err(|e| format!("Failed to hash password: {}", e))?;
     
     // Update in database directly
@@ -441,6 +546,35 @@ async fn reset_password(identifier: String, password: Option<String>) -> Result<
     Ok(())
 }
 
+/// Prints the emails the CLI would send, so the operator can pass the token on
+struct PrintMailer;
+
+#[async_trait::async_trait]
+impl Mailer for PrintMailer {
+    async fn send(&self, email: Email) -> Result<(), String> {
+        println!("To: {}\nSubject: {}\n\n{}", email.to, email.subject, email.body);
+        Ok(())
+    }
+}
+
+async fn send_verification(identifier: String) -> Result<(), Box<dyn std::error::Error>> {
+    let user = match find_user_by_identifier(&identifier).await? {
+        Some(user) => user,
+        None => {
+            eprintln!("User not found: {}", identifier);
+            return Ok(());
+        }
+    };
+
+    if send_email_verification(&user.email, &PrintMailer).await? {
+        println!("✓ {} must verify their email before signing in", user.email);
+    } else {
+        println!("Failed to start email verification");
+    }
+
+    Ok(())
+}
+
 async fn find_user_by_identifier(identifier: &str) -> Result<Option<AdminxUser>, Box<dyn std::error::Error>> {
     // First try to find by email
     if let Some(user) = get_admin_by_email(identifier).await {
@@ -455,4 +589,544 @@ async fn find_user_by_identifier(identifier: &str) -> Result<Option<AdminxUser>,
     }
     
     Ok(None)
-}
\ No newline at end of file
+}
+
+/// A validated row from an import CSV
+#[derive(Debug, Clone)]
+struct ImportRow {
+    username: String,
+    email: String,
+    password: String,
+    status: AdminxStatus,
+    roles: Vec<String>,
+}
+
+/// Outcome of an import run
+#[derive(Debug, Default)]
+struct ImportSummary {
+    created: Vec<String>,
+    skipped: Vec<(usize, String)>,
+    failed: Vec<(usize, String)>,
+}
+
+fn parse_status(status: &str) -> Option<AdminxStatus> {
+    match status.trim().to_lowercase().as_str() {
+        "active" => Some(AdminxStatus::Active),
+        "inactive" => Some(AdminxStatus::Inactive),
+        "suspended" => Some(AdminxStatus::Suspended),
+        _ => None,
+    }
+}
+
+fn is_valid_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !email.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+/// Parse and validate a single `username,email,password,status[,roles]` record.
+/// Roles are separated by `;` or `|` within the last column.
+fn parse_import_row(columns: &csv::StringRecord) -> Result<ImportRow, String> {
+    if columns.len() < 4 || columns.len() > 5 {
+        return Err(format!("expected 4 or 5 columns, found {}", columns.len()));
+    }
+
+    let username = &columns[0];
+    if username.is_empty() {
+        return Err("username cannot be empty".to_string());
+    }
+
+    let email = columns[1].to_lowercase();
+    if !is_valid_email(&email) {
+        return Err(format!("invalid email '{}'", &columns[1]));
+    }
+
+    let password = &columns[2];
+    check_password(password)?;
+
+    let status = parse_status(&columns[3])
+        .ok_or_else(|| format!("invalid status '{}' (expected active, inactive or suspended)", &columns[3]))?;
+
+    let roles = columns
+        .get(4)
+        .map(|roles| {
+            roles
+                .split(|c| c == ';' || c == '|')
+                .map(str::trim)
+                .filter(|role| !role.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(ImportRow {
+        username: username.to_string(),
+        email,
+        password: password.to_string(),
+        status,
+        roles,
+    })
+}
+
+/// Validate every row of `contents` and create the users that don't exist yet.
+/// `exists` and `create` are injected so the flow can be exercised without a database.
+async fn run_import<E, EF, C, CF>(
+    contents: &str,
+    dry_run: bool,
+    mut exists: E,
+    mut create: C,
+) -> ImportSummary
+where
+    E: FnMut(String) -> EF,
+    EF: Future<Output = bool>,
+    C: FnMut(ImportRow) -> CF,
+    CF: Future<Output = Result<ObjectId, Box<dyn std::error::Error>>>,
+{
+    let mut summary = ImportSummary::default();
+    let mut seen_emails = HashSet::new();
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .comment(Some(b'#'))
+        .from_reader(contents.as_bytes());
+
+    for (index, record) in reader.records().enumerate() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map_or(0, |position| position.line() as usize);
+                summary.failed.push((line, e.to_string()));
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, |position| position.line() as usize);
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        // Optional header row
+        if index == 0 && record[0].eq_ignore_ascii_case("username") {
+            continue;
+        }
+
+        let row = match parse_import_row(&record) {
+            Ok(row) => row,
+            Err(e) => {
+                summary.failed.push((line, e));
+                continue;
+            }
+        };
+
+        if !seen_emails.insert(row.email.clone()) {
+            summary.skipped.push((line, format!("{} appears earlier in the file", row.email)));
+            continue;
+        }
+        if exists(row.email.clone()).await {
+            summary.skipped.push((line, format!("{} already exists", row.email)));
+            continue;
+        }
+
+        if dry_run {
+            summary.created.push(row.email);
+            continue;
+        }
+
+        let email = row.email.clone();
+        match create(row).await {
+            Ok(_) => summary.created.push(email),
+            Err(e) => summary.failed.push((line, format!("{}: {}", email, e))),
+        }
+    }
+
+    summary
+}
+
+async fn import_users(file: PathBuf, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let contents = std::fs::read_to_string(&file)
+        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+
+    let summary = run_import(
+        &contents,
+        dry_run,
+        |email| async move { get_admin_by_email(&email).await.is_some() },
+        |row| async move {
+            AdminxUser::create_new_user_with_roles(row.username, row.email, row.password, row.status, row.roles).await
+        },
+    ).await;
+
+    let created_label = if dry_run { "Would create" } else { "Created" };
+    for email in &summary.created {
+        println!("✓ {}: {}", created_label, email);
+    }
+    for (line, reason) in &summary.skipped {
+        println!("- Skipped line {}: {}", line, reason);
+    }
+    for (line, reason) in &summary.failed {
+        eprintln!("✗ Failed line {}: {}", line, reason);
+    }
+
+    println!();
+    println!("Import summary{}:", if dry_run { " (dry run)" } else { "" });
+    println!("  {}: {}", created_label, summary.created.len());
+    println!("  Skipped: {}", summary.skipped.len());
+    println!("  Failed: {}", summary.failed.len());
+
+    if !summary.failed.is_empty() {
+        return Err(format!("{} row(s) failed to import", summary.failed.len()).into());
+    }
+    Ok(())
+}
+
+/// Columns available to `adminx export`, in default order
+const EXPORT_FIELDS: &[&str] = &["id", "username", "email", "status", "deleted", "created_at", "updated_at"];
+
+fn parse_export_fields(fields: Option<&str>) -> Result<Vec<String>, String> {
+    let fields = match fields {
+        Some(fields) => fields,
+        None => return Ok(EXPORT_FIELDS.iter().map(|f| f.to_string()).collect()),
+    };
+
+    let selected: Vec<String> = fields
+        .split(',')
+        .map(|f| f.trim().to_lowercase())
+        .filter(|f| !f.is_empty())
+        .collect();
+    if selected.is_empty() {
+        return Err("--fields must name at least one column".to_string());
+    }
+    if let Some(unknown) = selected.iter().find(|f| !EXPORT_FIELDS.contains(&f.as_str())) {
+        return Err(format!("Unknown field '{}'. Available: {}", unknown, EXPORT_FIELDS.join(", ")));
+    }
+    Ok(selected)
+}
+
+fn export_value(user: &AdminxUserPublic, field: &str) -> serde_json::Value {
+    match field {
+        "id" => serde_json::json!(user.id.map(|id| id.to_hex())),
+        "username" => serde_json::json!(user.username),
+        "email" => serde_json::json!(user.email),
+        "status" => serde_json::to_value(&user.status).unwrap_or(serde_json::Value::Null),
+        "deleted" => serde_json::json!(user.delete),
+        "created_at" => serde_json::json!(user.created_at.to_chrono().to_rfc3339()),
+        "updated_at" => serde_json::json!(user.updated_at.to_chrono().to_rfc3339()),
+        _ => serde_json::Value::Null,
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_export(users: &[AdminxUserPublic], format: &str, fields: &[String]) -> Result<String, String> {
+    match format {
+        "csv" => {
+            let mut output = fields.join(",");
+            output.push('\n');
+            for user in users {
+                let row: Vec<String> = fields
+                    .iter()
+                    .map(|field| match export_value(user, field) {
+                        serde_json::Value::String(s) => csv_escape(&s),
+                        serde_json::Value::Null => String::new(),
+                        other => other.to_string(),
+                    })
+                    .collect();
+                output.push_str(&row.join(","));
+                output.push('\n');
+            }
+            Ok(output)
+        }
+        "json" => {
+            let rows: Vec<serde_json::Map<String, serde_json::Value>> = users
+                .iter()
+                .map(|user| {
+                    fields
+                        .iter()
+                        .map(|field| (field.clone(), export_value(user, field)))
+                        .collect()
+                })
+                .collect();
+            serde_json::to_string_pretty(&rows).map_err(|e| e.to_string())
+        }
+        other => Err(format!("Unsupported format '{}'. Must be one of: csv, json", other)),
+    }
+}
+
+async fn export_users(
+    format: String,
+    out: PathBuf,
+    fields: Option<String>,
+    include_deleted: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let fields = parse_export_fields(fields.as_deref())?;
+    let users: Vec<AdminxUserPublic> = get_all_admins(include_deleted)
+        .await?
+        .iter()
+        .map(|u| u.to_public())
+        .collect();
+
+    let output = render_export(&users, &format.to_lowercase(), &fields)?;
+    std::fs::write(&out, output)
+        .map_err(|e| format!("Failed to write {}: {}", out.display(), e))?;
+
+    println!("✓ Exported {} users to {}", users.len(), out.display());
+    Ok(())
+}
+
+/// Result of a single `adminx doctor` check
+#[derive(Debug)]
+struct DoctorCheck {
+    name: &'static str,
+    passed: bool,
+    detail: String,
+}
+
+#[derive(Debug, Default)]
+struct DoctorReport {
+    checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    fn exit_code(&self) -> i32 {
+        if self.passed() { 0 } else { 1 }
+    }
+
+    fn print(&self) {
+        for check in &self.checks {
+            let mark = if check.passed { "✓" } else { "✗" };
+            println!("{} {:<15} {}", mark, check.name, check.detail);
+        }
+        println!();
+        if self.passed() {
+            println!("All checks passed");
+        } else {
+            let failed = self.checks.iter().filter(|check| !check.passed).count();
+            eprintln!("{} check(s) failed", failed);
+        }
+    }
+}
+
+/// Run the doctor checks. The ping and admin count are injected so the
+/// report can be produced without a live database.
+async fn run_doctor<P, PF, C, CF>(mut ping: P, mut count_admins: C) -> DoctorReport
+where
+    P: FnMut() -> PF,
+    PF: Future<Output = Result<bool, String>>,
+    C: FnMut() -> CF,
+    CF: Future<Output = Result<u64, String>>,
+{
+    let mut report = DoctorReport::default();
+
+    let started = Instant::now();
+    let ping_result = ping().await;
+    let latency: Duration = started.elapsed();
+    let reachable = matches!(ping_result, Ok(true));
+    report.checks.push(DoctorCheck {
+        name: "database ping",
+        passed: reachable,
+        detail: match ping_result {
+            Ok(true) => format!("ok ({} ms)", latency.as_millis()),
+            Ok(false) => "ping failed".to_string(),
+            Err(e) => format!("error: {}", e),
+        },
+    });
+
+    if !reachable {
+        report.checks.push(DoctorCheck {
+            name: "active admins",
+            passed: false,
+            detail: "skipped (database unreachable)".to_string(),
+        });
+        return report;
+    }
+
+    report.checks.push(match count_admins().await {
+        Ok(0) => DoctorCheck {
+            name: "active admins",
+            passed: false,
+            detail: "none found, run `adminx create` to add one".to_string(),
+        },
+        Ok(count) => DoctorCheck {
+            name: "active admins",
+            passed: true,
+            detail: count.to_string(),
+        },
+        Err(e) => DoctorCheck {
+            name: "active admins",
+            passed: false,
+            detail: format!("error: {}", e),
+        },
+    });
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    const CSV: &str = "username,email,password,status,roles
+alice,alice@example.com,supersecret,active,admin;editor
+bob,bob@example.com,supersecret,inactive
+carol,not-an-email,supersecret,active
+dave,dave@example.com,short,active
+erin,ALICE@example.com,supersecret,active
+";
+
+    fn existing(email: String) -> impl Future<Output = bool> {
+        async move { email == "bob@example.com" }
+    }
+
+    #[tokio::test]
+    async fn import_skips_duplicates_and_reports_invalid_rows() {
+        let created = RefCell::new(Vec::new());
+        let summary = run_import(CSV, false, existing, |row| {
+            created.borrow_mut().push((row.email.clone(), row.roles.clone()));
+            async { Ok(ObjectId::new()) }
+        }).await;
+
+        assert_eq!(summary.created, vec!["alice@example.com".to_string()]);
+        assert_eq!(
+            created.into_inner(),
+            vec![("alice@example.com".to_string(), vec!["admin".to_string(), "editor".to_string()])]
+        );
+        assert_eq!(summary.skipped.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![3, 6]);
+        assert_eq!(summary.failed.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![4, 5]);
+    }
+
+    #[tokio::test]
+    async fn import_reads_quoted_fields() {
+        let csv = "\"Smith, Jr\",smith@example.com,\"pass,word!\",active,\"editor; viewer\"\n";
+        let created = RefCell::new(Vec::new());
+        let summary = run_import(csv, false, |_| async { false }, |row| {
+            created.borrow_mut().push(row);
+            async { Ok(ObjectId::new()) }
+        }).await;
+
+        assert!(summary.failed.is_empty(), "{:?}", summary.failed);
+        let rows = created.into_inner();
+        assert_eq!(rows[0].username, "Smith, Jr");
+        assert_eq!(rows[0].password, "pass,word!");
+        assert_eq!(rows[0].roles, vec!["editor".to_string(), "viewer".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn dry_run_never_creates() {
+        let summary = run_import(CSV, true, existing, |_row| async {
+            Err("dry run must not create users".into())
+        }).await;
+
+        assert_eq!(summary.created.len(), 1);
+        assert_eq!(summary.skipped.len(), 2);
+        assert_eq!(summary.failed.len(), 2);
+    }
+
+    fn public_user(username: &str, email: &str) -> AdminxUserPublic {
+        AdminxUserPublic {
+            id: Some(ObjectId::new()),
+            username: username.to_string(),
+            email: email.to_string(),
+            delete: false,
+            status: AdminxStatus::Active,
+            created_at: mongodb::bson::DateTime::now(),
+            updated_at: mongodb::bson::DateTime::now(),
+            email_verified: true,
+        }
+    }
+
+    #[test]
+    fn export_round_trips_through_a_file() {
+        let users = vec![
+            public_user("plain", "plain@example.com"),
+            public_user("Smith, \"Jr\"", "smith@example.com"),
+        ];
+        let dir = tempfile::tempdir().unwrap();
+
+        let fields = parse_export_fields(Some("username,email,status")).unwrap();
+        let csv_path = dir.path().join("admins.csv");
+        std::fs::write(&csv_path, render_export(&users, "csv", &fields).unwrap()).unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines, vec![
+            "username,email,status",
+            "plain,plain@example.com,active",
+            "\"Smith, \"\"Jr\"\"\",smith@example.com,active",
+        ]);
+        assert!(!csv.contains("password"));
+
+        let all_fields = parse_export_fields(None).unwrap();
+        let json_path = dir.path().join("admins.json");
+        std::fs::write(&json_path, render_export(&users, "json", &all_fields).unwrap()).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+        assert_eq!(json[1]["username"], "Smith, \"Jr\"");
+        assert_eq!(json[0]["deleted"], false);
+        assert_eq!(json[0]["id"], users[0].id.unwrap().to_hex());
+        assert!(json[0].get("password").is_none());
+    }
+
+    #[test]
+    fn export_rejects_unknown_fields() {
+        assert!(parse_export_fields(Some("email,password")).is_err());
+        assert!(render_export(&[], "xml", &["email".to_string()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn doctor_succeeds_with_healthy_database() {
+        let report = run_doctor(|| async { Ok(true) }, || async { Ok(3) }).await;
+        assert!(report.passed());
+        assert_eq!(report.exit_code(), 0);
+        assert_eq!(report.checks[1].detail, "3");
+    }
+
+    #[tokio::test]
+    async fn doctor_fails_when_database_is_unreachable() {
+        let report = run_doctor(
+            || async { Err("connection refused".to_string()) },
+            || async { panic!("admins must not be counted without a connection") },
+        ).await;
+        assert_eq!(report.exit_code(), 1);
+        assert!(report.checks.iter().all(|check| !check.passed));
+
+        let report = run_doctor(|| async { Ok(true) }, || async { Ok(0) }).await;
+        assert_eq!(report.exit_code(), 1);
+    }
+
+    #[test]
+    fn cli_query_builds_the_same_filters_as_the_web_list() {
+        let cli = Cli::try_parse_from(["adminx", "list", "--query", "status=active&created_at__gte=2024-01-31T00:00:00Z&sort=-created_at"]).unwrap();
+        let query = match cli.command {
+            Commands::List { query, .. } => query,
+            _ => panic!("expected the list command"),
+        };
+        let options = list_query(query.as_deref(), AdminListQuery::default()).unwrap();
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/adminx/admins/api?status=active&created_at__gte=2024-01-31T00:00:00Z&sort=-created_at")
+            .to_http_request();
+        assert_eq!(options.filters, Some(adminx::filters::parse_query(req.query_string())));
+
+        let since = mongodb::bson::DateTime::parse_rfc3339_str("2024-01-31T00:00:00Z").unwrap();
+        assert_eq!(options.filter(), mongodb::bson::doc! { "delete": false, "status": "active", "created_at": { "$gte": since } });
+
+        assert!(list_query(None, AdminListQuery::default()).unwrap().filters.is_none());
+        assert!(list_query(Some("password=x"), AdminListQuery::default()).is_err());
+    }
+}
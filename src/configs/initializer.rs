@@ -6,13 +6,31 @@ use actix_web::{web};
 use actix_session::{SessionMiddleware, storage::CookieSessionStore, config::PersistentSession};
 use actix_web::cookie::{Key, SameSite};
 use env_logger::Env;
-use std::{env, time::Duration};
-use crate::router::register_all_admix_routes;
+use jsonwebtoken::Algorithm;
+use std::{env, net::IpAddr, str::FromStr, sync::Arc, time::Duration};
+use crate::router::register_all_admix_routes_with;
 use crate::utils::{
     database::{
         initiate_database
     },
+    rate_limit::{
+        InMemoryRateLimiter,
+        RateLimiter,
+        parse_trusted_proxies,
+        DEFAULT_MAX_ATTEMPTS,
+        DEFAULT_WINDOW,
+    },
+    password::{PasswordHashing, PasswordPolicy},
+    mailer::{LoggingMailer, Mailer},
+    timezone::timezone_from_env,
+    mount::normalize_base_mount,
+    constants::DEFAULT_BASE_MOUNT,
+    clock::{Clock, SystemClock},
+    branding::Branding,
+    jwt::{is_rsa_algorithm, JwtKeys},
 };
+use crate::helpers::error_pages::{ErrorPages, TemplateErrorPages};
+use chrono_tz::Tz;
 
 #[derive(Debug, Clone)]
 pub struct AdminxConfig {
@@ -21,11 +39,55 @@ pub struct AdminxConfig {
     pub environment: String,
     pub log_level: String,
     pub session_timeout: Duration,
+    /// Rate limiter used for login attempts (in-memory by default)
+    pub rate_limiter: Arc<dyn RateLimiter>,
+    /// JWT signing algorithm (HS256 by default)
+    pub jwt_algorithm: Algorithm,
+    /// Private key PEM path, required for RS* algorithms
+    pub jwt_private_key_path: Option<String>,
+    /// Public key PEM path, required for RS* algorithms
+    pub jwt_public_key_path: Option<String>,
+    /// RS* keys read from the PEM paths when the config is built
+    pub jwt_keys: Option<Arc<JwtKeys>>,
+    /// Expected `iss` claim
+    pub jwt_issuer: String,
+    /// Expected `aud` claim
+    pub jwt_audience: String,
+    /// Clock skew tolerated when checking `exp`
+    pub jwt_leeway: Duration,
+    /// Tokens with less lifetime left than this can no longer be refreshed
+    pub jwt_refresh_min_remaining: Duration,
+    /// Rules enforced whenever a password is set
+    pub password_policy: PasswordPolicy,
+    /// Algorithm for new password hashes (bcrypt at the default cost unless configured)
+    pub password_hashing: PasswordHashing,
+    /// Transport for verification and reset emails (logs only by default)
+    pub mailer: Arc<dyn Mailer>,
+    /// Refuse logins from admins who haven't verified their email
+    pub require_email_verification: bool,
+    /// How long a password reset link stays valid
+    pub password_reset_ttl: Duration,
+    /// Zone datetimes are displayed and exported in; stored values stay UTC
+    pub display_timezone: Tz,
+    /// Pages for unmatched `/adminx` paths and internal errors
+    pub error_pages: Arc<dyn ErrorPages>,
+    /// Path AdminX is served under, `/adminx` unless set (`ADMINX_BASE_MOUNT`)
+    pub base_mount: String,
+    /// How long a password re-entry unlocks actions that `requires_reauth()`
+    pub reauth_window: Duration,
+    /// Gzip/deflate/brotli responses for clients that accept it (`ADMINX_COMPRESS_RESPONSES`, on by default)
+    pub compress_responses: bool,
+    /// Time source for token expiry, timestamps and rate limits (the system clock by default)
+    pub clock: Arc<dyn Clock>,
+    /// App name, logo and color on the login page and layout ("AdminX" unless set)
+    pub branding: Branding,
+    /// Proxies whose `X-Forwarded-For`/`Forwarded` headers name the client (`ADMINX_TRUSTED_PROXIES`, none by default)
+    pub trusted_proxies: Vec<IpAddr>,
 }
 
 impl AdminxConfig {
     pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
-        Ok(Self {
+        let config = Self {
             jwt_secret: env::var("JWT_SECRET")
                 .map_err(|_| "JWT_SECRET is required")?,
             session_secret: env::var("SESSION_SECRET")
@@ -47,9 +109,157 @@ impl AdminxConfig {
                     .parse()
                     .unwrap_or(86400)
             ),
-        })
+            rate_limiter: Arc::new(InMemoryRateLimiter::default()),
+            jwt_algorithm: match env::var("JWT_ALGORITHM") {
+                Ok(alg) => Algorithm::from_str(&alg)
+                    .map_err(|_| format!("Unsupported JWT_ALGORITHM: {}", alg))?,
+                Err(_) => Algorithm::HS256,
+            },
+            jwt_private_key_path: env::var("JWT_PRIVATE_KEY_PATH").ok(),
+            jwt_public_key_path: env::var("JWT_PUBLIC_KEY_PATH").ok(),
+            jwt_keys: None,
+            jwt_issuer: env::var("JWT_ISSUER")
+                .unwrap_or_else(|_| "adminx".to_string()),
+            jwt_audience: env::var("JWT_AUDIENCE")
+                .unwrap_or_else(|_| "adminx".to_string()),
+            jwt_leeway: Duration::from_secs(
+                env::var("JWT_LEEWAY")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .unwrap_or(60)
+            ),
+            jwt_refresh_min_remaining: Duration::from_secs(
+                env::var("JWT_REFRESH_MIN_REMAINING")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .unwrap_or(30)
+            ),
+            password_policy: PasswordPolicy::from_env(),
+            password_hashing: PasswordHashing::from_env(),
+            mailer: Arc::new(LoggingMailer),
+            require_email_verification: env::var("ADMINX_REQUIRE_EMAIL_VERIFICATION")
+                .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+                .unwrap_or(false),
+            password_reset_ttl: Duration::from_secs(
+                env::var("ADMINX_PASSWORD_RESET_TTL")
+                    .unwrap_or_else(|_| "3600".to_string())
+                    .parse()
+                    .unwrap_or(3600)
+            ),
+            display_timezone: timezone_from_env(),
+            error_pages: Arc::new(TemplateErrorPages),
+            base_mount: normalize_base_mount(
+                &env::var("ADMINX_BASE_MOUNT").unwrap_or_else(|_| DEFAULT_BASE_MOUNT.to_string())
+            ),
+            reauth_window: Duration::from_secs(
+                env::var("ADMINX_REAUTH_WINDOW")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .unwrap_or(300)
+            ),
+            compress_responses: env::var("ADMINX_COMPRESS_RESPONSES")
+                .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+                .unwrap_or(true),
+            clock: Arc::new(SystemClock),
+            branding: Branding::from_env(),
+            trusted_proxies: env::var("ADMINX_TRUSTED_PROXIES")
+                .map(|v| parse_trusted_proxies(&v))
+                .unwrap_or_default(),
+        };
+
+        if !is_rsa_algorithm(config.jwt_algorithm) {
+            return Ok(config);
+        }
+        let (Some(private_key_path), Some(public_key_path)) = (config.jwt_private_key_path.clone(), config.jwt_public_key_path.clone()) else {
+            return Err("JWT_PRIVATE_KEY_PATH and JWT_PUBLIC_KEY_PATH are required for RSA algorithms".into());
+        };
+        let algorithm = config.jwt_algorithm;
+        Ok(config.with_rsa_jwt_keys(algorithm, &private_key_path, &public_key_path)?)
+    }
+
+    /// Sign tokens with an RS* `algorithm`, reading both PEMs now rather than per token
+    pub fn with_rsa_jwt_keys(mut self, algorithm: Algorithm, private_key_path: &str, public_key_path: &str) -> anyhow::Result<Self> {
+        self.jwt_keys = Some(Arc::new(JwtKeys::from_rsa_pem_files(private_key_path, public_key_path)?));
+        self.jwt_algorithm = algorithm;
+        self.jwt_private_key_path = Some(private_key_path.to_string());
+        self.jwt_public_key_path = Some(public_key_path.to_string());
+        Ok(self)
+    }
+
+    /// Swap in a custom rate limiter (e.g. one backed by Redis)
+    pub fn with_rate_limiter(mut self, limiter: Arc<dyn RateLimiter>) -> Self {
+        self.rate_limiter = limiter;
+        self
+    }
+    
+    /// Send verification/reset emails through a custom transport
+    pub fn with_mailer(mut self, mailer: Arc<dyn Mailer>) -> Self {
+        self.mailer = mailer;
+        self
+    }
+
+    /// Override the password policy read from the environment
+    pub fn with_password_policy(mut self, policy: PasswordPolicy) -> Self {
+        self.password_policy = policy;
+        self
+    }
+
+    /// Override the password hashing read from the environment
+    pub fn with_password_hashing(mut self, hashing: PasswordHashing) -> Self {
+        self.password_hashing = hashing;
+        self
+    }
+
+    /// Display datetimes in `tz` instead of `ADMINX_DISPLAY_TIMEZONE`/UTC
+    pub fn with_display_timezone(mut self, tz: Tz) -> Self {
+        self.display_timezone = tz;
+        self
+    }
+
+    /// Replace the built-in 404/500 pages
+    pub fn with_error_pages(mut self, pages: Arc<dyn ErrorPages>) -> Self {
+        self.error_pages = pages;
+        self
+    }
+
+    /// Serve AdminX under `mount` (e.g. `/admin`) instead of `/adminx`
+    pub fn with_base_mount(mut self, mount: &str) -> Self {
+        self.base_mount = normalize_base_mount(mount);
+        self
+    }
+
+    /// How long a re-entered password unlocks sensitive actions (`ADMINX_REAUTH_WINDOW`)
+    pub fn with_reauth_window(mut self, window: Duration) -> Self {
+        self.reauth_window = window;
+        self
+    }
+
+    /// Read the time from `clock`, e.g. a `FixedClock` in tests. The login rate limiter
+    /// is rebuilt on it, so swap in a custom one with `with_rate_limiter` afterwards.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.rate_limiter = Arc::new(InMemoryRateLimiter::with_clock(DEFAULT_MAX_ATTEMPTS, DEFAULT_WINDOW, Arc::clone(&clock)));
+        self.clock = clock;
+        self
+    }
+
+    /// Turn response compression on or off (`ADMINX_COMPRESS_RESPONSES`)
+    pub fn with_response_compression(mut self, enabled: bool) -> Self {
+        self.compress_responses = enabled;
+        self
     }
     
+    /// Show `branding` on the login page and layout (`ADMINX_APP_NAME`, `ADMINX_LOGO_URL`, `ADMINX_PRIMARY_COLOR`)
+    pub fn with_branding(mut self, branding: Branding) -> Self {
+        self.branding = branding;
+        self
+    }
+
+    /// Believe forwarded client addresses from these proxies (`ADMINX_TRUSTED_PROXIES`)
+    pub fn with_trusted_proxies(mut self, proxies: Vec<IpAddr>) -> Self {
+        self.trusted_proxies = proxies;
+        self
+    }
+
     pub fn is_production(&self) -> bool {
         self.environment == "production"
     }
@@ -124,8 +334,8 @@ pub fn get_adminx_session_middleware(config: &AdminxConfig) -> SessionMiddleware
 // Alternative using service configuration
 pub fn configure_adminx_services(cfg: &mut web::ServiceConfig) {
     let config = get_adminx_config();
+    cfg.service(register_all_admix_routes_with(&config));
     cfg.app_data(web::Data::new(config));
-    cfg.service(register_all_admix_routes());
 
 }
 
@@ -1,18 +1,32 @@
 // adminx/src/controllers/auth_controller.rs
 use actix_session::Session;
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use tera::Context;
 use tracing::{error, info, warn};
-use crate::helpers::template_helper::render_template;
-use crate::models::adminx_model::get_admin_by_email;
+use crate::helpers::template_helper::{insert_flash_messages, push_flash, render_template, take_flash_messages};
+use crate::models::adminx_model::{
+    find_admin_by_reset_token, get_admin_by_email, get_admin_by_id, password_reset_email,
+    rehash_password_if_needed, request_password_reset, reset_password_with_token, store_password_hash,
+    verify_admin_email, AdminxUser, PasswordResetError,
+};
+use mongodb::bson::oid::ObjectId;
 use crate::registry::get_registered_menus;
-use crate::utils::jwt::create_jwt_token;
-use crate::utils::structs::LoginForm;
+use crate::controllers::dashboard_controller::insert_dashboard_stats;
+use crate::utils::jwt::{create_jwt_token_with_roles, refresh_jwt_token};
+use crate::utils::structs::{
+    Claims, FlashLevel, FlashMessage, LoginForm, LoginQuery, PasswordChangeForm, PasswordResetForm,
+    PasswordResetRequestForm, ProfileEditForm, ReauthRequest, VerifyEmailRequest,
+};
+use crate::utils::reauth::stamp_reauth;
+use crate::utils::password::{current_password_policy, verify_password};
+use crate::utils::mount::{admin_path, base_mount};
+use std::collections::HashMap;
 use crate::configs::initializer::AdminxConfig;
-use crate::utils::auth::{is_rate_limited, reset_rate_limit, extract_claims_from_session};
-use std::time::Duration;
+use crate::utils::auth::{extract_claims_from_session, login_redirect, safe_next_path, session_claims};
 use crate::helpers::auth_helper::{
     create_base_template_context_with_auth,
+    validate_password_change,
+    validate_profile_edit,
 };
 
 
@@ -20,12 +34,13 @@ use crate::helpers::auth_helper::{
 pub async fn login_form(
     session: Session,
     config: web::Data<AdminxConfig>,
+    query: web::Query<LoginQuery>,
 ) -> impl Responder {
     // Check if user is already authenticated
     if let Ok(_claims) = extract_claims_from_session(&session, &config).await {
         // User is already logged in, redirect to dashboard
         return HttpResponse::Found()
-            .append_header(("Location", "/adminx"))
+            .append_header(("Location", base_mount()))
             .finish();
     }
     
@@ -33,10 +48,33 @@ pub async fn login_form(
     // Important: Set authentication status to false for login page
     ctx.insert("is_authenticated", &false);
     ctx.insert("page_title", "Login");
+    config.branding.insert_into(&mut ctx);
+    if query.reason.as_deref() == Some("expired") {
+        ctx.insert("notice", "Your session expired, please sign in again");
+    }
+    if let Some(next) = query.next.as_deref().and_then(safe_next_path) {
+        ctx.insert("next", next);
+    }
+    for flash in take_flash_messages(&session) {
+        match flash.level {
+            FlashLevel::Success | FlashLevel::Info => ctx.insert("success", &flash.message),
+            FlashLevel::Warning | FlashLevel::Error => ctx.insert("error", &flash.message),
+        }
+    }
     // Don't insert menus for unauthenticated users
     render_template("login.html.tera", ctx).await
 }
 
+/// Login page context re-shown after a failed sign-in
+fn login_error_context(config: &AdminxConfig, next: &str, error: &str) -> Context {
+    let mut ctx = Context::new();
+    ctx.insert("is_authenticated", &false);
+    ctx.insert("next", next);
+    ctx.insert("error", error);
+    config.branding.insert_into(&mut ctx);
+    ctx
+}
+
 /// POST /adminx/login - Authenticate and store token in session
 pub async fn login_action(
     form: web::Form<LoginForm>,
@@ -45,33 +83,25 @@ pub async fn login_action(
 ) -> impl Responder {
     let email = form.email.trim();
     let password = form.password.trim();
+    let next = form.next.as_deref().and_then(safe_next_path).map(str::to_string).unwrap_or_else(base_mount);
     
     info!("Attempting login for: {}", email);
     
     // Input validation
     if email.is_empty() || password.is_empty() {
         warn!("Empty email or password for login attempt");
-        let mut ctx = Context::new();
-        ctx.insert("is_authenticated", &false);
-        ctx.insert("error", "Email and password are required");
-        return render_template("login.html.tera", ctx).await;
+        return render_template("login.html.tera", login_error_context(&config, &next, "Email and password are required")).await;
     }
     
     if !email.contains('@') {
         warn!("Invalid email format: {}", email);
-        let mut ctx = Context::new();
-        ctx.insert("is_authenticated", &false);
-        ctx.insert("error", "Invalid email format");
-        return render_template("login.html.tera", ctx).await;
+        return render_template("login.html.tera", login_error_context(&config, &next, "Invalid email format")).await;
     }
     
     // Rate limiting check
-    if is_rate_limited(email, 5, Duration::from_secs(900)) {
+    if config.rate_limiter.check(email) {
         warn!("Rate limit exceeded for: {}", email);
-        let mut ctx = Context::new();
-        ctx.insert("is_authenticated", &false);
-        ctx.insert("error", "Too many login attempts. Please try again later.");
-        return render_template("login.html.tera", ctx).await;
+        return render_template("login.html.tera", login_error_context(&config, &next, "Too many login attempts. Please try again later.")).await;
     }
     
     // Dummy hash to prevent timing attacks
@@ -80,63 +110,55 @@ pub async fn login_action(
     match get_admin_by_email(email).await {
         Some(admin) => {
             if admin.verify_password(password) {
+                if config.require_email_verification && !admin.email_verified {
+                    warn!("Login blocked for unverified email: {}", email);
+                    return render_template("login.html.tera", login_error_context(&config, &next, "Please verify your email address before signing in")).await;
+                }
+
                 let admin_id = match &admin.id {
                     Some(id) => id.to_string(),
                     None => {
                         error!("Admin has no ID: {}", email);
-                        let mut ctx = Context::new();
-                        ctx.insert("is_authenticated", &false);
-                        ctx.insert("error", "Authentication failed - missing admin ID");
-                        return render_template("login.html.tera", ctx).await;
+                        return render_template("login.html.tera", login_error_context(&config, &next, "Authentication failed - missing admin ID")).await;
                     }
                 };
                 
                 // Use config for JWT creation
-                match create_jwt_token(&admin_id, email, "admin", &config) {
+                match create_jwt_token_with_roles(&admin_id, email, admin.session_role(), admin.roles.clone(), &config) {
                     Ok(token) => {
                         info!("Login successful for: {}", email);
                         
                         // Reset rate limit on successful login
-                        reset_rate_limit(email);
+                        config.rate_limiter.reset(email);
+                        rehash_password_if_needed(&admin, password, &config.password_hashing, store_password_hash).await;
                         
                         if let Err(err) = session.insert("admintoken", &token) {
                             error!("Session insertion failed: {}", err);
-                            let mut ctx = Context::new();
-                            ctx.insert("is_authenticated", &false);
-                            ctx.insert("error", "Session creation failed");
-                            return render_template("login.html.tera", ctx).await;
+                            return render_template("login.html.tera", login_error_context(&config, &next, "Session creation failed")).await;
                         }
+                        stamp_reauth(&session, config.clock.as_ref());
 
                         HttpResponse::Found()
-                            .append_header(("Location", "/adminx"))
+                            .append_header(("Location", next))
                             .finish()
                     }
                     Err(err) => {
                         error!("JWT generation failed for {}: {}", email, err);
-                        let mut ctx = Context::new();
-                        ctx.insert("is_authenticated", &false);
-                        ctx.insert("error", "Authentication failed - token generation error");
-                        render_template("login.html.tera", ctx).await
+                        render_template("login.html.tera", login_error_context(&config, &next, "Authentication failed - token generation error")).await
                     }
                 }
             } else {
                 // Perform dummy verification to maintain consistent timing
-                bcrypt::verify(password, dummy_hash).ok();
+                verify_password(password, dummy_hash);
                 warn!("Invalid password for: {}", email);
-                let mut ctx = Context::new();
-                ctx.insert("is_authenticated", &false);
-                ctx.insert("error", "Invalid email or password");
-                render_template("login.html.tera", ctx).await
+                render_template("login.html.tera", login_error_context(&config, &next, "Invalid email or password")).await
             }
         }
         None => {
             // Perform dummy verification to maintain consistent timing
-            bcrypt::verify(password, dummy_hash).ok();
+            verify_password(password, dummy_hash);
             warn!("Admin not found: {}", email);
-            let mut ctx = Context::new();
-            ctx.insert("is_authenticated", &false);
-            ctx.insert("error", "Invalid email or password");
-            render_template("login.html.tera", ctx).await
+            render_template("login.html.tera", login_error_context(&config, &next, "Invalid email or password")).await
         }
     }
 }
@@ -154,7 +176,7 @@ pub async fn logout_action(session: Session) -> impl Responder {
     info!("User logged out successfully: {}", if user_info == "unknown" { "session_token_unavailable" } else { "user_had_valid_session" });
     
     HttpResponse::Found()
-        .append_header(("Location", "/adminx/login"))
+        .append_header(("Location", admin_path("/login")))
         .finish()
 }
 
@@ -162,10 +184,17 @@ pub async fn logout_action(session: Session) -> impl Responder {
 pub async fn dashboard_view(
     session: Session,
     config: web::Data<AdminxConfig>,
+    req: HttpRequest,
 ) -> impl Responder {
-    match create_base_template_context_with_auth("Dashboard", "", &session, &config).await {
+    let claims = match session_claims(&session, &config) {
+        Ok(claims) => claims,
+        Err(e) => return login_redirect(e, &req),
+    };
+
+    match create_base_template_context_with_auth("Dashboard", "", "view", &session, &config).await {
         Ok(mut ctx) => {
             ctx.insert("page_title", "Dashboard");
+            insert_dashboard_stats(&mut ctx, &claims, &req).await;
             render_template("stats.html.tera", ctx).await
         }
         Err(redirect_response) => redirect_response,
@@ -176,24 +205,294 @@ pub async fn dashboard_view(
 pub async fn profile_view(
     session: Session,
     config: web::Data<AdminxConfig>,
+    req: HttpRequest,
 ) -> impl Responder {
-    match extract_claims_from_session(&session, &config).await {
-        Ok(claims) => {
-            let mut ctx = Context::new();
-            ctx.insert("is_authenticated", &true);
-            ctx.insert("user_email", &claims.email);
-            ctx.insert("user_role", &claims.role);
-            ctx.insert("user_roles", &claims.roles);
-            ctx.insert("current_user", &claims);
-            ctx.insert("menus", &get_registered_menus());
-            ctx.insert("page_title", "Profile");
-            render_template("profile.html.tera", ctx).await
+    match session_claims(&session, &config) {
+        Ok(claims) => render_profile(&claims, &session, None).await,
+        Err(e) => login_redirect(e, &req),
+    }
+}
+
+async fn render_profile(
+    claims: &Claims,
+    session: &Session,
+    password_errors: Option<&HashMap<&'static str, String>>,
+) -> HttpResponse {
+    let mut ctx = Context::new();
+    ctx.insert("is_authenticated", &true);
+    ctx.insert("user_email", &claims.email);
+    ctx.insert("user_role", &claims.role);
+    ctx.insert("user_roles", &claims.roles);
+    ctx.insert("current_user", claims);
+    ctx.insert("menus", &get_registered_menus());
+    ctx.insert("page_title", "Profile");
+    if let Some(errors) = password_errors {
+        ctx.insert("password_errors", errors);
+    }
+    insert_flash_messages(&mut ctx, session);
+    render_template("profile.html.tera", ctx).await
+}
+
+/// GET /adminx/profile/password - Show the change password form
+pub async fn change_password_form(
+    session: Session,
+    config: web::Data<AdminxConfig>,
+    req: HttpRequest,
+) -> impl Responder {
+    profile_view(session, config, req).await
+}
+
+/// POST /adminx/profile/password - Change the signed-in admin's password
+pub async fn change_password_action(
+    form: web::Form<PasswordChangeForm>,
+    session: Session,
+    config: web::Data<AdminxConfig>,
+    req: HttpRequest,
+) -> impl Responder {
+    let claims = match session_claims(&session, &config) {
+        Ok(claims) => claims,
+        Err(e) => return login_redirect(e, &req),
+    };
+
+    let mut user = match get_admin_by_email(&claims.email).await {
+        Some(user) => user,
+        None => {
+            warn!("Password change requested for unknown admin: {}", claims.email);
+            session.purge();
+            return HttpResponse::Found()
+                .append_header(("Location", admin_path("/login")))
+                .finish();
         }
-        Err(_) => {
+    };
+
+    if let Err(errors) = validate_password_change(&user, &form, &current_password_policy()) {
+        warn!("Rejected password change for {}: {:?}", claims.email, errors.keys());
+        return render_profile(&claims, &session, Some(&errors)).await;
+    }
+
+    match user.update_password(&form.current_password, &form.new_password).await {
+        Ok(_) => {
+            info!("Password changed for: {}", claims.email);
+            if form.sign_out.is_some() {
+                session.purge();
+                return HttpResponse::Found()
+                    .append_header(("Location", admin_path("/login")))
+                    .finish();
+            }
+            push_flash(&session, FlashMessage::success("Your password has been updated."));
+            HttpResponse::Found()
+                .append_header(("Location", admin_path("/profile")))
+                .finish()
+        }
+        Err(e) => {
+            error!("Password change failed for {}: {}", claims.email, e);
+            let mut errors = HashMap::new();
+            errors.insert("new_password", e.to_string());
+            render_profile(&claims, &session, Some(&errors)).await
+        }
+    }
+}
+
+async fn load_current_admin(claims: &Claims) -> Option<AdminxUser> {
+    let id = ObjectId::parse_str(&claims.sub).ok()?;
+    get_admin_by_id(&id).await
+}
+
+async fn render_profile_edit(
+    claims: &Claims,
+    form: &ProfileEditForm,
+    profile_errors: Option<&HashMap<&'static str, String>>,
+) -> HttpResponse {
+    let mut ctx = Context::new();
+    ctx.insert("is_authenticated", &true);
+    ctx.insert("current_user", claims);
+    ctx.insert("menus", &get_registered_menus());
+    ctx.insert("page_title", "Edit Profile");
+    ctx.insert("profile", form);
+    if let Some(errors) = profile_errors {
+        ctx.insert("profile_errors", errors);
+    }
+    render_template("profile_edit.html.tera", ctx).await
+}
+
+/// GET /adminx/profile/edit - Show the profile edit form
+pub async fn profile_edit_form(
+    session: Session,
+    config: web::Data<AdminxConfig>,
+    req: HttpRequest,
+) -> impl Responder {
+    let claims = match session_claims(&session, &config) {
+        Ok(claims) => claims,
+        Err(e) => return login_redirect(e, &req),
+    };
+
+    match load_current_admin(&claims).await {
+        Some(user) => {
+            let form = ProfileEditForm {
+                username: user.username,
+                email: user.email,
+            };
+            render_profile_edit(&claims, &form, None).await
+        }
+        None => {
+            warn!("Profile edit requested for unknown admin: {}", claims.sub);
+            session.purge();
+            HttpResponse::Found()
+                .append_header(("Location", admin_path("/login")))
+                .finish()
+        }
+    }
+}
+
+/// POST /adminx/profile/edit - Update the signed-in admin's username/email
+pub async fn profile_edit_action(
+    form: web::Form<ProfileEditForm>,
+    session: Session,
+    config: web::Data<AdminxConfig>,
+    req: HttpRequest,
+) -> impl Responder {
+    let claims = match session_claims(&session, &config) {
+        Ok(claims) => claims,
+        Err(e) => return login_redirect(e, &req),
+    };
+
+    let mut user = match load_current_admin(&claims).await {
+        Some(user) => user,
+        None => {
+            warn!("Profile edit submitted for unknown admin: {}", claims.sub);
+            session.purge();
+            return HttpResponse::Found()
+                .append_header(("Location", admin_path("/login")))
+                .finish();
+        }
+    };
+
+    let form = form.into_inner();
+    let email_owner = get_admin_by_email(&form.email.trim().to_lowercase()).await;
+    if let Err(errors) = validate_profile_edit(&user, &form, email_owner.as_ref()) {
+        return render_profile_edit(&claims, &form, Some(&errors)).await;
+    }
+
+    let email_changed = form.email.trim().to_lowercase() != user.email;
+    match user.update_profile(Some(form.username.trim().to_string()), Some(form.email.trim().to_string())).await {
+        Ok(_) => {
+            info!("Profile updated for: {}", user.email);
+            // Reissue the session token so the new email shows up in the claims
+            if email_changed {
+                match create_jwt_token_with_roles(&claims.sub, &user.email, &claims.role, claims.roles.clone(), &config) {
+                    Ok(token) => {
+                        if let Err(err) = session.insert("admintoken", &token) {
+                            error!("Failed to refresh session after profile update: {}", err);
+                        }
+                    }
+                    Err(err) => error!("Failed to reissue token after profile update: {}", err),
+                }
+            }
+            push_flash(&session, FlashMessage::success("Your profile has been updated."));
             HttpResponse::Found()
-                .append_header(("Location", "/adminx/login"))
+                .append_header(("Location", admin_path("/profile")))
                 .finish()
         }
+        Err(e) => {
+            error!("Profile update failed for {}: {}", claims.email, e);
+            let mut errors = HashMap::new();
+            errors.insert("email", e.to_string());
+            render_profile_edit(&claims, &form, Some(&errors)).await
+        }
+    }
+}
+
+/// Shown whether or not the email belongs to an admin
+const RESET_REQUESTED_MESSAGE: &str = "If an account exists for that email, a password reset link has been sent.";
+
+async fn render_reset_password(token: Option<&str>, error: Option<&str>, success: Option<&str>) -> HttpResponse {
+    let mut ctx = Context::new();
+    ctx.insert("is_authenticated", &false);
+    ctx.insert("page_title", "Reset Password");
+    if let Some(token) = token {
+        ctx.insert("token", token);
+    }
+    if let Some(error) = error {
+        ctx.insert("error", error);
+    }
+    if let Some(success) = success {
+        ctx.insert("success", success);
+    }
+    render_template("reset_password.html.tera", ctx).await
+}
+
+/// GET /adminx/reset-password - Ask for the email to send a reset link to
+pub async fn reset_password_request_form() -> impl Responder {
+    render_reset_password(None, None, None).await
+}
+
+/// POST /adminx/reset-password - Issue a reset token and email it
+pub async fn reset_password_request_action(
+    form: web::Form<PasswordResetRequestForm>,
+    config: web::Data<AdminxConfig>,
+) -> impl Responder {
+    let email = form.email.trim().to_lowercase();
+
+    if !email.is_empty() && !config.rate_limiter.check(&format!("reset:{}", email)) {
+        match request_password_reset(&email, config.password_reset_ttl, config.clock.bson_now()).await {
+            Ok(Some((user, token))) => {
+                if let Err(e) = config.mailer.send(password_reset_email(&user.email, &token)).await {
+                    error!("Failed to send password reset email to {}: {}", user.email, e);
+                }
+                info!("Password reset requested for: {}", user.email);
+            }
+            Ok(None) => warn!("Password reset requested for unknown or inactive admin: {}", email),
+            Err(e) => error!("Failed to store password reset token: {}", e),
+        }
+    }
+
+    // Same response either way so the form can't be used to probe for accounts
+    render_reset_password(None, None, Some(RESET_REQUESTED_MESSAGE)).await
+}
+
+/// GET /adminx/reset-password/{token} - Show the new password form
+pub async fn reset_password_form(token: web::Path<String>, config: web::Data<AdminxConfig>) -> impl Responder {
+    let token = token.into_inner();
+    let valid = match find_admin_by_reset_token(&token).await {
+        Some(user) => user.reset_token_expires_at.is_some_and(|expires_at| expires_at > config.clock.bson_now()),
+        None => false,
+    };
+
+    if valid {
+        render_reset_password(Some(&token), None, None).await
+    } else {
+        render_reset_password(None, Some("This reset link is invalid or has expired. Request a new one below."), None).await
+    }
+}
+
+/// POST /adminx/reset-password/{token} - Set the new password and consume the token
+pub async fn reset_password_action(
+    token: web::Path<String>,
+    form: web::Form<PasswordResetForm>,
+    session: Session,
+    config: web::Data<AdminxConfig>,
+) -> impl Responder {
+    let token = token.into_inner();
+
+    if form.new_password != form.confirm_password {
+        return render_reset_password(Some(&token), Some("Passwords do not match"), None).await;
+    }
+
+    match reset_password_with_token(&token, &form.new_password, config.clock.bson_now()).await {
+        Ok(()) => {
+            info!("Password reset completed via token");
+            push_flash(&session, FlashMessage::success("Your password has been reset. Sign in with your new password."));
+            HttpResponse::Found()
+                .append_header(("Location", admin_path("/login")))
+                .finish()
+        }
+        Err(PasswordResetError::WeakPassword(reason)) => {
+            render_reset_password(Some(&token), Some(&reason), None).await
+        }
+        Err(e) => {
+            warn!("Password reset rejected: {}", e);
+            render_reset_password(None, Some(&e.to_string()), None).await
+        }
     }
 }
 
@@ -243,7 +542,7 @@ pub async fn api_login_action(
     }
     
     // Rate limiting
-    if is_rate_limited(email, 5, Duration::from_secs(900)) {
+    if config.rate_limiter.check(email) {
         warn!(
             email = %email,
             ip = %ip,
@@ -259,6 +558,12 @@ pub async fn api_login_action(
     match get_admin_by_email(email).await {
         Some(admin) => {
             if admin.verify_password(password) {
+                if config.require_email_verification && !admin.email_verified {
+                    warn!(email = %email, ip = %ip, "Login blocked for unverified email");
+                    return auth_error_response("Email address not verified", 
+                        actix_web::http::StatusCode::FORBIDDEN);
+                }
+
                 let admin_id = match &admin.id {
                     Some(id) => id.to_string(),
                     None => {
@@ -268,7 +573,7 @@ pub async fn api_login_action(
                     }
                 };
                 
-                match create_jwt_token(&admin_id, email, "admin", &config) {
+                match create_jwt_token_with_roles(&admin_id, email, admin.session_role(), admin.roles.clone(), &config) {
                     Ok(token) => {
                         info!(
                             email = %email,
@@ -276,21 +581,23 @@ pub async fn api_login_action(
                             "API login successful"
                         );
                         
-                        reset_rate_limit(email);
+                        config.rate_limiter.reset(email);
+                        rehash_password_if_needed(&admin, password, &config.password_hashing, store_password_hash).await;
                         
                         if let Err(err) = session.insert("admintoken", &token) {
                             error!("Session insertion failed: {}", err);
                             return auth_error_response("Session creation failed", 
                                 actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
                         }
+                        stamp_reauth(&session, config.clock.as_ref());
                         
                         HttpResponse::Ok().json(serde_json::json!({
                             "success": true,
-                            "redirect": "/adminx",
+                            "redirect": base_mount(),
                             "message": "Login successful",
                             "user": {
                                 "email": email,
-                                "role": "admin"
+                                "role": admin.session_role()
                             }
                         }))
                     }
@@ -301,7 +608,7 @@ pub async fn api_login_action(
                     }
                 }
             } else {
-                bcrypt::verify(password, dummy_hash).ok();
+                verify_password(password, dummy_hash);
                 warn!(
                     email = %email,
                     ip = %ip,
@@ -312,7 +619,7 @@ pub async fn api_login_action(
             }
         }
         None => {
-            bcrypt::verify(password, dummy_hash).ok();
+            verify_password(password, dummy_hash);
             warn!(
                 email = %email,
                 ip = %ip,
@@ -324,6 +631,28 @@ pub async fn api_login_action(
     }
 }
 
+/// POST /adminx/api/verify - Confirm an admin's email with a verification token
+pub async fn verify_email_action(
+    payload: web::Json<VerifyEmailRequest>,
+) -> impl Responder {
+    match verify_admin_email(payload.token.trim()).await {
+        Ok(true) => {
+            info!("Email verified via token");
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": "Email address verified"
+            }))
+        }
+        Ok(false) => auth_error_response("Invalid or already used verification token", 
+            actix_web::http::StatusCode::BAD_REQUEST),
+        Err(e) => {
+            error!("Email verification failed: {}", e);
+            auth_error_response("Verification failed", 
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 /// API endpoint to check authentication status
 pub async fn check_auth_status(
     session: Session,
@@ -346,4 +675,84 @@ pub async fn check_auth_status(
             }))
         }
     }
-}
\ No newline at end of file
+}
+
+/// POST /adminx/api/auth/refresh - Issue a fresh token for the current session
+pub async fn api_refresh_token(
+    session: Session,
+    config: web::Data<AdminxConfig>,
+) -> impl Responder {
+    let claims = match extract_claims_from_session(&session, &config).await {
+        Ok(claims) => claims,
+        Err(_) => {
+            return auth_error_response("Not authenticated", 
+                actix_web::http::StatusCode::UNAUTHORIZED);
+        }
+    };
+
+    match refresh_jwt_token(&claims, &config) {
+        Ok((token, expiration)) => {
+            if let Err(err) = session.insert("admintoken", &token) {
+                error!("Session insertion failed: {}", err);
+                return auth_error_response("Session update failed", 
+                    actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+            }
+
+            info!(email = %claims.email, "Session token refreshed");
+
+            let expires_at = chrono::DateTime::from_timestamp(expiration as i64, 0)
+                .map(|dt| dt.to_rfc3339());
+
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "expires_at": expires_at,
+                "exp": expiration
+            }))
+        }
+        Err(err) => {
+            warn!(email = %claims.email, "Token refresh rejected: {}", err);
+            auth_error_response(&err.to_string(), 
+                actix_web::http::StatusCode::UNAUTHORIZED)
+        }
+    }
+}
+
+/// POST /adminx/api/auth/reauth - Re-enter the password to unlock actions that `requires_reauth()`
+pub async fn api_reauth_action(
+    payload: web::Json<ReauthRequest>,
+    session: Session,
+    config: web::Data<AdminxConfig>,
+) -> impl Responder {
+    let claims = match extract_claims_from_session(&session, &config).await {
+        Ok(claims) => claims,
+        Err(_) => {
+            return auth_error_response("Not authenticated", 
+                actix_web::http::StatusCode::UNAUTHORIZED);
+        }
+    };
+
+    if config.rate_limiter.check(&claims.email) {
+        warn!(email = %claims.email, "Re-authentication rate limit exceeded");
+        return auth_error_response("Too many attempts", 
+            actix_web::http::StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    let verified = get_admin_by_email(&claims.email)
+        .await
+        .is_some_and(|admin| admin.verify_password(payload.password.trim()));
+    if !verified {
+        warn!(email = %claims.email, "Re-authentication failed");
+        return auth_error_response("Invalid credentials", 
+            actix_web::http::StatusCode::UNAUTHORIZED);
+    }
+
+    config.rate_limiter.reset(&claims.email);
+    stamp_reauth(&session, config.clock.as_ref());
+    info!(email = %claims.email, "Re-authenticated");
+
+    let expires_at = config.clock.now() + chrono::Duration::seconds(config.reauth_window.as_secs() as i64);
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "reauth_expires_at": expires_at.to_rfc3339()
+    }))
+}
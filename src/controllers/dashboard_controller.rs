@@ -2,12 +2,158 @@
 
 use actix_web::{HttpResponse, Responder, web, HttpRequest};
 use actix_session::Session;
+use futures::{future::join_all, TryStreamExt};
+use mongodb::bson::{doc, Document};
+use serde::Serialize;
+use serde_json::Value;
+use std::future::Future;
+use std::sync::Arc;
 use tera::Context;
-use crate::registry::get_registered_menus;
+use crate::AdmixResource;
+use crate::registry::{all_resources, get_registered_menus, resource_capabilities};
 use crate::helpers::template_helper::{render_template, render_template_with_auth};
+use crate::helpers::resource_helper::{bson_to_json_value, hide_soft_deleted, read_count_options};
 use crate::configs::initializer::AdminxConfig;
+use crate::models::adminx_model::count_active_admins;
 use crate::utils::auth::extract_claims_from_session;
-use tracing::info;
+use crate::utils::structs::Claims;
+use crate::utils::constants::AUDIT_LOG_COLLECTION;
+use crate::utils::database::ADMINX_DATABASE;
+use crate::utils::mount::admin_path;
+use crate::utils::rbac::can_perform_action;
+use crate::utils::timezone::request_timezone;
+use crate::widgets::render_widgets;
+use chrono_tz::Tz;
+use tracing::{info, warn};
+
+/// Entries shown under "Recent Activity"
+const RECENT_ACTIVITY_LIMIT: i64 = 10;
+
+/// Document count for one resource on the stats page
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceStat {
+    pub name: String,
+    pub path: String,
+    /// `None` when the count query failed
+    pub count: Option<u64>,
+}
+
+/// Filter matching the documents a resource's list shows: soft-deleted ones are hidden
+pub fn visible_documents_filter(resource: &dyn AdmixResource) -> Document {
+    let mut filter = doc! {};
+    hide_soft_deleted(&mut filter, resource.soft_delete_field());
+    filter
+}
+
+/// Count every resource's visible documents concurrently with `count`
+pub async fn collect_resource_stats<F, Fut, E>(resources: &[Arc<dyn AdmixResource>], count: F) -> Vec<ResourceStat>
+where
+    F: Fn(Arc<dyn AdmixResource>, Document) -> Fut,
+    Fut: Future<Output = Result<u64, E>>,
+    E: std::fmt::Display,
+{
+    join_all(resources.iter().map(|resource| {
+        let counted = count(Arc::clone(resource), visible_documents_filter(resource.as_ref()));
+        async move {
+            let count = counted.await
+                .map_err(|e| warn!("Failed to count documents for {}: {}", resource.resource_name(), e))
+                .ok();
+            ResourceStat {
+                name: resource.resource_name().to_string(),
+                path: admin_path(&format!("/{}/list", resource.base_path())),
+                count,
+            }
+        }
+    }))
+    .await
+}
+
+pub(crate) async fn count_visible_documents(resource: Arc<dyn AdmixResource>, filter: Document) -> Result<u64, String> {
+    let collection = resource.try_get_collection().map_err(|e| e.to_string())?;
+    collection
+        .count_documents(filter, read_count_options(resource.as_ref()))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Audit log entries about the given resources (entries record the resource by name)
+pub fn recent_activity_filter(resources: &[Arc<dyn AdmixResource>]) -> Document {
+    let names: Vec<&str> = resources.iter().map(|resource| resource.resource_name()).collect();
+    doc! { "resource": { "$in": names } }
+}
+
+/// Latest audit log entries about `resources`, or nothing when there is no audit log collection
+async fn recent_activity(resources: &[Arc<dyn AdmixResource>], tz: Tz) -> Vec<Value> {
+    if resources.is_empty() {
+        return Vec::new();
+    }
+    let Some(db) = ADMINX_DATABASE.get() else {
+        return Vec::new();
+    };
+    let exists = db.list_collection_names(doc! { "name": AUDIT_LOG_COLLECTION }).await
+        .map(|names| !names.is_empty())
+        .unwrap_or(false);
+    if !exists {
+        return Vec::new();
+    }
+
+    let options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .limit(RECENT_ACTIVITY_LIMIT)
+        .build();
+    let entries = match db.collection::<Document>(AUDIT_LOG_COLLECTION).find(recent_activity_filter(resources), options).await {
+        Ok(cursor) => cursor.try_collect::<Vec<Document>>().await.unwrap_or_default(),
+        Err(e) => {
+            warn!("Failed to load recent activity: {}", e);
+            return Vec::new();
+        }
+    };
+
+    entries
+        .iter()
+        .map(|entry| {
+            Value::Object(
+                entry.iter()
+                    .filter(|(key, _)| key.as_str() != "_id")
+                    .map(|(key, value)| (key.clone(), bson_to_json_value(value, tz)))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Compute the stats page numbers for `claims` concurrently and add them to `ctx`
+pub async fn insert_dashboard_stats(ctx: &mut Context, claims: &Claims, req: &HttpRequest) {
+    // Only count, and show activity for, resources this admin may list
+    let mut roles = claims.roles.clone();
+    roles.push(claims.role.clone());
+    let resources: Vec<Arc<dyn AdmixResource>> = all_resources()
+        .into_iter()
+        .filter(|resource| can_perform_action(resource.as_ref(), &roles, "list"))
+        .collect();
+
+    let (resource_stats, active_admins, activity, widgets) = futures::join!(
+        collect_resource_stats(&resources, count_visible_documents),
+        count_active_admins(),
+        recent_activity(&resources, request_timezone(req)),
+        render_widgets(claims),
+    );
+    let active_admins = active_admins
+        .map_err(|e| warn!("Failed to count active admins: {}", e))
+        .ok();
+    insert_stats(ctx, &resource_stats, active_admins, &activity);
+    ctx.insert("widgets", &widgets);
+}
+
+/// Put the computed stats into the stats page context
+pub fn insert_stats(ctx: &mut Context, resource_stats: &[ResourceStat], active_admins: Option<u64>, recent_activity: &[Value]) {
+    let total_documents: u64 = resource_stats.iter().filter_map(|stat| stat.count).sum();
+    ctx.insert("resource_stats", resource_stats);
+    ctx.insert("total_resources", &resource_stats.len());
+    ctx.insert("total_documents", &total_documents);
+    ctx.insert("active_admins", &active_admins);
+    ctx.insert("recent_activity", recent_activity);
+}
 
 pub async fn adminx_home(
     session: Session,
@@ -32,7 +178,7 @@ pub async fn adminx_home(
         Err(_) => {
             // User not authenticated, redirect to login
             HttpResponse::Found()
-                .append_header(("Location", "/adminx/login"))
+                .append_header(("Location", admin_path("/login")))
                 .finish()
         }
     }
@@ -49,26 +195,33 @@ pub async fn adminx_home_with_helper(
     render_template_with_auth("layout.html.tera", ctx, &session, &config).await
 }
 
+/// GET /adminx/api/resources - Registered resources and what they support (admins only)
+pub async fn resources_api() -> impl Responder {
+    let resources = resource_capabilities();
+    HttpResponse::Ok().json(serde_json::json!({
+        "count": resources.len(),
+        "resources": resources,
+    }))
+}
+
 // Additional dashboard endpoints
 pub async fn adminx_stats(
     session: Session,
     config: web::Data<AdminxConfig>,
+    req: HttpRequest,
 ) -> impl Responder {
     match extract_claims_from_session(&session, &config).await {
         Ok(claims) => {
             let mut ctx = Context::new();
             ctx.insert("menus", &get_registered_menus());
             ctx.insert("current_user", &claims);
-            
-            // Add some stats data
-            ctx.insert("total_users", &42); // Replace with actual data
-            ctx.insert("total_resources", &get_registered_menus().len());
+            insert_dashboard_stats(&mut ctx, &claims, &req).await;
             
             render_template("stats.html.tera", ctx).await
         }
         Err(_) => {
             HttpResponse::Found()
-                .append_header(("Location", "/adminx/login"))
+                .append_header(("Location", admin_path("/login")))
                 .finish()
         }
     }
@@ -89,8 +242,61 @@ pub async fn adminx_profile(
         }
         Err(_) => {
             HttpResponse::Found()
-                .append_header(("Location", "/adminx/login"))
+                .append_header(("Location", admin_path("/login")))
                 .finish()
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestResource;
+
+    fn posts() -> TestResource {
+        TestResource::named("Posts", "posts").permit(&["title", "deleted"])
+    }
+
+    fn tags() -> TestResource {
+        TestResource::named("Tags", "tags").permit(&["name"])
+    }
+
+    /// In-memory stand-in for `count_documents`, honouring the soft-delete filter
+    async fn count_seeded(resource: Arc<dyn AdmixResource>, filter: Document) -> Result<u64, mongodb::error::Error> {
+        let seeded = match resource.collection_name() {
+            "posts" => vec![
+                doc! { "title": "a", "deleted": false },
+                doc! { "title": "b", "deleted": true },
+                doc! { "title": "c" },
+            ],
+            _ => vec![doc! { "name": "rust" }, doc! { "name": "deleted", "deleted": true }],
+        };
+        let hides_deleted = filter.contains_key("deleted");
+        Ok(seeded.iter().filter(|d| !hides_deleted || d.get_bool("deleted") != Ok(true)).count() as u64)
+    }
+
+    #[actix_rt::test]
+    async fn stats_context_counts_visible_documents() {
+        let resources: Vec<Arc<dyn AdmixResource>> = vec![Arc::new(posts()), Arc::new(tags())];
+        let stats = collect_resource_stats(&resources, count_seeded).await;
+
+        let mut ctx = Context::new();
+        insert_stats(&mut ctx, &stats, Some(3), &[]);
+        let ctx = ctx.into_json();
+
+        assert_eq!(ctx["resource_stats"][0]["name"], "Posts");
+        // Soft-deleted posts are hidden; tags have no soft delete so every document counts
+        assert_eq!(ctx["resource_stats"][0]["count"], 2);
+        assert_eq!(ctx["resource_stats"][1]["count"], 2);
+        assert_eq!(ctx["resource_stats"][1]["path"], "/adminx/tags/list");
+        assert_eq!(ctx["total_documents"], 4);
+        assert_eq!(ctx["total_resources"], 2);
+        assert_eq!(ctx["active_admins"], 3);
+    }
+
+    #[test]
+    fn recent_activity_only_covers_the_given_resources() {
+        let resources: Vec<Arc<dyn AdmixResource>> = vec![Arc::new(posts())];
+        assert_eq!(recent_activity_filter(&resources), doc! { "resource": { "$in": ["Posts"] } });
+    }
+}
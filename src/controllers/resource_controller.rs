@@ -1,45 +1,82 @@
 // crates/adminx/src/controllers/resource_controller.rs
-use actix_web::{web, HttpRequest, HttpResponse, Scope};
+use actix_web::{http::Method, middleware::from_fn, web, HttpMessage, HttpRequest, HttpResponse, ResponseError, Scope};
+use once_cell::sync::OnceCell;
 use serde_json::Value;
 use std::sync::Arc;
 use tracing::{info, warn, error};
 use actix_session::Session;
 use actix_multipart::Multipart;
-use futures::TryStreamExt;
-use std::collections::HashMap;
 
 use crate::configs::initializer::AdminxConfig;
 use crate::AdmixResource;
+use crate::menu::MenuAction;
+use crate::utils::reauth::check_reauth;
+use crate::utils::rate_limit::{InMemoryRateLimiter, RateLimiter};
+use crate::utils::rbac::{action_enabled, can_run_row_action, enabled_action_names, roles_from_request, visible_row_actions};
+use crate::helpers::rich_text::render_rich_text_fields;
+use crate::helpers::live_events::stream_resource_events;
+use crate::helpers::downloads::export_jobs::{download_export, enqueue_export, export_job_status, MongoExportJobStore};
+use crate::helpers::list_query::ListQuery;
+use crate::helpers::import::import_documents;
+use crate::helpers::bulk_status::{bulk_status_allowed, bulk_status_change, BulkStatusRequest};
+use crate::utils::clock::request_clock;
+use crate::helpers::redact::redact_sensitive;
+use crate::helpers::transaction::transactional_action;
+use crate::helpers::idempotency::{idempotent_create, request_user, IdempotencyScope, MongoIdempotencyStore, IDEMPOTENCY_FORM_FIELD};
 use crate::helpers::{
     form_helper::{
+        apply_field_defaults,
         extract_fields_for_form,
         to_map,
     },
     template_helper::{
         render_template,
+        render_403,
+        insert_flash_messages,
     },
     resource_helper::{
         check_authentication,
+        check_resource_permission,
         create_base_template_context,
         convert_form_data_to_json,
         handle_create_response,
         handle_update_response,
         handle_delete_response,
+        handle_restore_response,
         get_default_form_structure,
         get_default_view_structure,
         fetch_list_data,
+        list_columns,
         fetch_single_item_data,
+        record_error_page,
+        inline_editable_fields,
+        enforce_upload_limit,
+        enforce_reauth,
+        enforce_write_rate_limit,
+        read_multipart,
+        UploadLimits,
+        with_etag,
+        with_total_count,
+        json_body_config,
+        is_timeout,
+        export_error_response,
     }
 };
 
-/// Register all UI + API routes for a resource
+/// Register all UI + API routes for a resource; `api_only()` resources skip the HTML
+/// pages and form posts, and serve their custom and row actions under `/api`
 pub fn register_admix_resource_routes(resource: Box<dyn AdmixResource>) -> Scope {
     let base_path = resource.base_path().to_string();
     let resource_name = resource.resource_name().to_string();
+    let api_only = resource.api_only();
+
+    if api_only {
+        info!("Registering API-only routes for resource: {} at path: {}/api", resource_name, base_path);
+    } else {
+        info!("Registering routes for resource: {} at path: {}", resource_name, base_path);
+    }
     
-    info!("Registering routes for resource: {} at path: {}", resource_name, base_path);
-    
-    let mut scope = web::scope("");
+    let mut scope = web::scope("").app_data(json_body_config(resource.as_ref()));
 
     // ========================
     // UI Routes (HTML pages) - REGISTER THESE FIRST!
@@ -49,551 +86,2139 @@ pub fn register_admix_resource_routes(resource: Box<dyn AdmixResource>) -> Scope
     let ui_resource_name = resource_arc.resource_name().to_string();
     let ui_base_path = resource_arc.base_path().to_string();
 
-    // GET /list - HTML List view with download support
-    scope = scope.route("/list", web::get().to({
-        let resource = Arc::clone(&resource_arc);
-        let resource_name = ui_resource_name.clone();
-        move |req: HttpRequest, session: Session, config: web::Data<AdminxConfig>| {
-            let query_string = req.query_string().to_string();
-            let resource = Arc::clone(&resource);
-            let resource_name = resource_name.clone();
-            async move {
-                match check_authentication(&session, &config, &resource_name, "list").await {
-                    Ok(claims) => {
-                        // Parse query parameters directly from the request
-                        let query_params: std::collections::HashMap<String, String> = 
-                            serde_urlencoded::from_str(&query_string).unwrap_or_default();
-                        
-                        // CHECK FOR DOWNLOAD REQUESTS FIRST
-                        if let Some(download_format) = query_params.get("download") {
-                            info!("📥 Download request for {} in format: {} by user: {}", 
-                                  resource_name, download_format, claims.email);
+    // Only register routes for actions the resource allows
+    let is_enabled = |action: MenuAction| action_enabled(resource.as_ref(), action);
+    let has_page = |action: MenuAction| !api_only && is_enabled(action);
+    let action_prefix = if api_only { "/api" } else { "" };
+
+    if has_page(MenuAction::List) {
+        // GET /list - HTML List view with download support
+        scope = scope.route("/list", web::get().to({
+            let resource = Arc::clone(&resource_arc);
+            let resource_name = ui_resource_name.clone();
+            move |req: HttpRequest, mut query: ListQuery, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                let resource_name = resource_name.clone();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "list").await {
+                        Ok(claims) => {
+                            // Over-long search terms are rejected for the list and its downloads alike
+                            if let Err(e) = query.validate(resource.as_ref().as_ref()) {
+                                return e.error_response();
+                            }
                             
-                            match download_format.as_str() {
-                                "json" => {
-                                    match crate::helpers::downloads::json_download::export_data_as_json(&resource, &req, query_string).await {
-                                        Ok(response) => {
-                                            info!("✅ JSON export successful for {} by {}", resource_name, claims.email);
-                                            return response;
+                            // CHECK FOR DOWNLOAD REQUESTS FIRST
+                            if let Some(download_format) = &query.download {
+                                info!("📥 Download request for {} in format: {} by user: {}", 
+                                      resource_name, download_format, claims.email);
+                                
+                                match download_format.as_str() {
+                                    "json" => {
+                                        match crate::helpers::downloads::json_download::export_data_as_json(&resource, &req, &query).await {
+                                            Ok(response) => {
+                                                info!("✅ JSON export successful for {} by {}", resource_name, claims.email);
+                                                return response;
+                                            }
+                                            Err(e) => {
+                                                error!("❌ Failed to export JSON for {}: {}", resource_name, e);
+                                                return export_error_response(e.as_ref(), "Failed to export JSON data");
+                                            }
                                         }
-                                        Err(e) => {
-                                            error!("❌ Failed to export JSON for {}: {}", resource_name, e);
-                                            return HttpResponse::InternalServerError()
-                                                .content_type("text/plain")
-                                                .body(format!("Failed to export JSON data: {}", e));
+                                    }
+                                    "csv" => {
+                                        match crate::helpers::downloads::csv_download::export_data_as_csv(&resource, &req, &query).await {
+                                            Ok(response) => {
+                                                info!("✅ CSV export successful for {} by {}", resource_name, claims.email);
+                                                return response;
+                                            }
+                                            Err(e) => {
+                                                error!("❌ Failed to export CSV for {}: {}", resource_name, e);
+                                                return export_error_response(e.as_ref(), "Failed to export CSV data");
+                                            }
                                         }
                                     }
-                                }
-                                "csv" => {
-                                    match crate::helpers::downloads::csv_download::export_data_as_csv(&resource, &req, query_string).await {
-                                        Ok(response) => {
-                                            info!("✅ CSV export successful for {} by {}", resource_name, claims.email);
-                                            return response;
+                                    "pdf" => {
+                                        match crate::helpers::downloads::pdf_download::export_data_as_pdf(&resource, &req, &query).await {
+                                            Ok(response) => {
+                                                info!("✅ PDF download completed for {}", resource_name);
+                                                return response;
+                                            }
+                                            Err(e) => {
+                                                error!("❌ PDF download failed for {}: {}", resource_name, e);
+                                                return export_error_response(e.as_ref(), "PDF export failed");
+                                            }
                                         }
-                                        Err(e) => {
-                                            error!("❌ Failed to export CSV for {}: {}", resource_name, e);
-                                            return HttpResponse::InternalServerError()
-                                                .content_type("text/plain")
-                                                .body(format!("Failed to export CSV data: {}", e));
+                                    }
+                                    "ndjson" => {
+                                        match crate::helpers::downloads::ndjson_download::export_data_as_ndjson(&resource, &req, &query).await {
+                                            Ok(response) => {
+                                                info!("✅ NDJSON export started for {} by {}", resource_name, claims.email);
+                                                return response;
+                                            }
+                                            Err(e) => {
+                                                error!("❌ Failed to export NDJSON for {}: {}", resource_name, e);
+                                                return export_error_response(e.as_ref(), "Failed to export NDJSON data");
+                                            }
                                         }
                                     }
+                                    _ => {
+                                        warn!("⚠️ Unsupported download format requested: {}", download_format);
+                                        return HttpResponse::BadRequest()
+                                            .content_type("text/plain")
+                                            .body(format!("Unsupported download format: {}. Supported formats: json, ndjson, csv, pdf", download_format));
+                                    }
                                 }
-                                _ => {
-                                    warn!("⚠️ Unsupported download format requested: {}", download_format);
-                                    return HttpResponse::BadRequest()
-                                        .content_type("text/plain")
-                                        .body(format!("Unsupported download format: {}. Supported formats: json, csv", download_format));
-                                }
-                            }
-                        }
-                        
-                        // REGULAR LIST VIEW (No download request)
-                        info!("✅ List UI accessed by: {} for resource: {}", claims.email, resource_name);
-                        
-                        let mut ctx = create_base_template_context(&resource_name, &resource.base_path(), &claims);
-                        
-                        // Check for success/error messages from query parameters
-                        if query_params.contains_key("success") {
-                            match query_params.get("success").unwrap().as_str() {
-                                "created" => ctx.insert("toast_message", &"Successfully created new item!"),
-                                "updated" => ctx.insert("toast_message", &"Successfully updated item!"),
-                                "deleted" => ctx.insert("toast_message", &"Successfully deleted item!"),
-                                _ => {}
                             }
-                            ctx.insert("toast_type", &"success");
-                        }
-                        
-                        if query_params.contains_key("error") {
-                            match query_params.get("error").unwrap().as_str() {
-                                "create_failed" => ctx.insert("toast_message", &"Failed to create item. Please try again."),
-                                "update_failed" => ctx.insert("toast_message", &"Failed to update item. Please try again."),
-                                "delete_failed" => ctx.insert("toast_message", &"Failed to delete item. Please try again."),
-                                _ => {}
+                            
+                            // REGULAR LIST VIEW (No download request)
+                            info!("✅ List UI accessed by: {} for resource: {}", claims.email, resource_name);
+                            
+                            let mut ctx = create_base_template_context(&resource_name, &resource.base_path(), &claims, "list");
+                            ctx.insert("allowed_actions", &enabled_action_names(resource.as_ref().as_ref()));
+                            ctx.insert("inline_editable_fields", &inline_editable_fields(resource.as_ref().as_ref(), &roles_from_request(&req)));
+                            ctx.insert("row_actions", &visible_row_actions(resource.as_ref().as_ref(), &roles_from_request(&req)));
+                            ctx.insert("empty_state_message", &resource.empty_state_message());
+                            if action_enabled(resource.as_ref().as_ref(), MenuAction::Edit)
+                                && bulk_status_allowed(resource.as_ref().as_ref(), &roles_from_request(&req)) {
+                                ctx.insert("bulk_status_values", &resource.status_values().unwrap_or_default());
                             }
-                            ctx.insert("toast_type", &"error");
-                        }
-                        
-                        // Get filters configuration and current values
-                        let (filters, current_filters) = crate::helpers::resource_helper::get_filters_data(&resource, &query_params);
-                        ctx.insert("filters", &filters);
-                        ctx.insert("current_filters", &current_filters);
-                        ctx.insert("has_active_filters", &(!current_filters.is_empty()));
-                        
-                        // Fetch actual data from the resource (with filters applied)
-                        match fetch_list_data(&resource, &req, query_string).await {
-                            Ok((headers, rows, pagination)) => {
-                                ctx.insert("headers", &headers);
-                                ctx.insert("rows", &rows);
-                                ctx.insert("pagination", &pagination);
-                                
-                                info!("📊 Loaded {} items for {} list view", rows.len(), resource_name);
+                            
+                            // Check for success/error messages from query parameters
+                            if let Some(success) = &query.success {
+                                match success.as_str() {
+                                    "created" => ctx.insert("toast_message", &"Successfully created new item!"),
+                                    "updated" => ctx.insert("toast_message", &"Successfully updated item!"),
+                                    "deleted" => ctx.insert("toast_message", &"Successfully deleted item!"),
+                                    _ => {}
+                                }
+                                ctx.insert("toast_type", &"success");
                             }
-                            Err(e) => {
-                                error!("❌ Failed to fetch list data for {}: {}", resource_name, e);
-                                // Provide empty data as fallback
-                                let headers = vec!["id", "name", "email", "created_at"];
-                                let rows: Vec<serde_json::Map<String, serde_json::Value>> = vec![];
-                                let pagination = serde_json::json!({
-                                    "current": 1,
-                                    "total": 1,
-                                    "prev": null,
-                                    "next": null,
-                                    "filter_params": ""
-                                });
-                                
-                                ctx.insert("headers", &headers);
-                                ctx.insert("rows", &rows);
-                                ctx.insert("pagination", &pagination);
-                                ctx.insert("toast_message", &"Failed to load data. Please refresh the page.");
+                            
+                            if let Some(error) = &query.error {
+                                match error.as_str() {
+                                    "create_failed" => ctx.insert("toast_message", &"Failed to create item. Please try again."),
+                                    "update_failed" => ctx.insert("toast_message", &"Failed to update item. Please try again."),
+                                    "delete_failed" => ctx.insert("toast_message", &"Failed to delete item. Please try again."),
+                                    _ => {}
+                                }
                                 ctx.insert("toast_type", &"error");
                             }
+
+                            // Session flash messages take precedence over legacy query codes
+                            insert_flash_messages(&mut ctx, &session);
+                            
+                            // Get filters configuration and current values
+                            let (filters, current_filters) = crate::helpers::resource_helper::get_filters_data(&resource, &query.filters);
+                            ctx.insert("filters", &filters);
+                            ctx.insert("current_filters", &current_filters);
+                            ctx.insert("has_active_filters", &(!current_filters.is_empty()));
+                            
+                            // Fetch actual data from the resource (with filters applied)
+                            match fetch_list_data(&resource, &req, &query).await {
+                                Ok((headers, rows, pagination)) => {
+                                    ctx.insert("headers", &headers);
+                                    ctx.insert("rows", &rows);
+                                    ctx.insert("pagination", &pagination);
+                                    
+                                    info!("📊 Loaded {} items for {} list view", rows.len(), resource_name);
+                                }
+                                Err(e) => {
+                                    error!("❌ Failed to fetch list data for {}: {}", resource_name, e);
+                                    // Provide empty data as fallback
+                                    let headers = list_columns(resource.as_ref().as_ref(), &roles_from_request(&req));
+                                    let rows: Vec<serde_json::Map<String, serde_json::Value>> = vec![];
+                                    let pagination = serde_json::json!({
+                                        "current": 1,
+                                        "total": 1,
+                                        "prev": null,
+                                        "next": null,
+                                        "filter_params": ""
+                                    });
+                                    
+                                    ctx.insert("headers", &headers);
+                                    ctx.insert("rows", &rows);
+                                    ctx.insert("pagination", &pagination);
+                                    if is_timeout(e.as_ref()) {
+                                        ctx.insert("toast_message", &"Loading took too long. Narrow the filters and try again.");
+                                    } else {
+                                        ctx.insert("toast_message", &"Failed to load data. Please refresh the page.");
+                                    }
+                                    ctx.insert("toast_type", &"error");
+                                }
+                            }
+
+                            render_template("list.html.tera", ctx).await
                         }
+                        Err(response) => response
+                    }
+                }
+            }
+        }));
+    }
 
-                        render_template("list.html.tera", ctx).await
+    if has_page(MenuAction::List) && resource.live_updates() {
+        // GET /events - Server-sent events for live list updates
+        scope = scope.route("/events", web::get().to({
+            let resource = Arc::clone(&resource_arc);
+            move |req: HttpRequest, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "list").await {
+                        Ok(claims) => {
+                            info!("📡 Live events requested by {} for {}", claims.email, resource.resource_name());
+                            stream_resource_events(resource.as_ref().as_ref()).await
+                        }
+                        Err(response) => response
                     }
-                    Err(response) => response
                 }
             }
-        }
-    }));
+        }));
+    }
 
-    // GET /new - HTML New item form page
-    scope = scope.route("/new", web::get().to({
-        let resource = Arc::clone(&resource_arc);
-        let resource_name = ui_resource_name.clone();
-        let base_path = ui_base_path.clone();
-        move |_req: HttpRequest, session: Session, config: web::Data<AdminxConfig>| {
-            let resource = Arc::clone(&resource);
-            let resource_name = resource_name.clone();
-            let base_path = base_path.clone();
-            async move {
-                match check_authentication(&session, &config, &resource_name, "create").await {
-                    Ok(claims) => {
-                        info!("✅ New form UI accessed by: {} for resource: {}", claims.email, resource_name);
-                        
-                        let form = resource.form_structure()
-                            .unwrap_or_else(|| {
-                                warn!("No form structure defined for resource: {}", resource_name);
-                                get_default_form_structure()
-                            });
-
-                        let mut ctx = create_base_template_context(&resource_name, &base_path, &claims);
-                        let form_map = to_map(&form);
-                        ctx.insert("fields", &extract_fields_for_form(&form_map));
-                        ctx.insert("form_structure", &form);
-                        ctx.insert("form", &form);
-                        ctx.insert("is_edit_mode", &false);
-                        let supports_upload = resource.supports_file_upload();
-                        ctx.insert("supports_upload", &supports_upload);
-
-                        render_template("new.html.tera", ctx).await
+    if is_enabled(MenuAction::List) {
+        // POST /export - Queue a background export of the filtered list
+        scope = scope.route("/export", web::post().to({
+            let resource = Arc::clone(&resource_arc);
+            move |req: HttpRequest, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "list").await {
+                        Ok(claims) => enqueue_export(resource, &req, &claims, Arc::new(MongoExportJobStore)).await,
+                        Err(response) => response
                     }
-                    Err(response) => response
                 }
             }
-        }
-    }));
+        }));
 
-    // GET /view/{id} - HTML View single item page
-    scope = scope.route("/view/{id}", web::get().to({
-        let resource = Arc::clone(&resource_arc);
-        let resource_name = ui_resource_name.clone();
-        move |req: HttpRequest, id: web::Path<String>, session: Session, config: web::Data<AdminxConfig>| {
-            let resource = Arc::clone(&resource);
-            let resource_name = resource_name.clone();
-            async move {
-                match check_authentication(&session, &config, &resource_name, "view").await {
-                    Ok(claims) => {
-                        let item_id = id.into_inner();
-                        info!("✅ View UI accessed by: {} for resource: {} item: {}", claims.email, resource_name, item_id);
-                        
-                        let mut ctx = create_base_template_context(&resource_name, &resource.base_path(), &claims);
-                        
-                        // Check for success messages from query parameters
-                        let query_params: std::collections::HashMap<String, String> = 
-                            serde_urlencoded::from_str(&req.query_string()).unwrap_or_default();
-                        
-                        if query_params.contains_key("success") {
-                            match query_params.get("success").unwrap().as_str() {
-                                "updated" => ctx.insert("toast_message", &"Successfully updated item!"),
-                                _ => {}
-                            }
-                            ctx.insert("toast_type", &"success");
-                        }
-                        
-                        // Fetch the actual record data
-                        match fetch_single_item_data(&resource, &req, &item_id).await {
-                            Ok(record) => {
-                                let view_structure = resource.view_structure()
-                                    .unwrap_or_else(|| get_default_view_structure());
-                                ctx.insert("view_structure", &view_structure);
-                                ctx.insert("item_id", &item_id);
-                                ctx.insert("record", &record);
-
-                                render_template("view.html.tera", ctx).await
-                            }
-                            Err(e) => {
-                                error!("❌ Failed to fetch item {} for {}: {}", item_id, resource_name, e);
-                                HttpResponse::NotFound().body(format!("Item not found: {}", e))
-                            }
+        // GET /export/{job_id} - Export job status and progress
+        scope = scope.route("/export/{job_id}", web::get().to({
+            let resource = Arc::clone(&resource_arc);
+            move |req: HttpRequest, path: web::Path<String>, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "list").await {
+                        Ok(claims) => export_job_status(&MongoExportJobStore, resource.as_ref().as_ref(), &path, &claims).await,
+                        Err(response) => response
+                    }
+                }
+            }
+        }));
+
+        // GET /export/{job_id}/download - Finished export file
+        scope = scope.route("/export/{job_id}/download", web::get().to({
+            let resource = Arc::clone(&resource_arc);
+            move |req: HttpRequest, path: web::Path<String>, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "list").await {
+                        Ok(claims) => download_export(&MongoExportJobStore, resource.as_ref().as_ref(), &path, &claims, &req).await,
+                        Err(response) => response
+                    }
+                }
+            }
+        }));
+    }
+
+    if has_page(MenuAction::Create) {
+        // GET /new - HTML New item form page
+        scope = scope.route("/new", web::get().to({
+            let resource = Arc::clone(&resource_arc);
+            let resource_name = ui_resource_name.clone();
+            let base_path = ui_base_path.clone();
+            move |req: HttpRequest, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                let resource_name = resource_name.clone();
+                let base_path = base_path.clone();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "create").await {
+                        Ok(claims) => {
+                            info!("✅ New form UI accessed by: {} for resource: {}", claims.email, resource_name);
+                            
+                            let mut form = resource.form_structure()
+                                .unwrap_or_else(|| {
+                                    warn!("No form structure defined for resource: {}", resource_name);
+                                    get_default_form_structure()
+                                });
+                            apply_field_defaults(&mut form);
+
+                            let mut ctx = create_base_template_context(&resource_name, &base_path, &claims, "create");
+                            insert_flash_messages(&mut ctx, &session);
+                            let form_map = to_map(&form);
+                            ctx.insert("fields", &extract_fields_for_form(&form_map));
+                            ctx.insert("form_structure", &form);
+                            ctx.insert("form", &form);
+                            ctx.insert("is_edit_mode", &false);
+                            ctx.insert("idempotency_key", &uuid::Uuid::new_v4().simple().to_string());
+                            let supports_upload = resource.supports_file_upload();
+                            ctx.insert("supports_upload", &supports_upload);
+
+                            render_template("new.html.tera", ctx).await
                         }
+                        Err(response) => response
                     }
-                    Err(response) => response
                 }
             }
-        }
-    }));
+        }));
+    }
 
-    // GET /edit/{id} - HTML Edit item form page
-    scope = scope.route("/edit/{id}", web::get().to({
-        let resource = Arc::clone(&resource_arc);
-        let resource_name = ui_resource_name.clone();
-        let base_path = ui_base_path.clone();
-        move |_req: HttpRequest, id: web::Path<String>, session: Session, config: web::Data<AdminxConfig>| {
-            let resource = Arc::clone(&resource);
-            let resource_name = resource_name.clone();
-            let base_path = base_path.clone();
-            async move {
-                match check_authentication(&session, &config, &resource_name, "edit").await {
-                    Ok(claims) => {
-                        let item_id = id.into_inner();
-                        info!("✅ Edit form UI accessed by: {} for resource: {} item: {}", claims.email, resource_name, item_id);
-                        
-                        let mut ctx = create_base_template_context(&resource_name, &base_path, &claims);
-                        
-                        // Fetch the actual record data for editing
-                        let req = actix_web::test::TestRequest::get().to_http_request();
-                        match fetch_single_item_data(&resource, &req, &item_id).await {
-                            Ok(record) => {
-                                let form = resource.form_structure()
-                                    .unwrap_or_else(|| get_default_form_structure());
-
-                                let form_map = to_map(&form);
-
-                                // let mut cleaned_record = serde_json::Value::Object(raw_record.clone());
-                                // coerce_editor_json_fields(&mut cleaned_record, &form_map);
-                                // // Transform the raw MongoDB data using form structure
-                                // // let cleaned_record = coerce_editor_json_fields(&raw_record, &form_map);
-
-                                // println!("cleaned_record: {:?}", cleaned_record);
-                                ctx.insert("fields", &extract_fields_for_form(&form_map));
-                                ctx.insert("form_structure", &form);
-                                ctx.insert("form", &form);
-                                ctx.insert("item_id", &item_id);
-                                ctx.insert("is_edit_mode", &true);
-                                ctx.insert("record", &record);
-                                let supports_upload = resource.supports_file_upload();
-                                ctx.insert("supports_upload", &supports_upload);
-
-                                render_template("edit.html.tera", ctx).await
+    if has_page(MenuAction::View) {
+        // GET /view/{id} - HTML View single item page
+        scope = scope.route("/view/{id}", web::get().to({
+            let resource = Arc::clone(&resource_arc);
+            let resource_name = ui_resource_name.clone();
+            move |req: HttpRequest, id: web::Path<String>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                let resource_name = resource_name.clone();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "view").await {
+                        Ok(claims) => {
+                            let item_id = id.into_inner();
+                            info!("✅ View UI accessed by: {} for resource: {} item: {}", claims.email, resource_name, item_id);
+                            
+                            let mut ctx = create_base_template_context(&resource_name, &resource.base_path(), &claims, "view");
+                            ctx.insert("allowed_actions", &enabled_action_names(resource.as_ref().as_ref()));
+                            
+                            // Check for success messages from query parameters
+                            let query_params: std::collections::HashMap<String, String> = 
+                                serde_urlencoded::from_str(&req.query_string()).unwrap_or_default();
+                            
+                            if query_params.contains_key("success") {
+                                match query_params.get("success").unwrap().as_str() {
+                                    "updated" => ctx.insert("toast_message", &"Successfully updated item!"),
+                                    _ => {}
+                                }
+                                ctx.insert("toast_type", &"success");
                             }
-                            Err(e) => {
-                                error!("❌ Failed to fetch item {} for edit: {}", item_id, e);
-                                HttpResponse::NotFound().body(format!("Item not found: {}", e))
+                            insert_flash_messages(&mut ctx, &session);
+                            
+                            // Fetch the actual record data
+                            match fetch_single_item_data(&resource, &req, &item_id).await {
+                                Ok(record) => {
+                                    let view_structure = resource.view_structure()
+                                        .unwrap_or_else(|| get_default_view_structure());
+                                    ctx.insert("view_structure", &view_structure);
+                                    ctx.insert("item_id", &item_id);
+                                    ctx.insert("rich_html", &render_rich_text_fields(&record, &resource.field_types()));
+                                    ctx.insert("record", &record);
+                                    ctx.insert("extras", &resource.view_extras(&item_id, &claims).await);
+
+                                    render_template("view.html.tera", ctx).await
+                                }
+                                Err(e) => {
+                                    error!("❌ Failed to fetch item {} for {}: {}", item_id, resource_name, e);
+                                    record_error_page(&resource_name, &item_id, &e).await
+                                }
                             }
                         }
+                        Err(response) => response
                     }
-                    Err(response) => response
                 }
             }
-        }
-    }));
+        }));
+    }
 
-    // POST /create
-    scope = scope.route("/create", web::post().to({
-        let resource = Arc::clone(&resource_arc);
-        let resource_name = ui_resource_name.clone();
-        move |req: HttpRequest, form_data: web::Form<std::collections::HashMap<String, String>>, session: Session, config: web::Data<AdminxConfig>| {
-            let resource = Arc::clone(&resource);
-            let resource_name = resource_name.clone();
-            async move {
-                match check_authentication(&session, &config, &resource_name, "create").await {
-                    Ok(claims) => {
-                        info!("✅ Create form submitted by: {} for resource: {}", claims.email, resource_name);
-                        
-                        let json_payload = convert_form_data_to_json(form_data.into_inner());
-                        tracing::debug!("Converted form data to JSON: {:?}", json_payload);
-                        
-                        let create_response = resource.create(&req, json_payload).await;
-                        handle_create_response(create_response, &resource.base_path(), &resource_name)
+    if has_page(MenuAction::Edit) {
+        // GET /edit/{id} - HTML Edit item form page
+        scope = scope.route("/edit/{id}", web::get().to({
+            let resource = Arc::clone(&resource_arc);
+            let resource_name = ui_resource_name.clone();
+            let base_path = ui_base_path.clone();
+            move |req: HttpRequest, id: web::Path<String>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                let resource_name = resource_name.clone();
+                let base_path = base_path.clone();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "edit").await {
+                        Ok(claims) => {
+                            let item_id = id.into_inner();
+                            info!("✅ Edit form UI accessed by: {} for resource: {} item: {}", claims.email, resource_name, item_id);
+                            
+                            let mut ctx = create_base_template_context(&resource_name, &base_path, &claims, "edit");
+                            insert_flash_messages(&mut ctx, &session);
+                            
+                            // Fetch the record with the editor's read projection
+                            req.extensions_mut().insert(claims.clone());
+                            match fetch_single_item_data(&resource, &req, &item_id).await {
+                                Ok(record) => {
+                                    let form = resource.form_structure()
+                                        .unwrap_or_else(|| get_default_form_structure());
+
+                                    let form_map = to_map(&form);
+
+                                    // let mut cleaned_record = serde_json::Value::Object(raw_record.clone());
+                                    // coerce_editor_json_fields(&mut cleaned_record, &form_map);
+                                    // // Transform the raw MongoDB data using form structure
+                                    // // let cleaned_record = coerce_editor_json_fields(&raw_record, &form_map);
+
+                                    // println!("cleaned_record: {:?}", cleaned_record);
+                                    ctx.insert("fields", &extract_fields_for_form(&form_map));
+                                    ctx.insert("form_structure", &form);
+                                    ctx.insert("form", &form);
+                                    ctx.insert("item_id", &item_id);
+                                    ctx.insert("is_edit_mode", &true);
+                                    ctx.insert("uses_versioning", &resource.uses_versioning());
+                                    let (created_field, updated_field) = resource.timestamps().unwrap_or_default();
+                                    ctx.insert("record_created", &record.get(created_field));
+                                    ctx.insert("record_updated", &record.get(updated_field));
+                                    ctx.insert("record", &record);
+                                    let supports_upload = resource.supports_file_upload();
+                                    ctx.insert("supports_upload", &supports_upload);
+
+                                    render_template("edit.html.tera", ctx).await
+                                }
+                                Err(e) => {
+                                    error!("❌ Failed to fetch item {} for edit: {}", item_id, e);
+                                    record_error_page(&resource_name, &item_id, &e).await
+                                }
+                            }
+                        }
+                        Err(response) => response
                     }
-                    Err(response) => response
                 }
             }
-        }
-    }));
+        }));
+    }
 
-    // POST /create-with-files
-    scope = scope.route("/create-with-files", web::post().to({
-        let resource = Arc::clone(&resource_arc);
-        let resource_name = ui_resource_name.clone();
-        move |req: HttpRequest, mut payload: Multipart, session: Session, config: web::Data<AdminxConfig>| {
-            let resource = Arc::clone(&resource);
-            let resource_name = resource_name.clone();
-            async move {
-                if !resource.supports_file_upload() {
-                    return HttpResponse::BadRequest().body("File upload not supported for this resource");
-                }
-                
-                match check_authentication(&session, &config, &resource_name, "create").await {
-                    Ok(_claims) => {
-                        let mut form_data = HashMap::new();
-                        let mut files = HashMap::new();
-                        
-                        while let Some(mut field) = payload.try_next().await.unwrap_or(None) {
-                            let name = field.name().unwrap_or("").to_string();
+    if has_page(MenuAction::Create) {
+        // POST /create
+        scope = scope.route("/create", web::post().to({
+            let resource = Arc::clone(&resource_arc);
+            let resource_name = ui_resource_name.clone();
+            move |req: HttpRequest, form_data: web::Form<std::collections::HashMap<String, String>>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                let resource_name = resource_name.clone();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "create").await {
+                        Ok(claims) => {
+                            if let Err(response) = check_reauth(&session, &config, resource.as_ref().as_ref(), "create") {
+                                return response;
+                            }
+                            info!("✅ Create form submitted by: {} for resource: {}", claims.email, resource_name);
                             
-                            // Extract filename first and clone it to avoid borrow issues
-                            let filename = field
-                                .content_disposition()
-                                .and_then(|cd| cd.get_filename())
-                                .map(|f| f.to_string()); // Convert to owned String
+                            let mut form_data = form_data.into_inner();
+                            let form_key = form_data.remove(IDEMPOTENCY_FORM_FIELD);
+                            let scope = IdempotencyScope::from_request(&req, resource.base_path(), &claims.sub, form_key.as_deref());
+                            let json_payload = convert_form_data_to_json(form_data, &resource.field_types(), &resource.readonly_keys());
+                            tracing::debug!("Converted form data to JSON: {:?}", redact_sensitive(&json_payload, &resource.sensitive_fields()));
                             
-                            let mut data = Vec::new();
-                            while let Some(chunk) = field.try_next().await.unwrap_or(None) {
-                                data.extend_from_slice(&chunk);
+                            let create_response = idempotent_create(&MongoIdempotencyStore, scope, request_clock(&req).bson_now(), resource.create(&req, json_payload)).await;
+                            handle_create_response(create_response, &resource.base_path(), &resource_name, &session)
+                        }
+                        Err(response) => response
+                    }
+                }
+            }
+        }));
+    }
+
+    // Checked against Content-Length before the multipart handlers read anything
+    let upload_limit = resource_arc.max_upload_total_bytes();
+
+    if has_page(MenuAction::Create) {
+        // POST /create-with-files
+        scope = scope.route("/create-with-files", web::post().to({
+            let resource = Arc::clone(&resource_arc);
+            let resource_name = ui_resource_name.clone();
+            move |req: HttpRequest, mut payload: Multipart, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                let resource_name = resource_name.clone();
+                async move {
+                    if !resource.supports_file_upload() {
+                        return HttpResponse::BadRequest().body("File upload not supported for this resource");
+                    }
+                    
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "create").await {
+                        Ok(claims) => {
+                            if let Err(response) = check_reauth(&session, &config, resource.as_ref().as_ref(), "create") {
+                                return response;
                             }
+                            let (mut form_data, files) = match read_multipart(&mut payload, UploadLimits::of(resource.as_ref().as_ref()), true).await {
+                                Ok(parts) => parts,
+                                Err(e) => return e.error_response(),
+                            };
                             
-                            if let Some(filename) = filename {
-                                files.insert(name, (filename, data));
-                            } else {
-                                form_data.insert(name, String::from_utf8_lossy(&data).to_string());
-                            }
+                            let form_key = form_data.remove(IDEMPOTENCY_FORM_FIELD);
+                            let scope = IdempotencyScope::from_request(&req, resource.base_path(), &claims.sub, form_key.as_deref());
+                            let create_response = idempotent_create(&MongoIdempotencyStore, scope, request_clock(&req).bson_now(), resource.create_with_files(&req, form_data, files)).await;
+                            handle_create_response(create_response, &resource.base_path(), &resource_name, &session)
                         }
-                        
-                        let create_response = resource.create_with_files(&req, form_data, files).await;
-                        handle_create_response(create_response, &resource.base_path(), &resource_name)
+                        Err(response) => response
                     }
-                    Err(response) => response
                 }
             }
-        }
-    }));
+        }).wrap(from_fn(move |req, next| enforce_upload_limit(upload_limit, req, next))));
+    }
 
-    // POST /update/{id}/with-files
-    scope = scope.route("/update/{id}/with-files", web::post().to({
-        let resource = Arc::clone(&resource_arc);
-        let resource_name = ui_resource_name.clone();
-        move |req: HttpRequest, id: web::Path<String>, mut payload: Multipart, session: Session, config: web::Data<AdminxConfig>| {
-            let resource = Arc::clone(&resource);
-            let resource_name = resource_name.clone();
-            async move {
-                if !resource.supports_file_upload() {
-                    return HttpResponse::BadRequest().body("File upload not supported for this resource");
-                }
-                
-                match check_authentication(&session, &config, &resource_name, "update").await {
-                    Ok(claims) => {
-                        let item_id = id.into_inner();
-                        info!("✅ Update with files form submitted by: {} for resource: {} item: {}", 
-                              claims.email, resource_name, item_id);
-                        
-                        let mut form_data = HashMap::new();
-                        let mut files = HashMap::new();
-                        
-                        while let Some(mut field) = payload.try_next().await.unwrap_or(None) {
-                            let name = field.name().unwrap_or("").to_string();
+    if has_page(MenuAction::Edit) {
+        // POST /update/{id}/with-files
+        scope = scope.route("/update/{id}/with-files", web::post().to({
+            let resource = Arc::clone(&resource_arc);
+            let resource_name = ui_resource_name.clone();
+            move |req: HttpRequest, id: web::Path<String>, mut payload: Multipart, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                let resource_name = resource_name.clone();
+                async move {
+                    if !resource.supports_file_upload() {
+                        return HttpResponse::BadRequest().body("File upload not supported for this resource");
+                    }
+                    
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "update").await {
+                        Ok(claims) => {
+                            if let Err(response) = check_reauth(&session, &config, resource.as_ref().as_ref(), "update") {
+                                return response;
+                            }
+                            let item_id = id.into_inner();
+                            info!("✅ Update with files form submitted by: {} for resource: {} item: {}", 
+                                  claims.email, resource_name, item_id);
                             
-                            let filename = field
-                                .content_disposition()
-                                .and_then(|cd| cd.get_filename())
-                                .map(|f| f.to_string());
+                            // Only process non-empty files for updates
+                            let (form_data, files) = match read_multipart(&mut payload, UploadLimits::of(resource.as_ref().as_ref()), false).await {
+                                Ok(parts) => parts,
+                                Err(e) => return e.error_response(),
+                            };
                             
-                            let mut data = Vec::new();
-                            while let Some(chunk) = field.try_next().await.unwrap_or(None) {
-                                data.extend_from_slice(&chunk);
+                            let update_response = resource.update_with_files(&req, item_id.clone(), form_data, files).await;
+                            handle_update_response(update_response, &resource.base_path(), &item_id, &resource_name, &session)
+                        }
+                        Err(response) => response
+                    }
+                }
+            }
+        }).wrap(from_fn(move |req, next| enforce_upload_limit(upload_limit, req, next))));
+    }
+
+    if has_page(MenuAction::Edit) {
+        // POST /update/{id}
+        scope = scope.route("/update/{id}", web::post().to({
+            let resource = Arc::clone(&resource_arc);
+            let resource_name = ui_resource_name.clone();
+            move |req: HttpRequest, id: web::Path<String>, form_data: web::Form<std::collections::HashMap<String, String>>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                let resource_name = resource_name.clone();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "update").await {
+                        Ok(claims) => {
+                            if let Err(response) = check_reauth(&session, &config, resource.as_ref().as_ref(), "update") {
+                                return response;
                             }
+                            let item_id = id.into_inner();
+                            info!("✅ Update form submitted by: {} for resource: {} item: {}", claims.email, resource_name, item_id);
                             
-                            if let Some(filename) = filename {
-                                // Only process non-empty files for updates
-                                if !data.is_empty() {
-                                    files.insert(name, (filename, data));
-                                }
-                            } else {
-                                form_data.insert(name, String::from_utf8_lossy(&data).to_string());
+                            let json_payload = convert_form_data_to_json(form_data.into_inner(), &resource.field_types(), &resource.readonly_keys());
+                            tracing::debug!("Converted form data to JSON: {:?}", redact_sensitive(&json_payload, &resource.sensitive_fields()));
+                            
+                            let update_response = resource.update(&req, item_id.clone(), json_payload).await;
+                            handle_update_response(update_response, &resource.base_path(), &item_id, &resource_name, &session)
+                        }
+                        Err(response) => response
+                    }
+                }
+            }
+        }));
+    }
+
+    if has_page(MenuAction::Delete) {
+        // POST /{id}/delete
+        scope = scope.route("/{id}/delete", web::post().to({
+            let resource = Arc::clone(&resource_arc);
+            let resource_name = ui_resource_name.clone();
+            move |req: HttpRequest, id: web::Path<String>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                let resource_name = resource_name.clone();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "delete").await {
+                        Ok(claims) => {
+                            if let Err(response) = check_reauth(&session, &config, resource.as_ref().as_ref(), "delete") {
+                                return response;
                             }
+                            let item_id = id.into_inner();
+                            info!("✅ Delete form submitted by: {} for resource: {} item: {}", claims.email, resource_name, item_id);
+                            
+                            let delete_response = resource.delete(&req, item_id.clone()).await;
+                            handle_delete_response(delete_response, &resource.base_path(), &resource_name, &session)
                         }
-                        
-                        let update_response = resource.update_with_files(&req, item_id.clone(), form_data, files).await;
-                        handle_update_response(update_response, &resource.base_path(), &item_id, &resource_name)
+                        Err(response) => response
                     }
-                    Err(response) => response
                 }
             }
-        }
-    }));
+        }));
 
-    // POST /update/{id}
-    scope = scope.route("/update/{id}", web::post().to({
-        let resource = Arc::clone(&resource_arc);
-        let resource_name = ui_resource_name.clone();
-        move |req: HttpRequest, id: web::Path<String>, form_data: web::Form<std::collections::HashMap<String, String>>, session: Session, config: web::Data<AdminxConfig>| {
-            let resource = Arc::clone(&resource);
-            let resource_name = resource_name.clone();
-            async move {
-                match check_authentication(&session, &config, &resource_name, "update").await {
-                    Ok(claims) => {
-                        let item_id = id.into_inner();
-                        info!("✅ Update form submitted by: {} for resource: {} item: {}", claims.email, resource_name, item_id);
-                        
-                        let json_payload = convert_form_data_to_json(form_data.into_inner());
-                        tracing::debug!("Converted form data to JSON: {:?}", json_payload);
-                        
-                        let update_response = resource.update(&req, item_id.clone(), json_payload).await;
-                        handle_update_response(update_response, &resource.base_path(), &item_id, &resource_name)
+        // POST /{id}/restore - Undo a soft delete
+        scope = scope.route("/{id}/restore", web::post().to({
+            let resource = Arc::clone(&resource_arc);
+            let resource_name = ui_resource_name.clone();
+            move |req: HttpRequest, id: web::Path<String>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                let resource_name = resource_name.clone();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref().as_ref(), "delete").await {
+                        Ok(claims) => {
+                            if let Err(response) = check_reauth(&session, &config, resource.as_ref().as_ref(), "delete") {
+                                return response;
+                            }
+                            let item_id = id.into_inner();
+                            info!("✅ Restore form submitted by: {} for resource: {} item: {}", claims.email, resource_name, item_id);
+
+                            let restore_response = resource.restore(&req, item_id).await;
+                            handle_restore_response(restore_response, resource.base_path(), &resource_name, &session)
+                        }
+                        Err(response) => response
                     }
-                    Err(response) => response
                 }
             }
-        }
-    }));
+        }));
+    }
 
-    // POST /{id}/delete
-    scope = scope.route("/{id}/delete", web::post().to({
-        let resource = Arc::clone(&resource_arc);
-        let resource_name = ui_resource_name.clone();
-        move |req: HttpRequest, id: web::Path<String>, session: Session, config: web::Data<AdminxConfig>| {
-            let resource = Arc::clone(&resource);
-            let resource_name = resource_name.clone();
-            async move {
-                match check_authentication(&session, &config, &resource_name, "delete").await {
-                    Ok(claims) => {
-                        let item_id = id.into_inner();
-                        info!("✅ Delete form submitted by: {} for resource: {} item: {}", claims.email, resource_name, item_id);
-                        
-                        let delete_response = resource.delete(&req, item_id.clone()).await;
-                        handle_delete_response(delete_response, &resource.base_path(), &resource_name)
+
+    if is_enabled(MenuAction::Create) && is_enabled(MenuAction::Edit) {
+        // POST /import - Upsert a JSON array or NDJSON of documents; needs create and update
+        scope = scope.route("/import", web::post().to({
+            let resource = Arc::clone(&resource_arc);
+            move |req: HttpRequest, body: web::Bytes, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                async move {
+                    let resource = resource.as_ref().as_ref();
+                    if let Err(response) = check_resource_permission(&req, &config, resource, "create").await {
+                        return response;
+                    }
+                    match check_resource_permission(&req, &config, resource, "update").await {
+                        Ok(claims) => {
+                            for action in ["create", "update"] {
+                                if let Err(response) = check_reauth(&session, &config, resource, action) {
+                                    return response;
+                                }
+                            }
+                            import_documents(resource, &claims, &body).await
+                        }
+                        Err(response) => response
                     }
-                    Err(response) => response
                 }
             }
-        }
-    }));
+        }));
+    }
 
-    
+    if is_enabled(MenuAction::Edit) {
+        // POST /bulk-status - Set `status` on the selected documents
+        scope = scope.route("/bulk-status", web::post().to({
+            let resource = Arc::clone(&resource_arc);
+            move |req: HttpRequest, body: web::Json<BulkStatusRequest>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                async move {
+                    let resource = resource.as_ref().as_ref();
+                    match check_resource_permission(&req, &config, resource, "update").await {
+                        Ok(claims) => {
+                            if let Err(response) = check_reauth(&session, &config, resource, "update") {
+                                return response;
+                            }
+                            bulk_status_change(resource, &claims, request_clock(&req).bson_now(), body.into_inner()).await
+                        }
+                        Err(response) => response
+                    }
+                }
+            }
+        }));
+    }
 
     // ========================
     // API Routes (JSON endpoints) - MOVED TO /api PREFIX TO AVOID CONFLICTS
     // ========================
     
-    // GET /api - List all items (JSON API)
-    let list_resource = resource.clone_box();
-    scope = scope.route(
-        "/api",
-        web::get().to(move |req: HttpRequest| {
+    if is_enabled(MenuAction::List) {
+        // GET|HEAD /api - List all items (JSON API); HEAD answers with just X-Total-Count
+        let list_resource = resource.clone_box();
+        let list = move |req: HttpRequest, config: web::Data<AdminxConfig>| {
             let resource = list_resource.clone_box();
             async move {
+                match check_resource_permission(&req, &config, resource.as_ref(), "list").await {
+                    Ok(claims) => { req.extensions_mut().insert(claims); }
+                    Err(response) => return response,
+                }
                 info!("📡 List API endpoint called for resource: {}", resource.resource_name());
                 let query_string = req.query_string().to_string();
-                resource.list(&req, query_string).await
+                with_total_count(resource.list(&req, query_string).await, req.method() == Method::HEAD).await
             }
-        }),
-    );
-
-    // POST /api - Create new item (JSON API)
-    let create_resource = resource.clone_box();
-    scope = scope.route(
-        "/api",
-        web::post().to(move |req: HttpRequest, body: web::Json<Value>| {
-            let resource = create_resource.clone_box();
-            async move {
-                info!("📡 Create API endpoint called for resource: {}", resource.resource_name());
-                resource.create(&req, body.into_inner()).await
-            }
-        }),
-    );
-
-    // GET /api/{id} - Get single item (JSON API)
-    let get_resource = resource.clone_box();
-    scope = scope.route(
-        "/api/{id}",
-        web::get().to(move |req: HttpRequest, path: web::Path<String>| {
+        };
+        scope = scope
+            .route("/api", web::get().to(list.clone()))
+            .route("/api", web::head().to(list));
+    }
+
+    if is_enabled(MenuAction::Create) {
+        // POST /api - Create new item (JSON API)
+        let create_resource = resource.clone_box();
+        scope = scope.route(
+            "/api",
+            web::post().to(move |req: HttpRequest, body: web::Json<Value>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = create_resource.clone_box();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref(), "create").await {
+                        Ok(claims) => { req.extensions_mut().insert(claims); }
+                        Err(response) => return response,
+                    }
+                    if let Err(response) = check_reauth(&session, &config, resource.as_ref(), "create") {
+                        return response;
+                    }
+                    info!("📡 Create API endpoint called for resource: {}", resource.resource_name());
+                    let scope = IdempotencyScope::from_request(&req, resource.base_path(), &request_user(&req), None);
+                    idempotent_create(&MongoIdempotencyStore, scope, request_clock(&req).bson_now(), resource.create(&req, body.into_inner())).await
+                }
+            }),
+        );
+    }
+
+    if is_enabled(MenuAction::View) {
+        // GET|HEAD /api/{id} - Get single item (JSON API); HEAD answers with just the ETag
+        let get_resource = resource.clone_box();
+        let get = move |req: HttpRequest, path: web::Path<String>, config: web::Data<AdminxConfig>| {
             let resource = get_resource.clone_box();
             async move {
+                match check_resource_permission(&req, &config, resource.as_ref(), "view").await {
+                    Ok(claims) => { req.extensions_mut().insert(claims); }
+                    Err(response) => return response,
+                }
                 let id = path.into_inner();
                 info!("📡 Get API endpoint called for resource: {} with id: {}", resource.resource_name(), id);
-                resource.get(&req, id).await
-            }
-        }),
-    );
-
-    // PUT /api/{id} - Update item (JSON API)
-    let update_resource = resource.clone_box();
-    scope = scope.route(
-        "/api/{id}",
-        web::put().to(move |req: HttpRequest, path: web::Path<String>, body: web::Json<Value>| {
-            let resource = update_resource.clone_box();
-            async move {
-                let id = path.into_inner();
-                info!("📡 Update API endpoint called for resource: {} with id: {}", resource.resource_name(), id);
-                resource.update(&req, id, body.into_inner()).await
-            }
-        }),
-    );
-
-    // DELETE /api/{id} - Delete item (JSON API)
-    let delete_resource = resource.clone_box();
-    scope = scope.route(
-        "/api/{id}",
-        web::delete().to(move |req: HttpRequest, path: web::Path<String>| {
-            let resource = delete_resource.clone_box();
-            async move {
-                let id = path.into_inner();
-                info!("📡 Delete API endpoint called for resource: {} with id: {}", resource.resource_name(), id);
-                resource.delete(&req, id).await
+                with_etag(resource.get(&req, id).await, req.method() == Method::HEAD).await
             }
-        }),
-    );
+        };
+        scope = scope
+            .route("/api/{id}", web::get().to(get.clone()))
+            .route("/api/{id}", web::head().to(get));
+    }
+
+    if is_enabled(MenuAction::Edit) {
+        // PUT /api/{id} - Update item (JSON API)
+        let update_resource = resource.clone_box();
+        scope = scope.route(
+            "/api/{id}",
+            web::put().to(move |req: HttpRequest, path: web::Path<String>, body: web::Json<Value>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = update_resource.clone_box();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref(), "update").await {
+                        Ok(claims) => { req.extensions_mut().insert(claims); }
+                        Err(response) => return response,
+                    }
+                    if let Err(response) = check_reauth(&session, &config, resource.as_ref(), "update") {
+                        return response;
+                    }
+                    let id = path.into_inner();
+                    info!("📡 Update API endpoint called for resource: {} with id: {}", resource.resource_name(), id);
+                    resource.update(&req, id, body.into_inner()).await
+                }
+            }),
+        );
+    }
+
+    if is_enabled(MenuAction::Edit) {
+        // PATCH /api/{id} - Partially update item (inline editing)
+        let patch_resource = resource.clone_box();
+        scope = scope.route(
+            "/api/{id}",
+            web::patch().to(move |req: HttpRequest, path: web::Path<String>, body: web::Json<Value>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = patch_resource.clone_box();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref(), "update").await {
+                        Ok(claims) => { req.extensions_mut().insert(claims); }
+                        Err(response) => return response,
+                    }
+                    if let Err(response) = check_reauth(&session, &config, resource.as_ref(), "update") {
+                        return response;
+                    }
+                    let id = path.into_inner();
+                    info!("📡 Patch API endpoint called for resource: {} with id: {}", resource.resource_name(), id);
+                    resource.patch(&req, id, body.into_inner()).await
+                }
+            }),
+        );
+    }
+
+    if is_enabled(MenuAction::Delete) {
+        // DELETE /api/{id} - Delete item (JSON API)
+        let delete_resource = resource.clone_box();
+        scope = scope.route(
+            "/api/{id}",
+            web::delete().to(move |req: HttpRequest, path: web::Path<String>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = delete_resource.clone_box();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref(), "delete").await {
+                        Ok(claims) => { req.extensions_mut().insert(claims); }
+                        Err(response) => return response,
+                    }
+                    if let Err(response) = check_reauth(&session, &config, resource.as_ref(), "delete") {
+                        return response;
+                    }
+                    let id = path.into_inner();
+                    info!("📡 Delete API endpoint called for resource: {} with id: {}", resource.resource_name(), id);
+                    resource.delete(&req, id).await
+                }
+            }),
+        );
+
+        // POST /api/{id}/restore - Undo a soft delete (JSON API)
+        let restore_resource = resource.clone_box();
+        scope = scope.route(
+            "/api/{id}/restore",
+            web::post().to(move |req: HttpRequest, path: web::Path<String>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = restore_resource.clone_box();
+                async move {
+                    match check_resource_permission(&req, &config, resource.as_ref(), "delete").await {
+                        Ok(claims) => { req.extensions_mut().insert(claims); }
+                        Err(response) => return response,
+                    }
+                    if let Err(response) = check_reauth(&session, &config, resource.as_ref(), "delete") {
+                        return response;
+                    }
+                    let id = path.into_inner();
+                    info!("📡 Restore API endpoint called for resource: {} with id: {}", resource.resource_name(), id);
+                    resource.restore(&req, id).await
+                }
+            }),
+        );
+    }
 
     // ========================
     // Custom Actions
     // ========================
+    let reauth_for = |action: &'static str| {
+        let resource = Arc::clone(&resource_arc);
+        from_fn(move |req, next| enforce_reauth(Arc::clone(&resource), action, req, next))
+    };
     for action in resource_arc.custom_actions() {
-        let path = format!("/{{id}}/{}", action.name);
+        let path = format!("{}/{{id}}/{}", action_prefix, action.name);
         info!("Adding custom action: {} {} for resource: {}", action.method, path, resource_name);
         
-        match action.method {
-            "POST" => {
-                scope = scope.route(&path, web::post().to(action.handler));
+        let route = match action.method {
+            "POST" => web::post(),
+            "GET" => web::get(),
+            "PUT" => web::put(),
+            "DELETE" => web::delete(),
+            "PATCH" => web::patch(),
+            method => {
+                error!("Unsupported HTTP method: {} for action: {} in resource: {}", method, action.name, resource_name);
+                continue;
             }
-            "GET" => {
-                scope = scope.route(&path, web::get().to(action.handler));
+        };
+
+        if resource_arc.action_in_transaction(action.name) {
+            let resource = Arc::clone(&resource_arc);
+            let handler = action.handler;
+            scope = scope.route(&path, route.to(move |req: HttpRequest, id: web::Path<String>, body: web::Json<Value>| {
+                let resource = Arc::clone(&resource);
+                async move { transactional_action(resource.as_ref().as_ref(), handler, req, id, body).await }
+            }).wrap(reauth_for(action.name)));
+        } else {
+            scope = scope.route(&path, route.to(action.handler).wrap(reauth_for(action.name)));
+        }
+    }
+
+    // ========================
+    // Row Actions (list row buttons)
+    // ========================
+    for action in resource_arc.row_actions() {
+        let path = format!("{}/{{id}}/{}", action_prefix, action.name);
+        let route = match action.method {
+            "POST" => web::post(),
+            "GET" => web::get(),
+            method => {
+                error!("Unsupported HTTP method: {} for row action: {} in resource: {}", method, action.name, resource_name);
+                continue;
             }
-            "PUT" => {
-                scope = scope.route(&path, web::put().to(action.handler));
+        };
+        info!("Adding row action: {} {} for resource: {}", action.method, path, resource_name);
+
+        scope = scope.route(&path, route.to({
+            let resource = Arc::clone(&resource_arc);
+            let resource_name = ui_resource_name.clone();
+            move |req: HttpRequest, id: web::Path<String>, session: Session, config: web::Data<AdminxConfig>| {
+                let resource = Arc::clone(&resource);
+                let resource_name = resource_name.clone();
+                let action = action.clone();
+                async move {
+                    match check_authentication(&req, &config, &resource_name, action.name).await {
+                        Ok(claims) => {
+                            let email = claims.email.clone();
+                            req.extensions_mut().insert(claims);
+                            if !can_run_row_action(resource.as_ref().as_ref(), &roles_from_request(&req), &action) {
+                                warn!("🚫 {} may not run row action {} on {}", email, action.name, resource_name);
+                                return render_403().await;
+                            }
+                            if let Err(response) = check_reauth(&session, &config, resource.as_ref().as_ref(), action.name) {
+                                return response;
+                            }
+                            let item_id = id.into_inner();
+                            info!("✅ Row action {} run by: {} for resource: {} item: {}", action.name, email, resource_name, item_id);
+                            (action.handler)(req, item_id).await
+                        }
+                        Err(response) => response
+                    }
+                }
             }
-            "DELETE" => {
-                scope = scope.route(&path, web::delete().to(action.handler));
+        }));
+    }
+
+    info!("✅ Successfully registered all routes for resource: {}", resource_name);
+
+    match resource.write_rate_limit() {
+        Some((max_writes, window)) => {
+            info!("Limiting writes to {} per {:?} for resource: {}", max_writes, window, resource_name);
+            // Built on the first request so its windows follow the serving config's clock
+            let limiter: Arc<OnceCell<Arc<dyn RateLimiter>>> = Arc::new(OnceCell::new());
+            web::scope("").service(scope.wrap(from_fn(move |req: actix_web::dev::ServiceRequest, next| {
+                let limiter = Arc::clone(limiter.get_or_init(|| {
+                    Arc::new(InMemoryRateLimiter::with_clock(max_writes, window, request_clock(req.request())))
+                }));
+                enforce_write_rate_limit(limiter, window, base_path.clone(), req, next)
+            })))
+        }
+        None => scope,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test::{call_service, init_service, TestRequest}, App, http::StatusCode};
+    use mongodb::{bson::Document, Collection};
+    use crate::actions::RowAction;
+    use crate::test_support::{login_cookie, login_cookie_as, login_route, TestResource};
+
+    fn reports() -> TestResource {
+        TestResource::named("Reports", "reports").actions(vec![MenuAction::List, MenuAction::View])
+    }
+
+    #[actix_rt::test]
+    async fn test_read_only_resource_does_not_serve_new() {
+        let app = init_service(
+            App::new().service(register_admix_resource_routes(Box::new(reports())))
+        ).await;
+
+        let resp = call_service(&app, TestRequest::get().uri("/new").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = call_service(&app, TestRequest::post().uri("/create").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        // The list route is still registered (it fails later on missing app config, not routing)
+        let resp = call_service(&app, TestRequest::get().uri("/list").to_request()).await;
+        assert_ne!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn test_events_route_requires_live_updates() {
+        let app = init_service(
+            App::new().service(register_admix_resource_routes(Box::new(reports())))
+        ).await;
+
+        let resp = call_service(&app, TestRequest::get().uri("/events").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_enabled_action_names() {
+        assert_eq!(enabled_action_names(&reports()), vec!["list", "view"]);
+    }
+
+    struct UploadResource;
+
+    impl AdmixResource for UploadResource {
+        fn new() -> Self { UploadResource }
+        fn resource_name(&self) -> &'static str { "Images" }
+        fn base_path(&self) -> &'static str { "images" }
+        fn collection_name(&self) -> &'static str { "images" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(UploadResource) }
+
+        fn supports_file_upload(&self) -> bool { true }
+        fn max_upload_total_bytes(&self) -> usize { 256 }
+        fn max_files(&self) -> usize { 2 }
+    }
+
+    fn multipart_body(file_size: usize) -> Vec<u8> {
+        let mut body = b"--BOUNDARY\r\nContent-Disposition: form-data; name=\"image\"; filename=\"a.png\"\r\nContent-Type: image/png\r\n\r\n".to_vec();
+        body.extend(std::iter::repeat_n(b'x', file_size));
+        body.extend_from_slice(b"\r\n--BOUNDARY--\r\n");
+        body
+    }
+
+    #[actix_rt::test]
+    async fn test_oversized_upload_is_rejected_with_413() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .service(register_admix_resource_routes(Box::new(UploadResource)))
+        ).await;
+
+        for uri in ["/create-with-files", "/update/abc/with-files"] {
+            let body = multipart_body(1024);
+            let req = TestRequest::post()
+                .uri(uri)
+                .insert_header(("content-type", "multipart/form-data; boundary=BOUNDARY"))
+                .insert_header(("content-length", body.len().to_string()))
+                .set_payload(body)
+                .to_request();
+            let resp = call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        }
+
+        // Within the cap the request reaches the handler, which turns away the missing session
+        let body = multipart_body(16);
+        let req = TestRequest::post()
+            .uri("/create-with-files")
+            .insert_header(("content-type", "multipart/form-data; boundary=BOUNDARY"))
+            .insert_header(("content-length", body.len().to_string()))
+            .set_payload(body)
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        assert!(resp.headers().get("Location").unwrap().to_str().unwrap().starts_with("/adminx/login"));
+    }
+
+    fn multipart_files(count: usize) -> Vec<u8> {
+        let mut body = Vec::new();
+        for i in 0..count {
+            body.extend_from_slice(format!("--BOUNDARY\r\nContent-Disposition: form-data; name=\"image{i}\"; filename=\"{i}.png\"\r\nContent-Type: image/png\r\n\r\nxx\r\n").as_bytes());
+        }
+        body.extend_from_slice(b"--BOUNDARY--\r\n");
+        body
+    }
+
+    #[actix_rt::test]
+    async fn test_too_many_uploaded_files_are_rejected_with_400() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(register_admix_resource_routes(Box::new(UploadResource)))
+        ).await;
+        let cookie = login_cookie(&app, "admin").await;
+
+        for uri in ["/create-with-files", "/update/abc/with-files"] {
+            let req = TestRequest::post()
+                .uri(uri)
+                .cookie(cookie.clone())
+                .insert_header(("content-type", "multipart/form-data; boundary=BOUNDARY"))
+                .set_payload(multipart_files(3))
+                .to_request();
+            let resp = call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{}", uri);
+            let body: Value = actix_web::test::read_body_json(resp).await;
+            assert_eq!(body["error"], "Bad Request: At most 2 files can be uploaded at once");
+        }
+
+        // At the cap the upload is handed to the resource
+        let req = TestRequest::post()
+            .uri("/create-with-files")
+            .cookie(cookie)
+            .insert_header(("content-type", "multipart/form-data; boundary=BOUNDARY"))
+            .set_payload(multipart_files(2))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+    }
+
+    struct CountedResource;
+
+    impl AdmixResource for CountedResource {
+        fn new() -> Self { CountedResource }
+        fn resource_name(&self) -> &'static str { "Counted" }
+        fn base_path(&self) -> &'static str { "counted" }
+        fn collection_name(&self) -> &'static str { "counted" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(CountedResource) }
+
+        fn list(&self, _req: &HttpRequest, _query: String) -> futures::future::BoxFuture<'static, HttpResponse> {
+            let page = crate::pagination::PaginatedResponse::new(vec![serde_json::json!({ "name": "a" })], 42, 1, 1);
+            Box::pin(async move { HttpResponse::Ok().json(page) })
+        }
+
+        fn get(&self, _req: &HttpRequest, id: String) -> futures::future::BoxFuture<'static, HttpResponse> {
+            Box::pin(async move {
+                if id == "missing" {
+                    return crate::error::AdminxError::NotFound.error_response();
+                }
+                HttpResponse::Ok().json(serde_json::json!({ "id": id, "name": "a" }))
+            })
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_head_returns_list_count_and_etag_without_a_body() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+        use actix_web::test::read_body;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(register_admix_resource_routes(Box::new(CountedResource)))
+        ).await;
+        let cookie = login_cookie(&app, "admin").await;
+        let get = |uri: &str| TestRequest::get().uri(uri).cookie(cookie.clone()).to_request();
+        let head = |uri: &str| TestRequest::default().method(Method::HEAD).uri(uri).cookie(cookie.clone()).to_request();
+
+        let resp = call_service(&app, head("/api")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("X-Total-Count").unwrap(), "42");
+        assert!(read_body(resp).await.is_empty());
+
+        let resp = call_service(&app, get("/api")).await;
+        assert_eq!(resp.headers().get("X-Total-Count").unwrap(), "42");
+        assert!(!read_body(resp).await.is_empty());
+
+        let resp = call_service(&app, get("/api/abc")).await;
+        let etag = resp.headers().get("ETag").unwrap().clone();
+        let resp = call_service(&app, head("/api/abc")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("ETag").unwrap(), &etag);
+        assert!(read_body(resp).await.is_empty());
+
+        let resp = call_service(&app, head("/api/missing")).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert!(resp.headers().get("ETag").is_none());
+        assert!(read_body(resp).await.is_empty());
+    }
+
+    struct ReviewResource;
+
+    fn approve(_req: HttpRequest, id: String) -> futures::future::LocalBoxFuture<'static, HttpResponse> {
+        Box::pin(async move { HttpResponse::Ok().body(format!("approved {}", id)) })
+    }
+
+    impl AdmixResource for ReviewResource {
+        fn new() -> Self { ReviewResource }
+        fn resource_name(&self) -> &'static str { "Reviews" }
+        fn base_path(&self) -> &'static str { "reviews" }
+        fn collection_name(&self) -> &'static str { "reviews" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(ReviewResource) }
+
+        fn row_actions(&self) -> Vec<RowAction> {
+            vec![RowAction::new("approve", "Approve", approve).confirm("Approve this review?")]
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_row_action_runs_for_permitted_roles_only() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(register_admix_resource_routes(Box::new(ReviewResource)))
+        ).await;
+
+        let resp = call_service(&app, TestRequest::post().uri("/abc/approve").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+
+        for (role, expected) in [("admin", StatusCode::OK), ("viewer", StatusCode::FORBIDDEN)] {
+            let cookie = login_cookie(&app, role).await;
+            let resp = call_service(&app, TestRequest::post().uri("/abc/approve").cookie(cookie).to_request()).await;
+            assert_eq!(resp.status(), expected);
+            if expected == StatusCode::OK {
+                assert_eq!(actix_web::test::read_body(resp).await, "approved abc");
             }
-            "PATCH" => {
-                scope = scope.route(&path, web::patch().to(action.handler));
+        }
+    }
+
+    #[test]
+    fn test_list_renders_row_action_buttons() {
+        use crate::helpers::template_helper::ADMINX_TEMPLATES;
+        use crate::utils::structs::Claims;
+
+        let claims = Claims {
+            sub: "1".to_string(),
+            exp: 0,
+            email: "a@example.com".to_string(),
+            role: "admin".to_string(),
+            roles: vec![],
+            iss: "adminx".to_string(),
+            aud: "adminx".to_string(),
+        };
+        let mut ctx = create_base_template_context("Reviews", "reviews", &claims, "list");
+        ctx.insert("allowed_actions", &enabled_action_names(&ReviewResource));
+        ctx.insert("row_actions", &visible_row_actions(&ReviewResource, &["admin".to_string()]));
+        ctx.insert("headers", &vec!["id", "title"]);
+        ctx.insert("rows", &vec![serde_json::json!({ "id": "abc", "title": "Great" })]);
+        ctx.insert("pagination", &serde_json::json!({ "current": 1, "total": 1, "prev": null, "next": null, "filter_params": "" }));
+        ctx.insert("filters", &Vec::<String>::new());
+        ctx.insert("current_filters", &serde_json::json!({}));
+        ctx.insert("has_active_filters", &false);
+
+        let html = ADMINX_TEMPLATES.render("list.html.tera", &ctx).unwrap();
+        assert!(html.contains(r#"action="/adminx/reviews/abc/approve""#));
+        assert!(html.contains(r#"title="Approve""#));
+        assert!(html.contains("Approve this review?"));
+
+        ctx.insert("row_actions", &visible_row_actions(&ReviewResource, &["viewer".to_string()]));
+        let html = ADMINX_TEMPLATES.render("list.html.tera", &ctx).unwrap();
+        assert!(!html.contains("/abc/approve"));
+    }
+
+    #[actix_rt::test]
+    async fn test_import_requires_create_and_update() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/reviews").service(register_admix_resource_routes(Box::new(ReviewResource))))
+                .service(web::scope("/reports").service(register_admix_resource_routes(Box::new(reports()))))
+        ).await;
+
+        let resp = call_service(&app, TestRequest::post().uri("/reviews/import").set_payload("[]").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+
+        for (uri, role, expected) in [
+            ("/reports/import", "admin", StatusCode::NOT_FOUND),
+            ("/reviews/import", "viewer", StatusCode::FORBIDDEN),
+            // Permitted: the malformed body is rejected before any database access
+            ("/reviews/import", "admin", StatusCode::BAD_REQUEST),
+        ] {
+            let cookie = login_cookie(&app, role).await;
+            let resp = call_service(&app, TestRequest::post().uri(uri).cookie(cookie).set_payload("[{").to_request()).await;
+            assert_eq!(resp.status(), expected, "{} as {}", uri, role);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_writes_need_the_action_permission() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/reviews").service(register_admix_resource_routes(Box::new(ReviewResource))))
+        ).await;
+
+        let cookie = login_cookie(&app, "viewer").await;
+        let id = "64b7f0c2a1b2c3d4e5f60718";
+
+        // Signed in but not permitted: every UI and API write is refused before touching the database
+        let requests = [
+            TestRequest::post().uri("/reviews/create").set_form([("title", "x")]),
+            TestRequest::post().uri(&format!("/reviews/update/{}", id)).set_form([("title", "x")]),
+            TestRequest::post().uri(&format!("/reviews/{}/delete", id)),
+            TestRequest::post().uri(&format!("/reviews/{}/restore", id)),
+            TestRequest::post().uri("/reviews/api").set_json(serde_json::json!({ "title": "x" })),
+            TestRequest::put().uri(&format!("/reviews/api/{}", id)).set_json(serde_json::json!({ "title": "x" })),
+            TestRequest::patch().uri(&format!("/reviews/api/{}", id)).set_json(serde_json::json!({ "title": "x" })),
+            TestRequest::delete().uri(&format!("/reviews/api/{}", id)),
+            TestRequest::post().uri(&format!("/reviews/api/{}/restore", id)),
+        ];
+        for request in requests {
+            let request = request.cookie(cookie.clone()).to_request();
+            let target = format!("{} {}", request.method(), request.path());
+            let resp = call_service(&app, request).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN, "{}", target);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_reads_need_the_action_permission() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let articles = TestResource::named("Articles", "articles")
+            .role_permissions(serde_json::json!({ "viewer": ["list", "view"], "author": ["create"] }));
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/articles").service(register_admix_resource_routes(Box::new(articles))))
+        ).await;
+
+        let id = "64b7f0c2a1b2c3d4e5f60718";
+        for (role, uri, permitted) in [
+            ("viewer", "/articles/list".to_string(), true),
+            ("viewer", "/articles/api".to_string(), true),
+            ("viewer", format!("/articles/view/{}", id), true),
+            ("viewer", format!("/articles/api/{}", id), true),
+            ("viewer", "/articles/new".to_string(), false),
+            ("viewer", format!("/articles/edit/{}", id), false),
+            ("author", "/articles/new".to_string(), true),
+            ("author", "/articles/list".to_string(), false),
+            ("author", "/articles/api".to_string(), false),
+            ("author", format!("/articles/view/{}", id), false),
+            ("author", format!("/articles/api/{}", id), false),
+        ] {
+            let cookie = login_cookie(&app, role).await;
+            let resp = call_service(&app, TestRequest::get().uri(&uri).cookie(cookie).to_request()).await;
+            // Permitted reads get past the check and only fail later for want of a database
+            assert_eq!(resp.status() == StatusCode::FORBIDDEN, !permitted, "{} as {}: {}", uri, role, resp.status());
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_unauthenticated_api_gets_401_json_and_pages_redirect() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+        use crate::utils::structs::RoleGuard;
+        use actix_web::test::try_call_service;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .service(
+                    web::scope("/reviews")
+                        .service(register_admix_resource_routes(Box::new(ReviewResource)))
+                        .wrap(RoleGuard { allowed_roles: vec!["admin".to_string()] }),
+                )
+                .service(web::scope("/unguarded").service(register_admix_resource_routes(Box::new(ReviewResource))))
+        ).await;
+
+        for (uri, accept, expected) in [
+            ("/reviews/api", "*/*", StatusCode::UNAUTHORIZED),
+            ("/reviews/list", "text/html,application/xhtml+xml", StatusCode::FOUND),
+            ("/reviews/list", "application/json", StatusCode::UNAUTHORIZED),
+            // Without the role guard the handler's own session check decides
+            ("/unguarded/list", "text/html", StatusCode::FOUND),
+            ("/unguarded/list", "application/json", StatusCode::UNAUTHORIZED),
+        ] {
+            let req = TestRequest::get().uri(uri).insert_header(("Accept", accept)).to_request();
+            let resp = match try_call_service(&app, req).await {
+                Ok(resp) => resp.into_parts().1.map_into_boxed_body(),
+                Err(err) => err.error_response(),
+            };
+            assert_eq!(resp.status(), expected, "{} accepting {}", uri, accept);
+
+            if expected == StatusCode::FOUND {
+                assert_eq!(resp.headers().get("Location").unwrap(), "/adminx/login");
+            } else {
+                assert_eq!(resp.headers().get("Content-Type").unwrap(), "application/json");
+                let body: Value = serde_json::from_slice(&actix_web::body::to_bytes(resp.into_body()).await.unwrap()).unwrap();
+                assert_eq!(body["code"], "UNAUTHORIZED");
+                assert_eq!(body["error"], "Unauthorized: Missing token in session");
             }
-            method => {
-                error!("Unsupported HTTP method: {} for action: {} in resource: {}", method, action.name, resource_name);
+        }
+    }
+
+    struct VaultResource;
+
+    fn seal(_req: HttpRequest, id: web::Path<String>, _body: web::Json<Value>) -> std::pin::Pin<Box<dyn std::future::Future<Output = HttpResponse> + Send>> {
+        let id = id.into_inner();
+        Box::pin(async move { HttpResponse::Ok().json(serde_json::json!({ "sealed": id })) })
+    }
+
+    impl AdmixResource for VaultResource {
+        fn new() -> Self { VaultResource }
+        fn resource_name(&self) -> &'static str { "Vault" }
+        fn base_path(&self) -> &'static str { "vault" }
+        fn collection_name(&self) -> &'static str { "vault" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(VaultResource) }
+        fn requires_reauth(&self, _action: &str) -> bool { true }
+        fn custom_actions(&self) -> Vec<crate::actions::CustomAction> {
+            vec![crate::actions::CustomAction { name: "seal", method: "POST", handler: seal }]
+        }
+
+        fn delete(&self, _req: &HttpRequest, id: String) -> futures::future::BoxFuture<'static, HttpResponse> {
+            Box::pin(async move { HttpResponse::Ok().json(serde_json::json!({ "deleted": id })) })
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_writes_require_recent_reauth() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+        use crate::utils::reauth::stamp_reauth;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                // Stands in for a successful POST /api/auth/reauth, which needs the admin in the database
+                .route("/reauth", web::get().to(|session: Session, config: web::Data<AdminxConfig>| async move {
+                    stamp_reauth(&session, config.clock.as_ref());
+                    HttpResponse::Ok().finish()
+                }))
+                .service(web::scope("/vault").service(register_admix_resource_routes(Box::new(VaultResource))))
+        ).await;
+
+        let cookie = login_cookie(&app, "admin").await;
+
+        let resp = call_service(&app, TestRequest::post().uri("/vault/abc/delete").cookie(cookie.clone()).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let resp = call_service(&app, TestRequest::delete().uri("/vault/api/abc").cookie(cookie.clone()).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let body: Value = serde_json::from_slice(&actix_web::test::read_body(resp).await).unwrap();
+        assert_eq!(body["reauth_required"], true);
+        assert_eq!(body["reauth_url"], "/adminx/api/auth/reauth");
+
+        // Every other write asks too, built-in or custom
+        let item = serde_json::json!({ "name": "x" });
+        let writes = [
+            TestRequest::post().uri("/vault/create").set_form([("name", "x")]),
+            TestRequest::post().uri("/vault/update/abc").set_form([("name", "x")]),
+            TestRequest::post().uri("/vault/abc/restore"),
+            TestRequest::post().uri("/vault/api").set_json(&item),
+            TestRequest::put().uri("/vault/api/abc").set_json(&item),
+            TestRequest::patch().uri("/vault/api/abc").set_json(&item),
+            TestRequest::post().uri("/vault/import").set_payload("[]"),
+            TestRequest::post().uri("/vault/bulk-status").set_json(serde_json::json!({ "ids": ["abc"], "status": "open" })),
+            TestRequest::post().uri("/vault/abc/seal").set_json(&item),
+        ];
+        for request in writes {
+            let request = request.cookie(cookie.clone()).to_request();
+            let target = format!("{} {}", request.method(), request.path());
+            let resp = call_service(&app, request).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN, "{}", target);
+            let body: Value = serde_json::from_slice(&actix_web::test::read_body(resp).await).unwrap();
+            assert_eq!(body["code"], "REAUTH_REQUIRED", "{}", target);
+        }
+
+        let reauth = call_service(&app, TestRequest::get().uri("/reauth").cookie(cookie).to_request()).await;
+        let cookie = reauth.response().cookies().next().unwrap().into_owned();
+
+        let resp = call_service(&app, TestRequest::post().uri("/vault/abc/delete").cookie(cookie.clone()).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        assert_eq!(resp.headers().get("Location").unwrap(), "/adminx/vault/list");
+        let resp = call_service(&app, TestRequest::delete().uri("/vault/api/abc").cookie(cookie.clone()).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = call_service(&app, TestRequest::post().uri("/vault/abc/seal").cookie(cookie).set_json(&item).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    struct NotesResource;
+
+    impl AdmixResource for NotesResource {
+        fn new() -> Self { NotesResource }
+        fn resource_name(&self) -> &'static str { "Notes" }
+        fn base_path(&self) -> &'static str { "notes" }
+        fn collection_name(&self) -> &'static str { "notes" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(NotesResource) }
+        fn write_rate_limit(&self) -> Option<(u32, std::time::Duration)> {
+            Some((2, std::time::Duration::from_secs(60)))
+        }
+
+        fn create(&self, _req: &HttpRequest, _payload: Value) -> futures::future::BoxFuture<'static, HttpResponse> {
+            Box::pin(async { HttpResponse::Created().finish() })
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_writes_over_the_resource_limit_get_429() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/notes").service(register_admix_resource_routes(Box::new(NotesResource))))
+        ).await;
+
+        let create = |ip: &str| TestRequest::post()
+            .uri("/notes/api")
+            .peer_addr(ip.parse().unwrap())
+            .set_json(serde_json::json!({ "title": "hi" }));
+
+        let mut cookies = Vec::new();
+        for id in 1..=4 {
+            cookies.push(login_cookie_as(&app, "admin", &id.to_string()).await);
+        }
+
+        // Per IP, whichever admin is signed in
+        for cookie in &cookies[..2] {
+            assert_eq!(call_service(&app, create("10.0.0.1:4000").cookie(cookie.clone()).to_request()).await.status(), StatusCode::CREATED);
+        }
+        let resp = call_service(&app, create("10.0.0.1:4000").cookie(cookies[2].clone()).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = resp.headers().get("Retry-After").unwrap().to_str().unwrap().parse().unwrap();
+        assert!((59..=60).contains(&retry_after));
+        assert_eq!(call_service(&app, create("10.0.0.2:4000").cookie(cookies[3].clone()).to_request()).await.status(), StatusCode::CREATED);
+
+        // Per admin, whichever IP they come from
+        let cookie = login_cookie_as(&app, "admin", "42").await;
+        for ip in ["10.0.1.1:4000", "10.0.1.2:4000"] {
+            assert_eq!(call_service(&app, create(ip).cookie(cookie.clone()).to_request()).await.status(), StatusCode::CREATED);
+        }
+        let resp = call_service(&app, create("10.0.1.3:4000").cookie(cookie).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    struct TeamResource;
+
+    fn whoami(req: HttpRequest, _id: web::Path<String>, _body: web::Json<Value>) -> std::pin::Pin<Box<dyn std::future::Future<Output = HttpResponse> + Send>> {
+        use actix_web::FromRequest;
+        use crate::utils::auth::AuthenticatedUser;
+
+        let user = AuthenticatedUser::extract(&req).into_inner().ok();
+        Box::pin(async move {
+            match user {
+                Some(user) => HttpResponse::Ok().json(serde_json::json!({ "email": user.email, "role": user.role })),
+                None => HttpResponse::Unauthorized().finish(),
             }
+        })
+    }
+
+    impl AdmixResource for TeamResource {
+        fn new() -> Self { TeamResource }
+        fn resource_name(&self) -> &'static str { "Teams" }
+        fn base_path(&self) -> &'static str { "teams" }
+        fn collection_name(&self) -> &'static str { "teams" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(TeamResource) }
+        fn custom_actions(&self) -> Vec<crate::actions::CustomAction> {
+            vec![crate::actions::CustomAction { name: "whoami", method: "POST", handler: whoami }]
         }
     }
 
-    info!("✅ Successfully registered all routes for resource: {}", resource_name);
-    scope
-}
\ No newline at end of file
+    #[actix_rt::test]
+    async fn test_custom_action_extracts_the_signed_in_user() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/teams").service(register_admix_resource_routes(Box::new(TeamResource))))
+        ).await;
+
+        let whoami = || TestRequest::post().uri("/teams/abc/whoami").set_json(serde_json::json!({}));
+        let resp = call_service(&app, whoami().to_request()).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let cookie = login_cookie(&app, "editor").await;
+        let resp = call_service(&app, whoami().cookie(cookie).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = serde_json::from_slice(&actix_web::test::read_body(resp).await).unwrap();
+        assert_eq!(body, serde_json::json!({ "email": "a@example.com", "role": "editor" }));
+    }
+
+    #[derive(Clone)]
+    struct ProjectResource(Option<Collection<Document>>);
+
+    impl AdmixResource for ProjectResource {
+        fn new() -> Self { ProjectResource(None) }
+        fn resource_name(&self) -> &'static str { "Projects" }
+        fn base_path(&self) -> &'static str { "projects" }
+        fn collection_name(&self) -> &'static str { "projects" }
+        fn collection_override(&self) -> Option<Collection<Document>> { self.0.clone() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(self.clone()) }
+        fn permit_keys(&self) -> Vec<&'static str> { vec!["name"] }
+        fn max_per_page(&self) -> u64 { 2 }
+    }
+
+    /// A throwaway database on the server at `ADMINX_TEST_MONGODB_URL`
+    async fn test_database() -> mongodb::Database {
+        let url = std::env::var("ADMINX_TEST_MONGODB_URL").expect("ADMINX_TEST_MONGODB_URL");
+        let client = mongodb::Client::with_uri_str(&url).await.unwrap();
+        client.database(&format!("adminx_test_{}", uuid::Uuid::new_v4().simple()))
+    }
+
+    #[actix_rt::test]
+    async fn test_view_explains_malformed_ids() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/projects").service(register_admix_resource_routes(Box::new(ProjectResource(None)))))
+        ).await;
+
+        let cookie = login_cookie(&app, "admin").await;
+
+        for uri in ["/projects/view/not-an-id", "/projects/edit/not-an-id"] {
+            let resp = call_service(&app, TestRequest::get().uri(uri).cookie(cookie.clone()).to_request()).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+            let html = String::from_utf8(actix_web::test::read_body(resp).await.to_vec()).unwrap();
+            assert!(html.contains("Invalid Record ID"), "{}", uri);
+            assert!(html.contains("Go to Dashboard"));
+            assert!(!html.contains("Invalid ObjectId"));
+        }
+    }
+
+    #[actix_rt::test]
+    #[ignore = "needs a MongoDB at ADMINX_TEST_MONGODB_URL"]
+    async fn test_view_explains_missing_records() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let db = test_database().await;
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/projects").service(register_admix_resource_routes(Box::new(ProjectResource(Some(db.collection("projects")))))))
+        ).await;
+        let cookie = login_cookie(&app, "admin").await;
+
+        let id = mongodb::bson::oid::ObjectId::new().to_hex();
+        let resp = call_service(&app, TestRequest::get().uri(&format!("/projects/view/{}", id)).cookie(cookie).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let html = String::from_utf8(actix_web::test::read_body(resp).await.to_vec()).unwrap();
+        assert!(html.contains("Record Not Found"));
+        assert!(html.contains(&id));
+
+        db.drop(None).await.unwrap();
+    }
+
+    #[actix_rt::test]
+    #[ignore = "needs a MongoDB at ADMINX_TEST_MONGODB_URL"]
+    async fn test_api_list_ignores_sorts_outside_sortable_fields() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+        use mongodb::bson::{doc, DateTime as BsonDateTime};
+
+        let db = test_database().await;
+        let collection = db.collection::<Document>("projects");
+        collection.insert_many(vec![
+            doc! { "name": "a", "created_at": BsonDateTime::from_millis(1_000) },
+            doc! { "name": "b", "created_at": BsonDateTime::from_millis(2_000) },
+        ], None).await.unwrap();
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/projects").service(register_admix_resource_routes(Box::new(ProjectResource(Some(collection))))))
+        ).await;
+        let cookie = login_cookie(&app, "admin").await;
+
+        // `name` is not sortable, so the default newest-first order stands
+        for uri in ["/projects/api", "/projects/api?sort=name"] {
+            let resp = call_service(&app, TestRequest::get().uri(uri).cookie(cookie.clone()).to_request()).await;
+            let body: Value = serde_json::from_slice(&actix_web::test::read_body(resp).await).unwrap();
+            let names: Vec<&str> = body["data"].as_array().unwrap().iter().map(|d| d["name"].as_str().unwrap()).collect();
+            assert_eq!(names, ["b", "a"], "{}", uri);
+        }
+
+        db.drop(None).await.unwrap();
+    }
+
+    #[actix_rt::test]
+    #[ignore = "needs a MongoDB at ADMINX_TEST_MONGODB_URL"]
+    async fn test_api_list_caps_per_page_at_max_per_page() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+        use mongodb::bson::doc;
+
+        let db = test_database().await;
+        let collection = db.collection::<Document>("projects");
+        collection.insert_many(vec![doc! { "name": "a" }, doc! { "name": "b" }, doc! { "name": "c" }], None).await.unwrap();
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/projects").service(register_admix_resource_routes(Box::new(ProjectResource(Some(collection))))))
+        ).await;
+        let cookie = login_cookie(&app, "admin").await;
+
+        // per_page=0 is no "limit(0)" escape hatch, and large sizes stop at max_per_page()
+        for uri in ["/projects/api", "/projects/api?per_page=0", "/projects/api?per_page=1000000"] {
+            let resp = call_service(&app, TestRequest::get().uri(uri).cookie(cookie.clone()).to_request()).await;
+            let body: Value = serde_json::from_slice(&actix_web::test::read_body(resp).await).unwrap();
+            assert_eq!(body["data"].as_array().unwrap().len(), 2, "{}", uri);
+            assert_eq!(body["per_page"], 2, "{}", uri);
+            assert_eq!(body["total_pages"], 2, "{}", uri);
+        }
+
+        db.drop(None).await.unwrap();
+    }
+
+    struct MilestoneResource;
+
+    impl AdmixResource for MilestoneResource {
+        fn new() -> Self { MilestoneResource }
+        fn resource_name(&self) -> &'static str { "Milestones" }
+        fn base_path(&self) -> &'static str { "milestones" }
+        fn collection_name(&self) -> &'static str { "milestones" }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(MilestoneResource) }
+        fn permit_keys(&self) -> Vec<&'static str> { vec!["title", "due_on"] }
+        fn list_structure(&self) -> Option<Value> {
+            Some(serde_json::json!({ "columns": [{ "field": "title" }, { "field": "due_on" }] }))
+        }
+        fn empty_state_message(&self) -> Option<String> {
+            Some("No milestones planned yet.".to_string())
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_failed_list_keeps_resource_columns_and_empty_state() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/milestones").service(register_admix_resource_routes(Box::new(MilestoneResource))))
+        ).await;
+
+        let cookie = login_cookie(&app, "admin").await;
+
+        // No database is configured, so loading the rows fails
+        let resp = call_service(&app, TestRequest::get().uri("/milestones/list").cookie(cookie).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let html = String::from_utf8(actix_web::test::read_body(resp).await.to_vec()).unwrap();
+        assert!(html.contains("Due On"));
+        assert!(!html.contains("Email"));
+        assert!(html.contains("No milestones planned yet."));
+        assert!(!html.contains("No milestones found."));
+    }
+
+    struct WebhookResource;
+
+    impl AdmixResource for WebhookResource {
+        fn new() -> Self { WebhookResource }
+        fn resource_name(&self) -> &'static str { "Webhooks" }
+        fn base_path(&self) -> &'static str { "webhooks" }
+        fn collection_name(&self) -> &'static str { "webhooks" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(WebhookResource) }
+        fn api_only(&self) -> bool { true }
+        fn requires_reauth(&self, action: &str) -> bool { action == "delete" }
+        fn write_rate_limit(&self) -> Option<(u32, std::time::Duration)> {
+            Some((2, std::time::Duration::from_secs(60)))
+        }
+
+        fn list(&self, _req: &HttpRequest, query: String) -> futures::future::BoxFuture<'static, HttpResponse> {
+            Box::pin(async move { HttpResponse::Ok().json(serde_json::json!({ "data": [], "query": query })) })
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_api_only_resource_serves_no_html() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/webhooks").service(register_admix_resource_routes(Box::new(WebhookResource))))
+        ).await;
+        let cookie = login_cookie(&app, "admin").await;
+
+        for uri in ["/webhooks/list", "/webhooks/new", "/webhooks/view/abc", "/webhooks/edit/abc"] {
+            let resp = call_service(&app, TestRequest::get().uri(uri).to_request()).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{}", uri);
+        }
+
+        let resp = call_service(&app, TestRequest::get().uri("/webhooks/api?page=2").cookie(cookie).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = serde_json::from_slice(&actix_web::test::read_body(resp).await).unwrap();
+        assert_eq!(body["query"], "page=2");
+    }
+
+    #[actix_rt::test]
+    async fn test_api_only_resource_keeps_auth_reauth_and_rate_limit() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/webhooks").service(register_admix_resource_routes(Box::new(WebhookResource))))
+        ).await;
+        let peer = "10.0.3.1:4000".parse().unwrap();
+
+        let resp = call_service(&app, TestRequest::delete().uri("/webhooks/api/abc").peer_addr(peer).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let cookie = login_cookie(&app, "admin").await;
+        let resp = call_service(&app, TestRequest::delete().uri("/webhooks/api/abc").peer_addr(peer).cookie(cookie.clone()).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let body: Value = serde_json::from_slice(&actix_web::test::read_body(resp).await).unwrap();
+        assert_eq!(body["reauth_required"], true);
+
+        // The resource's write limit wraps the API-only routes too
+        let resp = call_service(&app, TestRequest::post().uri("/webhooks/api/abc/restore").peer_addr(peer).cookie(cookie).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[actix_rt::test]
+    async fn test_write_rate_limit_follows_the_config_clock() {
+        use crate::utils::clock::FixedClock;
+        use crate::utils::jwt::tests::test_config;
+
+        let clock = Arc::new(FixedClock::new(chrono::Utc::now()));
+        let config = test_config().with_clock(clock.clone());
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .service(web::scope("/webhooks").service(register_admix_resource_routes(Box::new(WebhookResource))))
+        ).await;
+        let peer = "10.0.3.2:4000".parse().unwrap();
+        let delete = || TestRequest::delete().uri("/webhooks/api/abc").peer_addr(peer).to_request();
+
+        assert_eq!(call_service(&app, delete()).await.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(call_service(&app, delete()).await.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(call_service(&app, delete()).await.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        clock.advance(chrono::Duration::seconds(61));
+        assert_eq!(call_service(&app, delete()).await.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[derive(Clone)]
+    struct OrderResource(Option<Collection<Document>>);
+
+    #[async_trait::async_trait]
+    impl AdmixResource for OrderResource {
+        fn new() -> Self { OrderResource(None) }
+        fn resource_name(&self) -> &'static str { "Orders" }
+        fn base_path(&self) -> &'static str { "orders" }
+        fn collection_name(&self) -> &'static str { "orders" }
+        fn collection_override(&self) -> Option<Collection<Document>> { self.0.clone() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(self.clone()) }
+        fn permit_keys(&self) -> Vec<&'static str> { vec!["customer"] }
+
+        async fn view_extras(&self, id: &str, _claims: &crate::utils::structs::Claims) -> Value {
+            use futures::TryStreamExt;
+
+            let orders = self.get_collection();
+            let line_items = orders.client().database(&orders.namespace().db).collection::<Document>("line_items");
+            let items: Vec<Document> = line_items
+                .find(mongodb::bson::doc! { "order_id": id }, None).await.unwrap()
+                .try_collect().await.unwrap();
+            let items: Vec<Value> = items.iter()
+                .map(|item| serde_json::json!({ "sku": item.get_str("sku").unwrap(), "quantity": item.get_i32("quantity").unwrap() }))
+                .collect();
+            serde_json::json!({ "line_items": items })
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_view_page_renders_view_extras() {
+        use crate::helpers::template_helper::ADMINX_TEMPLATES;
+        use crate::utils::structs::Claims;
+
+        let claims = Claims {
+            sub: "1".to_string(),
+            exp: 0,
+            email: "a@example.com".to_string(),
+            role: "admin".to_string(),
+            roles: vec![],
+            iss: "adminx".to_string(),
+            aud: "adminx".to_string(),
+        };
+        let mut ctx = create_base_template_context("Orders", "orders", &claims, "view");
+        ctx.insert("allowed_actions", &Vec::<String>::new());
+        ctx.insert("record", &serde_json::json!({ "id": "abc", "customer": "Ada" }));
+        ctx.insert("extras", &serde_json::json!({ "line_items": [{ "sku": "<b>WIDGET-1</b>", "quantity": 3 }], "notes": [] }));
+        let html = ADMINX_TEMPLATES.render("view.html.tera", &ctx).unwrap();
+        assert!(html.contains("Line Items") && html.contains("Quantity"));
+        assert!(html.contains("&lt;b&gt;WIDGET-1&lt;&#x2F;b&gt;"));
+    }
+
+    #[actix_rt::test]
+    #[ignore = "needs a MongoDB at ADMINX_TEST_MONGODB_URL"]
+    async fn test_view_page_loads_view_extras() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let db = test_database().await;
+
+        let order_id = db.collection::<Document>("orders")
+            .insert_one(mongodb::bson::doc! { "customer": "Ada" }, None).await.unwrap()
+            .inserted_id.as_object_id().unwrap().to_hex();
+        db.collection::<Document>("line_items").insert_many([
+            mongodb::bson::doc! { "order_id": &order_id, "sku": "WIDGET-1", "quantity": 3 },
+            mongodb::bson::doc! { "order_id": &order_id, "sku": "GADGET-7", "quantity": 1 },
+            mongodb::bson::doc! { "order_id": "someone-else", "sku": "OTHER-9", "quantity": 2 },
+        ], None).await.unwrap();
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/orders").service(register_admix_resource_routes(Box::new(OrderResource(Some(db.collection("orders")))))))
+        ).await;
+
+        let cookie = login_cookie(&app, "admin").await;
+
+        let resp = call_service(&app, TestRequest::get().uri(&format!("/orders/view/{}", order_id)).cookie(cookie).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let html = String::from_utf8(actix_web::test::read_body(resp).await.to_vec()).unwrap();
+        assert!(html.contains("Line Items"));
+        assert!(html.contains("WIDGET-1") && html.contains("GADGET-7"));
+        assert!(!html.contains("OTHER-9"));
+
+        db.drop(None).await.unwrap();
+    }
+
+    struct SnippetResource;
+
+    impl AdmixResource for SnippetResource {
+        fn new() -> Self { SnippetResource }
+        fn resource_name(&self) -> &'static str { "Snippets" }
+        fn base_path(&self) -> &'static str { "snippets" }
+        fn collection_name(&self) -> &'static str { "snippets" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(SnippetResource) }
+        fn max_json_body_bytes(&self) -> usize { 64 }
+
+        fn create(&self, _req: &HttpRequest, payload: Value) -> futures::future::BoxFuture<'static, HttpResponse> {
+            Box::pin(async move { HttpResponse::Created().json(payload) })
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_json_body_limit_is_per_resource() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/snippets").service(register_admix_resource_routes(Box::new(SnippetResource))))
+        ).await;
+        let cookie = login_cookie(&app, "admin").await;
+
+        let small = serde_json::json!({ "code": "x".repeat(20) });
+        let resp = call_service(&app, TestRequest::post().uri("/snippets/api").cookie(cookie.clone()).set_json(&small).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let large = serde_json::json!({ "code": "x".repeat(100) });
+        let resp = call_service(&app, TestRequest::post().uri("/snippets/api").cookie(cookie).set_json(&large).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body = String::from_utf8(actix_web::test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains("JSON body exceeds the 64 byte limit"), "{}", body);
+    }
+
+    fn tickets() -> TestResource {
+        TestResource::named("Tickets", "tickets").permit(&["subject", "status"]).statuses(&["open", "closed"])
+    }
+
+    #[actix_rt::test]
+    async fn test_bulk_status_rejects_unknown_values_and_shows_toolbar() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::helpers::template_helper::ADMINX_TEMPLATES;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/tickets").service(register_admix_resource_routes(Box::new(tickets()))))
+        ).await;
+
+        let resp = call_service(&app, TestRequest::post().uri("/tickets/bulk-status")
+            .set_json(serde_json::json!({ "ids": [], "status": "open" })).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+
+        let cookie = login_cookie(&app, "admin").await;
+
+        let id = mongodb::bson::oid::ObjectId::new().to_hex();
+        let resp = call_service(&app, TestRequest::post().uri("/tickets/bulk-status").cookie(cookie)
+            .set_json(serde_json::json!({ "ids": [id], "status": "deleted" })).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body: Value = serde_json::from_slice(&actix_web::test::read_body(resp).await).unwrap();
+        assert_eq!(body["error"], "Bad Request: Invalid status 'deleted', expected one of: open, closed");
+
+        let claims = crate::utils::structs::Claims {
+            sub: "1".to_string(),
+            exp: 0,
+            email: "a@example.com".to_string(),
+            role: "admin".to_string(),
+            roles: vec![],
+            iss: "adminx".to_string(),
+            aud: "adminx".to_string(),
+        };
+        let mut ctx = create_base_template_context("Tickets", "tickets", &claims, "list");
+        ctx.insert("allowed_actions", &enabled_action_names(&tickets()));
+        ctx.insert("headers", &vec!["id", "subject"]);
+        ctx.insert("rows", &vec![serde_json::json!({ "id": "abc", "subject": "Login fails" })]);
+        ctx.insert("pagination", &serde_json::json!({ "current": 1, "total": 1, "prev": null, "next": null, "filter_params": "" }));
+        ctx.insert("filters", &Vec::<String>::new());
+        ctx.insert("current_filters", &serde_json::json!({}));
+        ctx.insert("has_active_filters", &false);
+
+        let html = ADMINX_TEMPLATES.render("list.html.tera", &ctx).unwrap();
+        assert!(!html.contains(r#"id="bulk-status-toolbar""#));
+
+        ctx.insert("bulk_status_values", &tickets().status_values().unwrap());
+        let html = ADMINX_TEMPLATES.render("list.html.tera", &ctx).unwrap();
+        assert!(html.contains(r#"<option value="closed">Closed</option>"#));
+        assert!(html.contains(r#"class="bulk-select" value="abc""#));
+    }
+
+    fn products() -> TestResource {
+        TestResource::named("Products", "products")
+            .permit(&["sku", "stock"])
+            .form(serde_json::json!({
+                "groups": [{
+                    "title": "Details",
+                    "fields": [
+                        { "name": "sku", "label": "SKU", "field_type": "text", "pattern": "[A-Z]{3}-\\d{4}" },
+                        { "name": "stock", "label": "Stock", "field_type": "number", "min": 0 }
+                    ]
+                }]
+            }))
+    }
+
+    #[actix_rt::test]
+    async fn test_field_pattern_is_rendered_and_enforced_server_side() {
+        use crate::helpers::template_helper::ADMINX_TEMPLATES;
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(web::scope("/products").service(register_admix_resource_routes(Box::new(products()))))
+        ).await;
+        let cookie = login_cookie(&app, "admin").await;
+
+        let invalid = serde_json::json!({ "sku": "abc-12", "stock": -1 });
+        let resp = call_service(&app, TestRequest::post().uri("/products/api").cookie(cookie.clone()).set_json(&invalid).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = serde_json::from_slice(&actix_web::test::read_body(resp).await).unwrap();
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert_eq!(body["fields"], serde_json::json!({
+            "sku": "does not match the required format",
+            "stock": "must be at least 0",
+        }));
+
+        let resp = call_service(&app, TestRequest::patch().uri(&format!("/products/api/{}", mongodb::bson::oid::ObjectId::new().to_hex())).cookie(cookie.clone()).set_json(serde_json::json!({ "sku": "nope" })).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        // A matching value passes validation and only then needs the (missing) database
+        let valid = serde_json::json!({ "sku": "ABC-1234", "stock": 0 });
+        let resp = call_service(&app, TestRequest::post().uri("/products/api").cookie(cookie).set_json(&valid).to_request()).await;
+        assert_ne!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let claims = crate::utils::structs::Claims {
+            sub: "1".to_string(),
+            exp: 0,
+            email: "a@example.com".to_string(),
+            role: "admin".to_string(),
+            roles: vec![],
+            iss: "adminx".to_string(),
+            aud: "adminx".to_string(),
+        };
+        let mut ctx = create_base_template_context("Products", "products", &claims, "create");
+        ctx.insert("form_structure", &products().form_structure().unwrap());
+        ctx.insert("form", &products().form_structure().unwrap());
+        ctx.insert("is_edit_mode", &false);
+        ctx.insert("idempotency_key", "key");
+        ctx.insert("supports_upload", &false);
+        let html = ADMINX_TEMPLATES.render("new.html.tera", &ctx).unwrap();
+        assert!(html.contains(r#"pattern="[A-Z]{3}-\d{4}""#), "{}", html);
+        assert!(html.contains(r#"min="0""#));
+    }
+
+    #[test]
+    fn test_edit_form_preselects_native_bool_and_number_values() {
+        use crate::helpers::template_helper::ADMINX_TEMPLATES;
+
+        let form = serde_json::json!({
+            "groups": [{
+                "title": "Details",
+                "fields": [
+                    { "name": "priority", "label": "Priority", "field_type": "select",
+                      "options": [{ "value": "1", "label": "Low" }, { "value": 2, "label": "High" }] },
+                    { "name": "active", "label": "Active", "field_type": "boolean" },
+                    { "name": "featured", "label": "Featured", "field_type": "checkbox" }
+                ]
+            }]
+        });
+        let claims = crate::utils::structs::Claims {
+            sub: "1".to_string(),
+            exp: 0,
+            email: "a@example.com".to_string(),
+            role: "admin".to_string(),
+            roles: vec![],
+            iss: "adminx".to_string(),
+            aud: "adminx".to_string(),
+        };
+        let render = |record: Value| {
+            let mut ctx = create_base_template_context("Products", "products", &claims, "edit");
+            ctx.insert("form_structure", &form);
+            ctx.insert("form", &form);
+            ctx.insert("item_id", "abc");
+            ctx.insert("is_edit_mode", &true);
+            ctx.insert("supports_upload", &false);
+            ctx.insert("record", &record);
+            ADMINX_TEMPLATES.render("edit.html.tera", &ctx).unwrap()
+        };
+        // The markup of the tag starting at `marker`
+        let tag = |html: &str, marker: &str| {
+            let start = html.find(marker).unwrap_or_else(|| panic!("{} missing", marker));
+            html[start..start + html[start..].find('>').unwrap()].to_string()
+        };
+
+        let html = render(serde_json::json!({ "priority": 1, "active": false, "featured": true }));
+        assert!(tag(&html, r#"<option value="1""#).contains("selected"));
+        assert!(!tag(&html, r#"<option value="2""#).contains("selected"));
+        assert!(tag(&html, r#"id="active_false""#).contains("checked"));
+        assert!(!tag(&html, r#"id="active_true""#).contains("checked"));
+        assert!(tag(&html, r#"id="featured""#).contains("checked"));
+
+        let html = render(serde_json::json!({ "priority": 2, "active": true, "featured": false }));
+        assert!(tag(&html, r#"<option value="2""#).contains("selected"));
+        assert!(tag(&html, r#"id="active_true""#).contains("checked"));
+        assert!(!tag(&html, r#"id="active_false""#).contains("checked"));
+        assert!(!tag(&html, r#"id="featured""#).contains("checked"));
+
+        // Nothing stored, nothing preselected
+        let html = render(serde_json::json!({}));
+        assert!(!tag(&html, r#"<option value="1""#).contains("selected"));
+        assert!(!tag(&html, r#"id="active_false""#).contains("checked"));
+    }
+}
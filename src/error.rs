@@ -3,6 +3,9 @@
 use actix_web::{HttpResponse, ResponseError};
 use derive_more::Display;
 use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::utils::structs::ValidationErrors;
 
 #[derive(Debug, Display)]
 pub enum AdminxError {
@@ -10,27 +13,193 @@ pub enum AdminxError {
     NotFound,
     #[display(fmt = "Bad Request: {}", _0)]
     BadRequest(String),
+    /// Per-field messages, keyed by field name
+    #[display(fmt = "Validation failed: {}", "describe_fields(_0)")]
+    Validation(BTreeMap<String, String>),
+    #[display(fmt = "Unauthorized: {}", _0)]
+    Unauthorized(String),
+    #[display(fmt = "Forbidden: {}", _0)]
+    Forbidden(String),
+    #[display(fmt = "Conflict: {}", _0)]
+    Conflict(String),
+    /// A create clashed on `fields` with a soft-deleted record that can be restored instead
+    #[display(fmt = "Conflict: a deleted {} record already uses this {}; restore it instead", resource, fields)]
+    SoftDeletedConflict {
+        resource: String,
+        fields: String,
+        restore_id: String,
+        restore_url: String,
+    },
+    #[display(fmt = "Payload Too Large: upload exceeds {} bytes", _0)]
+    PayloadTooLarge(usize),
+    #[display(fmt = "Too Many Requests: retry in {} seconds", _0)]
+    TooManyRequests(u64),
+    #[display(fmt = "Gateway Timeout: the query took too long, narrow the filters and try again")]
+    Timeout,
+    /// A resource's `database_name()` was never registered with `initiate_named_database`
+    #[display(fmt = "Database '{}' is not registered", _0)]
+    UnknownDatabase(String),
     #[display(fmt = "Internal Server Error")]
     InternalError,
 }
 
 impl std::error::Error for AdminxError {}
 
+fn describe_fields(fields: &BTreeMap<String, String>) -> String {
+    fields.iter().map(|(field, message)| format!("{} {}", field, message)).collect::<Vec<_>>().join(", ")
+}
+
+impl AdminxError {
+    /// Stable, machine-readable code serialized as `code` in error bodies
+    pub fn code(&self) -> &'static str {
+        match self {
+            AdminxError::NotFound => "NOT_FOUND",
+            AdminxError::BadRequest(_) => "BAD_REQUEST",
+            AdminxError::Validation(_) => "VALIDATION_FAILED",
+            AdminxError::Unauthorized(_) => "UNAUTHORIZED",
+            AdminxError::Forbidden(_) => "FORBIDDEN",
+            AdminxError::Conflict(_) | AdminxError::SoftDeletedConflict { .. } => "CONFLICT",
+            AdminxError::PayloadTooLarge(_) => "PAYLOAD_TOO_LARGE",
+            AdminxError::TooManyRequests(_) => "RATE_LIMITED",
+            AdminxError::Timeout => "TIMEOUT",
+            AdminxError::UnknownDatabase(_) => "UNKNOWN_DATABASE",
+            AdminxError::InternalError => "INTERNAL_ERROR",
+        }
+    }
+}
+
+impl From<ValidationErrors> for AdminxError {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut fields = BTreeMap::new();
+        for error in errors.errors {
+            fields.entry(error.field).or_insert(error.message);
+        }
+        AdminxError::Validation(fields)
+    }
+}
+
 #[derive(Serialize)]
-struct ErrorResponse {
+struct ErrorResponse<'a> {
+    code: &'static str,
     error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<&'a BTreeMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    restore_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    restore_url: Option<&'a str>,
 }
 
 impl ResponseError for AdminxError {
-    fn error_response(&self) -> HttpResponse {
-        let status = match self {
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        match self {
             AdminxError::NotFound => actix_web::http::StatusCode::NOT_FOUND,
             AdminxError::BadRequest(_) => actix_web::http::StatusCode::BAD_REQUEST,
+            AdminxError::Validation(_) => actix_web::http::StatusCode::UNPROCESSABLE_ENTITY,
+            AdminxError::Unauthorized(_) => actix_web::http::StatusCode::UNAUTHORIZED,
+            AdminxError::Forbidden(_) => actix_web::http::StatusCode::FORBIDDEN,
+            AdminxError::Conflict(_) | AdminxError::SoftDeletedConflict { .. } => actix_web::http::StatusCode::CONFLICT,
+            AdminxError::PayloadTooLarge(_) => actix_web::http::StatusCode::PAYLOAD_TOO_LARGE,
+            AdminxError::TooManyRequests(_) => actix_web::http::StatusCode::TOO_MANY_REQUESTS,
+            AdminxError::Timeout => actix_web::http::StatusCode::GATEWAY_TIMEOUT,
+            AdminxError::UnknownDatabase(_) => actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
             AdminxError::InternalError => actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
-        };
+        }
+    }
 
-        HttpResponse::build(status).json(ErrorResponse {
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status_code());
+        if let AdminxError::TooManyRequests(retry_after) = self {
+            response.insert_header((actix_web::http::header::RETRY_AFTER, retry_after.to_string()));
+        }
+        response.json(ErrorResponse {
+            code: self.code(),
             error: self.to_string(),
+            fields: match self {
+                AdminxError::Validation(fields) => Some(fields),
+                _ => None,
+            },
+            restore_id: match self {
+                AdminxError::SoftDeletedConflict { restore_id, .. } => Some(restore_id),
+                _ => None,
+            },
+            restore_url: match self {
+                AdminxError::SoftDeletedConflict { restore_url, .. } => Some(restore_url),
+                _ => None,
+            },
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    async fn body(error: AdminxError) -> (u16, Value) {
+        let response = error.error_response();
+        let status = response.status().as_u16();
+        let bytes = actix_web::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[actix_rt::test]
+    async fn each_variant_serializes_its_code() {
+        let cases = vec![
+            (AdminxError::NotFound, 404, "NOT_FOUND"),
+            (AdminxError::BadRequest("Missing status".into()), 400, "BAD_REQUEST"),
+            (AdminxError::Validation(BTreeMap::new()), 422, "VALIDATION_FAILED"),
+            (AdminxError::Unauthorized("Session expired".into()), 401, "UNAUTHORIZED"),
+            (AdminxError::Forbidden("Insufficient permissions".into()), 403, "FORBIDDEN"),
+            (AdminxError::Conflict("Email taken".into()), 409, "CONFLICT"),
+            (AdminxError::PayloadTooLarge(1024), 413, "PAYLOAD_TOO_LARGE"),
+            (AdminxError::TooManyRequests(30), 429, "RATE_LIMITED"),
+            (AdminxError::Timeout, 504, "TIMEOUT"),
+            (AdminxError::UnknownDatabase("tenants".into()), 500, "UNKNOWN_DATABASE"),
+            (AdminxError::InternalError, 500, "INTERNAL_ERROR"),
+        ];
+        for (error, status, code) in cases {
+            let message = error.to_string();
+            let (actual_status, body) = body(error).await;
+            assert_eq!(actual_status, status, "{}", code);
+            assert_eq!(body["code"], code);
+            assert_eq!(body["error"], message);
+            if code != "VALIDATION_FAILED" {
+                assert!(body.get("fields").is_none(), "{}", code);
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn validation_errors_carry_their_fields() {
+        let mut errors = ValidationErrors::new();
+        errors.add("email", "is not a valid email");
+        errors.add("age", "must be at least 18");
+        errors.add("email", "is already taken");
+
+        let (status, body) = body(errors.into()).await;
+        assert_eq!(status, 422);
+        assert_eq!(body, json!({
+            "code": "VALIDATION_FAILED",
+            "error": "Validation failed: age must be at least 18, email is not a valid email",
+            "fields": { "age": "must be at least 18", "email": "is not a valid email" },
+        }));
+    }
+
+    #[actix_rt::test]
+    async fn soft_deleted_conflicts_point_at_the_restore() {
+        let (status, body) = body(AdminxError::SoftDeletedConflict {
+            resource: "Members".into(),
+            fields: "email".into(),
+            restore_id: "abc".into(),
+            restore_url: "/adminx/members/abc/restore".into(),
+        }).await;
+        assert_eq!(status, 409);
+        assert_eq!(body, json!({
+            "code": "CONFLICT",
+            "error": "Conflict: a deleted Members record already uses this email; restore it instead",
+            "restore_id": "abc",
+            "restore_url": "/adminx/members/abc/restore",
+        }));
+    }
+}
@@ -1,13 +1,26 @@
-use mongodb::bson::{doc, Document};
+use mongodb::bson::{doc, Bson, Document};
+use crate::pagination::page_offset;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FilterOptions {
     pub filter: Document,
     pub sort: Option<Document>,
     pub skip: u64,
     pub limit: u64,
+    /// Whether the query set `page` or `per_page`; `skip` and `limit` are the defaults otherwise
+    pub paged: bool,
 }
 
+/// Parse a JSON API query string into filter, sort and pagination options.
+///
+/// Plain `field=value` pairs filter on equality. A field may carry an operator suffix:
+///
+/// - `field__gte=18` / `field__lte=65` → `$gte` / `$lte`
+/// - `field__ne=archived` → `$ne`
+/// - `field__in=a,b,c` → `$in` (values split on commas)
+///
+/// Operator values that parse as numbers or booleans are compared as such.
+/// Several operators on the same field are combined, e.g. `age__gte=18&age__lte=65`.
 pub fn parse_query(query: &str) -> FilterOptions {
     let params: Vec<(&str, &str)> = querystring::querify(query);
 
@@ -15,30 +28,132 @@ pub fn parse_query(query: &str) -> FilterOptions {
     let mut sort_doc = None;
     let mut page = 1u64;
     let mut per_page = 25u64;
+    let mut paged = false;
 
     for (key, value) in params {
         match key {
-            "page" => page = value.parse().unwrap_or(1),
-            "per_page" => per_page = value.parse().unwrap_or(25),
+            "page" => {
+                page = value.parse().unwrap_or(1);
+                paged = true;
+            }
+            "per_page" => {
+                per_page = value.parse().unwrap_or(25);
+                paged = true;
+            }
             "sort" => {
                 let direction = if value.starts_with('-') { -1 } else { 1 };
                 let field = value.trim_start_matches('-').to_string();
                 sort_doc = Some(doc! { field: direction });
             }
             _ => {
-                if !value.is_empty() {
-                    filter_doc.insert(key, value);
+                if value.is_empty() {
+                    continue;
+                }
+                match parse_operator(key) {
+                    Some((field, operator)) => {
+                        let operand = if operator == "$in" {
+                            Bson::Array(value.split(',').map(|v| parse_scalar(v.trim())).collect())
+                        } else {
+                            parse_scalar(value)
+                        };
+                        match filter_doc.get_mut(field) {
+                            Some(Bson::Document(existing)) => {
+                                existing.insert(operator, operand);
+                            }
+                            _ => {
+                                filter_doc.insert(field, doc! { operator: operand });
+                            }
+                        }
+                    }
+                    None => {
+                        filter_doc.insert(key, value);
+                    }
                 }
             }
         }
     }
 
-    let skip = (page - 1) * per_page;
+    let skip = page_offset(page, per_page);
 
     FilterOptions {
         filter: filter_doc,
         sort: sort_doc,
         skip,
         limit: per_page,
+        paged,
+    }
+}
+
+// Split `field__op` into the field and its Mongo operator
+fn parse_operator(key: &str) -> Option<(&str, &'static str)> {
+    let (field, suffix) = key.rsplit_once("__")?;
+    let operator = match suffix {
+        "gte" => "$gte",
+        "lte" => "$lte",
+        "ne" => "$ne",
+        "in" => "$in",
+        _ => return None,
+    };
+    if field.is_empty() {
+        return None;
+    }
+    Some((field, operator))
+}
+
+fn parse_scalar(value: &str) -> Bson {
+    if let Ok(i) = value.parse::<i64>() {
+        Bson::Int64(i)
+    } else if let Ok(f) = value.parse::<f64>() {
+        Bson::Double(f)
+    } else if let Ok(b) = value.parse::<bool>() {
+        Bson::Boolean(b)
+    } else {
+        Bson::String(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_equality() {
+        assert_eq!(parse_query("status=active").filter, doc! { "status": "active" });
+    }
+
+    #[test]
+    fn test_gte_operator() {
+        assert_eq!(parse_query("age__gte=18").filter, doc! { "age": { "$gte": 18_i64 } });
+    }
+
+    #[test]
+    fn test_lte_operator() {
+        assert_eq!(parse_query("price__lte=9.5").filter, doc! { "price": { "$lte": 9.5 } });
+    }
+
+    #[test]
+    fn test_ne_operator() {
+        assert_eq!(parse_query("status__ne=archived").filter, doc! { "status": { "$ne": "archived" } });
+    }
+
+    #[test]
+    fn test_in_operator_splits_on_commas() {
+        assert_eq!(parse_query("status__in=a,b").filter, doc! { "status": { "$in": ["a", "b"] } });
+    }
+
+    #[test]
+    fn test_operators_on_same_field_are_combined() {
+        let opts = parse_query("age__gte=18&age__lte=65&page=2&per_page=10");
+
+        assert_eq!(opts.filter, doc! { "age": { "$gte": 18_i64, "$lte": 65_i64 } });
+        assert_eq!(opts.skip, 10);
+        assert_eq!(opts.limit, 10);
+        assert!(opts.paged);
+        assert!(!parse_query("age__gte=18").paged);
+    }
+
+    #[test]
+    fn test_unknown_suffix_is_plain_equality() {
+        assert_eq!(parse_query("first__name=x").filter, doc! { "first__name": "x" });
     }
 }
@@ -4,11 +4,18 @@ use actix_session::Session;
 use tera::Context;
 use crate::configs::initializer::AdminxConfig;
 use crate::utils::auth::extract_claims_from_session;
+use crate::utils::mount::admin_path;
 use crate::registry::get_registered_menus;
+use crate::helpers::resource_helper::build_breadcrumbs;
+use crate::models::adminx_model::AdminxUser;
+use crate::utils::password::{describe_violations, validate_password, PasswordPolicy};
+use crate::utils::structs::{PasswordChangeForm, ProfileEditForm};
+use std::collections::HashMap;
 
 pub async fn create_base_template_context_with_auth(
     resource_name: &str,
     base_path: &str,
+    action: &str,
     session: &Session,
     config: &AdminxConfig,
 ) -> Result<Context, HttpResponse> {
@@ -16,16 +23,173 @@ pub async fn create_base_template_context_with_auth(
         Ok(claims) => {
             let mut ctx = Context::new();
             ctx.insert("resource_name", resource_name);
-            ctx.insert("base_path", &format!("/adminx/{}", base_path));
+            ctx.insert("base_path", &admin_path(&format!("/{}", base_path)));
+            ctx.insert("breadcrumbs", &build_breadcrumbs(resource_name, base_path, action));
             ctx.insert("menus", &get_registered_menus());
             ctx.insert("current_user", &claims);
             ctx.insert("is_authenticated", &true);
+            config.branding.insert_into(&mut ctx);
             Ok(ctx)
         }
         Err(_) => {
             Err(HttpResponse::Found()
-                .append_header(("Location", "/adminx/login"))
+                .append_header(("Location", admin_path("/login")))
                 .finish())
         }
     }
-}
\ No newline at end of file
+}
+
+/// Check a profile password change against the stored user and the password policy.
+/// Errors are keyed by form field name so they can be shown next to the inputs.
+pub fn validate_password_change(
+    user: &AdminxUser,
+    form: &PasswordChangeForm,
+    policy: &PasswordPolicy,
+) -> Result<(), HashMap<&'static str, String>> {
+    let mut errors = HashMap::new();
+
+    if !user.verify_password(&form.current_password) {
+        errors.insert("current_password", "Current password is incorrect".to_string());
+    }
+
+    if let Err(violations) = validate_password(&form.new_password, policy) {
+        errors.insert("new_password", describe_violations(&violations));
+    } else if form.new_password == form.current_password {
+        errors.insert("new_password", "New password must differ from the current password".to_string());
+    }
+
+    if form.new_password != form.confirm_password {
+        errors.insert("confirm_password", "Passwords do not match".to_string());
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Check a profile edit before it is saved. `email_owner` is the admin currently
+/// holding the submitted email, if any; it must be `user` for the edit to pass.
+pub fn validate_profile_edit(
+    user: &AdminxUser,
+    form: &ProfileEditForm,
+    email_owner: Option<&AdminxUser>,
+) -> Result<(), HashMap<&'static str, String>> {
+    let mut errors = HashMap::new();
+
+    if form.username.trim().is_empty() {
+        errors.insert("username", "Username cannot be empty".to_string());
+    }
+
+    let email = form.email.trim();
+    if email.is_empty() || !email.contains('@') {
+        errors.insert("email", "Enter a valid email address".to_string());
+    } else if let Some(owner) = email_owner {
+        if owner.id != user.id {
+            errors.insert("email", format!("Email {} is already taken", email.to_lowercase()));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::auth::AdminxStatus;
+    use crate::utils::password::PasswordHashing;
+    use mongodb::bson::DateTime as BsonDateTime;
+
+    fn user_with_password(password: &str) -> AdminxUser {
+        AdminxUser {
+            id: None,
+            username: "admin".to_string(),
+            email: "admin@example.com".to_string(),
+            password: PasswordHashing::Bcrypt { cost: 4 }.hash(password).unwrap(),
+            delete: false,
+            status: AdminxStatus::Active,
+            created_at: BsonDateTime::now(),
+            updated_at: BsonDateTime::now(),
+            email_verified: true,
+            verification_token: None,
+            reset_token: None,
+            reset_token_expires_at: None,
+            roles: Vec::new(),
+        }
+    }
+
+    fn form(current: &str, new: &str, confirm: &str) -> PasswordChangeForm {
+        PasswordChangeForm {
+            current_password: current.to_string(),
+            new_password: new.to_string(),
+            confirm_password: confirm.to_string(),
+            sign_out: None,
+        }
+    }
+
+    #[test]
+    fn accepts_correct_password_change() {
+        let user = user_with_password("old-password");
+        let result = validate_password_change(&user, &form("old-password", "N3w-password", "N3w-password"), &PasswordPolicy::default());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn rejects_wrong_current_password() {
+        let user = user_with_password("old-password");
+        let errors = validate_password_change(&user, &form("not-my-password", "N3w-password", "N3w-password"), &PasswordPolicy::default())
+            .unwrap_err();
+        assert_eq!(errors.keys().collect::<Vec<_>>(), vec![&"current_password"]);
+    }
+
+    #[test]
+    fn rejects_weak_or_mismatched_new_password() {
+        let user = user_with_password("old-password");
+        let policy = PasswordPolicy { require_digit: true, ..PasswordPolicy::default() };
+
+        let errors = validate_password_change(&user, &form("old-password", "weakpassword", "weakpassword"), &policy)
+            .unwrap_err();
+        assert_eq!(errors["new_password"], "Password must contain a digit");
+        assert!(!errors.contains_key("current_password"));
+
+        let errors = validate_password_change(&user, &form("old-password", "N3w-password", "typo"), &policy)
+            .unwrap_err();
+        assert_eq!(errors.keys().collect::<Vec<_>>(), vec![&"confirm_password"]);
+    }
+
+    fn profile_form(username: &str, email: &str) -> ProfileEditForm {
+        ProfileEditForm {
+            username: username.to_string(),
+            email: email.to_string(),
+        }
+    }
+
+    #[test]
+    fn accepts_profile_edit_with_unused_or_own_email() {
+        let mut user = user_with_password("old-password");
+        user.id = Some(mongodb::bson::oid::ObjectId::new());
+
+        assert!(validate_profile_edit(&user, &profile_form("renamed", "new@example.com"), None).is_ok());
+        assert!(validate_profile_edit(&user, &profile_form("renamed", "ADMIN@example.com"), Some(&user)).is_ok());
+    }
+
+    #[test]
+    fn rejects_profile_edit_with_taken_email() {
+        let mut user = user_with_password("old-password");
+        user.id = Some(mongodb::bson::oid::ObjectId::new());
+        let mut other = user_with_password("other-password");
+        other.id = Some(mongodb::bson::oid::ObjectId::new());
+        other.email = "taken@example.com".to_string();
+
+        let errors = validate_profile_edit(&user, &profile_form("admin", "Taken@example.com"), Some(&other)).unwrap_err();
+        assert_eq!(errors["email"], "Email taken@example.com is already taken");
+
+        let errors = validate_profile_edit(&user, &profile_form(" ", "not-an-email"), None).unwrap_err();
+        assert_eq!(errors.len(), 2);
+    }
+}
@@ -0,0 +1,314 @@
+// adminx/src/helpers/bulk_status.rs
+use actix_web::{HttpResponse, ResponseError};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime as BsonDateTime, Document};
+use mongodb::options::FindOptions;
+use mongodb::Collection;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{error, info, warn};
+
+use crate::AdmixResource;
+use crate::error::AdminxError;
+use crate::helpers::resource_helper::{hide_soft_deleted, VERSION_FIELD};
+use crate::utils::constants::{AUDIT_LOG_COLLECTION, MAX_BULK_STATUS_IDS};
+use crate::utils::database::try_get_adminx_database;
+use crate::utils::rbac::writable_keys;
+use crate::utils::structs::Claims;
+
+/// Field `POST /bulk-status` sets
+pub const STATUS_FIELD: &str = "status";
+
+/// Body of `POST /bulk-status`
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkStatusRequest {
+    pub ids: Vec<String>,
+    pub status: String,
+}
+
+/// Where bulk status changes are applied and audited
+#[async_trait]
+pub trait BulkStatusStore: Send + Sync {
+    /// Apply `update` to the documents matching `filter`; the ids of those it matched
+    async fn update_matching(&self, filter: Document, update: Document) -> Result<Vec<ObjectId>, String>;
+    /// Append `entries` to the audit log
+    async fn record_audit(&self, entries: Vec<Document>) -> Result<(), String>;
+}
+
+/// Updates the resource's collection and audits into `AUDIT_LOG_COLLECTION`
+pub struct MongoBulkStatusStore(pub Collection<Document>);
+
+#[async_trait]
+impl BulkStatusStore for MongoBulkStatusStore {
+    async fn update_matching(&self, filter: Document, update: Document) -> Result<Vec<ObjectId>, String> {
+        let options = FindOptions::builder().projection(doc! { "_id": 1 }).build();
+        let matched: Vec<Document> = self.0.find(filter, options).await
+            .map_err(|e| e.to_string())?
+            .try_collect().await
+            .map_err(|e| e.to_string())?;
+        let ids: Vec<ObjectId> = matched.iter().filter_map(|doc| doc.get_object_id("_id").ok()).collect();
+        if ids.is_empty() {
+            return Ok(ids);
+        }
+        self.0.update_many(doc! { "_id": { "$in": &ids } }, update, None).await
+            .map_err(|e| e.to_string())?;
+        Ok(ids)
+    }
+
+    async fn record_audit(&self, entries: Vec<Document>) -> Result<(), String> {
+        let collection = try_get_adminx_database()
+            .map_err(|_| "the AdminX database is not initialized")?
+            .collection(AUDIT_LOG_COLLECTION);
+        if entries.is_empty() {
+            return Ok(());
+        }
+        collection.insert_many(entries, None).await.map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+/// Whether `roles` may change the resource's `status` field in bulk
+pub fn bulk_status_allowed(resource: &dyn AdmixResource, roles: &[String]) -> bool {
+    writable_keys(resource, roles).contains(&STATUS_FIELD) && !resource.readonly_keys().contains(&STATUS_FIELD)
+}
+
+/// Check a bulk status request: the field must be writable, the status one of
+/// `status_values()` (when set) and accepted by `validate()`, and the ids valid.
+/// Returns the deduplicated ids.
+pub fn validate_bulk_status(
+    resource: &dyn AdmixResource,
+    roles: &[String],
+    request: &BulkStatusRequest,
+) -> Result<Vec<ObjectId>, AdminxError> {
+    if !bulk_status_allowed(resource, roles) {
+        return Err(AdminxError::BadRequest(format!("{} has no writable status field", resource.resource_name())));
+    }
+    if request.status.trim().is_empty() {
+        return Err(AdminxError::BadRequest("Missing status".into()));
+    }
+    if let Some(values) = resource.status_values() {
+        if !values.contains(&request.status.as_str()) {
+            return Err(AdminxError::BadRequest(format!(
+                "Invalid status '{}', expected one of: {}", request.status, values.join(", ")
+            )));
+        }
+    }
+    let mut fields = serde_json::Map::new();
+    fields.insert(STATUS_FIELD.to_string(), Value::String(request.status.clone()));
+    resource.validate(&fields)?;
+    if request.ids.is_empty() {
+        return Err(AdminxError::BadRequest("No records selected".into()));
+    }
+    if request.ids.len() > MAX_BULK_STATUS_IDS {
+        return Err(AdminxError::BadRequest(format!("At most {} records can be changed at once", MAX_BULK_STATUS_IDS)));
+    }
+
+    let mut ids = Vec::with_capacity(request.ids.len());
+    for id in &request.ids {
+        let id = ObjectId::parse_str(id).map_err(|_| AdminxError::BadRequest(format!("Invalid ID format: {}", id)))?;
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    Ok(ids)
+}
+
+/// Set `status` on the requested documents that aren't soft deleted, bumping their
+/// updated timestamp and version, and write one audit entry per changed document
+pub async fn change_status(
+    resource: &dyn AdmixResource,
+    roles: &[String],
+    actor: &str,
+    now: BsonDateTime,
+    request: BulkStatusRequest,
+    store: &dyn BulkStatusStore,
+) -> Result<Value, AdminxError> {
+    let ids = validate_bulk_status(resource, roles, &request)?;
+
+    let mut filter = doc! { "_id": { "$in": &ids } };
+    hide_soft_deleted(&mut filter, resource.soft_delete_field());
+    let mut set = doc! { STATUS_FIELD: &request.status };
+    if let Some((_, updated)) = resource.timestamps() {
+        set.insert(updated, now);
+    }
+    let mut update = doc! { "$set": set };
+    if resource.uses_versioning() {
+        update.insert("$inc", doc! { VERSION_FIELD: 1_i64 });
+    }
+
+    let changed = store.update_matching(filter, update).await.map_err(|e| {
+        error!("Bulk status change on {} failed: {}", resource.resource_name(), e);
+        AdminxError::InternalError
+    })?;
+
+    let action = format!("changed status to {}", request.status);
+    let entries = changed.iter()
+        .map(|id| doc! {
+            "actor": actor,
+            "action": &action,
+            "resource": resource.resource_name(),
+            "document_id": id.to_hex(),
+            "created_at": now,
+        })
+        .collect();
+    if let Err(e) = store.record_audit(entries).await {
+        warn!("Could not audit the bulk status change on {}: {}", resource.resource_name(), e);
+    }
+
+    Ok(json!({
+        "success": true,
+        "status": request.status,
+        "updated": changed.len(),
+        "ids": changed.iter().map(|id| id.to_hex()).collect::<Vec<_>>(),
+    }))
+}
+
+/// POST /bulk-status - Set the status of the selected documents
+pub async fn bulk_status_change(
+    resource: &dyn AdmixResource,
+    claims: &Claims,
+    now: BsonDateTime,
+    request: BulkStatusRequest,
+) -> HttpResponse {
+    let mut roles = claims.roles.clone();
+    roles.push(claims.role.clone());
+    if let Err(e) = validate_bulk_status(resource, &roles, &request) {
+        warn!("Rejected bulk status change on {} by {}: {}", resource.resource_name(), claims.email, e);
+        return e.error_response();
+    }
+    let collection = match resource.try_get_collection() {
+        Ok(collection) => collection,
+        Err(e) => return e.error_response(),
+    };
+
+    match change_status(resource, &roles, &claims.email, now, request, &MongoBulkStatusStore(collection)).await {
+        Ok(summary) => {
+            info!("Bulk status change on {} by {}: {} set to {}", resource.resource_name(), claims.email, summary["updated"], summary["status"]);
+            HttpResponse::Ok().json(summary)
+        }
+        Err(e) => e.error_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestResource;
+    use std::sync::Mutex;
+
+    fn reviews() -> TestResource {
+        TestResource::named("Reviews", "reviews").permit(&["body", "status", "deleted"]).statuses(&["pending", "approved", "rejected"])
+    }
+
+    /// Matches every requested id except `missing`
+    #[derive(Default)]
+    struct MemoryStore {
+        missing: Option<ObjectId>,
+        updates: Mutex<Vec<(Document, Document)>>,
+        audit: Mutex<Vec<Document>>,
+    }
+
+    #[async_trait]
+    impl BulkStatusStore for MemoryStore {
+        async fn update_matching(&self, filter: Document, update: Document) -> Result<Vec<ObjectId>, String> {
+            let ids = filter.get_document("_id").unwrap().get_array("$in").unwrap()
+                .iter()
+                .filter_map(|id| id.as_object_id())
+                .filter(|id| Some(*id) != self.missing)
+                .collect();
+            self.updates.lock().unwrap().push((filter, update));
+            Ok(ids)
+        }
+
+        async fn record_audit(&self, entries: Vec<Document>) -> Result<(), String> {
+            self.audit.lock().unwrap().extend(entries);
+            Ok(())
+        }
+    }
+
+    fn roles() -> Vec<String> {
+        vec!["admin".to_string()]
+    }
+
+    #[actix_rt::test]
+    async fn valid_status_is_applied_and_audited() {
+        let (a, b, gone) = (ObjectId::new(), ObjectId::new(), ObjectId::new());
+        let store = MemoryStore { missing: Some(gone), ..Default::default() };
+        let now = BsonDateTime::from_millis(1_700_000_000_000);
+        let request = BulkStatusRequest {
+            ids: vec![a.to_hex(), b.to_hex(), a.to_hex(), gone.to_hex()],
+            status: "approved".to_string(),
+        };
+
+        let summary = change_status(&reviews(), &roles(), "a@example.com", now, request, &store).await.unwrap();
+        assert_eq!(summary["updated"], 2);
+        assert_eq!(summary["ids"], json!([a.to_hex(), b.to_hex()]));
+
+        let updates = store.updates.lock().unwrap();
+        let (filter, update) = &updates[0];
+        assert_eq!(filter.get_document("_id").unwrap().get_array("$in").unwrap().len(), 3);
+        assert_eq!(filter.get_document("deleted").unwrap(), &doc! { "$ne": true });
+        assert_eq!(update, &doc! { "$set": { "status": "approved", "updated_at": now } });
+
+        let audit = store.audit.lock().unwrap();
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit[0], doc! {
+            "actor": "a@example.com",
+            "action": "changed status to approved",
+            "resource": "Reviews",
+            "document_id": a.to_hex(),
+            "created_at": now,
+        });
+    }
+
+    #[actix_rt::test]
+    async fn status_outside_the_whitelist_is_rejected() {
+        let store = MemoryStore::default();
+        let request = BulkStatusRequest { ids: vec![ObjectId::new().to_hex()], status: "archived".to_string() };
+
+        let result = change_status(&reviews(), &roles(), "a@example.com", BsonDateTime::now(), request, &store).await;
+        match result {
+            Err(AdminxError::BadRequest(message)) => {
+                assert_eq!(message, "Invalid status 'archived', expected one of: pending, approved, rejected");
+            }
+            other => panic!("expected a bad request, got {:?}", other),
+        }
+        assert!(store.updates.lock().unwrap().is_empty());
+        assert!(store.audit.lock().unwrap().is_empty());
+
+        let bad_id = BulkStatusRequest { ids: vec!["nope".to_string()], status: "approved".to_string() };
+        assert!(matches!(validate_bulk_status(&reviews(), &roles(), &bad_id), Err(AdminxError::BadRequest(_))));
+    }
+
+    struct Tickets;
+
+    impl AdmixResource for Tickets {
+        fn new() -> Self { Tickets }
+        fn resource_name(&self) -> &'static str { "Tickets" }
+        fn base_path(&self) -> &'static str { "tickets" }
+        fn collection_name(&self) -> &'static str { "tickets" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(Tickets) }
+        fn permit_keys(&self) -> Vec<&'static str> { vec!["status"] }
+        fn validate(&self, fields: &serde_json::Map<String, Value>) -> Result<(), AdminxError> {
+            match fields.get("status").and_then(|s| s.as_str()) {
+                Some(status) if status.chars().all(|c| c.is_ascii_lowercase()) => Ok(()),
+                _ => Err(AdminxError::Validation([("status".to_string(), "must be lowercase".to_string())].into())),
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn status_failing_validation_is_rejected() {
+        let store = MemoryStore::default();
+        let request = BulkStatusRequest { ids: vec![ObjectId::new().to_hex()], status: "Closed".to_string() };
+
+        let result = change_status(&Tickets, &roles(), "a@example.com", BsonDateTime::now(), request, &store).await;
+        match result {
+            Err(AdminxError::Validation(fields)) => assert_eq!(fields["status"], "must be lowercase"),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+        assert!(store.updates.lock().unwrap().is_empty());
+    }
+
+}
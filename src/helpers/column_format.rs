@@ -0,0 +1,241 @@
+// crates/adminx/src/helpers/column_format.rs
+use lazy_static::lazy_static;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::AdmixResource;
+use crate::helpers::resource_helper::default_list_structure;
+
+/// A custom column formatter: receives the cell value and the column's `"format"` spec
+pub type ColumnFormatter = Arc<dyn Fn(&Value, &Value) -> Value + Send + Sync>;
+
+/// `"format"` specs from `list_structure()`, keyed by column field
+pub type ColumnFormats = HashMap<String, Value>;
+
+lazy_static! {
+    /// Formatters registered by the host application, looked up before the built-ins
+    static ref COLUMN_FORMATTERS: RwLock<HashMap<String, ColumnFormatter>> = RwLock::new(HashMap::new());
+}
+
+/// Register a formatter usable as `"format": "<name>"` or `"format": { "type": "<name>", ... }`.
+/// Registering a built-in name (`currency`, `number`, `datetime`, `date`, `boolean`,
+/// `enum_map`) replaces it.
+pub fn register_column_formatter<F>(name: &str, formatter: F)
+where
+    F: Fn(&Value, &Value) -> Value + Send + Sync + 'static,
+{
+    COLUMN_FORMATTERS
+        .write()
+        .expect("column formatter registry poisoned")
+        .insert(name.to_string(), Arc::new(formatter));
+}
+
+/// Format name of a spec, given either as a bare string or an object with `"type"`
+fn format_name(spec: &Value) -> Option<&str> {
+    match spec {
+        Value::String(name) => Some(name),
+        Value::Object(options) => options.get("type").and_then(|t| t.as_str()),
+        _ => None,
+    }
+}
+
+fn option_str<'a>(spec: &'a Value, key: &str) -> Option<&'a str> {
+    spec.get(key).and_then(|v| v.as_str())
+}
+
+fn option_decimals(spec: &Value, default: usize) -> usize {
+    spec.get("decimals").and_then(|v| v.as_u64()).map_or(default, |d| d as usize)
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// `1234567.5` with 2 decimals -> `1,234,567.50`
+fn group_thousands(number: f64, decimals: usize) -> String {
+    let fixed = format!("{:.*}", decimals, number.abs());
+    let (whole, fraction) = match fixed.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (fixed.as_str(), None),
+    };
+
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    if number < 0.0 && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+/// Parse the date strings AdminX renders: list-view `%Y-%m-%d %H:%M:%S` and export RFC 3339
+fn parse_datetime(value: &Value) -> Option<chrono::NaiveDateTime> {
+    let text = value.as_str()?;
+    chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .or_else(|| chrono::DateTime::parse_from_rfc3339(text).ok().map(|dt| dt.naive_local()))
+}
+
+fn builtin_format(name: &str, spec: &Value, value: &Value) -> Option<Value> {
+    let formatted = match name {
+        "currency" => {
+            let amount = as_number(value)?;
+            let symbol = option_str(spec, "symbol").unwrap_or("$");
+            let grouped = group_thousands(amount, option_decimals(spec, 2));
+            match grouped.strip_prefix('-') {
+                Some(positive) => format!("-{}{}", symbol, positive),
+                None => format!("{}{}", symbol, grouped),
+            }
+        }
+        "number" => group_thousands(as_number(value)?, option_decimals(spec, 0)),
+        "datetime" | "date" => {
+            let default_pattern = if name == "date" { "%Y-%m-%d" } else { "%Y-%m-%d %H:%M:%S" };
+            let pattern = option_str(spec, "pattern").unwrap_or(default_pattern);
+            parse_datetime(value)?.format(pattern).to_string()
+        }
+        "boolean" => {
+            let flag = match value {
+                Value::Bool(b) => *b,
+                Value::String(s) if s == "true" || s == "false" => s == "true",
+                _ => return None,
+            };
+            let label = if flag { option_str(spec, "true_label").unwrap_or("Yes") } else { option_str(spec, "false_label").unwrap_or("No") };
+            label.to_string()
+        }
+        "enum_map" => {
+            let key = match value {
+                Value::String(s) => s.clone(),
+                Value::Null => return None,
+                other => other.to_string(),
+            };
+            return spec.get("map").and_then(|map| map.get(&key)).cloned();
+        }
+        _ => return None,
+    };
+    Some(Value::String(formatted))
+}
+
+/// Apply a column's `"format"` spec to one value. Values a formatter can't handle
+/// (nulls, unparseable input, unknown formats) are returned unchanged.
+pub fn format_value(spec: &Value, value: &Value) -> Value {
+    let Some(name) = format_name(spec) else {
+        return value.clone();
+    };
+
+    let custom = COLUMN_FORMATTERS
+        .read()
+        .ok()
+        .and_then(|formatters| formatters.get(name).cloned());
+    if let Some(formatter) = custom {
+        return formatter(value, spec);
+    }
+
+    builtin_format(name, spec, value).unwrap_or_else(|| value.clone())
+}
+
+/// Collect the `"format"` specs of a list structure's columns
+pub fn column_formats(list_structure: &Value) -> ColumnFormats {
+    list_structure
+        .get("columns")
+        .and_then(|c| c.as_array())
+        .map(|cols| {
+            cols.iter()
+                .filter_map(|col| {
+                    let field = col.get("field").and_then(|f| f.as_str())?;
+                    let spec = col.get("format")?;
+                    Some((field.to_string(), spec.clone()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Column formats declared by a resource's `list_structure()`
+pub fn resource_column_formats(resource: &dyn AdmixResource) -> ColumnFormats {
+    column_formats(&resource.list_structure().unwrap_or_else(|| default_list_structure(resource)))
+}
+
+/// Format every cell of `row` that has a column format
+pub fn apply_column_formats(formats: &ColumnFormats, row: &mut Map<String, Value>) {
+    for (field, spec) in formats {
+        if let Some(value) = row.get_mut(field) {
+            *value = format_value(spec, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn formats() -> ColumnFormats {
+        column_formats(&json!({
+            "columns": [
+                { "field": "title", "label": "Title" },
+                { "field": "price", "label": "Price", "format": { "type": "currency", "symbol": "€" } },
+                { "field": "status", "label": "Status", "format": {
+                    "type": "enum_map",
+                    "map": { "pub": "Published", "draft": "Draft", "1": "Featured" }
+                } },
+                { "field": "published_at", "label": "Published", "format": { "type": "datetime", "pattern": "%d %b %Y" } }
+            ]
+        }))
+    }
+
+    #[test]
+    fn renders_currency_column() {
+        let mut row = Map::new();
+        row.insert("price".to_string(), json!(1234567.5));
+        apply_column_formats(&formats(), &mut row);
+        assert_eq!(row["price"], "€1,234,567.50");
+
+        assert_eq!(format_value(&json!("currency"), &json!(-42)), "-$42.00");
+        assert_eq!(format_value(&json!("currency"), &json!("N/A")), "N/A");
+        assert_eq!(format_value(&json!("currency"), &Value::Null), Value::Null);
+    }
+
+    #[test]
+    fn renders_enum_mapped_column() {
+        let mut row = Map::new();
+        row.insert("title".to_string(), json!("pub"));
+        row.insert("status".to_string(), json!("pub"));
+        apply_column_formats(&formats(), &mut row);
+        assert_eq!(row["status"], "Published");
+        // Columns without a format are untouched
+        assert_eq!(row["title"], "pub");
+
+        let spec = &formats()["status"];
+        assert_eq!(format_value(spec, &json!(1)), "Featured");
+        assert_eq!(format_value(spec, &json!("archived")), "archived");
+    }
+
+    #[test]
+    fn formats_dates_from_list_and_export_renderings() {
+        let spec = &formats()["published_at"];
+        assert_eq!(format_value(spec, &json!("2024-03-05 10:00:00")), "05 Mar 2024");
+        assert_eq!(format_value(spec, &json!("2024-03-05T10:00:00+00:00")), "05 Mar 2024");
+        assert_eq!(format_value(&json!({ "type": "boolean", "true_label": "On" }), &json!(true)), "On");
+    }
+
+    #[test]
+    fn custom_formatters_can_be_registered() {
+        register_column_formatter("percent", |value, _spec| {
+            as_number(value).map_or(value.clone(), |n| json!(format!("{:.0}%", n * 100.0)))
+        });
+        assert_eq!(format_value(&json!("percent"), &json!(0.25)), "25%");
+    }
+}
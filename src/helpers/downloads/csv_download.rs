@@ -1,184 +1,119 @@
 // crates/adminx/src/helpers/downloads/csv_download.rs
 use actix_web::{HttpRequest, HttpResponse};
+use crate::helpers::downloads::{export_response, wants_gzip};
 use std::sync::Arc;
 use tracing::{info};
-use std::collections::HashSet;
 use futures::TryStreamExt;
 use crate::AdmixResource;
 use chrono::Utc;
-use crate::utils::constants::{
-    DEFAULT_PAGE,
-    DEFAULT_PER_PAGE,
-};
+use crate::utils::rbac::roles_from_request;
+use crate::helpers::downloads::json_download::export_find_options;
+use crate::helpers::list_query::ListQuery;
+use crate::helpers::resource_helper::{bson_to_json_value, export_keys, query_error};
+use crate::helpers::column_format::{format_value, resource_column_formats, ColumnFormats};
+use crate::utils::timezone::{format_datetime, request_timezone};
+use chrono_tz::Tz;
+
+/// CSV datetimes carry their UTC offset so the export documents the display zone
+const CSV_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S %:z";
+
+/// UTF-8 byte order mark; Excel needs it to read accented text correctly
+const UTF8_BOM: &str = "\u{feff}";
+
+/// Leading characters spreadsheet apps treat as the start of a formula
+const FORMULA_PREFIXES: &[char] = &['=', '+', '-', '@', '\t', '\r'];
+
+/// Field delimiter, line ending, byte order mark and formula guard of a CSV export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub line_ending: &'static str,
+    /// Prepend a UTF-8 BOM. Off by default since other parsers may read it as data.
+    pub bom: bool,
+    /// Prefix values that would start a formula (`=`, `+`, `-`, `@`) with `'` so
+    /// spreadsheets show them as text. On by default; only the resource can turn it off.
+    pub safe: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self { delimiter: ',', line_ending: "\n", bom: false, safe: true }
+    }
+}
+
+impl CsvOptions {
+    /// Semicolon-delimited with CRLF line endings and a BOM, as Excel on Windows expects
+    pub fn excel() -> Self {
+        Self { delimiter: ';', line_ending: "\r\n", bom: true, safe: true }
+    }
+
+    /// The resource's `csv_options()` with `?delimiter=`, `?line_ending=` and `?bom=`
+    /// applied; unsupported values keep the resource's choice
+    pub fn for_query(resource: &dyn AdmixResource, query: &ListQuery) -> Self {
+        let mut options = resource.csv_options();
+        if let Some(delimiter) = query.delimiter.as_deref().and_then(parse_delimiter) {
+            options.delimiter = delimiter;
+        }
+        match query.line_ending.as_deref().map(str::to_ascii_lowercase).as_deref() {
+            Some("crlf") => options.line_ending = "\r\n",
+            Some("lf") => options.line_ending = "\n",
+            _ => {}
+        }
+        if let Some(bom) = query.bom {
+            options.bom = bom;
+        }
+        options
+    }
+}
+
+fn parse_delimiter(value: &str) -> Option<char> {
+    match value {
+        "," | "comma" => Some(','),
+        ";" | "semicolon" => Some(';'),
+        "\t" | "tab" => Some('\t'),
+        "|" | "pipe" => Some('|'),
+        _ => None,
+    }
+}
 
 /// Export data as CSV with pagination support
 pub async fn export_data_as_csv(
     resource: &Arc<Box<dyn AdmixResource>>,
     req: &HttpRequest,
-    _query_string: String,
+    query: &ListQuery,
 ) -> Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>> {
-    let collection = resource.get_collection();
-    
-    // Parse query parameters for filters and pagination
-    let query_params: std::collections::HashMap<String, String> = 
-        serde_urlencoded::from_str(req.query_string()).unwrap_or_default();
-    
-    // Extract pagination parameters
-    let page = query_params.get("page")
-        .and_then(|p| p.parse::<u64>().ok())
-        .unwrap_or(DEFAULT_PAGE);
-    
-    let per_page = query_params.get("per_page")
-        .and_then(|p| p.parse::<u64>().ok())
-        .unwrap_or(DEFAULT_PER_PAGE);
+    let collection = resource.try_get_collection()?;
     
-    let complete_export = query_params.get("complete")
-        .map(|v| v == "true")
-        .unwrap_or(false);
-    
-    // Build filter document (same logic as before)
-    let mut filter_doc = mongodb::bson::doc! {};
-    let permitted_fields: HashSet<&str> = resource.permit_keys().into_iter().collect();
-    
-    for (key, value) in &query_params {
-        if !value.is_empty() && 
-           (permitted_fields.contains(key.as_str()) || key == "search") && 
-           !["download", "page", "per_page", "complete"].contains(&key.as_str()) {
-            match key.as_str() {
-                "name" | "email" | "username" | "key" | "title" | "description" | "search" => {
-                    if key == "search" {
-                        let search_fields = vec!["name", "email", "username", "key", "title", "description"];
-                        let mut search_conditions = Vec::new();
-                        
-                        for field in search_fields {
-                            if permitted_fields.contains(field) {
-                                search_conditions.push(mongodb::bson::doc! {
-                                    field: {
-                                        "$regex": value,
-                                        "$options": "i"
-                                    }
-                                });
-                            }
-                        }
-                        
-                        if !search_conditions.is_empty() {
-                            filter_doc.insert("$or", search_conditions);
-                        }
-                    } else {
-                        filter_doc.insert(key, mongodb::bson::doc! {
-                            "$regex": value,
-                            "$options": "i"
-                        });
-                    }
-                }
-                "status" | "data_type" | "deleted" | "active" | "enabled" => {
-                    if value == "true" || value == "false" {
-                        let bool_val = value == "true";
-                        filter_doc.insert(key, bool_val);
-                    } else {
-                        filter_doc.insert(key, value);
-                    }
-                }
-                _ => {
-                    filter_doc.insert(key, value);
-                }
-            }
-        }
-    }
+    let filter_doc = query.filter_document(resource.as_ref().as_ref());
+    let page = query.page;
+    let complete_export = query.complete;
     
     info!("Exporting CSV with filters: {:?}", filter_doc);
     
-    // Configure find options with conditional pagination
-    let mut find_options = mongodb::options::FindOptions::default();
-    find_options.sort = Some(mongodb::bson::doc! { "created_at": -1 });
-    
     if complete_export {
-        // Export all records (no pagination limits)
         info!("Exporting complete CSV dataset (all records)");
-        // Don't set skip or limit - fetch everything
     } else {
-        // Apply pagination for current page only
-        let skip = (page - 1) * per_page;
-        find_options.skip = Some(skip);
-        find_options.limit = Some(per_page as i64);
-        info!("Exporting CSV page {} ({} records per page)", page, per_page);
+        info!("Exporting CSV page {} ({} records per page)", page, query.per_page_for(resource.as_ref().as_ref()));
     }
+    let find_options = export_find_options(resource.as_ref().as_ref(), query);
     
     let mut cursor = collection.find(filter_doc, find_options).await
-        .map_err(|e| format!("Database query failed: {}", e))?;
-    
-    // Build CSV headers
-    let mut headers = vec!["id".to_string()];
-    for field in resource.permit_keys() {
-        headers.push(field.to_string());
-    }
-    headers.push("created_at".to_string());
-    headers.push("updated_at".to_string());
+        .map_err(|e| query_error(resource.resource_name(), &e))?;
     
+    // Only export fields the user's roles may read
+    let readable = export_keys(resource.as_ref().as_ref(), &roles_from_request(req));
+
+    let formats = resource_column_formats(resource.as_ref().as_ref());
+    let tz = request_timezone(req);
+
+    let options = CsvOptions::for_query(resource.as_ref().as_ref(), query);
+
     // Start building CSV content
-    let mut csv_content = headers.join(",") + "\n";
+    let mut csv_content = csv_header(&readable, &options);
     
     let mut record_count = 0;
-    while let Some(doc) = cursor.try_next().await.unwrap_or(None) {
-        let mut row = Vec::new();
-        
-        // Add ID
-        if let Ok(oid) = doc.get_object_id("_id") {
-            row.push(escape_csv_field(&oid.to_hex()));
-        } else {
-            row.push("".to_string());
-        }
-        
-        // Add permitted fields
-        for field_name in resource.permit_keys() {
-            let field_value = if let Some(bson_val) = doc.get(field_name) {
-                match bson_val {
-                    mongodb::bson::Bson::String(s) => escape_csv_field(s),
-                    mongodb::bson::Bson::Boolean(b) => b.to_string(),
-                    mongodb::bson::Bson::Int32(i) => i.to_string(),
-                    mongodb::bson::Bson::Int64(i) => i.to_string(),
-                    mongodb::bson::Bson::Double(d) => d.to_string(),
-                    mongodb::bson::Bson::DateTime(dt) => {
-                        let timestamp_ms = dt.timestamp_millis();
-                        if let Some(datetime) = chrono::DateTime::from_timestamp_millis(timestamp_ms) {
-                            escape_csv_field(&datetime.format("%Y-%m-%d %H:%M:%S").to_string())
-                        } else {
-                            "".to_string()
-                        }
-                    }
-                    mongodb::bson::Bson::Null => "".to_string(),
-                    _ => escape_csv_field(&format!("{:?}", bson_val)),
-                }
-            } else {
-                "".to_string()
-            };
-            row.push(field_value);
-        }
-        
-        // Add timestamps
-        if let Ok(created_at) = doc.get_datetime("created_at") {
-            let timestamp_ms = created_at.timestamp_millis();
-            if let Some(datetime) = chrono::DateTime::from_timestamp_millis(timestamp_ms) {
-                row.push(escape_csv_field(&datetime.format("%Y-%m-%d %H:%M:%S").to_string()));
-            } else {
-                row.push("".to_string());
-            }
-        } else {
-            row.push("".to_string());
-        }
-        
-        if let Ok(updated_at) = doc.get_datetime("updated_at") {
-            let timestamp_ms = updated_at.timestamp_millis();
-            if let Some(datetime) = chrono::DateTime::from_timestamp_millis(timestamp_ms) {
-                row.push(escape_csv_field(&datetime.format("%Y-%m-%d %H:%M:%S").to_string()));
-            } else {
-                row.push("".to_string());
-            }
-        } else {
-            row.push("".to_string());
-        }
-        
-        csv_content.push_str(&(row.join(",") + "\n"));
+    while let Some(doc) = cursor.try_next().await.map_err(|e| query_error(resource.resource_name(), &e))? {
+        csv_content.push_str(&csv_row(&doc, &readable, &formats, tz, &options));
         record_count += 1;
     }
     
@@ -200,17 +135,158 @@ pub async fn export_data_as_csv(
         info!("✅ Exported {} records as CSV (page {})", record_count, page);
     }
     
-    Ok(HttpResponse::Ok()
-        .content_type("text/csv")
-        .append_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
-        .body(csv_content))
+    Ok(export_response("text/csv", &filename, csv_content.into_bytes(), wants_gzip(req, query)))
+}
+
+/// Header line: `id`, then the readable fields (see `export_keys` for the timestamps);
+/// preceded by the BOM when `options.bom` is set
+pub fn csv_header(readable: &[&str], options: &CsvOptions) -> String {
+    let mut headers = vec!["id"];
+    headers.extend_from_slice(readable);
+    let headers: Vec<String> = headers.into_iter().map(|header| escape_csv_field(header, options)).collect();
+    let bom = if options.bom { UTF8_BOM } else { "" };
+    format!("{}{}{}", bom, headers.join(&options.delimiter.to_string()), options.line_ending)
+}
+
+/// One CSV line for `doc`, matching `csv_header(readable, options)`
+pub fn csv_row(doc: &mongodb::bson::Document, readable: &[&str], formats: &ColumnFormats, tz: Tz, options: &CsvOptions) -> String {
+    let escape = |field: &str| escape_csv_field(field, options);
+    let mut row = Vec::new();
+    
+    // Add ID
+    if let Ok(oid) = doc.get_object_id("_id") {
+        row.push(escape(&oid.to_hex()));
+    } else {
+        row.push("".to_string());
+    }
+    
+    // Add permitted fields
+    for field_name in readable.iter().copied() {
+        let field_value = if let (Some(spec), Some(bson_val)) = (formats.get(field_name), doc.get(field_name)) {
+            match format_value(spec, &bson_to_json_value(bson_val, tz)) {
+                serde_json::Value::String(s) => escape(&s),
+                serde_json::Value::Null => "".to_string(),
+                other => escape(&other.to_string()),
+            }
+        } else if let Some(bson_val) = doc.get(field_name) {
+            match bson_val {
+                mongodb::bson::Bson::String(s) => escape(s),
+                mongodb::bson::Bson::Boolean(b) => b.to_string(),
+                mongodb::bson::Bson::Int32(i) => i.to_string(),
+                mongodb::bson::Bson::Int64(i) => i.to_string(),
+                mongodb::bson::Bson::Double(d) => d.to_string(),
+                mongodb::bson::Bson::DateTime(dt) => {
+                    format_datetime(dt, tz, CSV_DATETIME_FORMAT)
+                        .map(|datetime| escape(&datetime))
+                        .unwrap_or_default()
+                }
+                mongodb::bson::Bson::Null => "".to_string(),
+                _ => escape(&format!("{:?}", bson_val)),
+            }
+        } else {
+            "".to_string()
+        };
+        row.push(field_value);
+    }
+    
+    row.join(&options.delimiter.to_string()) + options.line_ending
 }
 
 /// Helper function to properly escape CSV fields
-fn escape_csv_field(field: &str) -> String {
-    if field.contains(',') || field.contains('"') || field.contains('\n') || field.contains('\r') {
-        format!("\"{}\"", field.replace('"', "\"\""))
+fn escape_csv_field(field: &str, options: &CsvOptions) -> String {
+    let field = if options.safe && field.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", field)
     } else {
         field.to_string()
+    };
+    if field.contains(options.delimiter) || field.contains('"') || field.contains('\n') || field.contains('\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::{doc, Document};
+    use mongodb::Collection;
+
+    struct Orders;
+
+    impl AdmixResource for Orders {
+        fn new() -> Self { Orders }
+        fn resource_name(&self) -> &'static str { "Orders" }
+        fn base_path(&self) -> &'static str { "orders" }
+        fn collection_name(&self) -> &'static str { "orders" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(Orders) }
+        fn csv_options(&self) -> CsvOptions { CsvOptions::excel() }
+    }
+
+    const FIELDS: &[&str] = &["title", "note", "created_at", "updated_at"];
+
+    fn export(options: &CsvOptions) -> String {
+        let doc = doc! { "title": "Cheese; aged", "note": "1,5 kg" };
+        csv_header(FIELDS, options) + &csv_row(&doc, FIELDS, &ColumnFormats::new(), Tz::UTC, options)
+    }
+
+    #[test]
+    fn semicolon_delimited_with_crlf() {
+        assert_eq!(
+            export(&CsvOptions::excel()),
+            "\u{feff}id;title;note;created_at;updated_at\r\n;\"Cheese; aged\";1,5 kg;;\r\n"
+        );
+        assert_eq!(
+            export(&CsvOptions::default()),
+            "id,title,note,created_at,updated_at\n,Cheese; aged,\"1,5 kg\",,\n"
+        );
+    }
+
+    #[test]
+    fn query_overrides_the_resource_options() {
+        assert_eq!(CsvOptions::for_query(&Orders, &ListQuery::default()), CsvOptions::excel());
+
+        let options = CsvOptions::for_query(&Orders, &ListQuery::parse("delimiter=%09&line_ending=LF"));
+        assert_eq!(options, CsvOptions { delimiter: '\t', line_ending: "\n", bom: true, safe: true });
+        assert!(export(&options).contains("\tCheese; aged\t"));
+
+        // Unsupported values keep the resource's choice
+        assert_eq!(CsvOptions::for_query(&Orders, &ListQuery::parse("delimiter=x&line_ending=cr")), CsvOptions::excel());
+    }
+
+    #[test]
+    fn bom_only_when_requested() {
+        const BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+        let plain = CsvOptions::for_query(&Orders, &ListQuery::parse("bom=false"));
+        assert!(!export(&plain).as_bytes().starts_with(BOM));
+        assert!(!export(&CsvOptions::default()).as_bytes().starts_with(BOM));
+
+        let options = CsvOptions { bom: true, ..CsvOptions::default() };
+        let body = export(&options);
+        assert!(body.as_bytes().starts_with(BOM));
+        assert!(body[UTF8_BOM.len()..].starts_with("id,title"));
+        assert_eq!(CsvOptions::for_query(&Orders, &ListQuery::parse("bom=true")), CsvOptions::excel());
+    }
+
+    #[test]
+    fn formula_values_are_exported_as_text() {
+        let options = CsvOptions::default();
+        for (value, expected) in [
+            ("=SUM(A1:A9)", "'=SUM(A1:A9)"),
+            ("+1 555 0100", "'+1 555 0100"),
+            ("-2+3", "'-2+3"),
+            ("@cmd", "'@cmd"),
+            ("=HYPERLINK(\"http://x\",\"y\")", "\"'=HYPERLINK(\"\"http://x\"\",\"\"y\"\")\""),
+            ("plain = text", "plain = text"),
+        ] {
+            assert_eq!(escape_csv_field(value, &options), expected, "{}", value);
+        }
+
+        let row = csv_row(&doc! { "title": "=1+1", "views": -3 }, &["title", "views", "created_at", "updated_at"], &ColumnFormats::new(), Tz::UTC, &options);
+        assert_eq!(row, ",'=1+1,-3,,\n");
+
+        let unsafe_options = CsvOptions { safe: false, ..options };
+        assert_eq!(escape_csv_field("=SUM(A1:A9)", &unsafe_options), "=SUM(A1:A9)");
+    }
+}
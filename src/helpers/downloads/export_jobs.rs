@@ -0,0 +1,597 @@
+// crates/adminx/src/helpers/downloads/export_jobs.rs
+use actix_files::NamedFile;
+use actix_web::{
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    web::Bytes,
+    HttpRequest, HttpResponse, ResponseError,
+};
+use async_trait::async_trait;
+use futures::{stream::LocalBoxStream, Stream, StreamExt};
+use mongodb::bson::{doc, DateTime as BsonDateTime};
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncWriteExt;
+use tracing::{error, info, warn};
+
+use crate::AdmixResource;
+use crate::helpers::column_format::{resource_column_formats, ColumnFormats};
+use crate::helpers::downloads::csv_download::{csv_header, csv_row, CsvOptions};
+use crate::helpers::downloads::json_download::export_find_options;
+use crate::helpers::downloads::ndjson_download::ndjson_stream;
+use crate::helpers::list_query::ListQuery;
+use crate::utils::clock::{request_clock, Clock};
+use crate::utils::constants::{EXPORT_JOBS_COLLECTION, EXPORT_JOB_PROGRESS_INTERVAL, EXPORT_JOB_TTL};
+use crate::utils::database::try_get_adminx_database;
+use crate::utils::mount::admin_path;
+use crate::helpers::resource_helper::{export_keys, read_count_options};
+use crate::utils::structs::Claims;
+use crate::utils::timezone::request_timezone;
+use chrono_tz::Tz;
+
+/// Formats a background export can write
+pub const EXPORT_JOB_FORMATS: &[&str] = &["csv", "ndjson"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportJobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    /// Downloaded or past `EXPORT_JOB_TTL`; the file has been deleted
+    Expired,
+}
+
+/// A background export, stored in `adminx_export_jobs`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExportJob {
+    #[serde(rename = "_id")]
+    pub id: String,
+    /// `base_path` of the exported resource
+    pub resource: String,
+    /// `csv` or `ndjson`
+    pub format: String,
+    /// `sub` of the admin who asked for it; only they can poll and download
+    pub requested_by: String,
+    pub status: ExportJobStatus,
+    pub processed: u64,
+    pub total: Option<u64>,
+    pub file_path: Option<String>,
+    pub error: Option<String>,
+    pub created_at: BsonDateTime,
+    pub updated_at: BsonDateTime,
+}
+
+impl ExportJob {
+    pub fn new(resource: &str, format: &str, requested_by: &str, now: BsonDateTime) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            resource: resource.to_string(),
+            format: format.to_string(),
+            requested_by: requested_by.to_string(),
+            status: ExportJobStatus::Queued,
+            processed: 0,
+            total: None,
+            file_path: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Where the finished file is written
+    pub fn output_path(&self) -> PathBuf {
+        exports_dir().join(format!("{}.{}", self.id, self.format))
+    }
+
+    /// Status payload returned when polling, with a download link once the file is ready
+    pub fn status_json(&self) -> serde_json::Value {
+        let base = admin_path(&format!("/{}/export/{}", self.resource, self.id));
+        let progress = match self.total {
+            Some(0) => Some(100),
+            Some(total) => Some((self.processed.min(total) * 100) / total),
+            None => None,
+        };
+        serde_json::json!({
+            "id": self.id,
+            "resource": self.resource,
+            "format": self.format,
+            "status": self.status,
+            "processed": self.processed,
+            "total": self.total,
+            "progress": progress,
+            "error": self.error,
+            "status_url": base,
+            "download_url": (self.status == ExportJobStatus::Completed).then(|| format!("{}/download", base)),
+        })
+    }
+}
+
+/// Directory export files are written to
+pub fn exports_dir() -> PathBuf {
+    std::env::temp_dir().join("adminx_exports")
+}
+
+/// Delete export files in `dir` last written more than `ttl` before `now`; returns how many
+pub async fn remove_expired_exports(dir: &Path, ttl: Duration, now: SystemTime) -> usize {
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return 0;
+    };
+    let mut removed = 0;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let expired = entry.metadata().await
+            .and_then(|meta| meta.modified())
+            .is_ok_and(|modified| modified + ttl <= now);
+        if !expired {
+            continue;
+        }
+        match tokio::fs::remove_file(entry.path()).await {
+            Ok(()) => removed += 1,
+            Err(e) => warn!("Could not remove expired export {}: {}", entry.path().display(), e),
+        }
+    }
+    removed
+}
+
+/// TTL index dropping job documents `EXPORT_JOB_TTL` after their last update
+pub fn export_job_ttl_index() -> IndexModel {
+    IndexModel::builder()
+        .keys(doc! { "updated_at": 1 })
+        .options(IndexOptions::builder().expire_after(EXPORT_JOB_TTL).build())
+        .build()
+}
+
+/// Create the TTL index on the export jobs collection
+pub async fn ensure_export_job_indexes() -> Result<(), String> {
+    try_get_adminx_database()
+        .map_err(|e| e.to_string())?
+        .collection::<ExportJob>(EXPORT_JOBS_COLLECTION)
+        .create_index(export_job_ttl_index(), None)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Persistence for export jobs, so progress can be polled from any worker
+#[async_trait]
+pub trait ExportJobStore: Send + Sync {
+    /// Insert or replace a job
+    async fn save(&self, job: &ExportJob) -> Result<(), String>;
+    async fn load(&self, id: &str) -> Result<Option<ExportJob>, String>;
+}
+
+/// Jobs stored in the AdminX database
+#[derive(Debug, Default, Clone)]
+pub struct MongoExportJobStore;
+
+impl MongoExportJobStore {
+    fn collection(&self) -> Result<mongodb::Collection<ExportJob>, String> {
+        try_get_adminx_database()
+            .map(|db| db.collection::<ExportJob>(EXPORT_JOBS_COLLECTION))
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl ExportJobStore for MongoExportJobStore {
+    async fn save(&self, job: &ExportJob) -> Result<(), String> {
+        let options = mongodb::options::ReplaceOptions::builder().upsert(true).build();
+        self.collection()?
+            .replace_one(doc! { "_id": &job.id }, job, options)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn load(&self, id: &str) -> Result<Option<ExportJob>, String> {
+        self.collection()?
+            .find_one(doc! { "_id": id }, None)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// The export as a stream of file chunks: the CSV header and one chunk per document
+pub fn export_chunks<S, E>(
+    format: &str,
+    documents: S,
+    readable: Vec<&'static str>,
+    formats: ColumnFormats,
+    tz: Tz,
+    csv_options: CsvOptions,
+) -> (Option<Bytes>, LocalBoxStream<'static, Result<Bytes, actix_web::Error>>)
+where
+    S: Stream<Item = Result<mongodb::bson::Document, E>> + 'static,
+    E: std::fmt::Display + 'static,
+{
+    match format {
+        "csv" => {
+            let header = Bytes::from(csv_header(&readable, &csv_options));
+            let rows = documents.map(move |doc| match doc {
+                Ok(doc) => Ok(Bytes::from(csv_row(&doc, &readable, &formats, tz, &csv_options))),
+                Err(e) => Err(actix_web::error::ErrorInternalServerError(e.to_string())),
+            });
+            (Some(header), rows.boxed_local())
+        }
+        _ => (None, ndjson_stream(documents, readable, formats, tz).boxed_local()),
+    }
+}
+
+async fn save_progress(store: &dyn ExportJobStore, job: &mut ExportJob, clock: &dyn Clock) {
+    job.updated_at = clock.bson_now();
+    if let Err(e) = store.save(job).await {
+        warn!("Failed to record progress of export job {}: {}", job.id, e);
+    }
+}
+
+async fn write_export(
+    store: &dyn ExportJobStore,
+    job: &mut ExportJob,
+    header: Option<Bytes>,
+    mut chunks: LocalBoxStream<'static, Result<Bytes, actix_web::Error>>,
+    path: &PathBuf,
+    clock: &dyn Clock,
+) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await.map_err(|e| e.to_string())?;
+    }
+    let mut file = tokio::fs::File::create(path).await.map_err(|e| e.to_string())?;
+    if let Some(header) = header {
+        file.write_all(&header).await.map_err(|e| e.to_string())?;
+    }
+
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk.map_err(|e| e.to_string())?;
+        file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+        job.processed += 1;
+        if job.processed.is_multiple_of(EXPORT_JOB_PROGRESS_INTERVAL) {
+            save_progress(store, job, clock).await;
+        }
+    }
+    file.flush().await.map_err(|e| e.to_string())
+}
+
+/// Write the export to `path`, recording progress and the final status in `store`
+/// with `clock`'s timestamps
+pub async fn run_export_job(
+    store: &dyn ExportJobStore,
+    mut job: ExportJob,
+    header: Option<Bytes>,
+    chunks: LocalBoxStream<'static, Result<Bytes, actix_web::Error>>,
+    path: PathBuf,
+    clock: &dyn Clock,
+) -> ExportJob {
+    job.status = ExportJobStatus::Running;
+    save_progress(store, &mut job, clock).await;
+
+    match write_export(store, &mut job, header, chunks, &path, clock).await {
+        Ok(()) => {
+            info!("✅ Export job {} wrote {} records to {}", job.id, job.processed, path.display());
+            job.status = ExportJobStatus::Completed;
+            job.total = Some(job.processed);
+            job.file_path = Some(path.to_string_lossy().into_owned());
+        }
+        Err(e) => {
+            error!("❌ Export job {} failed: {}", job.id, e);
+            job.status = ExportJobStatus::Failed;
+            job.error = Some(e);
+        }
+    }
+    save_progress(store, &mut job, clock).await;
+    job
+}
+
+fn claims_roles(claims: &Claims) -> Vec<String> {
+    let mut roles = claims.roles.clone();
+    roles.push(claims.role.clone());
+    roles
+}
+
+/// POST /export - Queue a complete export of the filtered list and run it in the background
+pub async fn enqueue_export(
+    resource: Arc<Box<dyn AdmixResource>>,
+    req: &HttpRequest,
+    claims: &Claims,
+    store: Arc<dyn ExportJobStore>,
+) -> HttpResponse {
+    let mut query = ListQuery::parse(req.query_string());
+    if let Err(e) = query.validate(resource.as_ref().as_ref()) {
+        return e.error_response();
+    }
+    let format = query.format.clone().unwrap_or_else(|| "csv".to_string());
+    let format = format.as_str();
+    if !EXPORT_JOB_FORMATS.contains(&format) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Unsupported export format: {}. Supported formats: {}", format, EXPORT_JOB_FORMATS.join(", "))
+        }));
+    }
+
+    let collection = match resource.try_get_collection() {
+        Ok(collection) => collection,
+        Err(e) => return e.error_response(),
+    };
+
+    query.complete = true;
+    let filter = query.filter_document(resource.as_ref().as_ref());
+    let find_options = export_find_options(resource.as_ref().as_ref(), &query);
+    let count_options = read_count_options(resource.as_ref().as_ref());
+
+    let clock = request_clock(req);
+    let job = ExportJob::new(resource.base_path(), format, &claims.sub, clock.bson_now());
+    if let Err(e) = store.save(&job).await {
+        error!("Failed to queue export job for {}: {}", resource.resource_name(), e);
+        return HttpResponse::InternalServerError().json(serde_json::json!({ "error": "Failed to queue export" }));
+    }
+    info!("📦 Export job {} queued by {} for {}", job.id, claims.email, resource.resource_name());
+
+    let response = HttpResponse::Accepted().json(job.status_json());
+
+    let readable = export_keys(resource.as_ref().as_ref(), &claims_roles(claims));
+    let formats = resource_column_formats(resource.as_ref().as_ref());
+    let tz = request_timezone(req);
+    let csv_options = CsvOptions::for_query(resource.as_ref().as_ref(), &query);
+    let now = SystemTime::from(clock.now());
+    actix_web::rt::spawn(async move {
+        let removed = remove_expired_exports(&exports_dir(), EXPORT_JOB_TTL, now).await;
+        if removed > 0 {
+            info!("Removed {} expired export files", removed);
+        }
+
+        let mut job = job;
+        job.total = collection.count_documents(filter.clone(), count_options).await.ok();
+
+        match collection.find(filter, find_options).await {
+            Ok(cursor) => {
+                let path = job.output_path();
+                let (header, chunks) = export_chunks(&job.format, cursor, readable, formats, tz, csv_options);
+                run_export_job(store.as_ref(), job, header, chunks, path, clock.as_ref()).await;
+            }
+            Err(e) => {
+                job.status = ExportJobStatus::Failed;
+                job.error = Some(format!("Database query failed: {}", e));
+                save_progress(store.as_ref(), &mut job, clock.as_ref()).await;
+            }
+        }
+    });
+
+    response
+}
+
+/// The job if it exists, belongs to this resource and was requested by this admin
+async fn find_own_job(
+    store: &dyn ExportJobStore,
+    resource: &dyn AdmixResource,
+    job_id: &str,
+    claims: &Claims,
+) -> Result<ExportJob, HttpResponse> {
+    match store.load(job_id).await {
+        Ok(Some(job)) if job.resource == resource.base_path() && job.requested_by == claims.sub => Ok(job),
+        Ok(_) => Err(HttpResponse::NotFound().json(serde_json::json!({ "error": "Export job not found" }))),
+        Err(e) => {
+            error!("Failed to load export job {}: {}", job_id, e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({ "error": "Failed to load export job" })))
+        }
+    }
+}
+
+/// GET /export/{job_id} - Poll a job's status and progress
+pub async fn export_job_status(
+    store: &dyn ExportJobStore,
+    resource: &dyn AdmixResource,
+    job_id: &str,
+    claims: &Claims,
+) -> HttpResponse {
+    match find_own_job(store, resource, job_id, claims).await {
+        Ok(job) => HttpResponse::Ok().json(job.status_json()),
+        Err(response) => response,
+    }
+}
+
+/// GET /export/{job_id}/download - Fetch the finished file. It is served once: the file
+/// is deleted as soon as it is open and the job is marked expired.
+pub async fn download_export(
+    store: &dyn ExportJobStore,
+    resource: &dyn AdmixResource,
+    job_id: &str,
+    claims: &Claims,
+    req: &HttpRequest,
+) -> HttpResponse {
+    let job = match find_own_job(store, resource, job_id, claims).await {
+        Ok(job) => job,
+        Err(response) => return response,
+    };
+    let Some(path) = job.file_path.as_ref().filter(|_| job.status == ExportJobStatus::Completed) else {
+        return HttpResponse::Conflict().json(job.status_json());
+    };
+
+    match NamedFile::open_async(path).await {
+        Ok(file) => {
+            // The open handle keeps the contents readable until the response is sent
+            if let Err(e) = tokio::fs::remove_file(path).await {
+                warn!("Could not remove downloaded export {}: {}", path, e);
+            }
+            let mut expired = job.clone();
+            expired.status = ExportJobStatus::Expired;
+            expired.file_path = None;
+            save_progress(store, &mut expired, request_clock(req).as_ref()).await;
+
+            file.set_content_disposition(ContentDisposition {
+                disposition: DispositionType::Attachment,
+                parameters: vec![DispositionParam::Filename(format!(
+                    "{}_{}.{}",
+                    resource.resource_name(),
+                    job.id,
+                    job.format
+                ))],
+            })
+            .into_response(req)
+        }
+        Err(e) => {
+            warn!("Export file for job {} is gone: {}", job.id, e);
+            HttpResponse::Gone().json(serde_json::json!({ "error": "Export file is no longer available" }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestResource;
+    use crate::utils::clock::{FixedClock, SystemClock};
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct InMemoryStore {
+        jobs: Mutex<HashMap<String, ExportJob>>,
+        history: Mutex<Vec<ExportJobStatus>>,
+    }
+
+    #[async_trait]
+    impl ExportJobStore for InMemoryStore {
+        async fn save(&self, job: &ExportJob) -> Result<(), String> {
+            self.history.lock().unwrap().push(job.status);
+            self.jobs.lock().unwrap().insert(job.id.clone(), job.clone());
+            Ok(())
+        }
+
+        async fn load(&self, id: &str) -> Result<Option<ExportJob>, String> {
+            Ok(self.jobs.lock().unwrap().get(id).cloned())
+        }
+    }
+
+    fn claims(sub: &str) -> Claims {
+        Claims {
+            sub: sub.to_string(),
+            exp: 0,
+            email: "a@example.com".to_string(),
+            role: "admin".to_string(),
+            roles: vec![],
+            iss: "adminx".to_string(),
+            aud: "adminx".to_string(),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn job_moves_from_queued_to_completed() {
+        let store = InMemoryStore::default();
+        let queued_at = chrono::Utc.with_ymd_and_hms(2024, 1, 31, 12, 0, 0).unwrap();
+        let clock = FixedClock::new(queued_at);
+        let job = ExportJob::new("posts", "csv", "admin-1", clock.bson_now());
+        store.save(&job).await.unwrap();
+        assert_eq!(store.load(&job.id).await.unwrap().unwrap().status_json()["status"], "queued");
+
+        let docs: Vec<Result<mongodb::bson::Document, String>> = vec![
+            Ok(doc! { "title": "First" }),
+            Ok(doc! { "title": "Second, with comma" }),
+            Ok(doc! { "title": "Third" }),
+        ];
+        let (header, chunks) = export_chunks("csv", futures::stream::iter(docs), vec!["title", "created_at", "updated_at"], ColumnFormats::new(), Tz::UTC, CsvOptions::default());
+        let path = job.output_path();
+        clock.advance(chrono::Duration::minutes(5));
+        let finished = run_export_job(&store, job.clone(), header, chunks, path.clone(), &clock).await;
+
+        assert_eq!(
+            *store.history.lock().unwrap(),
+            vec![ExportJobStatus::Queued, ExportJobStatus::Running, ExportJobStatus::Completed]
+        );
+        let polled = store.load(&job.id).await.unwrap().unwrap();
+        assert_eq!(polled, finished);
+        assert_eq!(polled.created_at, BsonDateTime::from_chrono(queued_at));
+        assert_eq!(polled.updated_at, BsonDateTime::from_chrono(queued_at + chrono::Duration::minutes(5)));
+        let status = polled.status_json();
+        assert_eq!(status["processed"], 3);
+        assert_eq!(status["progress"], 100);
+        assert_eq!(status["download_url"], format!("/adminx/posts/export/{}/download", job.id));
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written.lines().collect::<Vec<_>>(), vec![
+            "id,title,created_at,updated_at",
+            ",First,,",
+            ",\"Second, with comma\",,",
+            ",Third,,",
+        ]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn failed_stream_marks_job_failed() {
+        let store = InMemoryStore::default();
+        let job = ExportJob::new("posts", "ndjson", "admin-1", BsonDateTime::now());
+        let docs: Vec<Result<mongodb::bson::Document, String>> =
+            vec![Ok(doc! { "title": "First" }), Err("cursor died".to_string())];
+        let (header, chunks) = export_chunks("ndjson", futures::stream::iter(docs), vec!["title"], ColumnFormats::new(), Tz::UTC, CsvOptions::default());
+        let path = job.output_path();
+
+        let finished = run_export_job(&store, job, header, chunks, path.clone(), &SystemClock).await;
+        assert_eq!(finished.status, ExportJobStatus::Failed);
+        assert_eq!(finished.processed, 1);
+        assert!(finished.status_json()["download_url"].is_null());
+        let _ = std::fs::remove_file(path);
+    }
+
+    fn posts() -> TestResource {
+        TestResource::named("Posts", "posts")
+    }
+
+    #[actix_rt::test]
+    async fn downloads_are_served_once() {
+        let store = InMemoryStore::default();
+        let mut job = ExportJob::new("posts", "csv", "admin-1", BsonDateTime::now());
+        let path = job.output_path();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "id,title\n").unwrap();
+        job.status = ExportJobStatus::Completed;
+        job.file_path = Some(path.to_string_lossy().into_owned());
+        store.save(&job).await.unwrap();
+
+        let req = actix_web::test::TestRequest::default().to_http_request();
+        let resp = download_export(&store, &posts(), &job.id, &claims("admin-1"), &req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::OK);
+        assert!(!path.exists());
+
+        let expired = store.load(&job.id).await.unwrap().unwrap();
+        assert_eq!(expired.status, ExportJobStatus::Expired);
+        assert!(expired.status_json()["download_url"].is_null());
+        let again = download_export(&store, &posts(), &job.id, &claims("admin-1"), &req).await;
+        assert_eq!(again.status(), actix_web::http::StatusCode::CONFLICT);
+    }
+
+    #[actix_rt::test]
+    async fn expired_export_files_are_removed() {
+        let dir = std::env::temp_dir().join(format!("adminx_exports_test_{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("old.csv"), "id\n").unwrap();
+        let now = SystemTime::now();
+
+        assert_eq!(remove_expired_exports(&dir, EXPORT_JOB_TTL, now).await, 0);
+        assert!(dir.join("old.csv").exists());
+        assert_eq!(remove_expired_exports(&dir, EXPORT_JOB_TTL, now + EXPORT_JOB_TTL + Duration::from_secs(1)).await, 1);
+        assert!(!dir.join("old.csv").exists());
+        std::fs::remove_dir(dir).unwrap();
+    }
+
+    #[test]
+    fn job_documents_expire_after_the_ttl() {
+        let index = export_job_ttl_index();
+        assert_eq!(index.keys, doc! { "updated_at": 1 });
+        assert_eq!(index.options.unwrap().expire_after, Some(EXPORT_JOB_TTL));
+    }
+
+    #[actix_rt::test]
+    async fn only_the_requester_can_poll_a_job() {
+        let store = InMemoryStore::default();
+        let job = ExportJob::new("posts", "csv", "admin-1", BsonDateTime::now());
+        store.save(&job).await.unwrap();
+
+        let own = export_job_status(&store, &posts(), &job.id, &claims("admin-1")).await;
+        assert_eq!(own.status(), actix_web::http::StatusCode::OK);
+        let other = export_job_status(&store, &posts(), &job.id, &claims("admin-2")).await;
+        assert_eq!(other.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+}
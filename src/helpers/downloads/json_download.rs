@@ -1,178 +1,120 @@
 // crates/adminx/src/helpers/downloads/json_download.rs
 use actix_web::{HttpRequest, HttpResponse};
+use crate::helpers::downloads::{export_response, wants_gzip};
 use std::sync::Arc;
 use tracing::{info};
-use std::collections::HashSet;
 use futures::TryStreamExt;
 use crate::AdmixResource;
 use chrono::Utc;
-use crate::utils::constants::{
-    DEFAULT_PAGE,
-    DEFAULT_PER_PAGE,
-};
+use crate::utils::rbac::roles_from_request;
+use crate::helpers::list_query::ListQuery;
+use crate::helpers::resource_helper::{export_keys, query_error, read_find_options};
+use crate::helpers::column_format::{apply_column_formats, resource_column_formats};
+use crate::utils::timezone::{datetime_rfc3339, request_timezone};
+use chrono_tz::Tz;
+use crate::pagination::page_offset;
 
-pub async fn export_data_as_json(
-    resource: &Arc<Box<dyn AdmixResource>>,
-    req: &HttpRequest,
-    _query_string: String,
-) -> Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>> {
-    let collection = resource.get_collection();
-    
-    // Parse query parameters for filters and pagination
-    let query_params: std::collections::HashMap<String, String> = 
-        serde_urlencoded::from_str(req.query_string()).unwrap_or_default();
-    
-    // Extract pagination parameters
-    let page = query_params.get("page")
-        .and_then(|p| p.parse::<u64>().ok())
-        .unwrap_or(DEFAULT_PAGE);
+/// Find options for an export: the list's sort, limited to its current page unless `complete`
+pub fn export_find_options(resource: &dyn AdmixResource, query: &ListQuery) -> mongodb::options::FindOptions {
+    let mut find_options = read_find_options(resource);
+    find_options.sort = Some(query.sort_document(resource));
     
-    let per_page = query_params.get("per_page")
-        .and_then(|p| p.parse::<u64>().ok())
-        .unwrap_or(DEFAULT_PER_PAGE);
-    
-    let complete_export = query_params.get("complete")
-        .map(|v| v == "true")
-        .unwrap_or(false);
-    
-    // Build filter document from query parameters
-    let mut filter_doc = mongodb::bson::doc! {};
-    let permitted_fields: HashSet<&str> = resource.permit_keys().into_iter().collect();
+    if !query.complete {
+        // Apply pagination for current page only
+        let per_page = query.per_page_for(resource);
+        find_options.skip = Some(page_offset(query.page, per_page));
+        find_options.limit = Some(per_page as i64);
+    }
+    find_options
+}
+
+/// Convert a MongoDB document to the exported JSON object, keeping only `readable` fields
+/// (see `export_keys` for the timestamps). Dates are RFC 3339 in `tz`, offset included.
+pub fn document_to_export_json(doc: &mongodb::bson::Document, readable: &[&str], tz: Tz) -> serde_json::Value {
+    let mut json_doc = serde_json::Map::new();
+    
+    // Handle MongoDB ObjectId
+    if let Ok(oid) = doc.get_object_id("_id") {
+        json_doc.insert("id".to_string(), serde_json::Value::String(oid.to_hex()));
+    }
     
-    // Apply the same filters as the list view
-    for (key, value) in &query_params {
-        if !value.is_empty() && 
-           (permitted_fields.contains(key.as_str()) || key == "search") && 
-           !["download", "page", "per_page", "complete"].contains(&key.as_str()) {
-            match key.as_str() {
-                "name" | "email" | "username" | "key" | "title" | "description" | "search" => {
-                    if key == "search" {
-                        let search_fields = vec!["name", "email", "username", "key", "title", "description"];
-                        let mut search_conditions = Vec::new();
-                        
-                        for field in search_fields {
-                            if permitted_fields.contains(field) {
-                                search_conditions.push(mongodb::bson::doc! {
-                                    field: {
-                                        "$regex": value,
-                                        "$options": "i"
-                                    }
-                                });
-                            }
-                        }
-                        
-                        if !search_conditions.is_empty() {
-                            filter_doc.insert("$or", search_conditions);
-                        }
-                    } else {
-                        filter_doc.insert(key, mongodb::bson::doc! {
-                            "$regex": value,
-                            "$options": "i"
-                        });
+    // Convert all fields to JSON
+    for field_name in readable.iter().copied() {
+        if let Some(bson_val) = doc.get(field_name) {
+            match bson_val {
+                mongodb::bson::Bson::String(s) => {
+                    json_doc.insert(field_name.to_string(), serde_json::Value::String(s.clone()));
+                }
+                mongodb::bson::Bson::Boolean(b) => {
+                    json_doc.insert(field_name.to_string(), serde_json::Value::Bool(*b));
+                }
+                mongodb::bson::Bson::Int32(i) => {
+                    json_doc.insert(field_name.to_string(), serde_json::Value::Number(serde_json::Number::from(*i)));
+                }
+                mongodb::bson::Bson::Int64(i) => {
+                    json_doc.insert(field_name.to_string(), serde_json::Value::Number(serde_json::Number::from(*i)));
+                }
+                mongodb::bson::Bson::Double(d) => {
+                    if let Some(num) = serde_json::Number::from_f64(*d) {
+                        json_doc.insert(field_name.to_string(), serde_json::Value::Number(num));
                     }
                 }
-                "status" | "data_type" | "deleted" | "active" | "enabled" => {
-                    if value == "true" || value == "false" {
-                        let bool_val = value == "true";
-                        filter_doc.insert(key, bool_val);
-                    } else {
-                        filter_doc.insert(key, value);
+                mongodb::bson::Bson::DateTime(dt) => {
+                    if let Some(datetime) = datetime_rfc3339(dt, tz) {
+                        json_doc.insert(field_name.to_string(), serde_json::Value::String(datetime));
                     }
                 }
+                mongodb::bson::Bson::Null => {
+                    json_doc.insert(field_name.to_string(), serde_json::Value::Null);
+                }
                 _ => {
-                    filter_doc.insert(key, value);
+                    json_doc.insert(field_name.to_string(), serde_json::Value::String(format!("{:?}", bson_val)));
                 }
             }
         }
     }
     
-    info!("Exporting JSON with filters: {:?}", filter_doc);
+    serde_json::Value::Object(json_doc)
+}
+
+pub async fn export_data_as_json(
+    resource: &Arc<Box<dyn AdmixResource>>,
+    req: &HttpRequest,
+    query: &ListQuery,
+) -> Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let collection = resource.try_get_collection()?;
+    
+    let filter = query.filter_document(resource.as_ref().as_ref());
+    let page = query.page;
+    let per_page = query.per_page_for(resource.as_ref().as_ref());
+    let complete_export = query.complete;
     
-    // Configure find options with conditional pagination
-    let mut find_options = mongodb::options::FindOptions::default();
-    find_options.sort = Some(mongodb::bson::doc! { "created_at": -1 });
+    info!("Exporting JSON with filters: {:?}", filter);
     
     if complete_export {
         // Export all records (no pagination limits)
         info!("Exporting complete JSON dataset (all records)");
-        // Don't set skip or limit - fetch everything
     } else {
-        // Apply pagination for current page only
-        let skip = (page - 1) * per_page;
-        find_options.skip = Some(skip);
-        find_options.limit = Some(per_page as i64);
         info!("Exporting JSON page {} ({} records per page)", page, per_page);
     }
+    let find_options = export_find_options(resource.as_ref().as_ref(), query);
     
-    let mut cursor = collection.find(filter_doc, find_options).await
-        .map_err(|e| format!("Database query failed: {}", e))?;
+    let mut cursor = collection.find(filter, find_options).await
+        .map_err(|e| query_error(resource.resource_name(), &e))?;
     
+    // Only export fields the user's roles may read
+    let readable = export_keys(resource.as_ref().as_ref(), &roles_from_request(req));
+
+    let formats = resource_column_formats(resource.as_ref().as_ref());
+    let tz = request_timezone(req);
+
     let mut documents = Vec::new();
-    while let Some(doc) = cursor.try_next().await.unwrap_or(None) {
-        // Convert MongoDB document to JSON-friendly format
-        let mut json_doc = serde_json::Map::new();
-        
-        // Handle MongoDB ObjectId
-        if let Ok(oid) = doc.get_object_id("_id") {
-            json_doc.insert("id".to_string(), serde_json::Value::String(oid.to_hex()));
-        }
-        
-        // Convert all fields to JSON
-        for field_name in resource.permit_keys() {
-            if let Some(bson_val) = doc.get(field_name) {
-                match bson_val {
-                    mongodb::bson::Bson::String(s) => {
-                        json_doc.insert(field_name.to_string(), serde_json::Value::String(s.clone()));
-                    }
-                    mongodb::bson::Bson::Boolean(b) => {
-                        json_doc.insert(field_name.to_string(), serde_json::Value::Bool(*b));
-                    }
-                    mongodb::bson::Bson::Int32(i) => {
-                        json_doc.insert(field_name.to_string(), serde_json::Value::Number(serde_json::Number::from(*i)));
-                    }
-                    mongodb::bson::Bson::Int64(i) => {
-                        json_doc.insert(field_name.to_string(), serde_json::Value::Number(serde_json::Number::from(*i)));
-                    }
-                    mongodb::bson::Bson::Double(d) => {
-                        if let Some(num) = serde_json::Number::from_f64(*d) {
-                            json_doc.insert(field_name.to_string(), serde_json::Value::Number(num));
-                        }
-                    }
-                    mongodb::bson::Bson::DateTime(dt) => {
-                        let timestamp_ms = dt.timestamp_millis();
-                        if let Some(datetime) = chrono::DateTime::from_timestamp_millis(timestamp_ms) {
-                            json_doc.insert(field_name.to_string(), 
-                                         serde_json::Value::String(datetime.to_rfc3339()));
-                        }
-                    }
-                    mongodb::bson::Bson::Null => {
-                        json_doc.insert(field_name.to_string(), serde_json::Value::Null);
-                    }
-                    _ => {
-                        json_doc.insert(field_name.to_string(), serde_json::Value::String(format!("{:?}", bson_val)));
-                    }
-                }
-            }
-        }
-        
-        // Add standard timestamp fields
-        if let Ok(created_at) = doc.get_datetime("created_at") {
-            let timestamp_ms = created_at.timestamp_millis();
-            if let Some(datetime) = chrono::DateTime::from_timestamp_millis(timestamp_ms) {
-                json_doc.insert("created_at".to_string(), 
-                             serde_json::Value::String(datetime.to_rfc3339()));
-            }
-        }
-        
-        if let Ok(updated_at) = doc.get_datetime("updated_at") {
-            let timestamp_ms = updated_at.timestamp_millis();
-            if let Some(datetime) = chrono::DateTime::from_timestamp_millis(timestamp_ms) {
-                json_doc.insert("updated_at".to_string(), 
-                             serde_json::Value::String(datetime.to_rfc3339()));
-            }
+    while let Some(doc) = cursor.try_next().await.map_err(|e| query_error(resource.resource_name(), &e))? {
+        let mut json_doc = document_to_export_json(&doc, &readable, tz);
+        if let serde_json::Value::Object(fields) = &mut json_doc {
+            apply_column_formats(&formats, fields);
         }
-        
-        documents.push(serde_json::Value::Object(json_doc));
+        documents.push(json_doc);
     }
     
     // Enhanced JSON response with pagination info
@@ -182,6 +124,7 @@ pub async fn export_data_as_json(
             "total": documents.len(),
             "exported_at": Utc::now().to_rfc3339(),
             "resource": resource.resource_name(),
+            "timezone": tz.name(),
             "export_type": "complete"
         })
     } else {
@@ -190,6 +133,7 @@ pub async fn export_data_as_json(
             "total": documents.len(),
             "exported_at": Utc::now().to_rfc3339(),
             "resource": resource.resource_name(),
+            "timezone": tz.name(),
             "export_type": "paginated",
             "page": page,
             "per_page": per_page
@@ -217,8 +161,5 @@ pub async fn export_data_as_json(
         info!("✅ Exported {} records as JSON (page {})", documents.len(), page);
     }
     
-    Ok(HttpResponse::Ok()
-        .content_type("application/json")
-        .append_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
-        .body(json_string))
+    Ok(export_response("application/json", &filename, json_string.into_bytes(), wants_gzip(req, query)))
 }
\ No newline at end of file
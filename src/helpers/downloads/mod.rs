@@ -1,2 +1,170 @@
 pub mod csv_download;
-pub mod json_download;
\ No newline at end of file
+pub mod export_jobs;
+pub mod json_download;
+pub mod ndjson_download;
+pub mod pdf_download;
+
+use actix_web::{http::header, web::Bytes, HttpRequest, HttpResponse};
+use flate2::{write::GzEncoder, Compression};
+use futures::{Stream, StreamExt};
+use std::io::Write;
+
+use crate::helpers::list_query::ListQuery;
+
+/// Whether the client asked for a gzipped export, via `compress=gzip` or `Accept-Encoding`
+pub fn wants_gzip(req: &HttpRequest, query: &ListQuery) -> bool {
+    if query.wants_gzip() {
+        return true;
+    }
+
+    req.headers()
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accepted| {
+            accepted
+                .split(',')
+                .any(|encoding| encoding.split(';').next().unwrap_or("").trim().eq_ignore_ascii_case("gzip"))
+        })
+}
+
+/// Gzip a complete export body
+pub fn gzip_bytes(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+/// Gzip a streamed export chunk by chunk, without buffering the whole body
+pub fn gzip_stream<S>(stream: S) -> impl Stream<Item = Result<Bytes, actix_web::Error>>
+where
+    S: Stream<Item = Result<Bytes, actix_web::Error>>,
+{
+    let encoder = Some(GzEncoder::new(Vec::new(), Compression::default()));
+    futures::stream::unfold((Box::pin(stream), encoder), |(mut stream, mut encoder)| async move {
+        let active = encoder.as_mut()?;
+        loop {
+            match stream.next().await {
+                Some(Ok(chunk)) => {
+                    if let Err(e) = active.write_all(&chunk) {
+                        return Some((Err(actix_web::error::ErrorInternalServerError(e)), (stream, None)));
+                    }
+                    let compressed = std::mem::take(active.get_mut());
+                    if !compressed.is_empty() {
+                        return Some((Ok(Bytes::from(compressed)), (stream, encoder)));
+                    }
+                }
+                Some(Err(e)) => return Some((Err(e), (stream, None))),
+                None => {
+                    let finished = encoder.take()?.finish().map(Bytes::from).map_err(actix_web::error::ErrorInternalServerError);
+                    return Some((finished, (stream, None)));
+                }
+            }
+        }
+    })
+}
+
+fn attachment(filename: &str, gzip: bool) -> String {
+    let suffix = if gzip { ".gz" } else { "" };
+    format!("attachment; filename=\"{}{}\"", filename, suffix)
+}
+
+/// Download response for a fully rendered export, gzipped when `gzip` is set
+pub fn export_response(content_type: &str, filename: &str, body: Vec<u8>, gzip: bool) -> HttpResponse {
+    let mut response = HttpResponse::Ok();
+    response
+        .content_type(content_type)
+        .append_header((header::CONTENT_DISPOSITION, attachment(filename, gzip)));
+
+    if !gzip {
+        return response.body(body);
+    }
+    match gzip_bytes(&body) {
+        Ok(compressed) => response
+            .append_header((header::CONTENT_ENCODING, "gzip"))
+            .body(compressed),
+        Err(e) => {
+            tracing::error!("Failed to gzip export {}: {}", filename, e);
+            HttpResponse::InternalServerError().body("Failed to compress export")
+        }
+    }
+}
+
+/// Download response for a streamed export, gzipped on the fly when `gzip` is set
+pub fn streaming_export_response<S>(content_type: &str, filename: &str, stream: S, gzip: bool) -> HttpResponse
+where
+    S: Stream<Item = Result<Bytes, actix_web::Error>> + 'static,
+{
+    let mut response = HttpResponse::Ok();
+    response
+        .content_type(content_type)
+        .append_header((header::CONTENT_DISPOSITION, attachment(filename, gzip)));
+
+    if gzip {
+        response
+            .append_header((header::CONTENT_ENCODING, "gzip"))
+            .streaming(gzip_stream(stream))
+    } else {
+        response.streaming(stream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn gunzip(data: &[u8]) -> String {
+        let mut out = String::new();
+        GzDecoder::new(data).read_to_string(&mut out).unwrap();
+        out
+    }
+
+    fn gzip_requested(req: &HttpRequest) -> bool {
+        wants_gzip(req, &ListQuery::parse(req.query_string()))
+    }
+
+    #[test]
+    fn gzip_is_opt_in() {
+        assert!(!gzip_requested(&TestRequest::get().uri("/list?download=csv").to_http_request()));
+        assert!(gzip_requested(&TestRequest::get().uri("/list?download=csv&compress=gzip").to_http_request()));
+        assert!(gzip_requested(
+            &TestRequest::get()
+                .uri("/list?download=csv")
+                .insert_header((header::ACCEPT_ENCODING, "br, gzip;q=0.8"))
+                .to_http_request()
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn compressed_export_matches_uncompressed() {
+        let content = "id,title\n1,First\n2,Second\n".repeat(50);
+
+        let plain = export_response("text/csv", "posts.csv", content.clone().into_bytes(), false);
+        let plain = actix_web::body::to_bytes(plain.into_body()).await.unwrap();
+
+        let gzipped = export_response("text/csv", "posts.csv", content.clone().into_bytes(), true);
+        assert_eq!(gzipped.headers().get(header::CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(
+            gzipped.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+            "attachment; filename=\"posts.csv.gz\""
+        );
+        let gzipped = actix_web::body::to_bytes(gzipped.into_body()).await.unwrap();
+
+        assert_eq!(gunzip(&gzipped), String::from_utf8(plain.to_vec()).unwrap());
+    }
+
+    #[actix_rt::test]
+    async fn streamed_export_still_works_compressed() {
+        let lines: Vec<Result<Bytes, actix_web::Error>> = (0..100)
+            .map(|i| Ok(Bytes::from(format!("{{\"n\":{}}}\n", i))))
+            .collect();
+        let expected: String = (0..100).map(|i| format!("{{\"n\":{}}}\n", i)).collect();
+
+        let response = streaming_export_response("application/x-ndjson", "posts.ndjson", futures::stream::iter(lines), true);
+        let body = actix_web::body::to_bytes(response.into_body()).await.unwrap();
+
+        assert_eq!(gunzip(&body), expected);
+    }
+}
@@ -0,0 +1,258 @@
+// crates/adminx/src/helpers/downloads/pdf_download.rs
+use actix_web::{HttpRequest, HttpResponse};
+use crate::helpers::downloads::{export_response, wants_gzip};
+use std::sync::Arc;
+use tracing::info;
+use futures::TryStreamExt;
+use crate::AdmixResource;
+use chrono::Utc;
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str};
+use serde_json::Value;
+use crate::utils::rbac::{can_read_field, roles_from_request};
+use crate::utils::timezone::request_timezone;
+use crate::helpers::list_query::ListQuery;
+use crate::helpers::resource_helper::{build_list_rows, default_list_structure, query_error};
+use crate::helpers::downloads::json_download::export_find_options;
+
+// A4 landscape, in points
+const PAGE_WIDTH: f32 = 842.0;
+const PAGE_HEIGHT: f32 = 595.0;
+const MARGIN: f32 = 36.0;
+const FONT_SIZE: f32 = 8.0;
+const TITLE_SIZE: f32 = 12.0;
+const ROW_HEIGHT: f32 = 14.0;
+// Rough Helvetica advance, used to truncate cells to their column
+const CHAR_WIDTH: f32 = FONT_SIZE * 0.55;
+
+/// `(field, label)` pairs from `list_structure()`, minus columns `roles` may not read
+pub fn pdf_columns(resource: &dyn AdmixResource, roles: &[String]) -> Vec<(String, String)> {
+    let list_structure = resource.list_structure().unwrap_or_else(|| default_list_structure(resource));
+    let columns: Vec<(String, String)> = list_structure.get("columns")
+        .and_then(|c| c.as_array())
+        .map(|cols| {
+            cols.iter()
+                .filter_map(|col| {
+                    let field = col.get("field").and_then(|f| f.as_str())?;
+                    let label = col.get("label").and_then(|l| l.as_str()).unwrap_or(field);
+                    Some((field.to_string(), label.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_else(|| {
+            let timestamps = resource.timestamps();
+            let mut default_cols = vec![("id".to_string(), "ID".to_string())];
+            for field in resource.permit_keys() {
+                if field != "_id" && !timestamps.is_some_and(|(created, updated)| field == created || field == updated) {
+                    default_cols.push((field.to_string(), field.to_string()));
+                }
+            }
+            if let Some((created, _)) = timestamps {
+                default_cols.push((created.to_string(), "Created At".to_string()));
+            }
+            default_cols
+        });
+
+    columns
+        .into_iter()
+        .filter(|(field, _)| {
+            let root = field.split('.').next().unwrap_or(field);
+            field == "id" || can_read_field(resource, roles, root)
+        })
+        .collect()
+}
+
+/// Display text for a list row value
+fn cell_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Encode text for the built-in WinAnsi fonts, truncated to `max_chars`.
+/// Characters outside Latin-1 are replaced with `?`.
+fn pdf_text(text: &str, max_chars: usize) -> Vec<u8> {
+    let single_line = text.replace(['\r', '\n', '\t'], " ");
+    let mut chars: Vec<char> = single_line.chars().collect();
+    if chars.len() > max_chars {
+        chars.truncate(max_chars.saturating_sub(3));
+        chars.extend("...".chars());
+    }
+    chars
+        .into_iter()
+        .map(|c| match c as u32 {
+            0x20..=0x7E | 0xA0..=0xFF => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Render a titled table as a PDF, splitting rows across as many pages as needed
+pub fn render_table_pdf(title: &str, labels: &[String], rows: &[Vec<String>]) -> Vec<u8> {
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let font_id = Ref::new(3);
+    let bold_font_id = Ref::new(4);
+    let font = Name(b"F1");
+    let bold_font = Name(b"F2");
+
+    let column_width = (PAGE_WIDTH - 2.0 * MARGIN) / labels.len().max(1) as f32;
+    let max_chars = ((column_width - 4.0) / CHAR_WIDTH).max(1.0) as usize;
+    let table_top = PAGE_HEIGHT - MARGIN - TITLE_SIZE - ROW_HEIGHT;
+    let rows_per_page = (((table_top - MARGIN) / ROW_HEIGHT) as usize).saturating_sub(1).max(1);
+
+    let mut pages: Vec<&[Vec<String>]> = rows.chunks(rows_per_page).collect();
+    if pages.is_empty() {
+        pages.push(&[]);
+    }
+    let page_ids: Vec<Ref> = (0..pages.len()).map(|i| Ref::new(5 + 2 * i as i32)).collect();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id).kids(page_ids.iter().copied()).count(pages.len() as i32);
+    pdf.type1_font(font_id).base_font(Name(b"Helvetica")).encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.type1_font(bold_font_id).base_font(Name(b"Helvetica-Bold")).encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    let page_count = pages.len();
+    for (index, page_rows) in pages.into_iter().enumerate() {
+        let page_id = page_ids[index];
+        let content_id = Ref::new(page_id.get() + 1);
+
+        let mut content = Content::new();
+        let heading = format!("{} (page {} of {})", title, index + 1, page_count);
+        content.begin_text();
+        content.set_font(bold_font, TITLE_SIZE);
+        content.next_line(MARGIN, PAGE_HEIGHT - MARGIN - TITLE_SIZE);
+        content.show(Str(&pdf_text(&heading, usize::MAX)));
+        content.end_text();
+
+        let mut y = table_top;
+        for (column, label) in labels.iter().enumerate() {
+            content.begin_text();
+            content.set_font(bold_font, FONT_SIZE);
+            content.next_line(MARGIN + column as f32 * column_width, y);
+            content.show(Str(&pdf_text(label, max_chars)));
+            content.end_text();
+        }
+        content.set_line_width(0.5);
+        content.move_to(MARGIN, y - 4.0);
+        content.line_to(PAGE_WIDTH - MARGIN, y - 4.0);
+        content.stroke();
+
+        for row in page_rows {
+            y -= ROW_HEIGHT;
+            for (column, cell) in row.iter().enumerate() {
+                content.begin_text();
+                content.set_font(font, FONT_SIZE);
+                content.next_line(MARGIN + column as f32 * column_width, y);
+                content.show(Str(&pdf_text(cell, max_chars)));
+                content.end_text();
+            }
+        }
+
+        let mut page = pdf.page(page_id);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+        page.parent(page_tree_id);
+        page.contents(content_id);
+        let mut resources = page.resources();
+        let mut fonts = resources.fonts();
+        fonts.pair(font, font_id);
+        fonts.pair(bold_font, bold_font_id);
+        fonts.finish();
+        resources.finish();
+        page.finish();
+
+        pdf.stream(content_id, &content.finish());
+    }
+
+    pdf.finish()
+}
+
+/// Export the filtered list as a printable PDF table, capped at `max_pdf_rows()`
+pub async fn export_data_as_pdf(
+    resource: &Arc<Box<dyn AdmixResource>>,
+    req: &HttpRequest,
+    query: &ListQuery,
+) -> Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let collection = resource.try_get_collection()?;
+    let resource_ref = resource.as_ref().as_ref();
+
+    // Same filters as the list view
+    let filter_doc = query.filter_document(resource_ref);
+
+    info!("Exporting PDF with filters: {:?}", filter_doc);
+
+    // Bound memory: never render more than the resource's row cap
+    let max_rows = resource.max_pdf_rows() as i64;
+    let mut find_options = export_find_options(resource_ref, query);
+    find_options.limit = Some(find_options.limit.map_or(max_rows, |limit| limit.min(max_rows)));
+
+    let mut cursor = collection.find(filter_doc, find_options).await
+        .map_err(|e| query_error(resource.resource_name(), &e))?;
+
+    let mut documents = Vec::new();
+    while let Some(doc) = cursor.try_next().await.map_err(|e| query_error(resource.resource_name(), &e))? {
+        documents.push(doc);
+    }
+
+    let columns = pdf_columns(resource_ref, &roles_from_request(req));
+    let fields: Vec<String> = columns.iter().map(|(field, _)| field.clone()).collect();
+    let labels: Vec<String> = columns.into_iter().map(|(_, label)| label).collect();
+
+    let tz = request_timezone(req);
+    let rows: Vec<Vec<String>> = build_list_rows(resource_ref, &documents, &fields, tz)
+        .await
+        .iter()
+        .map(|row| fields.iter().map(|field| cell_text(row.get(field))).collect())
+        .collect();
+
+    let title = format!("{} (times in {})", resource.resource_name().replace('_', " "), tz.name());
+    let pdf = render_table_pdf(&title, &labels, &rows);
+
+    let filename = if query.complete {
+        format!("{}_{}_complete.pdf",
+                resource.resource_name(),
+                Utc::now().format("%Y%m%d_%H%M%S"))
+    } else {
+        format!("{}_page{}_{}.pdf",
+                resource.resource_name(),
+                query.page,
+                Utc::now().format("%Y%m%d_%H%M%S"))
+    };
+
+    info!("✅ Exported {} records as PDF", rows.len());
+
+    Ok(export_response("application/pdf", &filename, pdf, wants_gzip(req, query)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header;
+
+    #[actix_rt::test]
+    async fn pdf_response_has_pdf_content_type_and_body() {
+        let labels = vec!["ID".to_string(), "Title".to_string(), "Created At".to_string()];
+        let rows: Vec<Vec<String>> = (0..120)
+            .map(|i| vec![i.to_string(), format!("Post (#{}) — café", i), "2024-01-01 00:00:00".to_string()])
+            .collect();
+
+        let pdf = render_table_pdf("posts", &labels, &rows);
+        let response = export_response("application/pdf", "posts.pdf", pdf, false);
+
+        assert_eq!(response.headers().get(header::CONTENT_TYPE).unwrap(), "application/pdf");
+        let body = actix_web::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(!body.is_empty());
+        assert!(body.starts_with(b"%PDF"));
+        // 120 rows don't fit on one landscape page
+        assert!(String::from_utf8_lossy(&body).contains("/Count 4"));
+    }
+
+    #[test]
+    fn cells_are_truncated_and_latin1_encoded() {
+        assert_eq!(pdf_text("café", 10), b"caf\xe9".to_vec());
+        assert_eq!(pdf_text("a — b\nc", 10), b"a ? b c".to_vec());
+        assert_eq!(pdf_text("abcdefghij", 6), b"abc...".to_vec());
+    }
+}
@@ -0,0 +1,115 @@
+// crates/adminx/src/helpers/error_pages.rs
+use actix_web::{
+    body::MessageBody,
+    dev::ServiceResponse,
+    http::header,
+    middleware::ErrorHandlerResponse,
+    web, HttpRequest, HttpResponse,
+};
+use std::fmt;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::configs::initializer::AdminxConfig;
+use crate::helpers::template_helper::{error_page_context, ADMINX_TEMPLATES};
+use crate::utils::database::get_adminx_config;
+
+/// Pages shown for unmatched `/adminx` paths and internal errors.
+///
+/// Set a custom implementation with `AdminxConfig::with_error_pages`.
+pub trait ErrorPages: Send + Sync {
+    fn not_found(&self, req: &HttpRequest) -> HttpResponse;
+    fn server_error(&self, req: &HttpRequest) -> HttpResponse;
+}
+
+impl fmt::Debug for dyn ErrorPages {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ErrorPages")
+    }
+}
+
+/// Default pages rendered from `errors/404.html.tera` and `errors/500.html.tera`
+#[derive(Debug, Default, Clone)]
+pub struct TemplateErrorPages;
+
+impl ErrorPages for TemplateErrorPages {
+    fn not_found(&self, _req: &HttpRequest) -> HttpResponse {
+        let html = ADMINX_TEMPLATES
+            .render("errors/404.html.tera", &error_page_context())
+            .unwrap_or_else(|_| "<h1>404 - Page Not Found</h1>".to_string());
+        HttpResponse::NotFound().content_type("text/html").body(html)
+    }
+
+    fn server_error(&self, _req: &HttpRequest) -> HttpResponse {
+        let mut ctx = error_page_context();
+        ctx.insert("error_message", "An internal server error occurred.");
+        let html = ADMINX_TEMPLATES
+            .render("errors/500.html.tera", &ctx)
+            .unwrap_or_else(|_| "<h1>500 - Internal Server Error</h1>".to_string());
+        HttpResponse::InternalServerError().content_type("text/html").body(html)
+    }
+}
+
+/// The error pages configured for this app: the `AdminxConfig` app data, else the
+/// globally registered config, else the template defaults
+fn configured_error_pages(req: &HttpRequest) -> Arc<dyn ErrorPages> {
+    req.app_data::<web::Data<AdminxConfig>>()
+        .map(|config| config.error_pages.clone())
+        .or_else(|| get_adminx_config().map(|config| config.error_pages.clone()))
+        .unwrap_or_else(|| Arc::new(TemplateErrorPages))
+}
+
+/// Default service for the `/adminx` scope
+pub async fn not_found_page(req: HttpRequest) -> HttpResponse {
+    configured_error_pages(&req).not_found(&req)
+}
+
+/// `ErrorHandlers` callback replacing bare 500 responses with the error page.
+/// JSON bodies from the API routes are left alone.
+pub fn server_error_page<B: MessageBody>(res: ServiceResponse<B>) -> actix_web::Result<ErrorHandlerResponse<B>> {
+    let is_json = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("json"));
+    if is_json {
+        return Ok(ErrorHandlerResponse::Response(res.map_into_left_body()));
+    }
+
+    warn!("Internal error on {}, rendering error page", res.request().path());
+    let page = configured_error_pages(res.request()).server_error(res.request());
+    Ok(ErrorHandlerResponse::Response(res.into_response(page).map_into_right_body()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{
+        http::StatusCode,
+        middleware::ErrorHandlers,
+        test::{call_service, init_service, read_body, TestRequest},
+        App,
+    };
+
+    #[actix_rt::test]
+    async fn internal_errors_render_styled_page() {
+        let app = init_service(
+            App::new()
+                .wrap(ErrorHandlers::new().handler(StatusCode::INTERNAL_SERVER_ERROR, server_error_page))
+                .route("/boom", web::get().to(|| async { HttpResponse::InternalServerError().body("boom") }))
+                .route("/api", web::get().to(|| async {
+                    HttpResponse::InternalServerError().json(serde_json::json!({ "error": "boom" }))
+                })),
+        )
+        .await;
+
+        let resp = call_service(&app, TestRequest::get().uri("/boom").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = String::from_utf8(read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains("An internal server error occurred."));
+
+        let resp = call_service(&app, TestRequest::get().uri("/api").to_request()).await;
+        let body = String::from_utf8(read_body(resp).await.to_vec()).unwrap();
+        assert_eq!(body, r#"{"error":"boom"}"#);
+    }
+}
@@ -0,0 +1,135 @@
+// adminx/src/helpers/file_cleanup.rs
+use mongodb::bson::{Bson, Document};
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use tracing::{info, warn};
+
+/// Local paths stored in `fields` of `doc`. Strings and arrays of strings are read;
+/// URLs (anything with a scheme, e.g. S3 or CDN links) and empty values are skipped.
+pub fn local_file_paths(doc: &Document, fields: &[String]) -> Vec<PathBuf> {
+    fn push(value: &Bson, paths: &mut Vec<PathBuf>) {
+        match value {
+            Bson::String(path) if !path.is_empty() && !path.contains("://") => paths.push(PathBuf::from(path)),
+            Bson::Array(items) => items.iter().for_each(|item| push(item, paths)),
+            _ => {}
+        }
+    }
+
+    let mut paths = Vec::new();
+    for field in fields {
+        if let Some(value) = doc.get(field) {
+            push(value, &mut paths);
+        }
+    }
+    paths
+}
+
+/// `path` resolved (symlinks included) when it lies under `root`. Without a root only
+/// relative paths below the working directory qualify. `..` segments are never accepted.
+pub fn contained_path(path: &Path, root: Option<&Path>) -> Option<PathBuf> {
+    if path.components().any(|c| c == Component::ParentDir) {
+        return None;
+    }
+    let base = match root {
+        Some(root) => root.canonicalize().ok()?,
+        None if path.is_absolute() => return None,
+        None => std::env::current_dir().ok()?.canonicalize().ok()?,
+    };
+    let resolved = path.canonicalize().ok()?;
+    resolved.starts_with(&base).then_some(resolved)
+}
+
+/// Remove `paths` from disk, skipping any outside `root` (see `contained_path`).
+/// Files already gone are fine; other failures are logged rather than failing the
+/// delete that triggered the cleanup.
+pub async fn remove_local_files(paths: Vec<PathBuf>, root: Option<PathBuf>) {
+    for stored in paths {
+        let Some(path) = contained_path(&stored, root.as_deref()) else {
+            if stored.exists() {
+                warn!("Not removing {}: it is outside the upload root", stored.display());
+            }
+            continue;
+        };
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => info!("Removed uploaded file {}", path.display()),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => warn!("Could not remove uploaded file {}: {}", path.display(), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AdmixResource;
+    use mongodb::{bson::doc, Collection};
+
+    struct Avatars(PathBuf);
+
+    impl AdmixResource for Avatars {
+        fn new() -> Self { Avatars(PathBuf::new()) }
+        fn resource_name(&self) -> &'static str { "Avatars" }
+        fn base_path(&self) -> &'static str { "avatars" }
+        fn collection_name(&self) -> &'static str { "avatars" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(Avatars(self.0.clone())) }
+        fn supports_file_upload(&self) -> bool { true }
+        fn file_fields(&self) -> Vec<&'static str> { vec!["image"] }
+        fn image_variants(&self) -> Vec<crate::helpers::image_helper::ImageVariant> { vec![("thumb", 64, 64)] }
+        fn upload_root(&self) -> Option<PathBuf> { Some(self.0.clone()) }
+    }
+
+    #[actix_rt::test]
+    async fn default_cleanup_removes_stored_local_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let avatars = Avatars(dir.path().to_path_buf());
+        let image = dir.path().join("a.png");
+        let thumb = dir.path().join("a_thumb.png");
+        let other = dir.path().join("b.png");
+        for path in [&image, &thumb, &other] {
+            std::fs::write(path, b"png").unwrap();
+        }
+        let doc = doc! {
+            "image": image.to_str().unwrap(),
+            "image_thumb": thumb.to_str().unwrap(),
+            "title": other.to_str().unwrap(),
+        };
+
+        avatars.cleanup_files(&doc).await;
+        assert!(!image.exists());
+        assert!(!thumb.exists());
+        // Only upload fields are touched
+        assert!(other.exists());
+
+        // Running again, or on remote URLs, is a no-op
+        avatars.cleanup_files(&doc! { "image": "https://cdn.example.com/a.png" }).await;
+        avatars.cleanup_files(&doc).await;
+    }
+
+    #[actix_rt::test]
+    async fn cleanup_never_leaves_the_upload_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("uploads");
+        std::fs::create_dir(&root).unwrap();
+        let secret = dir.path().join("secret.txt");
+        std::fs::write(&secret, b"keep").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&secret, root.join("link.png")).unwrap();
+
+        let avatars = Avatars(root.clone());
+        for stored in [
+            secret.to_str().unwrap().to_string(),
+            root.join("../secret.txt").to_str().unwrap().to_string(),
+            root.join("link.png").to_str().unwrap().to_string(),
+        ] {
+            avatars.cleanup_files(&doc! { "image": &stored }).await;
+            assert!(secret.exists(), "{} escaped the upload root", stored);
+        }
+
+        // Without a root, absolute and `..` paths are refused outright
+        assert_eq!(contained_path(&secret, None), None);
+        assert_eq!(contained_path(Path::new("../secret.txt"), None), None);
+        assert_eq!(contained_path(&root.join("../secret.txt"), Some(&root)), None);
+        assert_eq!(contained_path(&secret, Some(&root)), None);
+    }
+}
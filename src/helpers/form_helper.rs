@@ -1,6 +1,21 @@
 // /crates/adminx/src/helpers/form_helper.rs
 use convert_case::{Case, Casing};
 use serde_json::{Value, json, Map as JsonMap};
+use std::collections::{BTreeMap, HashMap};
+use tracing::warn;
+
+use crate::error::AdminxError;
+
+/// HTML5 input constraints declared on a form field as `min`, `max`, `minlength`,
+/// `maxlength` and `pattern`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldConstraints {
+    pub min: Option<Value>,
+    pub max: Option<Value>,
+    pub minlength: Option<usize>,
+    pub maxlength: Option<usize>,
+    pub pattern: Option<String>,
+}
 
 /// Converts the dynamic form_structure JSON into a flat list of fields with name/label
 pub fn extract_fields_for_form(form_structure: &JsonMap<String, Value>) -> Vec<Value> {
@@ -34,4 +49,202 @@ pub fn to_map(form: &Value) -> JsonMap<String, Value> {
         Value::Object(map) => map.clone(),
         _ => JsonMap::new(),
     }
-}
\ No newline at end of file
+}
+
+/// Map each field name in a form_structure to its declared `field_type`
+pub fn extract_field_types(form_structure: &Value) -> HashMap<String, String> {
+    let mut types = HashMap::new();
+    if let Some(groups) = form_structure.get("groups").and_then(|g| g.as_array()) {
+        for group in groups {
+            if let Some(group_fields) = group.get("fields").and_then(|f| f.as_array()) {
+                for field in group_fields {
+                    let name = field.get("name").and_then(|n| n.as_str());
+                    let field_type = field.get("field_type").and_then(|t| t.as_str());
+                    if let (Some(name), Some(field_type)) = (name, field_type) {
+                        types.insert(name.to_string(), field_type.to_string());
+                    }
+                }
+            }
+        }
+    }
+    types
+}
+
+/// Map each field name in a form_structure to the constraints it declares; fields
+/// without any are left out
+pub fn extract_field_constraints(form_structure: &Value) -> HashMap<String, FieldConstraints> {
+    let mut constraints = HashMap::new();
+    let groups = form_structure.get("groups").and_then(|g| g.as_array()).into_iter().flatten();
+    for field in groups.filter_map(|group| group.get("fields").and_then(|f| f.as_array())).flatten() {
+        let Some(name) = field.get("name").and_then(|n| n.as_str()) else {
+            continue;
+        };
+        let bound = |key: &str| field.get(key).filter(|v| v.is_number() || v.is_string()).cloned();
+        let length = |key: &str| field.get(key).and_then(|v| v.as_u64()).map(|n| n as usize);
+        let field_constraints = FieldConstraints {
+            min: bound("min"),
+            max: bound("max"),
+            minlength: length("minlength"),
+            maxlength: length("maxlength"),
+            pattern: field.get("pattern").and_then(|p| p.as_str()).map(str::to_string),
+        };
+        if field_constraints != FieldConstraints::default() {
+            constraints.insert(name.to_string(), field_constraints);
+        }
+    }
+    constraints
+}
+
+/// Why `value` breaks `constraints`, if it does. Empty values pass, as in the browser;
+/// `required` is checked separately. Numbers are compared numerically, other bounds
+/// (e.g. ISO dates) as strings; `pattern` must match the whole value.
+pub fn constraint_violation(value: &Value, constraints: &FieldConstraints) -> Option<String> {
+    let text = match value {
+        Value::Null => return None,
+        Value::String(s) if s.is_empty() => return None,
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    let number = value.as_f64().or_else(|| text.trim().parse::<f64>().ok());
+    let below = |bound: &Value| match (number, bound.as_f64()) {
+        (Some(n), Some(bound)) => n < bound,
+        _ => bound.as_str().is_some_and(|bound| text.as_str() < bound),
+    };
+    let above = |bound: &Value| match (number, bound.as_f64()) {
+        (Some(n), Some(bound)) => n > bound,
+        _ => bound.as_str().is_some_and(|bound| text.as_str() > bound),
+    };
+    let bound_text = |bound: &Value| bound.as_str().map_or_else(|| bound.to_string(), str::to_string);
+
+    if let Some(min) = constraints.min.as_ref().filter(|min| below(min)) {
+        return Some(format!("must be at least {}", bound_text(min)));
+    }
+    if let Some(max) = constraints.max.as_ref().filter(|max| above(max)) {
+        return Some(format!("must be at most {}", bound_text(max)));
+    }
+    let length = text.chars().count();
+    if let Some(minlength) = constraints.minlength.filter(|&n| length < n) {
+        return Some(format!("must be at least {} characters", minlength));
+    }
+    if let Some(maxlength) = constraints.maxlength.filter(|&n| length > n) {
+        return Some(format!("must be at most {} characters", maxlength));
+    }
+    if let Some(pattern) = &constraints.pattern {
+        match regex::Regex::new(&format!("^(?:{})$", pattern)) {
+            Ok(regex) if !regex.is_match(&text) => return Some("does not match the required format".to_string()),
+            Ok(_) => {}
+            Err(e) => warn!("Ignoring invalid field pattern '{}': {}", pattern, e),
+        }
+    }
+    None
+}
+
+/// Check each submitted field against its constraints. Fails with every offending field.
+pub fn validate_field_constraints(
+    fields: &JsonMap<String, Value>,
+    constraints: &HashMap<String, FieldConstraints>,
+) -> Result<(), AdminxError> {
+    let violations: BTreeMap<String, String> = fields
+        .iter()
+        .filter_map(|(name, value)| {
+            let message = constraint_violation(value, constraints.get(name)?)?;
+            Some((name.clone(), message))
+        })
+        .collect();
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(AdminxError::Validation(violations))
+    }
+}
+
+/// Prefill `value` from `default` for fields that don't have a value yet (new forms)
+pub fn apply_field_defaults(form_structure: &mut Value) {
+    let Some(groups) = form_structure.get_mut("groups").and_then(|g| g.as_array_mut()) else {
+        return;
+    };
+    for group in groups {
+        let Some(fields) = group.get_mut("fields").and_then(|f| f.as_array_mut()) else {
+            continue;
+        };
+        for field in fields.iter_mut().filter_map(|f| f.as_object_mut()) {
+            let has_value = field
+                .get("value")
+                .is_some_and(|v| !v.is_null() && v.as_str() != Some(""));
+            if has_value {
+                continue;
+            }
+            let default = match field.get("default") {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Bool(b)) => b.to_string(),
+                Some(Value::Number(n)) => n.to_string(),
+                _ => continue,
+            };
+            field.insert("value".to_string(), Value::String(default));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_fill_only_empty_values() {
+        let mut form = json!({
+            "groups": [{
+                "fields": [
+                    { "name": "priority", "default": 3 },
+                    { "name": "status", "default": "open", "value": "closed" },
+                    { "name": "active", "default": true, "value": "" },
+                    { "name": "title" }
+                ]
+            }]
+        });
+        apply_field_defaults(&mut form);
+
+        let fields = &form["groups"][0]["fields"];
+        assert_eq!(fields[0]["value"], "3");
+        assert_eq!(fields[1]["value"], "closed");
+        assert_eq!(fields[2]["value"], "true");
+        assert!(fields[3].get("value").is_none());
+    }
+
+    #[test]
+    fn constraints_are_checked_server_side() {
+        let form = json!({
+            "groups": [{
+                "fields": [
+                    { "name": "sku", "pattern": "[A-Z]{3}-\\d{4}", "maxlength": 8 },
+                    { "name": "quantity", "field_type": "number", "min": 1, "max": 100 },
+                    { "name": "ships_on", "field_type": "date", "min": "2024-01-01" },
+                    { "name": "notes" }
+                ]
+            }]
+        });
+        let constraints = extract_field_constraints(&form);
+        assert_eq!(constraints.len(), 3);
+        assert_eq!(constraints["sku"].maxlength, Some(8));
+
+        let valid = to_map(&json!({ "sku": "ABC-1234", "quantity": 5, "ships_on": "2024-03-01", "notes": "x" }));
+        assert!(validate_field_constraints(&valid, &constraints).is_ok());
+        // Empty values are left to `required`
+        assert!(validate_field_constraints(&to_map(&json!({ "sku": "" })), &constraints).is_ok());
+
+        let invalid = to_map(&json!({ "sku": "abc-1234x", "quantity": "0", "ships_on": "2023-12-31" }));
+        match validate_field_constraints(&invalid, &constraints) {
+            Err(AdminxError::Validation(fields)) => {
+                assert_eq!(fields["sku"], "must be at most 8 characters");
+                assert_eq!(fields["quantity"], "must be at least 1");
+                assert_eq!(fields["ships_on"], "must be at least 2024-01-01");
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+        let wrong_format = to_map(&json!({ "sku": "AB-12345" }));
+        assert_eq!(
+            constraint_violation(&wrong_format["sku"], &constraints["sku"]).as_deref(),
+            Some("does not match the required format")
+        );
+    }
+}
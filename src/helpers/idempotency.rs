@@ -0,0 +1,440 @@
+// crates/adminx/src/helpers/idempotency.rs
+use actix_web::{
+    body::{to_bytes, BoxBody},
+    http::{header, StatusCode},
+    HttpMessage, HttpRequest, HttpResponse,
+};
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime as BsonDateTime};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::helpers::indexes::is_duplicate_key;
+use crate::utils::constants::{IDEMPOTENCY_KEYS_COLLECTION, IDEMPOTENCY_KEY_TTL};
+use crate::utils::database::try_get_adminx_database;
+use crate::utils::structs::Claims;
+
+/// Header API clients send to make a create safe to retry
+pub const IDEMPOTENCY_HEADER: &str = "Idempotency-Key";
+
+/// Hidden field the create forms carry, so a double-clicked submit only inserts once
+pub const IDEMPOTENCY_FORM_FIELD: &str = "_idempotency_key";
+
+/// Longest accepted key; anything larger is ignored rather than stored
+const MAX_KEY_LENGTH: usize = 255;
+
+/// How long a duplicate waits for the first request to finish before giving up with 409
+const PENDING_POLL_ATTEMPTS: u32 = 20;
+const PENDING_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A key scoped to one resource and one user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotencyScope {
+    pub resource: String,
+    pub user: String,
+    pub key: String,
+}
+
+impl IdempotencyScope {
+    /// Read the key from the `Idempotency-Key` header, falling back to the form token
+    pub fn from_request(req: &HttpRequest, resource: &str, user: &str, form_key: Option<&str>) -> Option<Self> {
+        let key = req
+            .headers()
+            .get(IDEMPOTENCY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .or(form_key)
+            .map(str::trim)
+            .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LENGTH)?;
+        Some(Self {
+            resource: resource.to_string(),
+            user: user.to_string(),
+            key: key.to_string(),
+        })
+    }
+
+    /// Record id; the same key from another user or resource is a different record
+    pub fn record_id(&self) -> String {
+        format!("{}:{}:{}", self.resource, self.user, self.key)
+    }
+}
+
+/// The user a create is attributed to: the claims the auth guard stored, if any
+pub fn request_user(req: &HttpRequest) -> String {
+    req.extensions()
+        .get::<Claims>()
+        .map(|claims| claims.sub.clone())
+        .unwrap_or_else(|| "anonymous".to_string())
+}
+
+/// The response replayed for a repeated key
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    /// `Location` of the created document
+    #[serde(default)]
+    pub location: Option<String>,
+    pub body: String,
+}
+
+impl StoredResponse {
+    fn to_response(&self) -> HttpResponse {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK);
+        let mut builder = HttpResponse::build(status);
+        if let Some(content_type) = &self.content_type {
+            builder.content_type(content_type.as_str());
+        }
+        if let Some(location) = &self.location {
+            builder.insert_header((header::LOCATION, location.as_str()));
+        }
+        builder.insert_header(("Idempotent-Replayed", "true"));
+        builder.body(self.body.clone())
+    }
+}
+
+/// A processed (or in-flight) key, stored in `adminx_idempotency_keys`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdempotencyRecord {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub resource: String,
+    pub user: String,
+    pub key: String,
+    /// Id of the created document, when the response carried one
+    pub created_id: Option<String>,
+    /// `None` while the first request is still running
+    pub response: Option<StoredResponse>,
+    pub expires_at: BsonDateTime,
+}
+
+impl IdempotencyRecord {
+    fn pending(scope: &IdempotencyScope, now: BsonDateTime) -> Self {
+        let expires_at = now.timestamp_millis() + IDEMPOTENCY_KEY_TTL.as_millis() as i64;
+        Self {
+            id: scope.record_id(),
+            resource: scope.resource.clone(),
+            user: scope.user.clone(),
+            key: scope.key.clone(),
+            created_id: None,
+            response: None,
+            expires_at: BsonDateTime::from_millis(expires_at),
+        }
+    }
+
+    fn is_expired(&self, now: BsonDateTime) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// Persistence for processed keys
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// Insert a pending record; `Ok(false)` when the id is already taken
+    async fn insert_pending(&self, record: &IdempotencyRecord) -> Result<bool, String>;
+    async fn load(&self, id: &str) -> Result<Option<IdempotencyRecord>, String>;
+    async fn complete(&self, id: &str, created_id: Option<String>, response: &StoredResponse) -> Result<(), String>;
+    async fn remove(&self, id: &str) -> Result<(), String>;
+}
+
+/// Keys stored in the AdminX database
+#[derive(Debug, Default, Clone)]
+pub struct MongoIdempotencyStore;
+
+impl MongoIdempotencyStore {
+    fn collection(&self) -> Result<mongodb::Collection<IdempotencyRecord>, String> {
+        try_get_adminx_database()
+            .map(|db| db.collection::<IdempotencyRecord>(IDEMPOTENCY_KEYS_COLLECTION))
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for MongoIdempotencyStore {
+    async fn insert_pending(&self, record: &IdempotencyRecord) -> Result<bool, String> {
+        match self.collection()?.insert_one(record, None).await {
+            Ok(_) => Ok(true),
+            Err(e) if is_duplicate_key(&e) => Ok(false),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    async fn load(&self, id: &str) -> Result<Option<IdempotencyRecord>, String> {
+        self.collection()?
+            .find_one(doc! { "_id": id }, None)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn complete(&self, id: &str, created_id: Option<String>, response: &StoredResponse) -> Result<(), String> {
+        let response = mongodb::bson::to_bson(response).map_err(|e| e.to_string())?;
+        self.collection()?
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "created_id": created_id, "response": response } },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn remove(&self, id: &str) -> Result<(), String> {
+        self.collection()?
+            .delete_one(doc! { "_id": id }, None)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Id of the created document from a create response body (`{"id": ...}`)
+fn created_id_from_body(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    match value.get("id")? {
+        serde_json::Value::String(id) => Some(id.clone()),
+        serde_json::Value::Object(map) => map.get("$oid").and_then(|oid| oid.as_str()).map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Run `create` at most once per key. A repeated key gets the stored response;
+/// a duplicate arriving while the first is still running waits for it, then
+/// gives up with 409. Failed creates release the key so they can be retried.
+/// Keys expire `IDEMPOTENCY_KEY_TTL` after `now`.
+pub async fn idempotent_create<F>(
+    store: &dyn IdempotencyStore,
+    scope: Option<IdempotencyScope>,
+    now: BsonDateTime,
+    create: F,
+) -> HttpResponse
+where
+    F: Future<Output = HttpResponse>,
+{
+    let Some(scope) = scope else {
+        return create.await;
+    };
+    let record = IdempotencyRecord::pending(&scope, now);
+
+    match reserve(store, &record, now).await {
+        Ok(true) => {}
+        Ok(false) => return replay(store, &record.id).await,
+        Err(e) => {
+            // Losing the store should not block creates
+            warn!("Idempotency store unavailable for {}: {}", record.id, e);
+            return create.await;
+        }
+    }
+
+    let (head, body) = create.await.into_parts();
+    let bytes = match to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            let _ = store.remove(&record.id).await;
+            return HttpResponse::InternalServerError().finish();
+        }
+    };
+
+    if head.status().is_success() {
+        let stored = StoredResponse {
+            status: head.status().as_u16(),
+            content_type: head
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+            location: head
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+            body: String::from_utf8_lossy(&bytes).into_owned(),
+        };
+        let created_id = created_id_from_body(&stored.body);
+        info!("Stored idempotency key {} (created id: {:?})", record.id, created_id);
+        if let Err(e) = store.complete(&record.id, created_id, &stored).await {
+            warn!("Failed to store idempotent response for {}: {}", record.id, e);
+        }
+    } else if let Err(e) = store.remove(&record.id).await {
+        warn!("Failed to release idempotency key {}: {}", record.id, e);
+    }
+
+    head.set_body(BoxBody::new(bytes))
+}
+
+/// Take the key, replacing an expired record left behind by an earlier request
+async fn reserve(store: &dyn IdempotencyStore, record: &IdempotencyRecord, now: BsonDateTime) -> Result<bool, String> {
+    if store.insert_pending(record).await? {
+        return Ok(true);
+    }
+    match store.load(&record.id).await? {
+        Some(existing) if existing.is_expired(now) => {
+            store.remove(&record.id).await?;
+            store.insert_pending(record).await
+        }
+        // Gone between the insert and the load: try once more
+        None => store.insert_pending(record).await,
+        Some(_) => Ok(false),
+    }
+}
+
+async fn replay(store: &dyn IdempotencyStore, id: &str) -> HttpResponse {
+    for _ in 0..PENDING_POLL_ATTEMPTS {
+        match store.load(id).await {
+            Ok(Some(IdempotencyRecord { response: Some(response), .. })) => {
+                info!("Replaying stored response for idempotency key {}", id);
+                return response.to_response();
+            }
+            Ok(Some(_)) => actix_web::rt::time::sleep(PENDING_POLL_INTERVAL).await,
+            // The first request failed and released the key
+            Ok(None) => break,
+            Err(e) => {
+                warn!("Failed to load idempotency key {}: {}", id, e);
+                break;
+            }
+        }
+    }
+    HttpResponse::Conflict().json(serde_json::json!({
+        "error": "A request with this Idempotency-Key is still being processed or has failed; retry with a new key"
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryStore(Mutex<HashMap<String, IdempotencyRecord>>);
+
+    #[async_trait]
+    impl IdempotencyStore for MemoryStore {
+        async fn insert_pending(&self, record: &IdempotencyRecord) -> Result<bool, String> {
+            let mut records = self.0.lock().unwrap();
+            if records.contains_key(&record.id) {
+                return Ok(false);
+            }
+            records.insert(record.id.clone(), record.clone());
+            Ok(true)
+        }
+
+        async fn load(&self, id: &str) -> Result<Option<IdempotencyRecord>, String> {
+            Ok(self.0.lock().unwrap().get(id).cloned())
+        }
+
+        async fn complete(&self, id: &str, created_id: Option<String>, response: &StoredResponse) -> Result<(), String> {
+            if let Some(record) = self.0.lock().unwrap().get_mut(id) {
+                record.created_id = created_id;
+                record.response = Some(response.clone());
+            }
+            Ok(())
+        }
+
+        async fn remove(&self, id: &str) -> Result<(), String> {
+            self.0.lock().unwrap().remove(id);
+            Ok(())
+        }
+    }
+
+    fn now() -> BsonDateTime {
+        BsonDateTime::from_millis(1_700_000_000_000)
+    }
+
+    fn scope(user: &str, key: &str) -> Option<IdempotencyScope> {
+        let req = TestRequest::post().insert_header((IDEMPOTENCY_HEADER, key)).to_http_request();
+        IdempotencyScope::from_request(&req, "posts", user, None)
+    }
+
+    async fn insert(documents: &Mutex<Vec<String>>) -> HttpResponse {
+        let mut documents = documents.lock().unwrap();
+        let id = format!("doc{}", documents.len() + 1);
+        documents.push(id.clone());
+        HttpResponse::Created()
+            .insert_header((header::LOCATION, format!("/adminx/posts/api/{}", id)))
+            .json(serde_json::json!({ "success": true, "id": { "$oid": id } }))
+    }
+
+    async fn body_of(response: HttpResponse) -> (StatusCode, String) {
+        let status = response.status();
+        let bytes = to_bytes(response.into_body()).await.unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[actix_rt::test]
+    async fn repeated_key_creates_one_document() {
+        let store = MemoryStore::default();
+        let documents = Mutex::new(Vec::new());
+
+        let first = body_of(idempotent_create(&store, scope("u1", "abc"), now(), insert(&documents)).await).await;
+        let replayed = idempotent_create(&store, scope("u1", "abc"), now(), insert(&documents)).await;
+        assert_eq!(replayed.headers().get(header::LOCATION).unwrap(), "/adminx/posts/api/doc1");
+        let second = body_of(replayed).await;
+
+        assert_eq!(documents.lock().unwrap().len(), 1);
+        assert_eq!(first.0, StatusCode::CREATED);
+        assert_eq!(first, second);
+        let record = store.load("posts:u1:abc").await.unwrap().unwrap();
+        assert_eq!(record.created_id.as_deref(), Some("doc1"));
+    }
+
+    #[actix_rt::test]
+    async fn keys_are_scoped_per_user_and_missing_keys_always_run() {
+        let store = MemoryStore::default();
+        let documents = Mutex::new(Vec::new());
+
+        idempotent_create(&store, scope("u1", "abc"), now(), insert(&documents)).await;
+        idempotent_create(&store, scope("u2", "abc"), now(), insert(&documents)).await;
+        idempotent_create(&store, None, now(), insert(&documents)).await;
+        idempotent_create(&store, None, now(), insert(&documents)).await;
+
+        assert_eq!(documents.lock().unwrap().len(), 4);
+    }
+
+    #[actix_rt::test]
+    async fn failed_creates_release_the_key() {
+        let store = MemoryStore::default();
+        let attempts = AtomicUsize::new(0);
+        let failing = || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            HttpResponse::BadRequest().finish()
+        };
+
+        idempotent_create(&store, scope("u1", "abc"), now(), failing()).await;
+        idempotent_create(&store, scope("u1", "abc"), now(), failing()).await;
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert!(store.load("posts:u1:abc").await.unwrap().is_none());
+    }
+
+    #[actix_rt::test]
+    async fn keys_expire_on_the_given_clock() {
+        let store = MemoryStore::default();
+        let documents = Mutex::new(Vec::new());
+        let later = |by: Duration| BsonDateTime::from_millis(now().timestamp_millis() + by.as_millis() as i64);
+
+        idempotent_create(&store, scope("u1", "abc"), now(), insert(&documents)).await;
+        store.0.lock().unwrap().get_mut("posts:u1:abc").unwrap().response = None;
+        // Still pending just before the TTL runs out, taken over once it has
+        let stuck = idempotent_create(&store, scope("u1", "abc"), later(IDEMPOTENCY_KEY_TTL - Duration::from_secs(1)), insert(&documents)).await;
+        assert_eq!(stuck.status(), StatusCode::CONFLICT);
+        idempotent_create(&store, scope("u1", "abc"), later(IDEMPOTENCY_KEY_TTL), insert(&documents)).await;
+
+        assert_eq!(documents.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn header_wins_over_form_token() {
+        let req = TestRequest::post().insert_header((IDEMPOTENCY_HEADER, "from-header")).to_http_request();
+        let scope = IdempotencyScope::from_request(&req, "posts", "u1", Some("from-form")).unwrap();
+        assert_eq!(scope.key, "from-header");
+
+        let req = TestRequest::post().to_http_request();
+        let scope = IdempotencyScope::from_request(&req, "posts", "u1", Some("from-form")).unwrap();
+        assert_eq!(scope.record_id(), "posts:u1:from-form");
+        assert!(IdempotencyScope::from_request(&req, "posts", "u1", Some("  ")).is_none());
+    }
+}
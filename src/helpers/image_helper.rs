@@ -0,0 +1,143 @@
+// crates/adminx/src/helpers/image_helper.rs
+use image::{ImageFormat, ImageReader};
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::Cursor;
+use tracing::{info, warn};
+
+use crate::error::AdminxError;
+
+/// A resized copy of an uploaded image: `(name, max_width, max_height)`
+pub type ImageVariant = (&'static str, u32, u32);
+
+/// Storage key for a variant: `photo.png` + `thumb` → `photo_thumb.png`
+pub fn variant_filename(filename: &str, variant: &str) -> String {
+    match filename.rsplit_once('.') {
+        Some((stem, ext)) => format!("{}_{}.{}", stem, variant, ext),
+        None => format!("{}_{}", filename, variant),
+    }
+}
+
+/// Resize `file_data` into every variant, keeping the aspect ratio and the
+/// original encoding. Returns `None` when the data is not a decodable image.
+pub fn resize_variants(file_data: &[u8], variants: &[ImageVariant]) -> Option<Vec<(&'static str, Vec<u8>)>> {
+    let reader = ImageReader::new(Cursor::new(file_data)).with_guessed_format().ok()?;
+    let format = reader.format()?;
+    let image = reader.decode().ok()?;
+    // Formats without an encoder fall back to PNG
+    let output_format = match format {
+        ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::Gif | ImageFormat::WebP => format,
+        _ => ImageFormat::Png,
+    };
+
+    let mut resized = Vec::with_capacity(variants.len());
+    for (name, width, height) in variants {
+        let mut bytes = Vec::new();
+        if let Err(e) = image.thumbnail(*width, *height).write_to(&mut Cursor::new(&mut bytes), output_format) {
+            warn!("Failed to encode {} variant: {}", name, e);
+            continue;
+        }
+        resized.push((*name, bytes));
+    }
+    Some(resized)
+}
+
+/// Image-aware upload for `process_file_upload`: stores the original and, for
+/// images, one resized copy per `image_variants()` entry.
+///
+/// `store(filename, bytes)` persists a file and returns its public URL. The result
+/// maps `field_name` to the original's URL and `<field_name>_<variant>` to each variant's.
+/// Non-image uploads are stored untouched without variants.
+pub async fn process_image_upload<S, Fut>(
+    variants: &[ImageVariant],
+    field_name: &str,
+    file_data: Vec<u8>,
+    filename: &str,
+    mut store: S,
+) -> Result<HashMap<String, String>, AdminxError>
+where
+    S: FnMut(String, Vec<u8>) -> Fut,
+    Fut: Future<Output = Result<String, AdminxError>>,
+{
+    let resized = if variants.is_empty() {
+        Vec::new()
+    } else {
+        resize_variants(&file_data, variants).unwrap_or_else(|| {
+            info!("Upload {} is not an image, storing without variants", filename);
+            Vec::new()
+        })
+    };
+
+    let mut urls = HashMap::new();
+    urls.insert(field_name.to_string(), store(filename.to_string(), file_data).await?);
+
+    for (name, bytes) in resized {
+        let url = store(variant_filename(filename, name), bytes).await?;
+        urls.insert(format!("{}_{}", field_name, name), url);
+    }
+
+    Ok(urls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, RgbImage};
+    use std::sync::{Arc, Mutex};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        RgbImage::new(width, height)
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    fn recording_store(
+        stored: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+    ) -> impl FnMut(String, Vec<u8>) -> std::future::Ready<Result<String, AdminxError>> {
+        move |name, bytes| {
+            stored.lock().unwrap().insert(name.clone(), bytes);
+            std::future::ready(Ok(format!("https://cdn.example.com/{}", name)))
+        }
+    }
+
+    #[actix_rt::test]
+    async fn png_upload_produces_thumbnail_variant() {
+        let stored = Arc::new(Mutex::new(HashMap::new()));
+        let urls = process_image_upload(
+            &[("thumb", 200, 200)],
+            "image_url",
+            png(400, 300),
+            "photo.png",
+            recording_store(stored.clone()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(urls["image_url"], "https://cdn.example.com/photo.png");
+        assert_eq!(urls["image_url_thumb"], "https://cdn.example.com/photo_thumb.png");
+
+        let stored = stored.lock().unwrap();
+        let thumb = image::load_from_memory(&stored["photo_thumb.png"]).unwrap();
+        assert_eq!(thumb.dimensions(), (200, 150));
+        assert_eq!(image::guess_format(&stored["photo_thumb.png"]).unwrap(), ImageFormat::Png);
+    }
+
+    #[actix_rt::test]
+    async fn non_image_upload_passes_through() {
+        let stored = Arc::new(Mutex::new(HashMap::new()));
+        let urls = process_image_upload(
+            &[("thumb", 200, 200)],
+            "attachment",
+            b"%PDF-1.4 not an image".to_vec(),
+            "report.pdf",
+            recording_store(stored.clone()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(urls.len(), 1);
+        assert_eq!(stored.lock().unwrap()["report.pdf"], b"%PDF-1.4 not an image".to_vec());
+    }
+}
@@ -0,0 +1,363 @@
+// crates/adminx/src/helpers/import.rs
+use actix_web::{HttpResponse, ResponseError};
+use async_trait::async_trait;
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use mongodb::options::{InsertManyOptions, UpdateOptions};
+use mongodb::Collection;
+use serde_json::{json, Map, Value};
+use tracing::{info, warn};
+
+use crate::AdmixResource;
+use crate::error::AdminxError;
+use crate::helpers::rich_text::sanitize_rich_text_fields;
+use crate::utils::clock::current_clock;
+use crate::utils::constants::IMPORT_BATCH_SIZE;
+use crate::utils::rbac::writable_keys;
+use crate::utils::structs::Claims;
+
+/// Where imported documents are written
+#[async_trait]
+pub trait ImportSink: Send + Sync {
+    /// Insert new documents, each carrying its `_id`; one result per document, in order
+    async fn insert_batch(&self, documents: Vec<Document>) -> Vec<Result<(), String>>;
+    /// `$set` `set` on the document with `id`, creating it with `set_on_insert` too when
+    /// missing. `Ok(true)` when it was inserted.
+    async fn upsert(&self, id: ObjectId, set: Document, set_on_insert: Document) -> Result<bool, String>;
+}
+
+/// Imports into the resource's collection
+pub struct CollectionImportSink(pub Collection<Document>);
+
+#[async_trait]
+impl ImportSink for CollectionImportSink {
+    async fn insert_batch(&self, documents: Vec<Document>) -> Vec<Result<(), String>> {
+        let count = documents.len();
+        let options = InsertManyOptions::builder().ordered(false).build();
+        match self.0.insert_many(documents, options).await {
+            Ok(_) => vec![Ok(()); count],
+            Err(e) => {
+                use mongodb::error::{BulkWriteFailure, ErrorKind};
+                let ErrorKind::BulkWrite(BulkWriteFailure { write_errors: Some(write_errors), .. }) = e.kind.as_ref() else {
+                    return vec![Err(e.to_string()); count];
+                };
+                let mut results = vec![Ok(()); count];
+                for write_error in write_errors {
+                    if let Some(result) = results.get_mut(write_error.index) {
+                        *result = Err(write_error.message.clone());
+                    }
+                }
+                results
+            }
+        }
+    }
+
+    async fn upsert(&self, id: ObjectId, set: Document, set_on_insert: Document) -> Result<bool, String> {
+        let mut update = doc! { "$set": set };
+        if !set_on_insert.is_empty() {
+            update.insert("$setOnInsert", set_on_insert);
+        }
+        let options = UpdateOptions::builder().upsert(true).build();
+        self.0
+            .update_one(doc! { "_id": id }, update, options)
+            .await
+            .map(|result| result.upserted_id.is_some())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Records from an import body: a JSON array, or one JSON document per line (NDJSON)
+pub fn parse_import_body(body: &[u8]) -> Result<Vec<Value>, AdminxError> {
+    let text = std::str::from_utf8(body)
+        .map_err(|_| AdminxError::BadRequest("Import body must be UTF-8".into()))?
+        .trim();
+    if text.starts_with('[') {
+        return serde_json::from_str(text)
+            .map_err(|e| AdminxError::BadRequest(format!("Invalid JSON array: {}", e)));
+    }
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| AdminxError::BadRequest(format!("Invalid JSON on line {}: {}", index + 1, e)))
+        })
+        .collect()
+}
+
+/// The `_id` (or exported `id`) of an import record, as a hex string or `{"$oid": ...}`
+fn record_id(fields: &mut Map<String, Value>) -> Result<Option<ObjectId>, String> {
+    let Some(value) = fields.remove("_id").or_else(|| fields.remove("id")) else {
+        return Ok(None);
+    };
+    let hex = match &value {
+        Value::String(hex) => Some(hex.as_str()),
+        Value::Object(map) => map.get("$oid").and_then(|oid| oid.as_str()),
+        _ => None,
+    };
+    hex.and_then(|hex| ObjectId::parse_str(hex).ok())
+        .map(Some)
+        .ok_or_else(|| format!("Invalid id {}", value))
+}
+
+/// Check one record against the fields `roles` may write and the resource's
+/// `validate()`. Server-managed timestamps are dropped; any other field that isn't
+/// writable fails the record.
+fn prepare_record(
+    resource: &dyn AdmixResource,
+    writable: &[&str],
+    record: Value,
+) -> Result<(Option<ObjectId>, Document), String> {
+    let Value::Object(mut fields) = record else {
+        return Err("Expected a JSON object".to_string());
+    };
+    let id = record_id(&mut fields)?;
+    if let Some((created, updated)) = resource.timestamps() {
+        fields.remove(created);
+        fields.remove(updated);
+    }
+
+    let readonly = resource.readonly_keys();
+    for key in fields.keys() {
+        if readonly.contains(&key.as_str()) {
+            return Err(format!("Field '{}' is read-only", key));
+        }
+        if !writable.contains(&key.as_str()) {
+            return Err(format!("Field '{}' is not permitted", key));
+        }
+    }
+    sanitize_rich_text_fields(&mut fields, &resource.field_types());
+    resource.validate(&fields).map_err(|e| e.to_string())?;
+
+    let document = mongodb::bson::to_document(&Value::Object(fields)).map_err(|e| e.to_string())?;
+    Ok((id, document))
+}
+
+fn record_result(index: usize, id: Option<ObjectId>, outcome: Result<&str, String>) -> Value {
+    let id = id.map(|id| id.to_hex());
+    match outcome {
+        Ok(status) => json!({ "index": index, "id": id, "status": status }),
+        Err(error) => json!({ "index": index, "id": id, "status": "failed", "error": error }),
+    }
+}
+
+/// Upsert `records` by `_id`, inserting those without one, `IMPORT_BATCH_SIZE` at a time.
+/// Returns the counts and one result per record, in input order.
+pub async fn import_records(
+    resource: &dyn AdmixResource,
+    roles: &[String],
+    records: Vec<Value>,
+    sink: &dyn ImportSink,
+) -> Value {
+    let writable = writable_keys(resource, roles);
+    let timestamps = resource.timestamps();
+    let soft_delete_field = resource.soft_delete_field();
+    let clock = current_clock();
+    let total = records.len();
+    let mut results = Vec::with_capacity(total);
+
+    let mut records = records.into_iter().enumerate().peekable();
+    while records.peek().is_some() {
+        let mut new_documents = Vec::new();
+        for (index, record) in records.by_ref().take(IMPORT_BATCH_SIZE) {
+            let (id, mut fields) = match prepare_record(resource, &writable, record) {
+                Ok(prepared) => prepared,
+                Err(error) => {
+                    results.push(record_result(index, None, Err(error)));
+                    continue;
+                }
+            };
+
+            let now = clock.bson_now();
+            let mut on_insert = Document::new();
+            if let Some((created, updated)) = timestamps {
+                fields.insert(updated, now);
+                on_insert.insert(created, now);
+            }
+            if let Some(field) = soft_delete_field.filter(|field| !fields.contains_key(*field)) {
+                on_insert.insert(field, false);
+            }
+
+            match id {
+                Some(id) => {
+                    if fields.is_empty() {
+                        results.push(record_result(index, Some(id), Err("No fields to import".to_string())));
+                        continue;
+                    }
+                    let outcome = sink.upsert(id, fields, on_insert).await
+                        .map(|inserted| if inserted { "inserted" } else { "updated" });
+                    results.push(record_result(index, Some(id), outcome));
+                }
+                None => {
+                    let id = ObjectId::new();
+                    let mut document = doc! { "_id": id };
+                    document.extend(on_insert);
+                    document.extend(fields);
+                    new_documents.push((index, id, document));
+                }
+            }
+        }
+
+        if new_documents.is_empty() {
+            continue;
+        }
+        let (placed, documents): (Vec<(usize, ObjectId)>, Vec<Document>) = new_documents
+            .into_iter()
+            .map(|(index, id, document)| ((index, id), document))
+            .unzip();
+        let outcomes = sink.insert_batch(documents).await;
+        for ((index, id), outcome) in placed.into_iter().zip(outcomes) {
+            results.push(record_result(index, Some(id), outcome.map(|_| "inserted")));
+        }
+    }
+
+    results.sort_by_key(|result| result["index"].as_u64());
+    let count = |status: &str| results.iter().filter(|result| result["status"] == status).count();
+    let (inserted, updated, failed) = (count("inserted"), count("updated"), count("failed"));
+    json!({
+        "success": failed == 0,
+        "total": total,
+        "inserted": inserted,
+        "updated": updated,
+        "failed": failed,
+        "results": results,
+    })
+}
+
+/// POST /import - Upsert a JSON array or NDJSON body into the resource's collection
+pub async fn import_documents(resource: &dyn AdmixResource, claims: &Claims, body: &[u8]) -> HttpResponse {
+    let records = match parse_import_body(body) {
+        Ok(records) => records,
+        Err(e) => return e.error_response(),
+    };
+    let collection = match resource.try_get_collection() {
+        Ok(collection) => collection,
+        Err(e) => return e.error_response(),
+    };
+
+    let mut roles = claims.roles.clone();
+    roles.push(claims.role.clone());
+    let summary = import_records(resource, &roles, records, &CollectionImportSink(collection)).await;
+
+    if summary["failed"].as_u64().unwrap_or(0) > 0 {
+        warn!("Import into {} by {} had {} failed record(s)", resource.resource_name(), claims.email, summary["failed"]);
+    }
+    info!(
+        "📥 Import into {} by {}: {} inserted, {} updated",
+        resource.resource_name(), claims.email, summary["inserted"], summary["updated"]
+    );
+    HttpResponse::Ok().json(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestResource;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    fn posts() -> TestResource {
+        TestResource::named("Posts", "posts")
+            .permit(&["title", "views"])
+            .form(json!({ "groups": [{ "fields": [{ "name": "views", "field_type": "number", "min": 0 }] }] }))
+    }
+
+    #[derive(Default)]
+    struct MemorySink(Mutex<HashMap<ObjectId, Document>>);
+
+    #[async_trait]
+    impl ImportSink for MemorySink {
+        async fn insert_batch(&self, documents: Vec<Document>) -> Vec<Result<(), String>> {
+            let mut stored = self.0.lock().unwrap();
+            documents
+                .into_iter()
+                .map(|document| {
+                    stored.insert(document.get_object_id("_id").unwrap(), document);
+                    Ok(())
+                })
+                .collect()
+        }
+
+        async fn upsert(&self, id: ObjectId, set: Document, set_on_insert: Document) -> Result<bool, String> {
+            let mut stored = self.0.lock().unwrap();
+            let inserted = !stored.contains_key(&id);
+            let document = stored.entry(id).or_insert_with(|| {
+                let mut document = doc! { "_id": id };
+                document.extend(set_on_insert);
+                document
+            });
+            document.extend(set);
+            Ok(inserted)
+        }
+    }
+
+    #[actix_rt::test]
+    async fn import_upserts_by_id_and_inserts_the_rest() {
+        let existing = ObjectId::new();
+        let sink = MemorySink::default();
+        sink.0.lock().unwrap().insert(existing, doc! { "_id": existing, "title": "Old", "views": 1 });
+
+        let records = parse_import_body(json!([
+            { "title": "New" },
+            { "id": existing.to_hex(), "title": "Renamed", "created_at": "2024-01-01T00:00:00Z" },
+            { "title": "Hidden", "secret": "x" },
+        ]).to_string().as_bytes()).unwrap();
+        let summary = import_records(&posts(), &["admin".to_string()], records, &sink).await;
+
+        assert_eq!(summary["total"], 3);
+        assert_eq!(summary["inserted"], 1);
+        assert_eq!(summary["updated"], 1);
+        assert_eq!(summary["failed"], 1);
+        assert_eq!(summary["success"], false);
+        let statuses: Vec<&str> = summary["results"].as_array().unwrap().iter().map(|r| r["status"].as_str().unwrap()).collect();
+        assert_eq!(statuses, vec!["inserted", "updated", "failed"]);
+        assert_eq!(summary["results"][2]["error"], "Field 'secret' is not permitted");
+
+        let stored = sink.0.lock().unwrap();
+        assert_eq!(stored.len(), 2);
+        let updated = &stored[&existing];
+        assert_eq!(updated.get_str("title"), Ok("Renamed"));
+        assert_eq!(updated.get_i32("views"), Ok(1));
+        assert!(updated.get_datetime("updated_at").is_ok());
+        assert!(!updated.contains_key("created_at"));
+        let new_id = ObjectId::parse_str(summary["results"][0]["id"].as_str().unwrap()).unwrap();
+        assert!(stored[&new_id].get_datetime("created_at").is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn unknown_ids_are_inserted_under_that_id() {
+        let id = ObjectId::new();
+        let sink = MemorySink::default();
+        let body = format!("{{\"_id\": {{\"$oid\": \"{}\"}}, \"title\": \"Seed\"}}\n\n{{\"_id\": \"nope\", \"title\": \"x\"}}\n", id.to_hex());
+
+        let records = parse_import_body(body.as_bytes()).unwrap();
+        let summary = import_records(&posts(), &["admin".to_string()], records, &sink).await;
+
+        assert_eq!(summary["inserted"], 1);
+        assert_eq!(summary["failed"], 1);
+        assert_eq!(summary["results"][0]["id"], id.to_hex());
+        assert!(sink.0.lock().unwrap()[&id].get_datetime("created_at").is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn records_failing_validation_are_reported_per_row() {
+        let sink = MemorySink::default();
+        let records = parse_import_body(json!([
+            { "title": "Fine", "views": 3 },
+            { "title": "Negative", "views": -1 },
+        ]).to_string().as_bytes()).unwrap();
+        let summary = import_records(&posts(), &["admin".to_string()], records, &sink).await;
+
+        assert_eq!(summary["inserted"], 1);
+        assert_eq!(summary["failed"], 1);
+        assert_eq!(summary["results"][1]["status"], "failed");
+        assert_eq!(summary["results"][1]["error"], "Validation failed: views must be at least 0");
+        assert_eq!(sink.0.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn malformed_bodies_are_rejected() {
+        assert!(matches!(parse_import_body(b"[{\"title\": 1}"), Err(AdminxError::BadRequest(_))));
+        assert!(matches!(parse_import_body(b"{\"title\": 1}\nnot json"), Err(AdminxError::BadRequest(message)) if message.contains("line 2")));
+        assert_eq!(parse_import_body(b"  \n").unwrap(), Vec::<Value>::new());
+    }
+}
@@ -0,0 +1,219 @@
+// adminx/src/helpers/indexes.rs
+use actix_web::{HttpResponse, ResponseError};
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::options::IndexOptions;
+use mongodb::{Collection, IndexModel};
+use tracing::{error, info, warn};
+
+use crate::error::AdminxError;
+use crate::helpers::downloads::export_jobs::ensure_export_job_indexes;
+use crate::registry::all_resources;
+use crate::utils::mount::admin_path;
+use crate::AdmixResource;
+
+/// MongoDB's duplicate key error code
+pub const DUPLICATE_KEY: i32 = 11000;
+
+/// An index a resource declares in `indexes()`, created by `ensure_indexes`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexSpec {
+    pub keys: Document,
+    pub unique: bool,
+    /// Only index records that aren't soft deleted, so a soft-deleted record doesn't
+    /// hold on to its unique values
+    pub partial: bool,
+}
+
+impl IndexSpec {
+    pub fn new(keys: Document) -> Self {
+        IndexSpec { keys, unique: false, partial: false }
+    }
+
+    pub fn unique(keys: Document) -> Self {
+        IndexSpec { keys, unique: true, partial: false }
+    }
+
+    /// Skip soft-deleted records (see `partial_filter`)
+    pub fn partial(mut self) -> Self {
+        self.partial = true;
+        self
+    }
+
+    /// The indexed field names
+    pub fn fields(&self) -> Vec<&str> {
+        self.keys.keys().map(String::as_str).collect()
+    }
+
+    /// `partialFilterExpression` for a partial index. MongoDB rejects `$ne` there, so
+    /// live records are matched as `{<field>: false}`, the value create and restore
+    /// write. Records missing the field wouldn't be indexed; `ensure_indexes` backfills
+    /// `false` for them first.
+    pub fn partial_filter(&self, soft_delete_field: Option<&str>) -> Option<Document> {
+        match soft_delete_field {
+            Some(field) if self.partial => Some(doc! { field: false }),
+            _ => None,
+        }
+    }
+}
+
+/// The resource's `indexes()` as models for `create_indexes`
+pub fn index_models(resource: &dyn AdmixResource) -> Vec<IndexModel> {
+    let soft_delete_field = resource.soft_delete_field();
+    resource.indexes()
+        .into_iter()
+        .map(|spec| {
+            if spec.partial && soft_delete_field.is_none() {
+                warn!("{} declares a partial index on {:?} but does not soft delete", resource.resource_name(), spec.fields());
+            }
+            let options = IndexOptions::builder()
+                .unique(spec.unique.then_some(true))
+                .partial_filter_expression(spec.partial_filter(soft_delete_field))
+                .build();
+            IndexModel::builder().keys(spec.keys).options(options).build()
+        })
+        .collect()
+}
+
+/// Create the resource's `indexes()`; existing identical indexes are left alone.
+/// Before a partial index is built, records without the soft-delete field get it set
+/// to `false`, so records written before soft deletes were enabled stay covered.
+pub async fn ensure_indexes(resource: &dyn AdmixResource) -> Result<(), AdminxError> {
+    let models = index_models(resource);
+    if models.is_empty() {
+        return Ok(());
+    }
+    let collection = resource.try_get_collection()?;
+    if let Some(field) = resource.soft_delete_field().filter(|_| resource.indexes().iter().any(|spec| spec.partial)) {
+        let backfilled = collection
+            .update_many(doc! { field: { "$exists": false } }, doc! { "$set": { field: false } }, None)
+            .await
+            .map_err(|e| {
+                error!("Could not backfill {} on {}: {}", field, resource.resource_name(), e);
+                AdminxError::InternalError
+            })?;
+        if backfilled.modified_count > 0 {
+            info!("Set {} to false on {} {} records", field, backfilled.modified_count, resource.resource_name());
+        }
+    }
+    collection.create_indexes(models, None).await.map_err(|e| {
+        error!("Could not create indexes for {}: {}", resource.resource_name(), e);
+        AdminxError::InternalError
+    })?;
+    info!("Indexes ready for {}", resource.resource_name());
+    Ok(())
+}
+
+/// `ensure_indexes` for every registered resource, plus the TTL index expiring
+/// export jobs, e.g. once at startup
+pub async fn ensure_all_indexes() -> Result<(), AdminxError> {
+    for resource in all_resources() {
+        ensure_indexes(resource.as_ref()).await?;
+    }
+    ensure_export_job_indexes().await.map_err(|e| {
+        error!("Could not create the export jobs TTL index: {}", e);
+        AdminxError::InternalError
+    })
+}
+
+/// Whether `error` is a unique index violation
+pub fn is_duplicate_key(error: &mongodb::error::Error) -> bool {
+    use mongodb::error::{ErrorKind, WriteFailure};
+    matches!(
+        error.kind.as_ref(),
+        ErrorKind::Write(WriteFailure::WriteError(write_error)) if write_error.code == DUPLICATE_KEY
+    )
+}
+
+/// The record holding `document`'s values for one of the unique `indexes`, with the
+/// fields it collides on
+async fn conflicting_record(
+    collection: &Collection<Document>,
+    indexes: &[IndexSpec],
+    soft_delete_field: Option<&str>,
+    document: &Document,
+) -> Option<(Document, Vec<String>)> {
+    for spec in indexes.iter().filter(|spec| spec.unique) {
+        let fields = spec.fields();
+        let mut filter: Document = fields.iter()
+            .map(|field| (field.to_string(), document.get(*field).cloned().unwrap_or(Bson::Null)))
+            .collect();
+        // A partial index only clashes with the records it covers
+        if let Some(partial) = spec.partial_filter(soft_delete_field) {
+            filter.extend(partial);
+        }
+        match collection.find_one(filter, None).await {
+            Ok(Some(existing)) => return Some((existing, fields.iter().map(|f| f.to_string()).collect())),
+            Ok(None) => {}
+            Err(e) => warn!("Could not look up the record conflicting on {:?}: {}", fields, e),
+        }
+    }
+    None
+}
+
+/// 409 for a create that hit a unique index. When the clash is with a soft-deleted
+/// record the body says so and carries its `restore_url`; a partial index
+/// (`IndexSpec::partial`) avoids the clash altogether.
+pub async fn duplicate_key_response(
+    resource_name: &str,
+    base_path: &str,
+    soft_delete_field: Option<&str>,
+    collection: &Collection<Document>,
+    indexes: &[IndexSpec],
+    document: &Document,
+) -> HttpResponse {
+    let Some((existing, fields)) = conflicting_record(collection, indexes, soft_delete_field, document).await else {
+        return AdminxError::Conflict(format!("{} conflicts with an existing record", resource_name)).error_response();
+    };
+    let fields = fields.join(", ");
+    let soft_deleted = soft_delete_field.is_some_and(|field| existing.get_bool(field).unwrap_or(false));
+    match existing.get_object_id("_id") {
+        Ok(id) if soft_deleted => {
+            warn!("Create on {} clashes on {} with soft-deleted record {}", resource_name, fields, id);
+            AdminxError::SoftDeletedConflict {
+                resource: resource_name.to_string(),
+                fields,
+                restore_id: id.to_hex(),
+                restore_url: admin_path(&format!("/{}/{}/restore", base_path, id.to_hex())),
+            }.error_response()
+        }
+        _ => AdminxError::Conflict(format!("A {} record with this {} already exists", resource_name, fields)).error_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Members;
+
+    impl AdmixResource for Members {
+        fn new() -> Self { Members }
+        fn resource_name(&self) -> &'static str { "Members" }
+        fn base_path(&self) -> &'static str { "members" }
+        fn collection_name(&self) -> &'static str { "members" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(Members) }
+        fn permit_keys(&self) -> Vec<&'static str> { vec!["email", "name", "deleted"] }
+        fn indexes(&self) -> Vec<IndexSpec> {
+            vec![IndexSpec::unique(doc! { "email": 1 }).partial(), IndexSpec::new(doc! { "name": 1 })]
+        }
+    }
+
+    #[test]
+    fn partial_unique_index_skips_soft_deleted_records() {
+        let models = index_models(&Members);
+        assert_eq!(models.len(), 2);
+
+        let email = models[0].options.as_ref().unwrap();
+        assert_eq!(models[0].keys, doc! { "email": 1 });
+        assert_eq!(email.unique, Some(true));
+        assert_eq!(email.partial_filter_expression, Some(doc! { "deleted": false }));
+
+        let name = models[1].options.as_ref().unwrap();
+        assert_eq!(name.unique, None);
+        assert_eq!(name.partial_filter_expression, None);
+
+        // Without a soft-delete field there is nothing to filter on
+        assert_eq!(IndexSpec::unique(doc! { "email": 1 }).partial().partial_filter(None), None);
+    }
+}
@@ -0,0 +1,320 @@
+// crates/adminx/src/helpers/list_query.rs
+use actix_web::{dev::Payload, FromRequest, HttpRequest};
+use mongodb::bson::{doc, Document};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::Infallible;
+use std::future::{ready, Ready};
+use tracing::warn;
+
+use crate::AdmixResource;
+use crate::error::AdminxError;
+use crate::helpers::resource_helper::{build_filter_document, enforce_search_limits, hide_soft_deleted};
+
+/// Query string of the list view and its downloads, parsed once per request.
+///
+/// Control parameters (`page`, `per_page`, `sort`, `download`, ...) get their own
+/// fields; every other non-empty parameter is a filter. Malformed numbers fall
+/// back to the defaults rather than failing the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListQuery {
+    /// 1-based page
+    pub page: u64,
+    /// Requested page size; resolve it against the resource with `per_page_for`
+    pub per_page: Option<u64>,
+    /// `field` for ascending, `-field` for descending
+    pub sort: Option<String>,
+    /// Export format requested with `download=`
+    pub download: Option<String>,
+    /// Export every page instead of the current one
+    pub complete: bool,
+    /// `compress=gzip`
+    pub compress: Option<String>,
+    /// CSV field delimiter (`,`, `;`, `tab`)
+    pub delimiter: Option<String>,
+    /// CSV line ending (`lf`, `crlf`)
+    pub line_ending: Option<String>,
+    /// Start CSV exports with a UTF-8 byte order mark
+    pub bom: Option<bool>,
+    /// Background export format (`POST /export?format=`)
+    pub format: Option<String>,
+    /// Legacy `success=`/`error=` codes shown as a toast on the list page
+    pub success: Option<String>,
+    pub error: Option<String>,
+    /// Field filters, `search` and range bounds (`*_from`, `*_to`, `*_min`, `*_max`)
+    pub filters: HashMap<String, String>,
+}
+
+impl Default for ListQuery {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            per_page: None,
+            sort: None,
+            download: None,
+            complete: false,
+            compress: None,
+            delimiter: None,
+            line_ending: None,
+            bom: None,
+            format: None,
+            success: None,
+            error: None,
+            filters: HashMap::new(),
+        }
+    }
+}
+
+impl ListQuery {
+    pub fn parse(query_string: &str) -> Self {
+        let params: Vec<(String, String)> = serde_urlencoded::from_str(query_string).unwrap_or_default();
+        let non_empty = |value: &str| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+
+        let mut query = Self::default();
+        for (key, value) in params {
+            match key.as_str() {
+                "page" => query.page = value.trim().parse().ok().filter(|p| *p > 0).unwrap_or(1),
+                "per_page" => query.per_page = value.trim().parse().ok().filter(|p| *p > 0),
+                "sort" => query.sort = non_empty(&value),
+                "download" => query.download = non_empty(&value),
+                "complete" => query.complete = value == "true",
+                "compress" => query.compress = non_empty(&value),
+                // Not trimmed: a tab delimiter is whitespace
+                "delimiter" => query.delimiter = Some(value).filter(|v| !v.is_empty()),
+                "line_ending" => query.line_ending = non_empty(&value),
+                "bom" => query.bom = value.trim().parse().ok(),
+                "format" => query.format = non_empty(&value),
+                "success" => query.success = non_empty(&value),
+                "error" => query.error = non_empty(&value),
+                _ if !value.is_empty() => {
+                    query.filters.insert(key, value);
+                }
+                _ => {}
+            }
+        }
+        query
+    }
+
+    /// Apply the resource's search limits: over-long terms are a bad request,
+    /// terms below the minimum are dropped
+    pub fn validate(&mut self, resource: &dyn AdmixResource) -> Result<(), AdminxError> {
+        enforce_search_limits(resource, &mut self.filters)
+    }
+
+    /// Page size: the requested one, else the resource's `default_per_page()`,
+    /// capped by `max_per_page()`
+    pub fn per_page_for<R: AdmixResource + ?Sized>(&self, resource: &R) -> u64 {
+        self.per_page
+            .unwrap_or_else(|| resource.default_per_page())
+            .min(resource.max_per_page())
+            .max(1)
+    }
+
+    /// Sort for the list and its exports; the resource's default (see
+    /// `default_sort_document`) unless `sort` names `_id` or one of `sortable_fields()`
+    pub fn sort_document<R: AdmixResource + ?Sized>(&self, resource: &R) -> Document {
+        let requested = self.sort.as_deref().and_then(|sort| {
+            let (field, direction) = match sort.strip_prefix('-') {
+                Some(field) => (field, -1),
+                None => (sort, 1),
+            };
+            let sortable = field == "_id" || resource.sortable_fields().iter().any(|f| f == field);
+            if !sortable {
+                warn!("Ignoring sort on '{}' for {}: not in sortable_fields()", field, resource.resource_name());
+            }
+            sortable.then(|| doc! { field: direction })
+        });
+        requested.unwrap_or_else(|| default_sort_document(resource))
+    }
+
+    /// Mongo filter for the list and its exports, with soft-deleted documents hidden
+    pub fn filter_document(&self, resource: &dyn AdmixResource) -> Document {
+        let permitted: HashSet<&str> = resource.permit_keys().into_iter().collect();
+        let mut filter = build_filter_document(&permitted, &resource.field_types(), &resource.searchable_fields(), &self.filters);
+        hide_soft_deleted(&mut filter, resource.soft_delete_field());
+        filter
+    }
+
+    /// Filters and sort as `key=value` pairs for pagination links, in a stable order
+    pub fn link_params(&self) -> Vec<String> {
+        let mut params: Vec<String> = self.filters
+            .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, urlencoding::encode(value)))
+            .collect();
+        if let Some(sort) = &self.sort {
+            params.push(format!("sort={}", urlencoding::encode(sort)));
+        }
+        params
+    }
+
+    pub fn wants_gzip(&self) -> bool {
+        self.compress.as_deref().is_some_and(|value| value.eq_ignore_ascii_case("gzip"))
+    }
+}
+
+/// The resource's `default_sort()`, else newest first by its created timestamp. `_id`
+/// breaks ties, which also keeps the order stable for documents without one.
+pub fn default_sort_document<R: AdmixResource + ?Sized>(resource: &R) -> Document {
+    let mut sort = match (resource.default_sort(), resource.timestamps()) {
+        (Some((field, direction)), _) => doc! { field: if direction < 0 { -1 } else { 1 } },
+        (None, Some((created, _))) => doc! { created: -1 },
+        (None, None) => doc! { "_id": -1 },
+    };
+    if !sort.contains_key("_id") {
+        sort.insert("_id", -1);
+    }
+    sort
+}
+
+impl FromRequest for ListQuery {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Infallible>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(Ok(ListQuery::parse(req.query_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestResource;
+    use mongodb::Collection;
+
+    struct Posts;
+
+    impl AdmixResource for Posts {
+        fn new() -> Self { Posts }
+        fn resource_name(&self) -> &'static str { "Posts" }
+        fn base_path(&self) -> &'static str { "posts" }
+        fn collection_name(&self) -> &'static str { "posts" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(Posts) }
+        fn permit_keys(&self) -> Vec<&'static str> { vec!["title", "status", "views"] }
+        fn max_per_page(&self) -> u64 { 50 }
+        fn list_structure(&self) -> Option<serde_json::Value> {
+            Some(serde_json::json!({ "columns": [
+                { "field": "title", "sortable": false },
+                { "field": "views", "sortable": true },
+                { "field": "updated_at", "sortable": true }
+            ]}))
+        }
+    }
+
+    #[test]
+    fn parses_a_representative_list_query() {
+        let query = ListQuery::parse(
+            "page=3&per_page=20&sort=-views&download=csv&complete=true&compress=gzip&title=rust%20tips&status=&views_min=10&success=created",
+        );
+
+        assert_eq!(query.page, 3);
+        assert_eq!(query.per_page, Some(20));
+        assert_eq!(query.sort.as_deref(), Some("-views"));
+        assert_eq!(query.download.as_deref(), Some("csv"));
+        assert!(query.complete);
+        assert!(query.wants_gzip());
+        assert_eq!(query.success.as_deref(), Some("created"));
+        assert_eq!(query.filters.len(), 2);
+        assert_eq!(query.filters["title"], "rust tips");
+        assert_eq!(query.filters["views_min"], "10");
+        assert_eq!(query.link_params(), vec!["title=rust%20tips", "views_min=10", "sort=-views"]);
+    }
+
+    #[test]
+    fn malformed_values_fall_back_to_defaults() {
+        let query = ListQuery::parse("page=abc&per_page=0&complete=yes");
+        assert_eq!(query, ListQuery::default());
+        assert_eq!(query.per_page_for(&Posts), 10);
+        assert_eq!(ListQuery::parse("per_page=500").per_page_for(&Posts), 50);
+    }
+
+    #[test]
+    fn sort_is_limited_to_sortable_fields() {
+        assert_eq!(Posts.sortable_fields(), vec!["views", "updated_at"]);
+        assert_eq!(ListQuery::parse("sort=-views").sort_document(&Posts), doc! { "views": -1 });
+        assert_eq!(ListQuery::parse("sort=updated_at").sort_document(&Posts), doc! { "updated_at": 1 });
+        // Permitted but not marked sortable, or not a field at all: the default sort applies
+        assert_eq!(ListQuery::parse("sort=-title").sort_document(&Posts), doc! { "created_at": -1, "_id": -1 });
+        assert_eq!(ListQuery::parse("sort=password").sort_document(&Posts), doc! { "created_at": -1, "_id": -1 });
+    }
+
+    #[test]
+    fn filter_document_uses_permitted_filters_only() {
+        let filter = ListQuery::parse("title=rust&secret=x&page=2").filter_document(&Posts);
+        assert_eq!(filter, doc! { "title": { "$regex": "rust", "$options": "i" } });
+    }
+
+    struct Tags;
+
+    impl AdmixResource for Tags {
+        fn new() -> Self { Tags }
+        fn resource_name(&self) -> &'static str { "Tags" }
+        fn base_path(&self) -> &'static str { "tags" }
+        fn collection_name(&self) -> &'static str { "tags" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(Tags) }
+        fn permit_keys(&self) -> Vec<&'static str> { vec!["name", "uses"] }
+        fn default_sort(&self) -> Option<(String, i32)> { Some(("name".to_string(), 1)) }
+        fn sortable_fields(&self) -> Vec<String> { vec!["name".to_string(), "uses".to_string()] }
+    }
+
+    #[test]
+    fn custom_default_sort_applies_to_list_and_exports() {
+        use crate::helpers::downloads::json_download::export_find_options;
+
+        let query = ListQuery::parse("name=rust");
+        assert_eq!(query.sort_document(&Tags), doc! { "name": 1, "_id": -1 });
+        assert_eq!(export_find_options(&Tags, &query).sort, Some(doc! { "name": 1, "_id": -1 }));
+
+        // An explicit sort still wins
+        assert_eq!(ListQuery::parse("sort=-uses").sort_document(&Tags), doc! { "uses": -1 });
+    }
+
+    struct Products;
+
+    impl AdmixResource for Products {
+        fn new() -> Self { Products }
+        fn resource_name(&self) -> &'static str { "Products" }
+        fn base_path(&self) -> &'static str { "products" }
+        fn collection_name(&self) -> &'static str { "products" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(Products) }
+        fn permit_keys(&self) -> Vec<&'static str> { vec!["sku", "label", "name"] }
+        fn searchable_fields(&self) -> Vec<&'static str> { vec!["sku", "label"] }
+    }
+
+    #[test]
+    fn search_covers_the_resource_searchable_fields() {
+        let search = |field: &str| doc! { field: { "$regex": "ab-1", "$options": "i" } };
+
+        let filter = ListQuery::parse("search=ab-1").filter_document(&Products);
+        assert_eq!(filter, doc! { "$or": [search("sku"), search("label")] });
+
+        // The default is the usual text fields among the permitted keys
+        assert_eq!(Posts.searchable_fields(), vec!["title"]);
+        let filter = ListQuery::parse("search=ab-1").filter_document(&Posts);
+        assert_eq!(filter, doc! { "$or": [search("title")] });
+    }
+
+    fn events() -> TestResource {
+        TestResource::named("Events", "events").permit(&["name"]).timestamps(Some(("createdAt", "updatedAt")))
+    }
+
+    fn readings() -> TestResource {
+        TestResource::named("Readings", "readings").permit(&["value"]).timestamps(None)
+    }
+
+    #[test]
+    fn sorting_follows_the_resource_timestamps() {
+        assert_eq!(ListQuery::default().sort_document(&events()), doc! { "createdAt": -1, "_id": -1 });
+        // The default list columns make only the created timestamp sortable
+        assert_eq!(ListQuery::parse("sort=createdAt").sort_document(&events()), doc! { "createdAt": 1 });
+        assert_eq!(ListQuery::parse("sort=-updatedAt").sort_document(&events()), doc! { "createdAt": -1, "_id": -1 });
+        assert_eq!(ListQuery::parse("sort=created_at").sort_document(&events()), doc! { "createdAt": -1, "_id": -1 });
+
+        assert_eq!(ListQuery::default().sort_document(&readings()), doc! { "_id": -1 });
+        assert_eq!(ListQuery::parse("sort=created_at").sort_document(&readings()), doc! { "_id": -1 });
+        assert_eq!(ListQuery::parse("sort=_id").sort_document(&readings()), doc! { "_id": 1 });
+    }
+}
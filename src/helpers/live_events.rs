@@ -0,0 +1,136 @@
+// crates/adminx/src/helpers/live_events.rs
+use actix_web::{http::header, web::Bytes, HttpResponse, ResponseError};
+use futures::{Stream, StreamExt};
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::change_stream::event::{ChangeStreamEvent, OperationType};
+use tracing::{error, info, warn};
+
+use crate::AdmixResource;
+
+/// Server error code for `$changeStream` on a standalone server
+const CHANGE_STREAM_UNSUPPORTED: i32 = 40573;
+
+/// A document change pushed to list views
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveEvent {
+    /// `create`, `update` or `delete`
+    pub kind: &'static str,
+    pub id: String,
+}
+
+/// Map a change stream event to a live event; drops, renames and the like are skipped
+pub fn live_event_from_change(change: &ChangeStreamEvent<Document>) -> Option<LiveEvent> {
+    let kind = match change.operation_type {
+        OperationType::Insert => "create",
+        OperationType::Update | OperationType::Replace => "update",
+        OperationType::Delete => "delete",
+        _ => return None,
+    };
+    let id = match change.document_key.as_ref()?.get("_id")? {
+        Bson::ObjectId(oid) => oid.to_hex(),
+        Bson::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    Some(LiveEvent { kind, id })
+}
+
+/// Encode an event as an SSE message: the kind is the event name, the id the JSON data
+pub fn sse_frame(event: &LiveEvent) -> Bytes {
+    Bytes::from(format!(
+        "event: {}\ndata: {}\n\n",
+        event.kind,
+        serde_json::json!({ "id": event.id })
+    ))
+}
+
+/// Turn a change stream into SSE messages
+pub fn sse_stream<S, E>(changes: S) -> impl Stream<Item = Result<Bytes, actix_web::Error>>
+where
+    S: Stream<Item = Result<ChangeStreamEvent<Document>, E>>,
+    E: std::fmt::Display,
+{
+    changes.filter_map(|change| async move {
+        match change {
+            Ok(change) => live_event_from_change(&change).map(|event| Ok(sse_frame(&event))),
+            Err(e) => {
+                error!("Live event stream aborted: {}", e);
+                Some(Err(actix_web::error::ErrorInternalServerError(e.to_string())))
+            }
+        }
+    })
+}
+
+/// Whether `watch` failed because the deployment has no change streams (standalone server)
+pub fn change_streams_unsupported(err: &mongodb::error::Error) -> bool {
+    matches!(
+        err.kind.as_ref(),
+        mongodb::error::ErrorKind::Command(command) if command.code == CHANGE_STREAM_UNSUPPORTED
+    )
+}
+
+/// Open a change stream on the resource's collection and stream it as `text/event-stream`.
+/// Responds 404 when the deployment doesn't support change streams.
+pub async fn stream_resource_events(resource: &dyn AdmixResource) -> HttpResponse {
+    let pipeline = vec![doc! {
+        "$match": { "operationType": { "$in": ["insert", "update", "replace", "delete"] } }
+    }];
+
+    let collection = match resource.try_get_collection() {
+        Ok(collection) => collection,
+        Err(e) => return e.error_response(),
+    };
+
+    match collection.watch(pipeline, None).await {
+        Ok(changes) => {
+            info!("📡 Live events stream opened for {}", resource.resource_name());
+            HttpResponse::Ok()
+                .content_type("text/event-stream")
+                .insert_header((header::CACHE_CONTROL, "no-cache"))
+                // Compression would hold events back until its buffer fills
+                .insert_header(header::ContentEncoding::Identity)
+                .streaming(sse_stream(changes))
+        }
+        Err(e) if change_streams_unsupported(&e) => {
+            warn!("Live events unavailable for {}: {}", resource.resource_name(), e);
+            HttpResponse::NotFound().body("Live updates require a MongoDB replica set")
+        }
+        Err(e) => {
+            error!("Failed to open change stream for {}: {}", resource.resource_name(), e);
+            HttpResponse::InternalServerError().body("Failed to open live updates")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::oid::ObjectId;
+
+    fn change(operation: &str, id: ObjectId) -> ChangeStreamEvent<Document> {
+        mongodb::bson::from_document(doc! {
+            "_id": { "_data": "8263" },
+            "operationType": operation,
+            "documentKey": { "_id": id },
+        })
+        .unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn insert_produces_create_event() {
+        let inserted = ObjectId::new();
+        let changes: Vec<Result<ChangeStreamEvent<Document>, String>> = vec![
+            Ok(change("insert", inserted)),
+            Ok(change("drop", ObjectId::new())),
+            Ok(change("delete", inserted)),
+        ];
+
+        let frames: Vec<String> = sse_stream(futures::stream::iter(changes))
+            .map(|frame| String::from_utf8(frame.unwrap().to_vec()).unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0], format!("event: create\ndata: {{\"id\":\"{}\"}}\n\n", inserted.to_hex()));
+        assert!(frames[1].starts_with("event: delete\n"));
+    }
+}
@@ -2,4 +2,17 @@ pub mod template_helper;
 pub mod form_helper;
 pub mod resource_helper;
 pub mod auth_helper;
-pub mod downloads;
\ No newline at end of file
+pub mod downloads;
+pub mod rich_text;
+pub mod image_helper;
+pub mod column_format;
+pub mod live_events;
+pub mod error_pages;
+pub mod idempotency;
+pub mod import;
+pub mod list_query;
+pub mod redact;
+pub mod transaction;
+pub mod bulk_status;
+pub mod file_cleanup;
+pub mod indexes;
\ No newline at end of file
@@ -1,60 +1,74 @@
 // crates/adminx/src/helpers/resource_helper.rs - Complete Fixed Version
-use actix_web::{web, HttpRequest, HttpResponse, Scope};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Scope};
+use actix_web::http::header::{HeaderName, HeaderValue, ETAG};
 use serde_json::Value;
 use std::sync::Arc;
 use tera::Context;
 use tracing::{info, warn, error};
 use std::collections::HashSet;
-use actix_session::Session;
+use actix_session::{Session, SessionExt};
 use futures::TryStreamExt;
+use mongodb::options::SelectionCriteria;
 
 use crate::AdmixResource;
 use crate::configs::initializer::AdminxConfig;
-use crate::utils::auth::extract_claims_from_session;
-use crate::utils::structs::Claims;
+use crate::utils::auth::{request_claims, session_claims, unauthenticated_response};
+use crate::utils::rate_limit::{client_ip, RateLimiter};
+use crate::utils::reauth::check_reauth;
+use crate::utils::structs::{Breadcrumb, Claims, FlashMessage};
+use crate::helpers::template_helper::{push_flash, render_404_with_message, render_500};
+use crate::helpers::rich_text::sanitize_rich_text_fields;
+use crate::helpers::column_format::{apply_column_formats, resource_column_formats};
+use crate::utils::timezone::{datetime_rfc3339, format_datetime, request_timezone, DISPLAY_DATETIME_FORMAT};
+use chrono_tz::Tz;
 use crate::registry::get_registered_menus;
+use crate::utils::rbac::{can_perform_action, can_read_field, readable_keys, roles_from_request, writable_keys};
+use crate::error::AdminxError;
+use crate::pagination::{page_offset, total_pages};
+use crate::helpers::list_query::ListQuery;
+use crate::utils::mount::{admin_path, base_mount};
+use crate::utils::constants::MAX_FORM_NESTING_DEPTH;
 
-/// Check authentication and return user claims or redirect response
+/// MongoDB's error code for an operation that ran past its `maxTimeMS`
+const MAX_TIME_MS_EXPIRED: i32 = 50;
+
+/// Check authentication and return user claims, or the response for an unauthenticated
+/// request: a 401 JSON body for API clients, a login redirect for browser pages
 pub async fn check_authentication(
-    session: &Session,
+    req: &HttpRequest,
     config: &AdminxConfig,
     resource_name: &str,
     action: &str,
 ) -> Result<Claims, HttpResponse> {
-    match extract_claims_from_session(session, config).await {
+    match session_claims(&req.get_session(), config) {
         Ok(claims) => {
             info!("🔐 Authenticated user {} accessing {} action on resource {}", 
                   claims.email, action, resource_name);
             Ok(claims)
         }
-        Err(_) => {
+        Err(e) => {
             warn!("⚠️  Unauthenticated access attempt to {} action on resource {}", action, resource_name);
-            Err(HttpResponse::Found()
-                .append_header(("Location", "/adminx/login"))
-                .finish())
+            Err(unauthenticated_response(e, req))
         }
     }
 }
 
 /// Check if user has permission for resource action - Enhanced version
 pub async fn check_resource_permission(
-    session: &Session,
+    req: &HttpRequest,
     config: &AdminxConfig,
     resource: &dyn AdmixResource,
     action: &str,
 ) -> Result<Claims, HttpResponse> {
-    match extract_claims_from_session(session, config).await {
+    match session_claims(&req.get_session(), config) {
         Ok(claims) => {
-            let user_roles: HashSet<String> = {
+            let user_roles: Vec<String> = {
                 let mut roles = claims.roles.clone();
                 roles.push(claims.role.clone());
-                roles.into_iter().collect()
+                roles
             };
             
-            let allowed_roles: HashSet<String> = 
-                resource.allowed_roles().into_iter().collect();
-            
-            if user_roles.intersection(&allowed_roles).next().is_some() {
+            if can_perform_action(resource, &user_roles, action) {
                 info!("User {} has permission for {} action on resource {}", 
                       claims.email, action, resource.resource_name());
                 Ok(claims)
@@ -62,6 +76,7 @@ pub async fn check_resource_permission(
                 warn!("User {} lacks permission for {} action on resource {} (user roles: {:?}, required: {:?})", 
                       claims.email, action, resource.resource_name(), claims.roles, resource.allowed_roles());
                 Err(HttpResponse::Forbidden().json(serde_json::json!({
+                    "code": "FORBIDDEN",
                     "error": "Insufficient permissions",
                     "required_roles": resource.allowed_roles(),
                     "user_roles": claims.roles,
@@ -70,11 +85,7 @@ pub async fn check_resource_permission(
                 })))
             }
         }
-        Err(_) => {
-            Err(HttpResponse::Found()
-                .append_header(("Location", "/adminx/login"))
-                .finish())
-        }
+        Err(e) => Err(unauthenticated_response(e, req)),
     }
 }
 
@@ -83,10 +94,12 @@ pub fn create_base_template_context(
     resource_name: &str,
     base_path: &str,
     claims: &Claims,
+    action: &str,
 ) -> Context {
     let mut ctx = Context::new();
     ctx.insert("resource_name", resource_name);
-    ctx.insert("base_path", &format!("/adminx/{}", base_path));
+    ctx.insert("base_path", &admin_path(&format!("/{}", base_path)));
+    ctx.insert("breadcrumbs", &build_breadcrumbs(resource_name, base_path, action));
     ctx.insert("menus", &get_registered_menus());
     ctx.insert("current_user", claims);
     ctx.insert("is_authenticated", &true);
@@ -94,92 +107,399 @@ pub fn create_base_template_context(
 }
 
 
+/// Build the Dashboard → Resource → Action trail for a resource page
+pub fn build_breadcrumbs(resource_name: &str, base_path: &str, action: &str) -> Vec<Breadcrumb> {
+    let mut breadcrumbs = vec![Breadcrumb {
+        label: "Dashboard".to_string(),
+        url: base_mount(),
+    }];
+
+    if base_path.is_empty() {
+        return breadcrumbs;
+    }
+
+    let resource_url = admin_path(&format!("/{}/list", base_path));
+    breadcrumbs.push(Breadcrumb {
+        label: resource_name.to_string(),
+        url: resource_url.clone(),
+    });
+
+    let action_label = match action {
+        "create" | "new" => Some("New"),
+        "view" => Some("View"),
+        "edit" | "update" => Some("Edit"),
+        _ => None,
+    };
+    if let Some(label) = action_label {
+        breadcrumbs.push(Breadcrumb {
+            label: label.to_string(),
+            url: resource_url,
+        });
+    }
+
+    breadcrumbs
+}
+
+
 pub fn handle_delete_response(
     response: HttpResponse,
     base_path: &str,
     resource_name: &str,
+    session: &Session,
 ) -> HttpResponse {
     if response.status().is_success() {
         info!("✅ Resource '{}' item deleted successfully, redirecting to list", resource_name);
-        let location = format!("/adminx/{}/list?success=deleted", base_path);
-        HttpResponse::Found()
-            .append_header(("Location", location))
-            .finish()
+        push_flash(session, FlashMessage::success("Successfully deleted item!"));
     } else {
         error!("❌ Resource '{}' item deletion failed with status: {}", resource_name, response.status());
-        let location = format!("/adminx/{}/list?error=delete_failed", base_path);
-        HttpResponse::Found()
-            .append_header(("Location", location))
-            .finish()
+        push_flash(session, FlashMessage::error("Failed to delete item. Please try again."));
+    }
+    HttpResponse::Found()
+        .append_header(("Location", admin_path(&format!("/{}/list", base_path))))
+        .finish()
+}
+
+pub fn handle_restore_response(
+    response: HttpResponse,
+    base_path: &str,
+    resource_name: &str,
+    session: &Session,
+) -> HttpResponse {
+    if response.status().is_success() {
+        info!("✅ Resource '{}' item restored successfully, redirecting to list", resource_name);
+        push_flash(session, FlashMessage::success("Successfully restored item!"));
+    } else {
+        error!("❌ Resource '{}' item restore failed with status: {}", resource_name, response.status());
+        push_flash(session, FlashMessage::error("Failed to restore item. Please try again."));
     }
+    HttpResponse::Found()
+        .append_header(("Location", admin_path(&format!("/{}/list", base_path))))
+        .finish()
 }
 
-/// Handle form data conversion from HTML form to JSON - Enhanced version
+/// Handle form data conversion from HTML form to JSON - Enhanced version.
+/// Keys in bracket (`address[city]`) or dot (`address.city`) notation become nested
+/// objects. Keys naming a `reserved` field, deeper than `MAX_FORM_NESTING_DEPTH`, with a
+/// `$` segment or otherwise malformed are dropped.
 pub fn convert_form_data_to_json(
-    form_data: std::collections::HashMap<String, String>
+    form_data: std::collections::HashMap<String, String>,
+    field_types: &std::collections::HashMap<String, String>,
+    reserved: &[&str],
 ) -> Value {
     let mut json_data = serde_json::Map::new();
-    
+
+    // Sorted so a plain key always lands before nested keys under it
+    let mut form_data: Vec<(String, String)> = form_data.into_iter().collect();
+    form_data.sort();
+
     for (key, value) in form_data {
         // Skip editor mode fields (they're just for UI state)
-        if key.ends_with("_mode") {
+        if key.ends_with("_mode") || value.is_empty() {
             continue;
         }
-        
-        if !value.is_empty() {
-            // Handle boolean fields
-            if key == "deleted" || key == "active" || key == "enabled" || key.ends_with("_flag") {
-                match value.as_str() {
-                    "true" | "1" | "on" => {
-                        json_data.insert(key, serde_json::Value::Bool(true));
-                    }
-                    "false" | "0" | "off" => {
-                        json_data.insert(key, serde_json::Value::Bool(false));
-                    }
-                    _ => {
-                        // If it's not a clear boolean, treat as string
-                        json_data.insert(key, serde_json::Value::String(value));
-                    }
-                }
+
+        let Some(path) = parse_form_key(&key) else {
+            warn!("Dropped malformed or too deeply nested form field '{}'", key);
+            continue;
+        };
+        if reserved.contains(&path[0].as_str()) {
+            warn!("Dropped form field '{}' targeting reserved field '{}'", key, path[0]);
+            continue;
+        }
+
+        let (leaf, parents) = path.split_last().unwrap();
+        match nested_object(&mut json_data, parents) {
+            Some(target) => {
+                target.insert(leaf.clone(), form_value(leaf, value));
             }
-            // Handle numeric fields
-            else if key.ends_with("_id") || key.ends_with("_count") || key.ends_with("_number") {
-                if let Ok(num) = value.parse::<i64>() {
-                    json_data.insert(key, serde_json::Value::Number(serde_json::Number::from(num)));
-                } else if let Ok(num) = value.parse::<f64>() {
-                    if let Some(num_val) = serde_json::Number::from_f64(num) {
-                        json_data.insert(key, serde_json::Value::Number(num_val));
-                    } else {
-                        json_data.insert(key, serde_json::Value::String(value));
-                    }
-                } else {
-                    json_data.insert(key, serde_json::Value::String(value));
-                }
+            None => warn!("Dropped form field '{}' nesting under a non-object value", key),
+        }
+    }
+
+    sanitize_rich_text_fields(&mut json_data, field_types);
+    
+    serde_json::Value::Object(json_data)
+}
+
+/// Path segments of a form key in plain, bracket or dot notation, or `None` when it has
+/// an empty or `$`-prefixed segment, unbalanced brackets or more than
+/// `MAX_FORM_NESTING_DEPTH` segments
+fn parse_form_key(key: &str) -> Option<Vec<String>> {
+    let (head, mut rest) = match key.find('[') {
+        Some(open) => (&key[..open], &key[open..]),
+        None => (key, ""),
+    };
+
+    let mut path: Vec<String> = head.split('.').take(MAX_FORM_NESTING_DEPTH + 1).map(str::to_string).collect();
+    while !rest.is_empty() {
+        let inner = rest.strip_prefix('[')?;
+        let close = inner.find(']')?;
+        path.push(inner[..close].to_string());
+        rest = &inner[close + 1..];
+        if path.len() > MAX_FORM_NESTING_DEPTH {
+            return None;
+        }
+    }
+
+    let valid = path.len() <= MAX_FORM_NESTING_DEPTH
+        && path.iter().all(|segment| !segment.is_empty() && !segment.starts_with('$') && !segment.contains(['[', ']']));
+    valid.then_some(path)
+}
+
+/// The object at `parents` under `map`, created as needed; `None` when a non-object is in the way
+fn nested_object<'a>(
+    mut map: &'a mut serde_json::Map<String, Value>,
+    parents: &[String],
+) -> Option<&'a mut serde_json::Map<String, Value>> {
+    for parent in parents {
+        map = map.entry(parent.clone()).or_insert_with(|| Value::Object(serde_json::Map::new())).as_object_mut()?;
+    }
+    Some(map)
+}
+
+/// JSON value of a submitted form field, typed by naming convention
+fn form_value(key: &str, value: String) -> Value {
+    // Handle boolean fields
+    if key == "deleted" || key == "active" || key == "enabled" || key.ends_with("_flag") {
+        match value.as_str() {
+            "true" | "1" | "on" => serde_json::Value::Bool(true),
+            "false" | "0" | "off" => serde_json::Value::Bool(false),
+            // If it's not a clear boolean, treat as string
+            _ => serde_json::Value::String(value),
+        }
+    }
+    // Handle numeric fields
+    else if key.ends_with("_id") || key.ends_with("_count") || key.ends_with("_number") {
+        if let Ok(num) = value.parse::<i64>() {
+            serde_json::Value::Number(serde_json::Number::from(num))
+        } else if let Some(num_val) = value.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+            serde_json::Value::Number(num_val)
+        } else {
+            serde_json::Value::String(value)
+        }
+    }
+    // JSON fields (`data`, `*_json`, `*_config`) are kept as strings, as are all others
+    else {
+        serde_json::Value::String(value)
+    }
+}
+
+
+/// Route middleware rejecting uploads whose declared `Content-Length` exceeds `limit`
+/// with 413, before the handler starts reading the body
+pub async fn enforce_upload_limit(
+    limit: usize,
+    req: actix_web::dev::ServiceRequest,
+    next: actix_web::middleware::Next<actix_web::body::BoxBody>,
+) -> Result<actix_web::dev::ServiceResponse<actix_web::body::BoxBody>, actix_web::Error> {
+    let declared = req
+        .headers()
+        .get(actix_web::http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+
+    if declared.is_some_and(|length| length > limit) {
+        warn!("Rejected upload of {:?} bytes to {} (limit {})", declared, req.path(), limit);
+        let response = actix_web::ResponseError::error_response(&AdminxError::PayloadTooLarge(limit));
+        return Ok(req.into_response(response));
+    }
+
+    next.call(req).await
+}
+
+/// Route middleware applying `check_reauth` for `action` before handlers that don't
+/// take the session themselves (custom and row actions)
+pub async fn enforce_reauth(
+    resource: Arc<Box<dyn AdmixResource>>,
+    action: &'static str,
+    req: actix_web::dev::ServiceRequest,
+    next: actix_web::middleware::Next<actix_web::body::BoxBody>,
+) -> Result<actix_web::dev::ServiceResponse<actix_web::body::BoxBody>, actix_web::Error> {
+    let resource = resource.as_ref().as_ref();
+    if !resource.requires_reauth(action) {
+        return next.call(req).await;
+    }
+    let Some(config) = req.app_data::<web::Data<AdminxConfig>>().cloned() else {
+        error!("{} needs re-authentication for {} but no AdminxConfig is registered", resource.resource_name(), action);
+        return Ok(req.into_response(actix_web::ResponseError::error_response(&AdminxError::InternalError)));
+    };
+    if let Err(response) = check_reauth(&req.get_session(), &config, resource, action) {
+        return Ok(req.into_response(response));
+    }
+    next.call(req).await
+}
+
+/// Scope middleware counting writes (POST/PUT/PATCH/DELETE) against `limiter` per
+/// admin and per client IP (see `client_ip`), keyed by `scope_key`. Over the limit the request gets 429
+/// with `Retry-After`, falling back to the whole `window` when the limiter can't tell.
+pub async fn enforce_write_rate_limit(
+    limiter: Arc<dyn RateLimiter>,
+    window: std::time::Duration,
+    scope_key: String,
+    req: actix_web::dev::ServiceRequest,
+    next: actix_web::middleware::Next<actix_web::body::BoxBody>,
+) -> Result<actix_web::dev::ServiceResponse<actix_web::body::BoxBody>, actix_web::Error> {
+    use actix_web::http::Method;
+
+    if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.call(req).await;
+    }
+
+    let user = request_claims(req.request()).map(|claims| claims.sub);
+    let trusted_proxies = req.app_data::<web::Data<AdminxConfig>>()
+        .map(|config| config.trusted_proxies.clone())
+        .unwrap_or_default();
+    let ip = client_ip(req.request(), &trusted_proxies);
+
+    let keys: Vec<String> = [
+        user.map(|user| format!("{}:user:{}", scope_key, user)),
+        ip.map(|ip| format!("{}:ip:{}", scope_key, ip)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    // Count every key, not just up to the first one over its limit
+    let limited: Vec<&String> = keys.iter().filter(|key| limiter.check(key)).collect();
+
+    if limited.is_empty() {
+        return next.call(req).await;
+    }
+
+    let retry_after = limited
+        .iter()
+        .map(|key| limiter.retry_after(key).unwrap_or(window))
+        .max()
+        .unwrap_or(window);
+    // Round up so clients never retry a moment too early
+    let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    warn!("Write rate limit hit on {} by {:?}, retry in {}s", req.path(), limited, seconds);
+    let response = actix_web::ResponseError::error_response(&AdminxError::TooManyRequests(seconds.max(1)));
+    Ok(req.into_response(response))
+}
+
+/// `X-Total-Count`, the header carrying a list's total document count
+pub const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
+/// Buffer `response`'s body, let `header` derive a header from it when the response is
+/// a success, and drop the body for `HEAD` requests
+async fn with_body_header(
+    response: HttpResponse,
+    head: bool,
+    header: impl FnOnce(&[u8]) -> Option<(HeaderName, String)>,
+) -> HttpResponse {
+    let (mut response, body) = response.into_parts();
+    let bytes = match actix_web::body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Could not buffer response body: {}", e);
+            return actix_web::ResponseError::error_response(&AdminxError::InternalError);
+        }
+    };
+    if response.status().is_success() {
+        if let Some((name, value)) = header(&bytes) {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                response.headers_mut().insert(name, value);
             }
-            // Handle JSON fields (try to parse as JSON first)
-            else if key == "data" || key.ends_with("_json") || key.ends_with("_config") {
-                // Try to parse as JSON first to validate, but store as string
-                match serde_json::from_str::<serde_json::Value>(&value) {
-                    Ok(_) => {
-                        // If it parsed successfully, store as string (most APIs expect JSON fields as strings)
-                        json_data.insert(key, serde_json::Value::String(value));
-                    }
-                    Err(_) => {
-                        // If it's not valid JSON, store as-is
-                        json_data.insert(key, serde_json::Value::String(value));
-                    }
+        }
+    }
+    let body = if head { actix_web::web::Bytes::new() } else { bytes };
+    response.set_body(body).map_into_boxed_body()
+}
+
+/// A list response with `X-Total-Count` taken from its `total`; body dropped for `HEAD`
+pub async fn with_total_count(response: HttpResponse, head: bool) -> HttpResponse {
+    with_body_header(response, head, |body| {
+        let total = serde_json::from_slice::<Value>(body).ok()?.get("total")?.as_u64()?;
+        Some((HeaderName::from_static(TOTAL_COUNT_HEADER), total.to_string()))
+    }).await
+}
+
+/// A single-document response with an `ETag` of its body; body dropped for `HEAD`
+pub async fn with_etag(response: HttpResponse, head: bool) -> HttpResponse {
+    use sha2::{Digest, Sha256};
+    with_body_header(response, head, |body| {
+        Some((ETAG, format!("\"{}\"", hex::encode(&Sha256::digest(body)[..16]))))
+    }).await
+}
+
+/// Uploaded files by field name: `(filename, bytes)`
+pub type UploadedFiles = std::collections::HashMap<String, (String, Vec<u8>)>;
+
+/// Limits `read_multipart` enforces on one upload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadLimits {
+    pub max_files: usize,
+    pub max_file_size: usize,
+    pub max_total_bytes: usize,
+}
+
+impl UploadLimits {
+    /// The resource's `max_files()`, `max_file_size()` and `max_upload_total_bytes()`
+    pub fn of(resource: &dyn AdmixResource) -> Self {
+        UploadLimits {
+            max_files: resource.max_files(),
+            max_file_size: resource.max_file_size(),
+            max_total_bytes: resource.max_upload_total_bytes(),
+        }
+    }
+}
+
+/// Read a multipart body into text fields and files. Fails with 400 on the first file
+/// beyond `max_files` (before reading it), and with 413 once a file exceeds
+/// `max_file_size` or more than `max_total_bytes` have been read in total (covers
+/// chunked bodies without a Content-Length). Empty file fields are dropped unless
+/// `keep_empty_files` is set.
+pub async fn read_multipart(
+    payload: &mut actix_multipart::Multipart,
+    limits: UploadLimits,
+    keep_empty_files: bool,
+) -> Result<(std::collections::HashMap<String, String>, UploadedFiles), AdminxError> {
+    let mut form_data = std::collections::HashMap::new();
+    let mut files = std::collections::HashMap::new();
+    let mut total = 0usize;
+    let mut file_count = 0usize;
+
+    while let Some(mut field) = payload.try_next().await.unwrap_or(None) {
+        let name = field.name().unwrap_or("").to_string();
+        let filename = field
+            .content_disposition()
+            .and_then(|cd| cd.get_filename())
+            .map(|f| f.to_string());
+
+        // Browsers send unselected file inputs with an empty filename; those don't count
+        if filename.as_deref().is_some_and(|f| !f.is_empty()) {
+            file_count += 1;
+            if file_count > limits.max_files {
+                return Err(AdminxError::BadRequest(format!("At most {} files can be uploaded at once", limits.max_files)));
+            }
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.try_next().await.unwrap_or(None) {
+            total += chunk.len();
+            if total > limits.max_total_bytes {
+                return Err(AdminxError::PayloadTooLarge(limits.max_total_bytes));
+            }
+            data.extend_from_slice(&chunk);
+            if filename.is_some() && data.len() > limits.max_file_size {
+                return Err(AdminxError::PayloadTooLarge(limits.max_file_size));
+            }
+        }
+
+        match filename {
+            Some(filename) => {
+                if keep_empty_files || !data.is_empty() {
+                    files.insert(name, (filename, data));
                 }
             }
-            // Default: treat as string
-            else {
-                json_data.insert(key, serde_json::Value::String(value));
+            None => {
+                form_data.insert(name, String::from_utf8_lossy(&data).to_string());
             }
         }
     }
-    
-    serde_json::Value::Object(json_data)
-}
 
+    Ok((form_data, files))
+}
 
 /*-------------------------------------------------------------------------
 /// START Handle resource creation response and return appropriate redirect
@@ -188,20 +508,24 @@ pub fn handle_create_response(
     response: HttpResponse,
     base_path: &str,
     resource_name: &str,
+    session: &Session,
 ) -> HttpResponse {
-    if response.status().is_success() {
+    let location = if response.status().is_success() {
         info!("✅ Resource '{}' created successfully, redirecting to list", resource_name);
-        let location = format!("/adminx/{}/list?success=created", base_path);
-        HttpResponse::Found()
-            .append_header(("Location", location))
-            .finish()
+        push_flash(session, FlashMessage::success("Successfully created new item!"));
+        admin_path(&format!("/{}/list", base_path))
     } else {
         error!("❌ Resource '{}' creation failed with status: {}", resource_name, response.status());
-        let location = format!("/adminx/{}/new?error=create_failed", base_path);
-        HttpResponse::Found()
-            .append_header(("Location", location))
-            .finish()
-    }
+        if response.status() == actix_web::http::StatusCode::UNPROCESSABLE_ENTITY {
+            push_flash(session, FlashMessage::error("Some fields are invalid. Check the values and try again."));
+        } else {
+            push_flash(session, FlashMessage::error("Failed to create item. Please try again."));
+        }
+        admin_path(&format!("/{}/new", base_path))
+    };
+    HttpResponse::Found()
+        .append_header(("Location", location))
+        .finish()
 }
 
 pub fn handle_update_response(
@@ -209,20 +533,26 @@ pub fn handle_update_response(
     base_path: &str,
     item_id: &str,
     resource_name: &str,
+    session: &Session,
 ) -> HttpResponse {
-    if response.status().is_success() {
+    let location = if response.status().is_success() {
         info!("✅ Resource '{}' item '{}' updated successfully, redirecting to view", resource_name, item_id);
-        let location = format!("/adminx/{}/view/{}?success=updated", base_path, item_id);
-        HttpResponse::Found()
-            .append_header(("Location", location))
-            .finish()
+        push_flash(session, FlashMessage::success("Successfully updated item!"));
+        admin_path(&format!("/{}/view/{}", base_path, item_id))
     } else {
         error!("❌ Resource '{}' item '{}' update failed with status: {}", resource_name, item_id, response.status());
-        let location = format!("/adminx/{}/edit/{}?error=update_failed", base_path, item_id);
-        HttpResponse::Found()
-            .append_header(("Location", location))
-            .finish()
-    }
+        if response.status() == actix_web::http::StatusCode::CONFLICT {
+            push_flash(session, FlashMessage::error("This item was changed by someone else. Review the latest version and try again."));
+        } else if response.status() == actix_web::http::StatusCode::UNPROCESSABLE_ENTITY {
+            push_flash(session, FlashMessage::error("Some fields are invalid. Check the values and try again."));
+        } else {
+            push_flash(session, FlashMessage::error("Failed to update item. Please try again."));
+        }
+        admin_path(&format!("/{}/edit/{}", base_path, item_id))
+    };
+    HttpResponse::Found()
+        .append_header(("Location", location))
+        .finish()
 }
 /*-------------------------------------------------------------------------
 /// END Handle resource creation response and return appropriate redirect
@@ -230,6 +560,185 @@ pub fn handle_update_response(
 
 
 
+/// Field holding the document revision for resources using `uses_versioning()`
+pub const VERSION_FIELD: &str = "version";
+
+/// Read the revision an update was based on. Form posts send it as a string.
+pub fn submitted_version(payload: &Value) -> Option<i64> {
+    match payload.get(VERSION_FIELD)? {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Filter and update documents for an optimistic-concurrency update.
+/// Version 0 also matches documents saved before versioning was enabled.
+pub fn versioned_update(
+    oid: mongodb::bson::oid::ObjectId,
+    set_doc: mongodb::bson::Document,
+    version: i64,
+) -> (mongodb::bson::Document, mongodb::bson::Document) {
+    let filter = if version == 0 {
+        mongodb::bson::doc! {
+            "_id": oid,
+            "$or": [
+                { VERSION_FIELD: 0_i64 },
+                { VERSION_FIELD: { "$exists": false } },
+            ]
+        }
+    } else {
+        mongodb::bson::doc! { "_id": oid, VERSION_FIELD: version }
+    };
+    let update = mongodb::bson::doc! {
+        "$set": set_doc,
+        "$inc": { VERSION_FIELD: 1_i64 },
+    };
+    (filter, update)
+}
+
+/// Query parameters matched with a case-insensitive regex by the list filters
+pub const TEXT_SEARCH_KEYS: &[&str] = &["name", "email", "username", "key", "title", "description", "search"];
+
+/// Apply the resource's search length limits to the text filters in `query_params`.
+/// Terms shorter than `min_search_length()` are dropped; one longer than
+/// `max_search_length()` is a bad request.
+pub fn enforce_search_limits(
+    resource: &dyn AdmixResource,
+    query_params: &mut std::collections::HashMap<String, String>,
+) -> Result<(), AdminxError> {
+    let (min, max) = (resource.min_search_length(), resource.max_search_length());
+    for key in TEXT_SEARCH_KEYS {
+        let Some(value) = query_params.get(*key) else {
+            continue;
+        };
+        let length = value.trim().chars().count();
+        if length > max {
+            warn!("Rejected {} filter of {} characters (max {})", key, length, max);
+            return Err(AdminxError::BadRequest(format!(
+                "The {} filter may be at most {} characters long",
+                key, max
+            )));
+        }
+        if length < min {
+            query_params.remove(*key);
+        }
+    }
+    Ok(())
+}
+
+/// Hide soft-deleted documents from a list filter. A query that filters on the
+/// soft-delete field itself (`?is_deleted=true` to browse the trash) is left as is.
+pub fn hide_soft_deleted(filter: &mut mongodb::bson::Document, soft_delete_field: Option<&str>) {
+    let Some(field) = soft_delete_field else {
+        return;
+    };
+    match filter.get(field) {
+        Some(mongodb::bson::Bson::String(value)) if value == "true" || value == "false" => {
+            let deleted = value == "true";
+            filter.insert(field, deleted);
+        }
+        Some(_) => {}
+        None => {
+            filter.insert(field, mongodb::bson::doc! { "$ne": true });
+        }
+    }
+}
+
+/// Body of a successful create/update: the summary fields plus, when the resource
+/// returns full documents, the stored document under `data`
+pub fn saved_response_body(mut body: Value, document: Option<mongodb::bson::Document>) -> Value {
+    if let (Value::Object(map), Some(document)) = (&mut body, document) {
+        map.insert("data".to_string(), serde_json::json!(document));
+    }
+    body
+}
+
+/// The fields a create stores: the submitted ones among `permitted`, then `defaults`
+/// for the keys left out (a submitted `null` is kept). Defaults are chosen by the server, so they are kept even
+/// for fields the creating role may not write.
+pub fn create_fields(
+    payload: &Value,
+    permitted: &HashSet<&str>,
+    defaults: &serde_json::Map<String, Value>,
+) -> serde_json::Map<String, Value> {
+    let mut fields = permitted_fields(payload, permitted);
+    for (key, value) in defaults {
+        fields.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+    fields
+}
+
+/// The fields of a payload among `permitted`; everything else is dropped when saving
+pub fn permitted_fields(payload: &Value, permitted: &HashSet<&str>) -> serde_json::Map<String, Value> {
+    payload.as_object()
+        .map(|map| map.iter()
+            .filter(|(key, _)| permitted.contains(key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect())
+        .unwrap_or_default()
+}
+
+/// 201 for a newly inserted document, with `Location` pointing at its JSON API route
+pub fn created_response(base_path: &str, inserted_id: &mongodb::bson::Bson, body: Value) -> HttpResponse {
+    let id = match inserted_id {
+        mongodb::bson::Bson::ObjectId(oid) => oid.to_hex(),
+        mongodb::bson::Bson::String(id) => id.clone(),
+        other => other.to_string(),
+    };
+    HttpResponse::Created()
+        .append_header((actix_web::http::header::LOCATION, admin_path(&format!("/{}/api/{}", base_path, urlencoding::encode(&id)))))
+        .json(body)
+}
+
+/// Response for an `update_one` result. Only an unmatched filter is a 404 (or a 409
+/// for a versioned update); a matched document the payload left unchanged is a
+/// success with `"modified": false`. `document`, when given, is returned under `data`.
+pub fn update_result_response(
+    resource_name: &str,
+    id: &str,
+    versioned: bool,
+    matched_count: u64,
+    modified_count: u64,
+    document: Option<mongodb::bson::Document>,
+) -> HttpResponse {
+    if matched_count == 0 {
+        if versioned {
+            warn!("Stale version for {} in {}", id, resource_name);
+            return actix_web::ResponseError::error_response(&AdminxError::Conflict("This record was modified by someone else".into()));
+        }
+        warn!("No document found to update with id: {} for {}", id, resource_name);
+        return actix_web::ResponseError::error_response(&AdminxError::NotFound);
+    }
+
+    let modified = modified_count > 0;
+    if modified {
+        info!("Document {} updated successfully for {}", id, resource_name);
+    } else {
+        info!("Document {} for {} already had the submitted values", id, resource_name);
+    }
+    HttpResponse::Ok().json(saved_response_body(serde_json::json!({
+        "success": true,
+        "message": if modified {
+            format!("{} updated successfully", resource_name)
+        } else {
+            format!("{} unchanged", resource_name)
+        },
+        "modified": modified,
+        "modified_count": modified_count
+    }), document))
+}
+
+/// Update marking a document deleted (or restoring it) via the soft-delete field,
+/// bumping `updated_field` to `now` when the resource manages timestamps
+pub fn soft_delete_update(field: &str, deleted: bool, updated_field: Option<&str>, now: mongodb::bson::DateTime) -> mongodb::bson::Document {
+    let mut set = mongodb::bson::doc! { field: deleted };
+    if let Some(updated) = updated_field {
+        set.insert(updated, now);
+    }
+    mongodb::bson::doc! { "$set": set }
+}
+
 /// Get default list structure for resources that don't define one
 pub fn get_default_list_structure() -> Value {
     serde_json::json!({
@@ -250,54 +759,64 @@ pub fn get_default_list_structure() -> Value {
     })
 }
 
-/// Fetch list data - Generic version that works with any resource
-pub async fn fetch_list_data(
-    resource: &Arc<Box<dyn AdmixResource>>,
-    req: &HttpRequest,
-    _query_string: String,
-) -> Result<(Vec<String>, Vec<serde_json::Map<String, Value>>, Value), Box<dyn std::error::Error + Send + Sync>> {
-    let collection = resource.get_collection();
-    
-    // Parse query parameters for pagination and filters
-    let query_params: std::collections::HashMap<String, String> = 
-        serde_urlencoded::from_str(req.query_string()).unwrap_or_default();
-    
-    let page: u64 = query_params.get("page")
-        .and_then(|p| p.parse().ok())
-        .unwrap_or(1);
-    let per_page: u64 = query_params.get("per_page")
-        .and_then(|p| p.parse().ok())
-        .unwrap_or(10);
-    
-    let skip = (page - 1) * per_page;
-    
-    // Build filter document from query parameters
+/// `get_default_list_structure()` with the created column under the resource's own
+/// timestamp name, or left out when the resource manages no timestamps
+pub fn default_list_structure<R: AdmixResource + ?Sized>(resource: &R) -> Value {
+    let mut structure = get_default_list_structure();
+    if let Some(columns) = structure.get_mut("columns").and_then(|c| c.as_array_mut()) {
+        match resource.timestamps() {
+            Some((created, _)) => columns[1]["field"] = Value::from(created),
+            None => columns.truncate(1),
+        }
+    }
+    structure
+}
+
+/// `readable_keys()` followed by the resource's timestamp fields: the columns of an export
+pub fn export_keys<R: AdmixResource + ?Sized>(resource: &R, roles: &[String]) -> Vec<&'static str> {
+    let mut keys = readable_keys(resource, roles);
+    if let Some((created, updated)) = resource.timestamps() {
+        for field in [created, updated] {
+            if !keys.contains(&field) {
+                keys.push(field);
+            }
+        }
+    }
+    keys
+}
+
+/// Page sizes offered by the list view's page-size selector
+pub const PER_PAGE_OPTIONS: [u64; 4] = [10, 25, 50, 100];
+
+/// Build the MongoDB filter for the list view from query parameters. Only permitted
+/// fields are filtered on; dotted paths like `address.city` are passed through as-is.
+/// Exact-match values are coerced to the field's type from `field_types` when known.
+/// `search` matches any of `search_fields` (see `AdmixResource::searchable_fields`).
+pub fn build_filter_document(
+    permitted_fields: &HashSet<&str>,
+    field_types: &std::collections::HashMap<String, String>,
+    search_fields: &[&str],
+    query_params: &std::collections::HashMap<String, String>,
+) -> mongodb::bson::Document {
     let mut filter_doc = mongodb::bson::doc! {};
     
-    // Get permitted query fields for security
-    let permitted_fields: HashSet<&str> = resource.permit_keys().into_iter().collect();
-    
     // Build filters based on query parameters
-    for (key, value) in &query_params {
-        if !value.is_empty() && (permitted_fields.contains(key.as_str()) || key == "search") {
+    for (key, value) in query_params {
+        if !value.is_empty() && (is_permitted_field(permitted_fields, key) || key == "search") {
             match key.as_str() {
                 // Text fields that should use regex search
                 "name" | "email" | "username" | "key" | "title" | "description" | "search" => {
                     if key == "search" {
-                        // Global search across multiple fields
-                        let search_fields = vec!["name", "email", "username", "key", "title", "description"];
-                        let mut search_conditions = Vec::new();
-                        
-                        for field in search_fields {
-                            if permitted_fields.contains(field) {
-                                search_conditions.push(mongodb::bson::doc! {
-                                    field: {
-                                        "$regex": value,
-                                        "$options": "i"
-                                    }
-                                });
-                            }
-                        }
+                        // Global search across the resource's searchable fields
+                        let search_conditions: Vec<mongodb::bson::Document> = search_fields
+                            .iter()
+                            .map(|field| mongodb::bson::doc! {
+                                *field: {
+                                    "$regex": value,
+                                    "$options": "i"
+                                }
+                            })
+                            .collect();
                         
                         if !search_conditions.is_empty() {
                             filter_doc.insert("$or", search_conditions);
@@ -312,7 +831,9 @@ pub async fn fetch_list_data(
                 // Exact match fields
                 "status" | "data_type" | "deleted" | "active" | "enabled" => {
                     // Handle boolean fields properly
-                    if value == "true" || value == "false" {
+                    if let Some(field_type) = field_types.get(key) {
+                        filter_doc.insert(key, coerce_filter_value(field_type, value));
+                    } else if value == "true" || value == "false" {
                         let bool_val = value == "true";
                         filter_doc.insert(key, bool_val);
                     } else {
@@ -322,7 +843,7 @@ pub async fn fetch_list_data(
                 // Date range filters
                 key if key.ends_with("_from") => {
                     let base_field = key.trim_end_matches("_from");
-                    if permitted_fields.contains(base_field) {
+                    if is_permitted_field(permitted_fields, base_field) {
                         if let Ok(date) = chrono::DateTime::parse_from_rfc3339(&format!("{}T00:00:00Z", value)) {
                             let existing_filter = filter_doc.get_mut(base_field);
                             match existing_filter {
@@ -340,7 +861,7 @@ pub async fn fetch_list_data(
                 }
                 key if key.ends_with("_to") => {
                     let base_field = key.trim_end_matches("_to");
-                    if permitted_fields.contains(base_field) {
+                    if is_permitted_field(permitted_fields, base_field) {
                         if let Ok(date) = chrono::DateTime::parse_from_rfc3339(&format!("{}T23:59:59Z", value)) {
                             let existing_filter = filter_doc.get_mut(base_field);
                             match existing_filter {
@@ -359,7 +880,7 @@ pub async fn fetch_list_data(
                 // Number range filters
                 key if key.ends_with("_min") => {
                     let base_field = key.trim_end_matches("_min");
-                    if permitted_fields.contains(base_field) {
+                    if is_permitted_field(permitted_fields, base_field) {
                         if let Ok(num) = value.parse::<f64>() {
                             let existing_filter = filter_doc.get_mut(base_field);
                             match existing_filter {
@@ -377,7 +898,7 @@ pub async fn fetch_list_data(
                 }
                 key if key.ends_with("_max") => {
                     let base_field = key.trim_end_matches("_max");
-                    if permitted_fields.contains(base_field) {
+                    if is_permitted_field(permitted_fields, base_field) {
                         if let Ok(num) = value.parse::<f64>() {
                             let existing_filter = filter_doc.get_mut(base_field);
                             match existing_filter {
@@ -394,35 +915,106 @@ pub async fn fetch_list_data(
                     }
                 }
                 _ => {
-                    // Default: exact match for other fields
-                    filter_doc.insert(key, value);
+                    // Default: exact match for other fields, typed when the field type is known
+                    match field_types.get(key) {
+                        Some(field_type) => filter_doc.insert(key, coerce_filter_value(field_type, value)),
+                        None => filter_doc.insert(key, value),
+                    };
                 }
             }
         }
     }
     
-    info!("Applied filters: {:?}", filter_doc);
-    
-    // Get total count with filters
-    let total = collection.count_documents(filter_doc.clone(), None).await
-        .unwrap_or(0);
-    
-    // Fetch documents with pagination and filters
-    let mut find_options = mongodb::options::FindOptions::default();
-    find_options.skip = Some(skip);
-    find_options.limit = Some(per_page as i64);
-    find_options.sort = Some(mongodb::bson::doc! { "created_at": -1 });
-    
-    let mut cursor = collection.find(filter_doc, find_options).await
-        .map_err(|e| format!("Database query failed: {}", e))?;
-    
-    let mut documents = Vec::new();
-    while let Some(doc) = cursor.try_next().await.unwrap_or(None) {
-        documents.push(doc);
+    filter_doc
+}
+
+/// Coerce a query string filter value to the BSON type matching `field_type`,
+/// falling back to the raw string when it doesn't parse
+pub fn coerce_filter_value(field_type: &str, value: &str) -> mongodb::bson::Bson {
+    use mongodb::bson::Bson;
+
+    match field_type {
+        "number" | "integer" | "int" => value.parse::<i64>().map(Bson::Int64)
+            .or_else(|_| value.parse::<f64>().map(Bson::Double))
+            .unwrap_or_else(|_| Bson::String(value.to_string())),
+        "float" | "decimal" | "double" => value.parse::<f64>().map(Bson::Double)
+            .unwrap_or_else(|_| Bson::String(value.to_string())),
+        "boolean" | "checkbox" | "bool" => match value {
+            "true" | "1" | "on" => Bson::Boolean(true),
+            "false" | "0" | "off" => Bson::Boolean(false),
+            _ => Bson::String(value.to_string()),
+        },
+        _ => Bson::String(value.to_string()),
     }
-    
-    // Get column structure from resource's list_structure or use defaults
-    let list_structure = resource.list_structure().unwrap_or_else(|| get_default_list_structure());
+}
+
+/// A field (or dotted path whose root field) is in `permitted_fields`
+fn is_permitted_field(permitted_fields: &HashSet<&str>, field: &str) -> bool {
+    permitted_fields.contains(field)
+        || field.split_once('.').is_some_and(|(root, _)| permitted_fields.contains(root))
+}
+
+/// Look up a possibly dotted path like `address.city` in a document
+pub fn get_path<'a>(doc: &'a mongodb::bson::Document, path: &str) -> Option<&'a mongodb::bson::Bson> {
+    let mut segments = path.split('.');
+    let mut current = doc.get(segments.next()?)?;
+    for segment in segments {
+        current = current.as_document()?.get(segment)?;
+    }
+    Some(current)
+}
+
+/// Find options for read-only queries, routed per the resource's `read_preference()`
+/// and bounded by its `query_timeout()`
+pub fn read_find_options<R: AdmixResource + ?Sized>(resource: &R) -> mongodb::options::FindOptions {
+    let mut options = mongodb::options::FindOptions::default();
+    options.selection_criteria = Some(SelectionCriteria::ReadPreference(resource.read_preference()));
+    options.max_time = resource.query_timeout();
+    options
+}
+
+/// Count options matching `read_find_options`
+pub fn read_count_options<R: AdmixResource + ?Sized>(resource: &R) -> mongodb::options::CountOptions {
+    let mut options = mongodb::options::CountOptions::default();
+    options.selection_criteria = Some(SelectionCriteria::ReadPreference(resource.read_preference()));
+    options.max_time = resource.query_timeout();
+    options
+}
+
+/// Whether a boxed list or export error is a query that ran past `query_timeout()`
+pub fn is_timeout(e: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    matches!(e.downcast_ref::<AdminxError>(), Some(AdminxError::Timeout))
+}
+
+/// Response for a failed export: 504 when it timed out, else a plain-text 500 starting with `message`
+pub fn export_error_response(e: &(dyn std::error::Error + Send + Sync + 'static), message: &str) -> HttpResponse {
+    if is_timeout(e) {
+        return actix_web::ResponseError::error_response(&AdminxError::Timeout);
+    }
+    HttpResponse::InternalServerError()
+        .content_type("text/plain")
+        .body(format!("{}: {}", message, e))
+}
+
+/// `AdminxError` for a failed read: `Timeout` when it ran past `query_timeout()`
+/// (MongoDB's `MaxTimeMSExpired`), else an internal error
+pub fn query_error(resource_name: &str, e: &mongodb::error::Error) -> AdminxError {
+    match e.kind.as_ref() {
+        mongodb::error::ErrorKind::Command(command) if command.code == MAX_TIME_MS_EXPIRED => {
+            warn!("Query on {} exceeded its time limit", resource_name);
+            AdminxError::Timeout
+        }
+        _ => {
+            error!("Query on {} failed: {}", resource_name, e);
+            AdminxError::InternalError
+        }
+    }
+}
+
+/// List view columns: the `list_structure()` fields, else `id`, the permitted fields and
+/// the created timestamp, minus the ones `roles` may not read
+pub fn list_columns(resource: &dyn AdmixResource, roles: &[String]) -> Vec<String> {
+    let list_structure = resource.list_structure().unwrap_or_else(|| default_list_structure(resource));
     let columns = list_structure.get("columns")
         .and_then(|c| c.as_array())
         .map(|cols| {
@@ -433,115 +1025,254 @@ pub async fn fetch_list_data(
         })
         .unwrap_or_else(|| {
             // Default columns based on permitted fields
+            let timestamps = resource.timestamps();
             let mut default_cols = vec!["id".to_string()];
             let permitted = resource.permit_keys();
             for field in permitted {
-                if field != "_id" && field != "created_at" && field != "updated_at" {
+                if field != "_id" && !timestamps.is_some_and(|(created, updated)| field == created || field == updated) {
                     default_cols.push(field.to_string());
                 }
             }
-            default_cols.push("created_at".to_string());
+            if let Some((created, _)) = timestamps {
+                default_cols.push(created.to_string());
+            }
             default_cols
         });
-    
-    // Convert MongoDB documents to the format expected by the template
-    let rows: Vec<serde_json::Map<String, Value>> = documents
+
+    // Hide columns the current user's roles may not read
+    columns
         .into_iter()
-        .map(|doc| {
-            let mut row = serde_json::Map::new();
-            
-            // Handle MongoDB ObjectId
-            if let Ok(oid) = doc.get_object_id("_id") {
-                row.insert("id".to_string(), Value::String(oid.to_hex()));
-            }
-            
-            // Extract fields based on permitted fields and columns
-            for field_name in &columns {
-                if field_name == "id" {
-                    continue; // Already handled above
-                }
-                
-                // Try different data types for each field
-                if let Ok(string_val) = doc.get_str(field_name) {
-                    row.insert(field_name.clone(), Value::String(string_val.to_string()));
-                } else if let Ok(bool_val) = doc.get_bool(field_name) {
-                    row.insert(field_name.clone(), Value::String(bool_val.to_string()));
-                } else if let Ok(int_val) = doc.get_i32(field_name) {
-                    row.insert(field_name.clone(), Value::String(int_val.to_string()));
-                } else if let Ok(int64_val) = doc.get_i64(field_name) {
-                    row.insert(field_name.clone(), Value::String(int64_val.to_string()));
-                } else if let Ok(datetime_val) = doc.get_datetime(field_name) {
-                    let timestamp_ms = datetime_val.timestamp_millis();
-                    if let Some(datetime) = chrono::DateTime::from_timestamp_millis(timestamp_ms) {
-                        row.insert(field_name.clone(), 
-                                 Value::String(datetime.format("%Y-%m-%d %H:%M:%S").to_string()));
-                    } else {
-                        row.insert(field_name.clone(), Value::String("N/A".to_string()));
-                    }
-                } else if doc.contains_key(field_name) {
-                    // Handle other BSON types
-                    if let Some(bson_val) = doc.get(field_name) {
-                        match bson_val {
-                            mongodb::bson::Bson::String(s) => {
-                                row.insert(field_name.clone(), Value::String(s.clone()));
-                            }
-                            mongodb::bson::Bson::Boolean(b) => {
-                                row.insert(field_name.clone(), Value::String(b.to_string()));
-                            }
-                            mongodb::bson::Bson::Int32(i) => {
-                                row.insert(field_name.clone(), Value::String(i.to_string()));
-                            }
-                            mongodb::bson::Bson::Int64(i) => {
-                                row.insert(field_name.clone(), Value::String(i.to_string()));
-                            }
-                            mongodb::bson::Bson::Double(d) => {
-                                row.insert(field_name.clone(), Value::String(d.to_string()));
-                            }
-                            mongodb::bson::Bson::Null => {
-                                row.insert(field_name.clone(), Value::String("".to_string()));
-                            }
-                            _ => {
-                                row.insert(field_name.clone(), Value::String(format!("{:?}", bson_val)));
-                            }
-                        }
-                    }
-                } else {
-                    // Field doesn't exist in document
-                    row.insert(field_name.clone(), Value::String("N/A".to_string()));
-                }
-            }
-            
-            row
+        .filter(|col| {
+            let root = col.split('.').next().unwrap_or(col);
+            col == "id" || can_read_field(resource, roles, root)
         })
-        .collect();
+        .collect()
+}
+
+/// Fetch list data - Generic version that works with any resource.
+/// `query` should already have been checked with `ListQuery::validate`.
+pub async fn fetch_list_data(
+    resource: &Arc<Box<dyn AdmixResource>>,
+    req: &HttpRequest,
+    query: &ListQuery,
+) -> Result<(Vec<String>, Vec<serde_json::Map<String, Value>>, Value), Box<dyn std::error::Error + Send + Sync>> {
+    let collection = resource.try_get_collection()?;
     
-    let total_pages = if per_page > 0 { (total + per_page - 1) / per_page } else { 1 };
+    let page = query.page;
+    let per_page = query.per_page_for(resource.as_ref().as_ref());
     
-    // Build pagination with current filters
-    let mut filter_params = Vec::new();
-    for (key, value) in &query_params {
-        if key != "page" && !value.is_empty() {
-            filter_params.push(format!("{}={}", key, urlencoding::encode(value)));
-        }
+    let skip = page_offset(page, per_page);
+    
+    // Only permitted fields are filtered on
+    let filter_doc = query.filter_document(resource.as_ref().as_ref());
+    
+    info!("Applied filters: {:?}", filter_doc);
+    
+    // Get total count with filters
+    let total = collection.count_documents(filter_doc.clone(), read_count_options(resource.as_ref().as_ref())).await
+        .map_err(|e| query_error(resource.resource_name(), &e))?;
+    
+    // Fetch documents with pagination and filters
+    let mut find_options = read_find_options(resource.as_ref().as_ref());
+    find_options.skip = Some(skip);
+    find_options.limit = Some(per_page as i64);
+    find_options.sort = Some(query.sort_document(resource.as_ref().as_ref()));
+    
+    let mut cursor = collection.find(filter_doc, find_options).await
+        .map_err(|e| query_error(resource.resource_name(), &e))?;
+    
+    let mut documents = Vec::new();
+    while let Some(doc) = cursor.try_next().await.map_err(|e| query_error(resource.resource_name(), &e))? {
+        documents.push(doc);
     }
-    let filter_string = if filter_params.is_empty() {
+    
+    let columns = list_columns(resource.as_ref().as_ref(), &roles_from_request(req));
+    
+    // Convert MongoDB documents to the format expected by the template
+    let rows = build_list_rows(resource.as_ref().as_ref(), &documents, &columns, request_timezone(req)).await;
+    
+    let total_pages = total_pages(total, per_page);
+    
+    // Build pagination with current filters
+    let mut filter_params = query.link_params();
+    let join_params = |params: &[String]| if params.is_empty() {
         String::new()
     } else {
-        format!("&{}", filter_params.join("&"))
+        format!("&{}", params.join("&"))
     };
+    // Filters without the page size, used by the page-size selector
+    let base_params = join_params(&filter_params);
+
+    // Keep a non-default page size across pagination and filter links
+    if per_page != resource.default_per_page() {
+        filter_params.push(format!("per_page={}", per_page));
+    }
+    let filter_string = join_params(&filter_params);
+
+    let per_page_options: Vec<u64> = PER_PAGE_OPTIONS
+        .iter()
+        .copied()
+        .filter(|size| *size <= resource.max_per_page())
+        .collect();
     
     let pagination = serde_json::json!({
         "current": page,
         "total": total_pages,
         "prev": if page > 1 { Some(page - 1) } else { None },
         "next": if page < total_pages { Some(page + 1) } else { None },
-        "filter_params": filter_string
+        "filter_params": filter_string,
+        "base_params": base_params,
+        "per_page": per_page,
+        "per_page_options": per_page_options
     });
     
     info!("Fetched {} items for list view (page {} of {}) with filters", rows.len(), page, total_pages);
     Ok((columns, rows, pagination))
 }
 
+/// Fields of the `list_structure` columns marked `"computed": true`, filled in by
+/// `compute_column()` rather than read from the document
+pub fn computed_columns(list_structure: &Value) -> Vec<String> {
+    list_structure.get("columns")
+        .and_then(|c| c.as_array())
+        .map(|cols| {
+            cols.iter()
+                .filter(|col| col.get("computed").and_then(|c| c.as_bool()).unwrap_or(false))
+                .filter_map(|col| col.get("field").and_then(|f| f.as_str()))
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Render a page of documents into rows, attaching whatever the resource's
+/// `prefetch` hook loaded for each document and applying column `"format"` specs
+pub async fn build_list_rows(
+    resource: &dyn AdmixResource,
+    documents: &[mongodb::bson::Document],
+    columns: &[String],
+    tz: Tz,
+) -> Vec<serde_json::Map<String, Value>> {
+    let prefetched = resource.prefetch(documents).await;
+    let formats = resource_column_formats(resource);
+    let computed: Vec<&String> = resource.list_structure()
+        .map(|structure| computed_columns(&structure))
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|field| columns.iter().find(|col| **col == field))
+        .collect();
+
+    documents
+        .iter()
+        .map(|doc| {
+            let mut row = document_to_row(doc, columns, tz);
+            for field in &computed {
+                row.insert(field.to_string(), resource.compute_column(field, doc).unwrap_or(Value::Null));
+            }
+            let related = doc.get_object_id("_id").ok().and_then(|oid| prefetched.get(&oid));
+            if let Some(Value::Object(related)) = related {
+                for (key, value) in related {
+                    row.insert(key.clone(), value.clone());
+                }
+            }
+            apply_column_formats(&formats, &mut row);
+            row
+        })
+        .collect()
+}
+
+/// Convert one MongoDB document to a list row holding `columns`, keeping native JSON
+/// types for booleans, numbers and nulls. Columns may be dotted paths into nested
+/// documents. Missing fields render as "N/A"; dates are shown in `tz`.
+pub fn document_to_row(doc: &mongodb::bson::Document, columns: &[String], tz: Tz) -> serde_json::Map<String, Value> {
+    let mut row = serde_json::Map::new();
+    
+    // Handle MongoDB ObjectId
+    if let Ok(oid) = doc.get_object_id("_id") {
+        row.insert("id".to_string(), Value::String(oid.to_hex()));
+    }
+    
+    for field_name in columns {
+        if field_name == "id" {
+            continue; // Already handled above
+        }
+        
+        let value = match get_path(doc, field_name) {
+            Some(bson_val) => bson_to_json_value(bson_val, tz),
+            // Nested paths with a missing segment render blank
+            None if field_name.contains('.') => Value::Null,
+            None => Value::String("N/A".to_string()),
+        };
+        row.insert(field_name.clone(), value);
+    }
+    
+    row
+}
+
+/// Convert a BSON value to JSON for templates and API consumers. Booleans, numbers and
+/// nulls keep their type; dates become display strings in `tz` and ObjectIds hex strings.
+pub fn bson_to_json_value(value: &mongodb::bson::Bson, tz: Tz) -> Value {
+    use mongodb::bson::Bson;
+
+    match value {
+        Bson::String(s) => Value::String(s.clone()),
+        Bson::Boolean(b) => Value::Bool(*b),
+        Bson::Int32(i) => Value::from(*i),
+        Bson::Int64(i) => Value::from(*i),
+        Bson::Double(d) => serde_json::Number::from_f64(*d).map(Value::Number).unwrap_or(Value::Null),
+        Bson::Null | Bson::Undefined => Value::Null,
+        Bson::DateTime(dt) => Value::String(
+            format_datetime(dt, tz, DISPLAY_DATETIME_FORMAT).unwrap_or_else(|| "N/A".to_string()),
+        ),
+        Bson::ObjectId(oid) => Value::String(oid.to_hex()),
+        // For complex types, convert to string representation
+        other => Value::String(format!("{:?}", other)),
+    }
+}
+
+/// Validate a partial (inline) update. Every key must be writable by `roles` and not
+/// listed in `readonly_keys()`; any other key rejects the whole update.
+pub fn validate_partial_update<R: AdmixResource + ?Sized>(
+    resource: &R,
+    roles: &[String],
+    payload: Value,
+) -> Result<serde_json::Map<String, Value>, AdminxError> {
+    let Value::Object(fields) = payload else {
+        return Err(AdminxError::BadRequest("Expected a JSON object".into()));
+    };
+    if fields.is_empty() {
+        return Err(AdminxError::BadRequest("No fields to update".into()));
+    }
+
+    let readonly = resource.readonly_keys();
+    let writable = writable_keys(resource, roles);
+    for key in fields.keys() {
+        if readonly.contains(&key.as_str()) {
+            return Err(AdminxError::BadRequest(format!("Field '{}' is read-only", key)));
+        }
+        if !writable.contains(&key.as_str()) {
+            return Err(AdminxError::BadRequest(format!("Field '{}' is not editable", key)));
+        }
+    }
+
+    Ok(fields)
+}
+
+/// Columns flagged `"inline_editable": true` in `list_structure()` that `roles` may write
+pub fn inline_editable_fields(resource: &dyn AdmixResource, roles: &[String]) -> Vec<String> {
+    let writable = writable_keys(resource, roles);
+    let readonly = resource.readonly_keys();
+    resource.list_structure()
+        .and_then(|structure| structure.get("columns").and_then(|c| c.as_array()).cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter(|col| col.get("inline_editable").and_then(|v| v.as_bool()).unwrap_or(false))
+        .filter_map(|col| col.get("field").and_then(|f| f.as_str()))
+        .filter(|field| writable.contains(field) && !readonly.contains(field))
+        .map(|field| field.to_string())
+        .collect()
+}
+
 /// Get filters data and current filter values for the template
 pub fn get_filters_data(
     resource: &Arc<Box<dyn AdmixResource>>,
@@ -608,19 +1339,21 @@ pub fn get_filters_data(
 /// Fetch single item data for view/edit pages - Generic version that works with any resource
 pub async fn fetch_single_item_data(
     resource: &Arc<Box<dyn AdmixResource>>,
-    _req: &HttpRequest,
+    req: &HttpRequest,
     id: &str,
-) -> Result<serde_json::Map<String, Value>, Box<dyn std::error::Error + Send + Sync>> {
-    let collection = resource.get_collection();
-    
+) -> Result<serde_json::Map<String, Value>, AdminxError> {
     // Parse ObjectId
     let oid = mongodb::bson::oid::ObjectId::parse_str(id)
-        .map_err(|e| format!("Invalid ObjectId: {}", e))?;
+        .map_err(|e| AdminxError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+    let collection = resource.try_get_collection()?;
     
     // Find the document
     let doc = collection.find_one(mongodb::bson::doc! { "_id": oid }, None).await
-        .map_err(|e| format!("Database query failed: {}", e))?
-        .ok_or("Document not found")?;
+        .map_err(|e| {
+            error!("Database query failed for {}: {}", resource.resource_name(), e);
+            AdminxError::InternalError
+        })?
+        .ok_or(AdminxError::NotFound)?;
     
     // Convert to template-friendly format
     let mut record = serde_json::Map::new();
@@ -630,84 +1363,48 @@ pub async fn fetch_single_item_data(
         record.insert("id".to_string(), Value::String(oid.to_hex()));
     }
     
-    // Get all permitted fields from the resource and extract them from the document
-    let permitted_fields = resource.permit_keys();
+    // Get all permitted fields the user may read and extract them from the document
+    let permitted_fields = readable_keys(resource.as_ref().as_ref(), &roles_from_request(req));
+    let tz = request_timezone(req);
+    let timestamps = resource.timestamps();
+    let is_timestamp = |field: &str| timestamps.is_some_and(|(created, updated)| field == created || field == updated);
     
     for field_name in permitted_fields {
-        // Try different data types for each field
-        if let Ok(string_val) = doc.get_str(field_name) {
-            record.insert(field_name.to_string(), Value::String(string_val.to_string()));
-        } else if let Ok(bool_val) = doc.get_bool(field_name) {
-            record.insert(field_name.to_string(), Value::String(bool_val.to_string()));
-        } else if let Ok(int_val) = doc.get_i32(field_name) {
-            record.insert(field_name.to_string(), Value::String(int_val.to_string()));
-        } else if let Ok(int64_val) = doc.get_i64(field_name) {
-            record.insert(field_name.to_string(), Value::String(int64_val.to_string()));
-        } else if let Ok(float_val) = doc.get_f64(field_name) {
-            record.insert(field_name.to_string(), Value::String(float_val.to_string()));
-        } else if let Ok(datetime_val) = doc.get_datetime(field_name) {
-            let timestamp_ms = datetime_val.timestamp_millis();
-            if let Some(datetime) = chrono::DateTime::from_timestamp_millis(timestamp_ms) {
+        match doc.get(field_name) {
+            Some(mongodb::bson::Bson::DateTime(datetime_val)) => {
                 // For date/datetime fields, format them appropriately
-                if field_name.contains("date") || field_name.contains("time") || field_name == "created_at" || field_name == "updated_at" {
-                    record.insert(field_name.to_string(), 
-                                Value::String(datetime.format("%Y-%m-%d %H:%M:%S").to_string()));
+                let formatted = if field_name.contains("date") || field_name.contains("time") || is_timestamp(field_name) {
+                    format_datetime(datetime_val, tz, DISPLAY_DATETIME_FORMAT)
                 } else {
-                    record.insert(field_name.to_string(), 
-                                Value::String(datetime.to_rfc3339()));
-                }
-            } else {
-                record.insert(field_name.to_string(), Value::String("N/A".to_string()));
+                    datetime_rfc3339(datetime_val, tz)
+                };
+                record.insert(field_name.to_string(), 
+                            Value::String(formatted.unwrap_or_else(|| "N/A".to_string())));
             }
-        }
-        // If field exists but we can't parse it, try to get it as a generic BSON value
-        else if doc.contains_key(field_name) {
-            if let Some(bson_val) = doc.get(field_name) {
-                match bson_val {
-                    mongodb::bson::Bson::String(s) => {
-                        record.insert(field_name.to_string(), Value::String(s.clone()));
-                    }
-                    mongodb::bson::Bson::Boolean(b) => {
-                        record.insert(field_name.to_string(), Value::String(b.to_string()));
-                    }
-                    mongodb::bson::Bson::Int32(i) => {
-                        record.insert(field_name.to_string(), Value::String(i.to_string()));
-                    }
-                    mongodb::bson::Bson::Int64(i) => {
-                        record.insert(field_name.to_string(), Value::String(i.to_string()));
-                    }
-                    mongodb::bson::Bson::Double(d) => {
-                        record.insert(field_name.to_string(), Value::String(d.to_string()));
-                    }
-                    mongodb::bson::Bson::Null => {
-                        record.insert(field_name.to_string(), Value::String("".to_string()));
-                    }
-                    _ => {
-                        // For complex types, convert to string representation
-                        record.insert(field_name.to_string(), Value::String(format!("{:?}", bson_val)));
-                    }
-                }
+            Some(bson_val) => {
+                record.insert(field_name.to_string(), bson_to_json_value(bson_val, tz));
             }
+            None => {}
         }
     }
     
-    // Always handle standard timestamp fields even if not in permit_keys
-    if !record.contains_key("created_at") {
-        if let Ok(created_at) = doc.get_datetime("created_at") {
-            let timestamp_ms = created_at.timestamp_millis();
-            if let Some(datetime) = chrono::DateTime::from_timestamp_millis(timestamp_ms) {
-                record.insert("created_at".to_string(), 
-                             Value::String(datetime.format("%Y-%m-%d %H:%M:%S").to_string()));
-            }
-        }
+    if resource.uses_versioning() {
+        let version = match doc.get(VERSION_FIELD) {
+            Some(mongodb::bson::Bson::Int32(v)) => *v as i64,
+            Some(mongodb::bson::Bson::Int64(v)) => *v,
+            _ => 0,
+        };
+        record.insert(VERSION_FIELD.to_string(), Value::from(version));
     }
-    
-    if !record.contains_key("updated_at") {
-        if let Ok(updated_at) = doc.get_datetime("updated_at") {
-            let timestamp_ms = updated_at.timestamp_millis();
-            if let Some(datetime) = chrono::DateTime::from_timestamp_millis(timestamp_ms) {
-                record.insert("updated_at".to_string(), 
-                             Value::String(datetime.format("%Y-%m-%d %H:%M:%S").to_string()));
+
+    // Always handle the timestamp fields even if not in permit_keys
+    if let Some((created, updated)) = timestamps {
+        for field in [created, updated] {
+            if record.contains_key(field) {
+                continue;
+            }
+            if let Some(formatted) = doc.get_datetime(field).ok().and_then(|dt| format_datetime(dt, tz, DISPLAY_DATETIME_FORMAT)) {
+                record.insert(field.to_string(), Value::String(formatted));
             }
         }
     }
@@ -717,6 +1414,22 @@ pub async fn fetch_single_item_data(
     Ok(record)
 }
 
+/// Styled page for a view/edit whose record could not be loaded: a malformed id and a
+/// missing record both get the 404 page, each with its own explanation
+pub async fn record_error_page(resource_name: &str, item_id: &str, error: &AdminxError) -> HttpResponse {
+    match error {
+        AdminxError::BadRequest(_) => render_404_with_message(
+            "Invalid Record ID",
+            &format!("This link doesn't point to a {} record: record IDs are 24-character hexadecimal strings.", resource_name),
+        ).await,
+        AdminxError::NotFound => render_404_with_message(
+            "Record Not Found",
+            &format!("There is no {} record with ID {}. It may have been deleted.", resource_name, item_id),
+        ).await,
+        _ => render_500(None).await,
+    }
+}
+
 pub fn get_default_form_structure() -> Value {
     serde_json::json!({
         "groups": [
@@ -740,94 +1453,22 @@ pub fn get_default_view_structure() -> Value {
     })
 }
 
-/// Register API-only routes without UI components
-pub fn register_api_only_routes(resource: Box<dyn AdmixResource>) -> Scope {
-    let resource_name = resource.resource_name().to_string();
-    info!("Registering API-only routes for resource: {}", resource_name);
-    
-    let mut scope = web::scope("");
-
-    // GET / - List all items
-    let list_resource = resource.clone_box();
-    scope = scope.route("", web::get().to(move |req: HttpRequest, query: web::Query<String>| {
-        let resource = list_resource.clone_box();
-        async move { 
-            info!("📡 List API endpoint called for resource: {}", resource.resource_name());
-            resource.list(&req, query.into_inner()).await 
-        }
-    }));
+/// JSON extractor settings for a resource's routes: bodies over `max_json_body_bytes()`
+/// get a 400 naming the limit instead of actix's bare overflow error
+pub fn json_body_config(resource: &dyn AdmixResource) -> web::JsonConfig {
+    let limit = resource.max_json_body_bytes();
+    let resource_name = resource.resource_name();
+    web::JsonConfig::default().limit(limit).error_handler(move |err, _req| {
+        use actix_web::error::JsonPayloadError;
 
-    // POST / - Create new item
-    let create_resource = resource.clone_box();
-    scope = scope.route("", web::post().to(move |req: HttpRequest, body: web::Json<Value>| {
-        let resource = create_resource.clone_box();
-        async move { 
-            info!("📡 Create API endpoint called for resource: {}", resource.resource_name());
-            resource.create(&req, body.into_inner()).await 
-        }
-    }));
-
-    // GET /{id} - Get single item
-    let get_resource = resource.clone_box();
-    scope = scope.route("/{id}", web::get().to(move |req: HttpRequest, path: web::Path<String>| {
-        let resource = get_resource.clone_box();
-        async move { 
-            let id = path.into_inner();
-            info!("📡 Get API endpoint called for resource: {} with id: {}", resource.resource_name(), id);
-            resource.get(&req, id).await 
-        }
-    }));
-
-    // PUT /{id} - Update item
-    let update_resource = resource.clone_box();
-    scope = scope.route("/{id}", web::put().to(move |req: HttpRequest, path: web::Path<String>, body: web::Json<Value>| {
-        let resource = update_resource.clone_box();
-        async move { 
-            let id = path.into_inner();
-            info!("📡 Update API endpoint called for resource: {} with id: {}", resource.resource_name(), id);
-            resource.update(&req, id, body.into_inner()).await 
-        }
-    }));
-
-    // DELETE /{id} - Delete item
-    let delete_resource = resource.clone_box();
-    scope = scope.route("/{id}", web::delete().to(move |req: HttpRequest, path: web::Path<String>| {
-        let resource = delete_resource.clone_box();
-        async move { 
-            let id = path.into_inner();
-            info!("📡 Delete API endpoint called for resource: {} with id: {}", resource.resource_name(), id);
-            resource.delete(&req, id).await 
-        }
-    }));
-
-    // Add custom actions
-    for action in resource.custom_actions() {
-        let path = format!("/{{id}}/{}", action.name);
-        info!("Adding custom action: {} {} for resource: {}", action.method, path, resource_name);
-        
-        match action.method {
-            "POST" => {
-                scope = scope.route(&path, web::post().to(action.handler));
-            }
-            "GET" => {
-                scope = scope.route(&path, web::get().to(action.handler));
-            }
-            "PUT" => {
-                scope = scope.route(&path, web::put().to(action.handler));
-            }
-            "DELETE" => {
-                scope = scope.route(&path, web::delete().to(action.handler));
-            }
-            "PATCH" => {
-                scope = scope.route(&path, web::patch().to(action.handler));
-            }
-            method => {
-                error!("Unsupported HTTP method: {} for action: {} in resource: {}", method, action.name, resource_name);
+        match err {
+            JsonPayloadError::Overflow { .. } | JsonPayloadError::OverflowKnownLength { .. } => {
+                warn!("Rejected JSON body over {} bytes for {}", limit, resource_name);
+                AdminxError::BadRequest(format!("JSON body exceeds the {} byte limit", limit)).into()
             }
+            other => other.into(),
         }
-    }
-
-    scope
+    })
 }
 
 /// Register protected routes with role-based access control
@@ -837,17 +1478,20 @@ pub fn register_protected_resource_routes(resource: Box<dyn AdmixResource>) -> S
     
     info!("Registering protected routes for resource: {} with roles: {:?}", resource_name, allowed_roles);
     
-    let mut scope = web::scope("");
+    let mut scope = web::scope("").app_data(json_body_config(resource.as_ref()));
 
     // GET / - List with role check
     let list_resource = resource.clone_box();
     scope = scope.route(
         "",
-        web::get().to(move |req: HttpRequest, query: web::Query<String>, session: Session, config: web::Data<AdminxConfig>| {
+        web::get().to(move |req: HttpRequest, query: web::Query<String>, config: web::Data<AdminxConfig>| {
             let resource = list_resource.clone_box();
             async move {
-                match check_resource_permission(&session, &config, resource.as_ref(), "list").await {
-                    Ok(_claims) => resource.list(&req, query.into_inner()).await,
+                match check_resource_permission(&req, &config, resource.as_ref(), "list").await {
+                    Ok(claims) => {
+                        req.extensions_mut().insert(claims);
+                        resource.list(&req, query.into_inner()).await
+                    }
                     Err(response) => response,
                 }
             }
@@ -858,11 +1502,14 @@ pub fn register_protected_resource_routes(resource: Box<dyn AdmixResource>) -> S
     let create_resource = resource.clone_box();
     scope = scope.route(
         "",
-        web::post().to(move |req: HttpRequest, body: web::Json<Value>, session: Session, config: web::Data<AdminxConfig>| {
+        web::post().to(move |req: HttpRequest, body: web::Json<Value>, config: web::Data<AdminxConfig>| {
             let resource = create_resource.clone_box();
             async move {
-                match check_resource_permission(&session, &config, resource.as_ref(), "create").await {
-                    Ok(_claims) => resource.create(&req, body.into_inner()).await,
+                match check_resource_permission(&req, &config, resource.as_ref(), "create").await {
+                    Ok(claims) => {
+                        req.extensions_mut().insert(claims);
+                        resource.create(&req, body.into_inner()).await
+                    }
                     Err(response) => response,
                 }
             }
@@ -873,12 +1520,15 @@ pub fn register_protected_resource_routes(resource: Box<dyn AdmixResource>) -> S
     let get_resource = resource.clone_box();
     scope = scope.route(
         "/{id}",
-        web::get().to(move |req: HttpRequest, path: web::Path<String>, session: Session, config: web::Data<AdminxConfig>| {
+        web::get().to(move |req: HttpRequest, path: web::Path<String>, config: web::Data<AdminxConfig>| {
             let resource = get_resource.clone_box();
             async move {
                 let id = path.into_inner();
-                match check_resource_permission(&session, &config, resource.as_ref(), "view").await {
-                    Ok(_claims) => resource.get(&req, id).await,
+                match check_resource_permission(&req, &config, resource.as_ref(), "view").await {
+                    Ok(claims) => {
+                        req.extensions_mut().insert(claims);
+                        resource.get(&req, id).await
+                    }
                     Err(response) => response,
                 }
             }
@@ -889,12 +1539,15 @@ pub fn register_protected_resource_routes(resource: Box<dyn AdmixResource>) -> S
     let update_resource = resource.clone_box();
     scope = scope.route(
         "/{id}",
-        web::put().to(move |req: HttpRequest, path: web::Path<String>, body: web::Json<Value>, session: Session, config: web::Data<AdminxConfig>| {
+        web::put().to(move |req: HttpRequest, path: web::Path<String>, body: web::Json<Value>, config: web::Data<AdminxConfig>| {
             let resource = update_resource.clone_box();
             async move {
                 let id = path.into_inner();
-                match check_resource_permission(&session, &config, resource.as_ref(), "update").await {
-                    Ok(_claims) => resource.update(&req, id, body.into_inner()).await,
+                match check_resource_permission(&req, &config, resource.as_ref(), "update").await {
+                    Ok(claims) => {
+                        req.extensions_mut().insert(claims);
+                        resource.update(&req, id, body.into_inner()).await
+                    }
                     Err(response) => response,
                 }
             }
@@ -905,12 +1558,15 @@ pub fn register_protected_resource_routes(resource: Box<dyn AdmixResource>) -> S
     let delete_resource = resource.clone_box();
     scope = scope.route(
         "/{id}",
-        web::delete().to(move |req: HttpRequest, path: web::Path<String>, session: Session, config: web::Data<AdminxConfig>| {
+        web::delete().to(move |req: HttpRequest, path: web::Path<String>, config: web::Data<AdminxConfig>| {
             let resource = delete_resource.clone_box();
             async move {
                 let id = path.into_inner();
-                match check_resource_permission(&session, &config, resource.as_ref(), "delete").await {
-                    Ok(_claims) => resource.delete(&req, id).await,
+                match check_resource_permission(&req, &config, resource.as_ref(), "delete").await {
+                    Ok(claims) => {
+                        req.extensions_mut().insert(claims);
+                        resource.delete(&req, id).await
+                    }
                     Err(response) => response,
                 }
             }
@@ -918,4 +1574,645 @@ pub fn register_protected_resource_routes(resource: Box<dyn AdmixResource>) -> S
     );
 
     scope
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestResource;
+    use mongodb::{bson::Document, options::ReadPreference, Collection};
+    use std::collections::HashMap;
+
+    struct AuditResource;
+
+    impl AdmixResource for AuditResource {
+        fn new() -> Self { AuditResource }
+        fn resource_name(&self) -> &'static str { "Audits" }
+        fn base_path(&self) -> &'static str { "audits" }
+        fn collection_name(&self) -> &'static str { "audits" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(AuditResource) }
+        fn read_preference(&self) -> ReadPreference {
+            ReadPreference::SecondaryPreferred { options: Default::default() }
+        }
+
+        fn query_timeout(&self) -> Option<std::time::Duration> {
+            Some(std::time::Duration::from_secs(2))
+        }
+
+        fn default_per_page(&self) -> u64 {
+            25
+        }
+
+        fn max_per_page(&self) -> u64 {
+            50
+        }
+    }
+
+    fn query(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_resource_default_per_page_is_used() {
+        assert_eq!(ListQuery::parse("").per_page_for(&AuditResource), 25);
+        assert_eq!(ListQuery::parse("per_page=abc").per_page_for(&AuditResource), 25);
+    }
+
+    #[test]
+    fn test_url_per_page_overrides_default() {
+        assert_eq!(ListQuery::parse("per_page=10").per_page_for(&AuditResource), 10);
+        assert_eq!(ListQuery::parse("per_page=100").per_page_for(&AuditResource), 50);
+    }
+
+    #[test]
+    fn test_boolean_field_stays_json_bool() {
+        let doc = mongodb::bson::doc! { "active": true, "count": 42, "note": mongodb::bson::Bson::Null };
+        let columns = vec!["active".to_string(), "count".to_string(), "note".to_string(), "missing".to_string()];
+        let row = document_to_row(&doc, &columns, Tz::UTC);
+
+        assert_eq!(row["active"], Value::Bool(true));
+        assert_eq!(row["count"], serde_json::json!(42));
+        assert_eq!(row["note"], Value::Null);
+        assert_eq!(row["missing"], Value::String("N/A".to_string()));
+    }
+
+    #[test]
+    fn test_nested_column_is_read_by_path() {
+        let doc = mongodb::bson::doc! { "address": { "city": "Pune" }, "name": "Asha" };
+        let columns = vec!["address.city".to_string(), "address.zip".to_string(), "profile.bio".to_string()];
+        let row = document_to_row(&doc, &columns, Tz::UTC);
+
+        assert_eq!(row["address.city"], Value::String("Pune".to_string()));
+        // Missing leaf and missing intermediate both render blank
+        assert_eq!(row["address.zip"], Value::Null);
+        assert_eq!(row["profile.bio"], Value::Null);
+    }
+
+    #[test]
+    fn test_nested_field_filter_uses_dotted_key() {
+        let permitted: HashSet<&str> = ["address", "status"].into_iter().collect();
+        let filter = build_filter_document(&permitted, &HashMap::new(), &[], &query(&[("address.city", "Pune"), ("secret.key", "x")]));
+
+        assert_eq!(filter, mongodb::bson::doc! { "address.city": "Pune" });
+    }
+
+    #[test]
+    fn test_filter_coerces_boolean_and_integer_fields() {
+        let permitted: HashSet<&str> = ["published", "priority", "code"].into_iter().collect();
+        let field_types: HashMap<String, String> = [
+            ("published".to_string(), "boolean".to_string()),
+            ("priority".to_string(), "number".to_string()),
+        ].into_iter().collect();
+        let filter = build_filter_document(&permitted, &field_types, &[], &query(&[
+            ("published", "true"),
+            ("priority", "1"),
+            ("code", "1"),
+        ]));
+
+        assert_eq!(filter.get("published"), Some(&mongodb::bson::Bson::Boolean(true)));
+        assert_eq!(filter.get("priority"), Some(&mongodb::bson::Bson::Int64(1)));
+        // Unknown type falls back to a string match
+        assert_eq!(filter.get("code"), Some(&mongodb::bson::Bson::String("1".to_string())));
+    }
+
+    fn tasks() -> TestResource {
+        TestResource::named("Tasks", "tasks")
+            .permit(&["title", "priority"])
+            .list(serde_json::json!({
+                "columns": [
+                    { "field": "title", "inline_editable": true },
+                    { "field": "priority" },
+                    { "field": "created_at", "inline_editable": true }
+                ]
+            }))
+    }
+
+    #[test]
+    fn test_partial_update_keeps_only_given_fields() {
+        let fields = validate_partial_update(&tasks(), &[], serde_json::json!({ "title": "Ship it" })).unwrap();
+
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields["title"], Value::String("Ship it".to_string()));
+        assert_eq!(inline_editable_fields(&tasks(), &[]), vec!["title".to_string()]);
+    }
+
+    #[test]
+    fn test_partial_update_rejects_readonly_field() {
+        let result = validate_partial_update(&tasks(), &[], serde_json::json!({ "title": "x", "created_at": "2024-01-01" }));
+
+        assert!(matches!(result, Err(AdminxError::BadRequest(msg)) if msg.contains("read-only")));
+    }
+
+    #[test]
+    fn test_edit_page_breadcrumbs() {
+        let claims = Claims {
+            sub: "1".to_string(),
+            exp: 0,
+            email: "admin@example.com".to_string(),
+            role: "admin".to_string(),
+            roles: vec!["admin".to_string()],
+            iss: "adminx".to_string(),
+            aud: "adminx".to_string(),
+        };
+        let ctx = create_base_template_context("Posts", "posts", &claims, "edit");
+
+        let breadcrumbs = ctx.get("breadcrumbs").unwrap();
+        assert_eq!(breadcrumbs, &serde_json::json!([
+            { "label": "Dashboard", "url": "/adminx" },
+            { "label": "Posts", "url": "/adminx/posts/list" },
+            { "label": "Edit", "url": "/adminx/posts/list" }
+        ]));
+    }
+
+    #[test]
+    fn create_response_flashes_once_and_redirects_cleanly() {
+        use actix_session::SessionExt;
+        use crate::helpers::template_helper::insert_flash_messages;
+
+        let req = actix_web::test::TestRequest::default().to_http_request();
+        let session = req.get_session();
+
+        let response = handle_create_response(HttpResponse::Created().finish(), "audits", "Audits", &session);
+        assert_eq!(response.headers().get("Location").unwrap(), "/adminx/audits/list");
+
+        let mut ctx = Context::new();
+        insert_flash_messages(&mut ctx, &session);
+        let json = ctx.into_json();
+        assert_eq!(json["toast_message"], "Successfully created new item!");
+        assert_eq!(json["toast_type"], "success");
+        assert_eq!(json["flash_messages"][0]["level"], "success");
+
+        let mut ctx = Context::new();
+        insert_flash_messages(&mut ctx, &session);
+        assert!(ctx.get("toast_message").is_none());
+    }
+
+    #[test]
+    fn failed_update_flashes_error_on_edit_page() {
+        use actix_session::SessionExt;
+        use crate::helpers::template_helper::take_flash_messages;
+
+        let req = actix_web::test::TestRequest::default().to_http_request();
+        let session = req.get_session();
+
+        let response = handle_update_response(HttpResponse::BadRequest().finish(), "audits", "42", "Audits", &session);
+        assert_eq!(response.headers().get("Location").unwrap(), "/adminx/audits/edit/42");
+
+        let flashes = take_flash_messages(&session);
+        assert_eq!(flashes.len(), 1);
+        assert!(matches!(flashes[0].level, crate::utils::structs::FlashLevel::Error));
+        assert!(take_flash_messages(&session).is_empty());
+    }
+
+    #[test]
+    fn versioned_update_bumps_submitted_version() {
+        let oid = mongodb::bson::oid::ObjectId::new();
+        let payload = serde_json::json!({ "title": "Hello", "version": "3" });
+        let version = submitted_version(&payload).unwrap();
+
+        let (filter, update) = versioned_update(oid, mongodb::bson::doc! { "title": "Hello" }, version);
+        assert_eq!(filter, mongodb::bson::doc! { "_id": oid, "version": 3_i64 });
+        assert_eq!(update.get_document("$inc").unwrap(), &mongodb::bson::doc! { "version": 1_i64 });
+        assert_eq!(update.get_document("$set").unwrap().get_str("title").unwrap(), "Hello");
+
+        // Unversioned legacy documents are matched by version 0
+        let (filter, _) = versioned_update(oid, mongodb::bson::Document::new(), 0);
+        assert_eq!(filter.get_array("$or").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn stale_version_conflict_flashes_and_returns_to_edit() {
+        use actix_session::SessionExt;
+        use actix_web::ResponseError;
+        use crate::helpers::template_helper::take_flash_messages;
+
+        assert_eq!(submitted_version(&serde_json::json!({ "title": "x" })), None);
+        assert_eq!(submitted_version(&serde_json::json!({ "version": "abc" })), None);
+
+        let conflict = AdminxError::Conflict("stale".into()).error_response();
+        assert_eq!(conflict.status(), actix_web::http::StatusCode::CONFLICT);
+
+        let req = actix_web::test::TestRequest::default().to_http_request();
+        let session = req.get_session();
+        let response = handle_update_response(conflict, "audits", "42", "Audits", &session);
+        assert_eq!(response.headers().get("Location").unwrap(), "/adminx/audits/edit/42");
+        let flashes = take_flash_messages(&session);
+        assert!(flashes[0].message.contains("changed by someone else"));
+    }
+
+    #[test]
+    fn read_options_carry_resource_read_preference() {
+        let expected = Some(SelectionCriteria::ReadPreference(AuditResource.read_preference()));
+        assert_eq!(read_find_options(&AuditResource).selection_criteria, expected);
+        assert_eq!(read_count_options(&AuditResource).selection_criteria, expected);
+        assert!(matches!(
+            read_find_options(&AuditResource).selection_criteria,
+            Some(SelectionCriteria::ReadPreference(ReadPreference::SecondaryPreferred { .. }))
+        ));
+    }
+
+    #[test]
+    fn read_options_carry_resource_query_timeout() {
+        let timeout = Some(std::time::Duration::from_secs(2));
+        assert_eq!(read_find_options(&AuditResource).max_time, timeout);
+        assert_eq!(read_count_options(&AuditResource).max_time, timeout);
+        assert_eq!(read_find_options(&tasks()).max_time, None);
+
+        let query = ListQuery { complete: true, ..Default::default() };
+        assert_eq!(crate::helpers::downloads::json_download::export_find_options(&AuditResource, &query).max_time, timeout);
+
+        let command_error = |code: i32, name: &str| {
+            let error: mongodb::error::CommandError = mongodb::bson::from_document(mongodb::bson::doc! {
+                "code": code, "codeName": name, "errmsg": "query failed"
+            }).unwrap();
+            mongodb::error::Error::from(mongodb::error::ErrorKind::Command(error))
+        };
+        let error = query_error("Audits", &command_error(50, "MaxTimeMSExpired"));
+        assert!(matches!(error, AdminxError::Timeout));
+        assert_eq!(actix_web::ResponseError::error_response(&error).status(), actix_web::http::StatusCode::GATEWAY_TIMEOUT);
+
+        let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(error);
+        assert!(is_timeout(boxed.as_ref()));
+        assert_eq!(export_error_response(boxed.as_ref(), "Failed to export CSV data").status(), actix_web::http::StatusCode::GATEWAY_TIMEOUT);
+        let other: Box<dyn std::error::Error + Send + Sync> = "disk full".into();
+        assert_eq!(export_error_response(other.as_ref(), "Failed to export CSV data").status(), actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(matches!(query_error("Audits", &command_error(2, "BadValue")), AdminxError::InternalError));
+    }
+
+    struct PrefetchingResource {
+        calls: std::sync::Mutex<Vec<usize>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AdmixResource for PrefetchingResource {
+        fn new() -> Self { PrefetchingResource { calls: Default::default() } }
+        fn resource_name(&self) -> &'static str { "Posts" }
+        fn base_path(&self) -> &'static str { "posts" }
+        fn collection_name(&self) -> &'static str { "posts" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(PrefetchingResource::new()) }
+
+        async fn prefetch(&self, docs: &[Document]) -> HashMap<mongodb::bson::oid::ObjectId, Value> {
+            self.calls.lock().unwrap().push(docs.len());
+            docs.iter()
+                .filter_map(|doc| doc.get_object_id("_id").ok())
+                .map(|oid| (oid, serde_json::json!({ "author_name": format!("author-{}", oid) })))
+                .collect()
+        }
+    }
+
+    #[actix_rt::test]
+    async fn prefetch_runs_once_per_page_with_every_document() {
+        let resource = PrefetchingResource::new();
+        let docs: Vec<Document> = (0..3)
+            .map(|i| mongodb::bson::doc! { "_id": mongodb::bson::oid::ObjectId::new(), "title": format!("Post {}", i) })
+            .collect();
+        let columns = vec!["title".to_string(), "author_name".to_string()];
+
+        let rows = build_list_rows(&resource, &docs, &columns, Tz::UTC).await;
+
+        assert_eq!(*resource.calls.lock().unwrap(), vec![3]);
+        assert_eq!(rows.len(), 3);
+        let first_id = docs[0].get_object_id("_id").unwrap();
+        assert_eq!(rows[0]["author_name"], Value::String(format!("author-{}", first_id)));
+        assert_eq!(rows[2]["title"], Value::String("Post 2".to_string()));
+    }
+
+    struct PeopleResource;
+
+    impl AdmixResource for PeopleResource {
+        fn new() -> Self { PeopleResource }
+        fn resource_name(&self) -> &'static str { "People" }
+        fn base_path(&self) -> &'static str { "people" }
+        fn collection_name(&self) -> &'static str { "people" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(PeopleResource) }
+        fn permit_keys(&self) -> Vec<&'static str> { vec!["first_name", "last_name"] }
+
+        fn list_structure(&self) -> Option<Value> {
+            Some(serde_json::json!({
+                "columns": [
+                    { "field": "full_name", "label": "Full Name", "computed": true },
+                    { "field": "first_name" }
+                ]
+            }))
+        }
+
+        fn compute_column(&self, field: &str, doc: &Document) -> Option<Value> {
+            match field {
+                "full_name" => {
+                    let first = doc.get_str("first_name").ok()?;
+                    let last = doc.get_str("last_name").ok()?;
+                    Some(Value::String(format!("{} {}", first, last)))
+                }
+                _ => None,
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn computed_columns_are_filled_from_the_document() {
+        let docs = vec![
+            mongodb::bson::doc! { "_id": mongodb::bson::oid::ObjectId::new(), "first_name": "Ada", "last_name": "Lovelace" },
+            mongodb::bson::doc! { "_id": mongodb::bson::oid::ObjectId::new(), "first_name": "Grace" },
+        ];
+        let columns = list_columns(&PeopleResource, &[]);
+        assert_eq!(columns, vec!["full_name", "first_name"]);
+
+        let rows = build_list_rows(&PeopleResource, &docs, &columns, Tz::UTC).await;
+        assert_eq!(rows[0]["full_name"], "Ada Lovelace");
+        assert_eq!(rows[0]["first_name"], "Ada");
+        // A missing source field leaves the cell blank rather than "N/A"
+        assert_eq!(rows[1]["full_name"], Value::Null);
+    }
+
+    struct TrashResource;
+
+    impl AdmixResource for TrashResource {
+        fn new() -> Self { TrashResource }
+        fn resource_name(&self) -> &'static str { "Notes" }
+        fn base_path(&self) -> &'static str { "notes" }
+        fn collection_name(&self) -> &'static str { "notes" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(TrashResource) }
+        fn permit_keys(&self) -> Vec<&'static str> { vec!["title", "deleted", "is_deleted"] }
+        fn soft_delete_field(&self) -> Option<&'static str> { Some("is_deleted") }
+    }
+
+    #[test]
+    fn soft_delete_field_defaults_to_permitted_deleted() {
+        struct LegacyResource;
+        impl AdmixResource for LegacyResource {
+            fn new() -> Self { LegacyResource }
+            fn resource_name(&self) -> &'static str { "Legacy" }
+            fn base_path(&self) -> &'static str { "legacy" }
+            fn collection_name(&self) -> &'static str { "legacy" }
+            fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+            fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(LegacyResource) }
+            fn permit_keys(&self) -> Vec<&'static str> { vec!["title", "deleted"] }
+        }
+
+        assert_eq!(LegacyResource.soft_delete_field(), Some("deleted"));
+        assert_eq!(AuditResource.soft_delete_field(), None);
+    }
+
+    #[test]
+    fn custom_soft_delete_field_drives_delete_restore_and_list_hiding() {
+        use crate::controllers::dashboard_controller::visible_documents_filter;
+
+        let field = TrashResource.soft_delete_field();
+
+        let now = mongodb::bson::DateTime::from_millis(1_700_000_000_000);
+        let deleted = soft_delete_update(field.unwrap(), true, Some("updated_at"), now);
+        assert_eq!(deleted.get_document("$set").unwrap().get_bool("is_deleted"), Ok(true));
+        assert_eq!(deleted.get_document("$set").unwrap().get_datetime("updated_at"), Ok(&now));
+        let restored = soft_delete_update(field.unwrap(), false, Some("updated_at"), now);
+        assert_eq!(restored.get_document("$set").unwrap().get_bool("is_deleted"), Ok(false));
+        assert!(!deleted.get_document("$set").unwrap().contains_key("deleted"));
+
+        let hidden = mongodb::bson::doc! { "is_deleted": { "$ne": true } };
+        assert_eq!(visible_documents_filter(&TrashResource), hidden);
+        assert_eq!(ListQuery::default().filter_document(&TrashResource), hidden);
+
+        let permitted: HashSet<&str> = TrashResource.permit_keys().into_iter().collect();
+        let query: HashMap<String, String> = [("title".to_string(), "x".to_string())].into_iter().collect();
+        let mut filter = build_filter_document(&permitted, &HashMap::new(), &[], &query);
+        hide_soft_deleted(&mut filter, field);
+        assert_eq!(filter.get_document("is_deleted").unwrap(), &mongodb::bson::doc! { "$ne": true });
+        assert!(!filter.contains_key("deleted"));
+
+        // Asking for the trash explicitly shows deleted documents
+        let query: HashMap<String, String> = [("is_deleted".to_string(), "true".to_string())].into_iter().collect();
+        let mut filter = build_filter_document(&permitted, &HashMap::new(), &[], &query);
+        hide_soft_deleted(&mut filter, field);
+        assert_eq!(filter.get_bool("is_deleted"), Ok(true));
+    }
+
+    #[actix_rt::test]
+    async fn restore_needs_a_soft_delete_field() {
+        let req = actix_web::test::TestRequest::default().to_http_request();
+        let response = AuditResource.restore(&req, "abc".to_string()).await;
+        assert_eq!(response.status(), actix_web::http::StatusCode::BAD_REQUEST);
+
+        let response = TrashResource.restore(&req, "not-an-id".to_string()).await;
+        assert_eq!(response.status(), actix_web::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn over_length_search_is_rejected() {
+        let mut query: HashMap<String, String> = [
+            ("search".to_string(), "a".repeat(129)),
+        ].into_iter().collect();
+        let err = enforce_search_limits(&AuditResource, &mut query).unwrap_err();
+        assert!(matches!(err, AdminxError::BadRequest(_)));
+
+        let mut query: HashMap<String, String> = [
+            ("title".to_string(), "é".repeat(128)),
+            ("status".to_string(), "x".repeat(500)),
+        ].into_iter().collect();
+        assert!(enforce_search_limits(&AuditResource, &mut query).is_ok());
+        assert_eq!(query.len(), 2);
+    }
+
+    #[test]
+    fn below_minimum_search_is_ignored() {
+        struct ShortSearchResource;
+        impl AdmixResource for ShortSearchResource {
+            fn new() -> Self { ShortSearchResource }
+            fn resource_name(&self) -> &'static str { "Tags" }
+            fn base_path(&self) -> &'static str { "tags" }
+            fn collection_name(&self) -> &'static str { "tags" }
+            fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+            fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(ShortSearchResource) }
+            fn permit_keys(&self) -> Vec<&'static str> { vec!["name"] }
+            fn min_search_length(&self) -> usize { 3 }
+        }
+
+        let mut query: HashMap<String, String> = [
+            ("search".to_string(), " ab ".to_string()),
+            ("name".to_string(), "rust".to_string()),
+        ].into_iter().collect();
+        enforce_search_limits(&ShortSearchResource, &mut query).unwrap();
+        assert!(!query.contains_key("search"));
+
+        let permitted: HashSet<&str> = ShortSearchResource.permit_keys().into_iter().collect();
+        let filter = build_filter_document(&permitted, &HashMap::new(), &[], &query);
+        assert!(!filter.contains_key("$or"));
+        assert!(filter.contains_key("name"));
+    }
+
+    #[test]
+    fn saved_response_includes_server_timestamps() {
+        let created_at = mongodb::bson::DateTime::from_millis(1_700_000_000_000);
+        let updated_at = mongodb::bson::DateTime::from_millis(1_700_000_500_000);
+        let stored = mongodb::bson::doc! { "title": "Hello", "created_at": created_at, "updated_at": updated_at };
+        let summary = serde_json::json!({ "success": true, "id": "abc" });
+
+        assert!(AuditResource.return_full_document());
+        let body = saved_response_body(summary.clone(), Some(stored));
+        assert_eq!(body["id"], "abc");
+        assert_eq!(body["data"]["title"], "Hello");
+        assert_eq!(body["data"]["created_at"], serde_json::json!(created_at));
+        assert_eq!(body["data"]["updated_at"], serde_json::json!(updated_at));
+
+        assert_eq!(saved_response_body(summary.clone(), None), summary);
+    }
+
+    #[actix_rt::test]
+    async fn update_is_not_found_only_when_nothing_matched() {
+        let stored = mongodb::bson::doc! { "title": "Same" };
+        let unchanged = update_result_response("Audits", "abc", false, 1, 0, Some(stored));
+        assert_eq!(unchanged.status(), actix_web::http::StatusCode::OK);
+        let body = actix_web::body::to_bytes(unchanged.into_body()).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["success"], true);
+        assert_eq!(body["modified"], false);
+        assert_eq!(body["modified_count"], 0);
+        assert_eq!(body["data"]["title"], "Same");
+
+        let updated = update_result_response("Audits", "abc", false, 1, 1, None);
+        let body = actix_web::body::to_bytes(updated.into_body()).await.unwrap();
+        assert_eq!(serde_json::from_slice::<Value>(&body).unwrap()["modified"], true);
+
+        let missing = update_result_response("Audits", "abc", false, 0, 0, None);
+        assert_eq!(missing.status(), actix_web::http::StatusCode::NOT_FOUND);
+        let stale = update_result_response("Audits", "abc", true, 0, 0, None);
+        assert_eq!(stale.status(), actix_web::http::StatusCode::CONFLICT);
+    }
+
+    #[test]
+    fn timestamp_names_drive_readonly_keys_exports_and_default_columns() {
+        use crate::helpers::downloads::csv_download::{csv_header, CsvOptions};
+
+        struct CamelResource;
+        impl AdmixResource for CamelResource {
+            fn new() -> Self { CamelResource }
+            fn resource_name(&self) -> &'static str { "Camel" }
+            fn base_path(&self) -> &'static str { "camel" }
+            fn collection_name(&self) -> &'static str { "camel" }
+            fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+            fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(CamelResource) }
+            fn permit_keys(&self) -> Vec<&'static str> { vec!["title"] }
+            fn timestamps(&self) -> Option<(&'static str, &'static str)> { Some(("createdAt", "updatedAt")) }
+        }
+
+        struct UntimedResource;
+        impl AdmixResource for UntimedResource {
+            fn new() -> Self { UntimedResource }
+            fn resource_name(&self) -> &'static str { "Untimed" }
+            fn base_path(&self) -> &'static str { "untimed" }
+            fn collection_name(&self) -> &'static str { "untimed" }
+            fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+            fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(UntimedResource) }
+            fn permit_keys(&self) -> Vec<&'static str> { vec!["title"] }
+            fn timestamps(&self) -> Option<(&'static str, &'static str)> { None }
+        }
+
+        assert_eq!(CamelResource.readonly_keys(), vec!["_id", "createdAt", "updatedAt"]);
+        let keys = export_keys(&CamelResource, &[]);
+        assert_eq!(keys, vec!["title", "createdAt", "updatedAt"]);
+        assert_eq!(csv_header(&keys, &CsvOptions::default()), "id,title,createdAt,updatedAt\n");
+        assert_eq!(default_list_structure(&CamelResource)["columns"][1]["field"], "createdAt");
+
+        assert_eq!(UntimedResource.readonly_keys(), vec!["_id"]);
+        assert_eq!(export_keys(&UntimedResource, &[]), vec!["title"]);
+        assert_eq!(default_list_structure(&UntimedResource)["columns"].as_array().unwrap().len(), 1);
+        let update = soft_delete_update("deleted", true, None, mongodb::bson::DateTime::now());
+        assert_eq!(update, mongodb::bson::doc! { "$set": { "deleted": true } });
+    }
+
+    #[actix_rt::test]
+    async fn test_created_response_points_at_the_new_document() {
+        let oid = mongodb::bson::oid::ObjectId::new();
+        let response = created_response("posts", &mongodb::bson::Bson::ObjectId(oid), serde_json::json!({ "success": true }));
+        assert_eq!(response.status(), actix_web::http::StatusCode::CREATED);
+        assert_eq!(
+            response.headers().get("Location").unwrap().to_str().unwrap(),
+            format!("/adminx/posts/api/{}", oid.to_hex())
+        );
+
+        let response = crate::utils::mount::with_base_mount("/admin".to_string(), async {
+            created_response("tags", &mongodb::bson::Bson::String("rust lang".to_string()), serde_json::json!({}))
+        }).await;
+        assert_eq!(response.headers().get("Location").unwrap(), "/admin/tags/api/rust%20lang");
+    }
+
+    #[test]
+    fn test_default_values_fill_only_missing_fields() {
+        let defaults = serde_json::json!({ "status": "pending", "priority": 3 });
+        let defaults = defaults.as_object().unwrap();
+
+        let permitted: HashSet<&str> = ["title", "status", "priority"].into_iter().collect();
+
+        let payload = create_fields(&serde_json::json!({ "title": "Launch", "priority": 1 }), &permitted, defaults);
+        assert_eq!(Value::Object(payload), serde_json::json!({ "title": "Launch", "status": "pending", "priority": 1 }));
+
+        let payload = create_fields(&serde_json::json!({ "status": null }), &permitted, defaults);
+        assert_eq!(Value::Object(payload), serde_json::json!({ "status": null, "priority": 3 }));
+    }
+
+    #[test]
+    fn test_defaults_apply_to_fields_the_role_cannot_write() {
+        let defaults = serde_json::json!({ "status": "pending" });
+        let permitted: HashSet<&str> = ["title"].into_iter().collect();
+
+        let fields = create_fields(&serde_json::json!({ "title": "Launch", "status": "approved" }), &permitted, defaults.as_object().unwrap());
+        assert_eq!(Value::Object(fields), serde_json::json!({ "title": "Launch", "status": "pending" }));
+    }
+
+    #[test]
+    fn test_list_columns_follow_the_resource() {
+        let roles = vec!["admin".to_string()];
+        assert_eq!(list_columns(&tasks(), &roles), vec!["title", "priority", "created_at"]);
+        // Without a list_structure: the id and the resource's own created timestamp
+        assert_eq!(list_columns(&AuditResource, &roles), vec!["id", "created_at"]);
+    }
+
+    #[test]
+    fn test_form_keys_build_nested_json_without_reserved_or_deep_keys() {
+        let form = |pairs: &[(&str, &str)]| -> std::collections::HashMap<String, String> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        let reserved = AuditResource.readonly_keys();
+        let field_types = std::collections::HashMap::new();
+
+        let json = convert_form_data_to_json(form(&[
+            ("title", "Launch"),
+            ("address[city]", "Pune"),
+            ("address.zip", "411001"),
+            ("address[geo][active]", "on"),
+        ]), &field_types, &reserved);
+        assert_eq!(json, serde_json::json!({
+            "title": "Launch",
+            "address": { "city": "Pune", "zip": "411001", "geo": { "active": true } }
+        }));
+
+        // Reserved fields can't be reached in any notation
+        let json = convert_form_data_to_json(form(&[
+            ("title", "Launch"),
+            ("created_at", "2000-01-01"),
+            ("created_at[$date]", "0"),
+            ("[created_at]", "2000-01-01"),
+            ("_id", "abc"),
+            ("meta[$set]", "x"),
+            ("title][created_at", "x"),
+        ]), &field_types, &reserved);
+        assert_eq!(json, serde_json::json!({ "title": "Launch" }));
+
+        // Depth is capped, however the segments are written
+        let deep = format!("a{}", "[b]".repeat(10_000));
+        let json = convert_form_data_to_json(form(&[
+            (&deep, "x"),
+            (&"a.".repeat(10_000), "x"),
+            ("a[b][c][d][e]", "x"),
+            ("a[b][c][d]", "kept"),
+        ]), &field_types, &reserved);
+        assert_eq!(json, serde_json::json!({ "a": { "b": { "c": { "d": "kept" } } } }));
+
+        // A plain value is not turned into an object by a nested key under it
+        let json = convert_form_data_to_json(form(&[("tag", "rust"), ("tag[name]", "x")]), &field_types, &reserved);
+        assert_eq!(json, serde_json::json!({ "tag": "rust" }));
+    }
+}
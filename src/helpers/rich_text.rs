@@ -0,0 +1,97 @@
+// crates/adminx/src/helpers/rich_text.rs
+use pulldown_cmark::{html, Options, Parser};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Field type for HTML produced by a WYSIWYG editor; sanitized before storage
+pub const RICHTEXT_FIELD: &str = "richtext";
+/// Field type for markdown source; stored as-is and rendered to HTML on display
+pub const MARKDOWN_FIELD: &str = "markdown";
+
+/// Strip scripts, event handler attributes and other unsafe markup from HTML
+pub fn sanitize_html(input: &str) -> String {
+    ammonia::clean(input)
+}
+
+/// Render markdown to sanitized HTML
+pub fn render_markdown(input: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+
+    let mut output = String::new();
+    html::push_html(&mut output, Parser::new_ext(input, options));
+    // Markdown allows inline HTML, so the output is sanitized as well
+    sanitize_html(&output)
+}
+
+/// Sanitize every `richtext` field in a payload in place, whatever the client sent
+pub fn sanitize_rich_text_fields(payload: &mut Map<String, Value>, field_types: &HashMap<String, String>) {
+    for (key, value) in payload.iter_mut() {
+        if field_types.get(key).map(String::as_str) != Some(RICHTEXT_FIELD) {
+            continue;
+        }
+        if let Value::String(html) = value {
+            *html = sanitize_html(html);
+        }
+    }
+}
+
+/// Display HTML for the `richtext` and `markdown` fields of a record, keyed by field name
+pub fn render_rich_text_fields(
+    record: &Map<String, Value>,
+    field_types: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    record
+        .iter()
+        .filter_map(|(key, value)| {
+            let text = value.as_str()?;
+            let rendered = match field_types.get(key).map(String::as_str) {
+                Some(RICHTEXT_FIELD) => sanitize_html(text),
+                Some(MARKDOWN_FIELD) => render_markdown(text),
+                _ => return None,
+            };
+            Some((key.clone(), rendered))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn types() -> HashMap<String, String> {
+        HashMap::from([
+            ("body".to_string(), RICHTEXT_FIELD.to_string()),
+            ("summary".to_string(), MARKDOWN_FIELD.to_string()),
+        ])
+    }
+
+    #[test]
+    fn script_and_handlers_are_stripped_from_richtext() {
+        let mut payload = json!({
+            "body": "<p onclick=\"steal()\">Hello <b>world</b></p><script>alert(1)</script>",
+            "title": "<script>kept as plain text</script>"
+        });
+        sanitize_rich_text_fields(payload.as_object_mut().unwrap(), &types());
+
+        assert_eq!(payload["body"], "<p>Hello <b>world</b></p>");
+        // Only richtext fields are touched
+        assert_eq!(payload["title"], "<script>kept as plain text</script>");
+    }
+
+    #[test]
+    fn markdown_renders_to_html() {
+        assert_eq!(
+            render_markdown("# Title\n\nSome *emphasis* and a [link](https://example.com)."),
+            "<h1>Title</h1>\n<p>Some <em>emphasis</em> and a <a href=\"https://example.com\" rel=\"noopener noreferrer\">link</a>.</p>\n"
+        );
+        assert_eq!(render_markdown("<script>alert(1)</script>hi"), "hi");
+
+        let record = json!({ "summary": "**bold**", "title": "plain" });
+        let rendered = render_rich_text_fields(record.as_object().unwrap(), &types());
+        assert_eq!(rendered["summary"], "<p><strong>bold</strong></p>\n");
+        assert!(!rendered.contains_key("title"));
+    }
+}
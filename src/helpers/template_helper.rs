@@ -2,10 +2,17 @@
 use actix_web::{HttpResponse};
 use actix_session::Session;
 use once_cell::sync::Lazy;
-use std::sync::Arc;
-use tera::{Context, Tera};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tera::{Context, Filter, Function, Tera};
 use crate::configs::initializer::AdminxConfig;
 use crate::utils::auth::extract_claims_from_session;
+use crate::utils::database::get_adminx_config;
+use crate::utils::mount::{admin_path, base_mount};
+use crate::utils::branding::current_branding;
+use crate::utils::structs::{FlashLevel, FlashMessage};
+use crate::utils::timezone::{configured_timezone, format_datetime, parse_timezone, DISPLAY_DATETIME_FORMAT};
 use tracing::{error, warn};
 use chrono::Datelike;
 
@@ -21,12 +28,77 @@ const TEMPLATE_FILES: &[(&str, &str)] = &[
     ("view.html.tera", include_str!("../templates/view.html.tera")),
     ("login.html.tera", include_str!("../templates/login.html.tera")),
     ("profile.html.tera", include_str!("../templates/profile.html.tera")),
+    ("profile_edit.html.tera", include_str!("../templates/profile_edit.html.tera")),
+    ("reset_password.html.tera", include_str!("../templates/reset_password.html.tera")),
     ("stats.html.tera", include_str!("../templates/stats.html.tera")),
     ("errors/404.html.tera", include_str!("../templates/errors/404.html.tera")),
     ("errors/500.html.tera", include_str!("../templates/errors/500.html.tera")),
 ];
 
-pub static ADMINX_TEMPLATES: Lazy<Arc<Tera>> = Lazy::new(|| {
+/// Filters and functions added to the templates when they are built
+#[derive(Default)]
+pub(crate) struct TemplateExtensions {
+    filters: HashMap<String, Arc<dyn Filter>>,
+    functions: HashMap<String, Arc<dyn Function>>,
+}
+
+impl TemplateExtensions {
+    fn add_filter<F: Filter + 'static>(&mut self, name: &str, filter: F) {
+        self.filters.insert(name.to_string(), Arc::new(filter));
+    }
+
+    fn add_function<F: Function + 'static>(&mut self, name: &str, function: F) {
+        self.functions.insert(name.to_string(), Arc::new(function));
+    }
+
+    fn register(&self, tera: &mut Tera) {
+        for (name, filter) in &self.filters {
+            let filter = Arc::clone(filter);
+            tera.register_filter(name, move |value: &Value, args: &HashMap<String, Value>| filter.filter(value, args));
+        }
+        for (name, function) in &self.functions {
+            let function = Arc::clone(function);
+            tera.register_function(name, move |args: &HashMap<String, Value>| function.call(args));
+        }
+    }
+}
+
+/// Extensions registered by the host application
+static TEMPLATE_EXTENSIONS: Lazy<RwLock<TemplateExtensions>> = Lazy::new(Default::default);
+
+pub static ADMINX_TEMPLATES: Lazy<Arc<Tera>> = Lazy::new(|| Arc::new(build_templates()));
+
+/// Register a Tera filter for the AdminX templates (and templates overriding them), e.g.
+/// `{{ price | currency }}`. Filters are added when the templates are first rendered, so
+/// register them at startup; returns false when the shared templates were already built.
+/// Registering a built-in name (`format_datetime`) replaces it.
+pub fn register_template_filter<F: Filter + 'static>(name: &str, filter: F) -> bool {
+    TEMPLATE_EXTENSIONS.write().expect("template extension registry poisoned").add_filter(name, filter);
+    templates_pending(name)
+}
+
+/// Register a Tera function for the AdminX templates, e.g. `{{ support_email() }}`.
+/// Same timing as `register_template_filter`.
+pub fn register_template_function<F: Function + 'static>(name: &str, function: F) -> bool {
+    TEMPLATE_EXTENSIONS.write().expect("template extension registry poisoned").add_function(name, function);
+    templates_pending(name)
+}
+
+fn templates_pending(name: &str) -> bool {
+    let pending = Lazy::get(&ADMINX_TEMPLATES).is_none();
+    if !pending {
+        warn!("Template extension '{}' was registered after the first render and will not be used", name);
+    }
+    pending
+}
+
+/// A fresh Tera with the AdminX templates, built-in filters and registered extensions
+pub(crate) fn build_templates() -> Tera {
+    build_templates_with(&TEMPLATE_EXTENSIONS.read().expect("template extension registry poisoned"))
+}
+
+/// A fresh Tera with the AdminX templates, built-in filters and `extensions`
+pub(crate) fn build_templates_with(extensions: &TemplateExtensions) -> Tera {
     let mut tera = Tera::default();
 
     for (name, content) in TEMPLATE_FILES {
@@ -34,12 +106,48 @@ pub static ADMINX_TEMPLATES: Lazy<Arc<Tera>> = Lazy::new(|| {
             .unwrap_or_else(|e| panic!("Failed to add {}: {}", name, e));
     }
 
+    tera.register_filter("format_datetime", format_datetime_filter);
+    extensions.register(&mut tera);
+
     tera.autoescape_on(vec![]); // Disable autoescaping if rendering raw HTML
-    Arc::new(tera)
-});
+    tera
+}
 
-pub async fn render_template(template_name: &str, ctx: Context) -> HttpResponse {
+/// `{{ created_at | format_datetime }}`: a stored datetime (extended JSON `$date`, epoch
+/// milliseconds or RFC 3339) in the configured display zone. Takes optional `format` and
+/// `tz` (IANA name) arguments; anything that isn't a datetime passes through.
+fn format_datetime_filter(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let millis = match value {
+        Value::Object(map) => match map.get("$date") {
+            Some(Value::Object(date)) => date.get("$numberLong").and_then(|n| n.as_str()).and_then(|n| n.parse().ok()),
+            Some(Value::String(date)) => chrono::DateTime::parse_from_rfc3339(date).ok().map(|dt| dt.timestamp_millis()),
+            Some(Value::Number(n)) => n.as_i64(),
+            _ => None,
+        },
+        Value::String(date) => chrono::DateTime::parse_from_rfc3339(date).ok().map(|dt| dt.timestamp_millis()),
+        Value::Number(n) => n.as_i64(),
+        _ => None,
+    };
+    let Some(millis) = millis else {
+        return Ok(value.clone());
+    };
+
+    let pattern = args.get("format").and_then(|f| f.as_str()).unwrap_or(DISPLAY_DATETIME_FORMAT);
+    let tz = match args.get("tz").and_then(|tz| tz.as_str()) {
+        Some(name) => parse_timezone(name).ok_or_else(|| tera::Error::msg(format!("Unknown timezone '{}'", name)))?,
+        None => configured_timezone(),
+    };
+    Ok(format_datetime(&mongodb::bson::DateTime::from_millis(millis), tz, pattern).map_or_else(|| value.clone(), Value::String))
+}
+
+pub async fn render_template(template_name: &str, mut ctx: Context) -> HttpResponse {
     let tera = Arc::clone(&ADMINX_TEMPLATES);
+    if !ctx.contains_key("admin_base") {
+        ctx.insert("admin_base", &base_mount());
+    }
+    if !ctx.contains_key("branding") {
+        current_branding().insert_into(&mut ctx);
+    }
     match tera.render(template_name, &ctx) {
         Ok(html) => HttpResponse::Ok().content_type("text/html").body(html),
         Err(err) => {
@@ -47,6 +155,8 @@ pub async fn render_template(template_name: &str, ctx: Context) -> HttpResponse
             let mut error_ctx = Context::new();
             error_ctx.insert("error", &err.to_string());
             error_ctx.insert("template_name", template_name);
+            error_ctx.insert("admin_base", &base_mount());
+            current_branding().insert_into(&mut error_ctx);
             
             let fallback_html = tera
                 .render("errors/500.html.tera", &error_ctx)
@@ -106,7 +216,7 @@ pub async fn render_protected_template(
             render_template(template_name, context).await
         }
         Err(_) => {
-            let redirect_to = redirect_url.unwrap_or("/adminx/login");
+            let redirect_to = redirect_url.map(str::to_string).unwrap_or_else(|| admin_path("/login"));
             HttpResponse::Found()
                 .append_header(("Location", redirect_to))
                 .finish()
@@ -149,7 +259,7 @@ pub async fn render_role_protected_template(
             }
         }
         Err(_) => {
-            let redirect_to = redirect_url.unwrap_or("/adminx/login");
+            let redirect_to = redirect_url.map(str::to_string).unwrap_or_else(|| admin_path("/login"));
             HttpResponse::Found()
                 .append_header(("Location", redirect_to))
                 .finish()
@@ -157,10 +267,22 @@ pub async fn render_role_protected_template(
     }
 }
 
+/// Context shared by the error pages, which show debugging hints in development
+pub fn error_page_context() -> Context {
+    let mut ctx = Context::new();
+    let environment = get_adminx_config()
+        .map(|config| config.environment.clone())
+        .unwrap_or_else(|| "production".to_string());
+    ctx.insert("app_environment", &environment);
+    ctx.insert("admin_base", &base_mount());
+    current_branding().insert_into(&mut ctx);
+    ctx
+}
+
 // Error page renderers
 pub async fn render_404() -> HttpResponse {
     let tera = Arc::clone(&ADMINX_TEMPLATES);
-    let ctx = Context::new();
+    let ctx = error_page_context();
     let html = tera
         .render("errors/404.html.tera", &ctx)
         .unwrap_or_else(|_| "<h1>404 - Page Not Found</h1>".to_string());
@@ -169,9 +291,23 @@ pub async fn render_404() -> HttpResponse {
         .body(html)
 }
 
+/// The 404 page with its own heading and explanation in place of the generic ones
+pub async fn render_404_with_message(title: &str, message: &str) -> HttpResponse {
+    let tera = Arc::clone(&ADMINX_TEMPLATES);
+    let mut ctx = error_page_context();
+    ctx.insert("error_title", title);
+    ctx.insert("error_message", message);
+    let html = tera
+        .render("errors/404.html.tera", &ctx)
+        .unwrap_or_else(|_| format!("<h1>404 - {}</h1><p>{}</p>", title, message));
+    HttpResponse::NotFound()
+        .content_type("text/html")
+        .body(html)
+}
+
 pub async fn render_403() -> HttpResponse {
     let tera = Arc::clone(&ADMINX_TEMPLATES);
-    let mut ctx = Context::new();
+    let mut ctx = error_page_context();
     ctx.insert("error_message", "You don't have permission to access this resource.");
     
     let html = tera
@@ -184,7 +320,7 @@ pub async fn render_403() -> HttpResponse {
 
 pub async fn render_500(error_message: Option<&str>) -> HttpResponse {
     let tera = Arc::clone(&ADMINX_TEMPLATES);
-    let mut ctx = Context::new();
+    let mut ctx = error_page_context();
     ctx.insert("error_message", &error_message.unwrap_or("An internal server error occurred."));
     
     let html = tera
@@ -198,7 +334,7 @@ pub async fn render_500(error_message: Option<&str>) -> HttpResponse {
 // Template context helpers
 pub fn create_base_context() -> Context {
     let mut ctx = Context::new();
-    ctx.insert("app_name", "AdminX");
+    current_branding().insert_into(&mut ctx);
     ctx.insert("app_version", env!("CARGO_PKG_VERSION"));
     ctx.insert("current_year", &chrono::Utc::now().year());
     ctx
@@ -208,4 +344,83 @@ pub fn add_flash_messages(mut context: Context, messages: Vec<(&str, &str)>) ->
     // messages is Vec<(level, message)> where level is "success", "error", "warning", "info"
     context.insert("flash_messages", &messages);
     context
-}
\ No newline at end of file
+}
+
+/// Session key holding flash messages queued for the next rendered page
+const FLASH_SESSION_KEY: &str = "adminx_flash";
+
+/// Queue a one-shot message to be shown on the next rendered page
+pub fn push_flash(session: &Session, flash: FlashMessage) {
+    let mut messages: Vec<FlashMessage> = session
+        .get(FLASH_SESSION_KEY)
+        .unwrap_or_default()
+        .unwrap_or_default();
+    messages.push(flash);
+    if let Err(e) = session.insert(FLASH_SESSION_KEY, &messages) {
+        warn!("Failed to store flash message in session: {}", e);
+    }
+}
+
+/// Read and clear all queued flash messages
+pub fn take_flash_messages(session: &Session) -> Vec<FlashMessage> {
+    let messages: Vec<FlashMessage> = session
+        .remove_as(FLASH_SESSION_KEY)
+        .and_then(|result| result.ok())
+        .unwrap_or_default();
+    messages
+}
+
+/// Move queued flash messages into the template context.
+/// The latest message also drives the existing `toast_message`/`toast_type` variables.
+pub fn insert_flash_messages(ctx: &mut Context, session: &Session) {
+    let messages = take_flash_messages(session);
+    if let Some(latest) = messages.last() {
+        let toast_type = match latest.level {
+            FlashLevel::Success => "success",
+            FlashLevel::Info => "info",
+            FlashLevel::Warning => "warning",
+            FlashLevel::Error => "error",
+        };
+        ctx.insert("toast_message", &latest.message);
+        ctx.insert("toast_type", toast_type);
+        ctx.insert("flash_messages", &messages);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn registered_filters_and_functions_render_in_templates() {
+        let mut extensions = TemplateExtensions::default();
+        extensions.add_filter("currency", |value: &Value, args: &HashMap<String, Value>| {
+            let symbol = args.get("symbol").and_then(|s| s.as_str()).unwrap_or("$");
+            Ok(Value::String(format!("{}{:.2}", symbol, value.as_f64().unwrap_or_default())))
+        });
+        extensions.add_function("support_email", |_: &HashMap<String, Value>| Ok(json!("help@example.com")));
+
+        let mut tera = build_templates_with(&extensions);
+        tera.add_raw_template(
+            "order.html.tera",
+            "{{ total | currency(symbol=\"€\") }} {{ placed_at | format_datetime(tz=\"Asia/Kolkata\") }} \
+             {{ note | truncate(length=5) }} {{ support_email() }}",
+        ).unwrap();
+
+        let mut ctx = Context::new();
+        ctx.insert("total", &12.5);
+        ctx.insert("placed_at", &json!({ "$date": { "$numberLong": "1719835200000" } }));
+        ctx.insert("note", "Leave at the door");
+        assert_eq!(
+            tera.render("order.html.tera", &ctx).unwrap(),
+            "€12.50 2024-07-01 17:30:00 Leave… help@example.com"
+        );
+
+        // The test's extensions stay out of the global registry
+        assert!(!TEMPLATE_EXTENSIONS.read().unwrap().filters.contains_key("currency"));
+
+        Lazy::force(&ADMINX_TEMPLATES);
+        assert!(!templates_pending("late"));
+    }
+}
@@ -0,0 +1,191 @@
+// adminx/src/helpers/transaction.rs
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, ResponseError};
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use mongodb::ClientSession;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::{Mutex, MutexGuard};
+use tracing::{error, warn};
+
+use crate::actions::DynHandler;
+use crate::error::AdminxError;
+use crate::AdmixResource;
+
+/// A session with an open transaction that can be committed or aborted.
+/// Implemented for `ClientSession`; tests can substitute an in-memory one.
+#[async_trait]
+pub trait Transaction: Send {
+    async fn commit(&mut self) -> Result<(), String>;
+    async fn abort(&mut self) -> Result<(), String>;
+}
+
+#[async_trait]
+impl Transaction for ClientSession {
+    async fn commit(&mut self) -> Result<(), String> {
+        self.commit_transaction().await.map_err(|e| e.to_string())
+    }
+
+    async fn abort(&mut self) -> Result<(), String> {
+        self.abort_transaction().await.map_err(|e| e.to_string())
+    }
+}
+
+/// Start a session with an open transaction on the client behind the resource's collection.
+/// MongoDB only supports transactions on replica sets and sharded clusters; against a
+/// standalone server this fails with an internal error.
+pub async fn start_transaction(resource: &dyn AdmixResource) -> Result<ClientSession, AdminxError> {
+    let collection = resource.try_get_collection()?;
+    let mut session = collection.client().start_session(None).await.map_err(|e| {
+        error!("Could not start a session for {}: {}", resource.resource_name(), e);
+        AdminxError::InternalError
+    })?;
+    session.start_transaction(None).await.map_err(|e| {
+        error!("Could not start a transaction for {} (transactions need a replica set): {}", resource.resource_name(), e);
+        AdminxError::InternalError
+    })?;
+    Ok(session)
+}
+
+/// Commit when the work succeeded, abort otherwise. A failed commit is an error.
+pub async fn finish_transaction<S: Transaction + ?Sized>(session: &mut S, succeeded: bool) -> Result<(), AdminxError> {
+    if succeeded {
+        session.commit().await.map_err(|e| {
+            error!("Transaction commit failed: {}", e);
+            AdminxError::InternalError
+        })
+    } else {
+        if let Err(e) = session.abort().await {
+            warn!("Transaction abort failed: {}", e);
+        }
+        Ok(())
+    }
+}
+
+/// Run `work` inside `session`'s transaction: committed when it returns `Ok`, aborted
+/// (so none of its writes apply) when it returns `Err`
+pub async fn run_in_transaction<S, T, F>(session: &mut S, work: F) -> Result<T, AdminxError>
+where
+    S: Transaction,
+    F: for<'s> FnOnce(&'s mut S) -> BoxFuture<'s, Result<T, AdminxError>>,
+{
+    let result = work(session).await;
+    finish_transaction(session, result.is_ok()).await?;
+    result
+}
+
+/// `run_in_transaction` in a new transaction on the resource's database
+pub async fn with_transaction<T, F>(resource: &dyn AdmixResource, work: F) -> Result<T, AdminxError>
+where
+    F: for<'s> FnOnce(&'s mut ClientSession) -> BoxFuture<'s, Result<T, AdminxError>>,
+{
+    let mut session = start_transaction(resource).await?;
+    run_in_transaction(&mut session, work).await
+}
+
+/// The transaction a custom action runs in when its resource's
+/// `action_in_transaction()` says so. Pass the locked session to the
+/// `*_with_session` collection methods.
+#[derive(Clone)]
+pub struct ActionTransaction(Arc<Mutex<ClientSession>>);
+
+impl ActionTransaction {
+    pub async fn session(&self) -> MutexGuard<'_, ClientSession> {
+        self.0.lock().await
+    }
+}
+
+/// The request's action transaction, if the action runs in one
+pub fn action_transaction(req: &HttpRequest) -> Option<ActionTransaction> {
+    req.extensions().get::<ActionTransaction>().cloned()
+}
+
+/// Run a custom action in a transaction, committing on a 2xx response and aborting
+/// on anything else
+pub async fn transactional_action(
+    resource: &dyn AdmixResource,
+    handler: DynHandler,
+    req: HttpRequest,
+    id: web::Path<String>,
+    body: web::Json<Value>,
+) -> HttpResponse {
+    let session = match start_transaction(resource).await {
+        Ok(session) => session,
+        Err(e) => return e.error_response(),
+    };
+    let transaction = ActionTransaction(Arc::new(Mutex::new(session)));
+    req.extensions_mut().insert(transaction.clone());
+
+    let response = handler(req, id, body).await;
+    let mut session = transaction.session().await;
+    match finish_transaction(&mut *session, response.status().is_success()).await {
+        Ok(()) => response,
+        Err(e) => e.error_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+
+    /// Buffers writes until commit, like a server-side transaction
+    #[derive(Default)]
+    struct MemoryTransaction {
+        pending: Vec<(String, i64)>,
+        committed: Arc<StdMutex<Vec<(String, i64)>>>,
+        aborted: bool,
+    }
+
+    impl MemoryTransaction {
+        fn add(&mut self, account: &str, amount: i64) -> Result<(), AdminxError> {
+            if amount < -100 {
+                return Err(AdminxError::BadRequest("Insufficient balance".into()));
+            }
+            self.pending.push((account.to_string(), amount));
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Transaction for MemoryTransaction {
+        async fn commit(&mut self) -> Result<(), String> {
+            self.committed.lock().unwrap().append(&mut self.pending);
+            Ok(())
+        }
+
+        async fn abort(&mut self) -> Result<(), String> {
+            self.pending.clear();
+            self.aborted = true;
+            Ok(())
+        }
+    }
+
+    fn transfer(amount: i64) -> impl for<'s> FnOnce(&'s mut MemoryTransaction) -> BoxFuture<'s, Result<(), AdminxError>> {
+        move |tx| Box::pin(async move {
+            tx.add("alice", amount)?;
+            tx.add("bob", -amount)?;
+            Ok(())
+        })
+    }
+
+    #[actix_rt::test]
+    async fn failing_multi_write_rolls_back() {
+        let mut tx = MemoryTransaction::default();
+        let result = run_in_transaction(&mut tx, transfer(500)).await;
+
+        assert!(matches!(result, Err(AdminxError::BadRequest(_))));
+        assert!(tx.aborted);
+        // The first write happened inside the transaction but never landed
+        assert!(tx.committed.lock().unwrap().is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn successful_multi_write_commits_every_write() {
+        let mut tx = MemoryTransaction::default();
+        run_in_transaction(&mut tx, transfer(50)).await.unwrap();
+
+        assert!(!tx.aborted);
+        assert_eq!(*tx.committed.lock().unwrap(), vec![("alice".to_string(), 50), ("bob".to_string(), -50)]);
+    }
+}
@@ -18,9 +18,12 @@ pub mod configs;
 pub mod models;
 pub mod schemas;
 pub mod errors;
+pub mod widgets;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 // Re-export main types for easier importing
-pub use schemas::adminx_schema::AdminxSchema;
+pub use schemas::adminx_schema::{AdminxSchema, form_structure_from_model};
 
 // Export configuration and app creation functions
 pub use configs::initializer::{
@@ -34,19 +37,30 @@ pub use configs::initializer::{
 // Export commonly used utilities - ✅ FIXED: Use Claims from structs only
 pub use utils::{
     jwt::create_jwt_token, // ✅ Don't export Claims from jwt
-    auth::{extract_claims_from_session, AdminxStatus, NewAdminxUser, InitOutcome},
+    auth::{extract_claims_from_session, AuthenticatedUser, AdminxStatus, NewAdminxUser, InitOutcome},
     structs::{LoginForm, RoleGuard, Claims}, // ✅ Export Claims from structs
+    clock::{Clock, FixedClock, SystemClock},
+    branding::Branding,
+    database::{initiate_named_database, initiate_databases},
 };
 
 // Export core traits and types
 pub use resource::AdmixResource;
+pub use actions::RowAction;
 
 // Export models
 pub use models::adminx_model::{AdminxUser, AdminxUserPublic};
 
 // Export controllers for custom route registration
 pub use controllers::{
-    auth_controller::{login_form, login_action, logout_action},
+    auth_controller::{
+        login_form, login_action, logout_action,
+        change_password_form, change_password_action,
+        profile_edit_form, profile_edit_action,
+        verify_email_action,
+        reset_password_request_form, reset_password_request_action,
+        reset_password_form, reset_password_action,
+    },
     dashboard_controller::{adminx_home, adminx_stats, adminx_profile},
 };
 
@@ -61,8 +75,22 @@ pub use helpers::template_helper::{
     render_404,
     render_403,
     render_500,
+    register_template_filter,
+    register_template_function,
 };
 
+// Export error page customization
+pub use helpers::error_pages::{ErrorPages, TemplateErrorPages};
+
+// Export index declarations
+pub use helpers::indexes::{ensure_all_indexes, ensure_indexes, IndexSpec};
+
+// Export list/export column formatting
+pub use helpers::column_format::register_column_formatter;
+
+// Export dashboard widgets
+pub use widgets::{register_widget, DashboardWidget, WidgetContext};
+
 // Export middleware
 pub use middleware::role_guard::RoleGuardMiddleware;
 
@@ -81,6 +109,7 @@ pub mod prelude {
         RoleGuard,
         render_template,
         extract_claims_from_session,
+        AuthenticatedUser,
         AdmixResource, // ✅ Added this for convenience
     };
 }
@@ -156,6 +185,8 @@ mod tests {
             email: "test@example.com".to_string(),
             role: "admin".to_string(),
             roles: vec!["admin".to_string()],
+            iss: "adminx".to_string(),
+            aud: "adminx".to_string(),
         };
         
         assert_eq!(claims.role, "admin");
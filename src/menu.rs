@@ -33,6 +33,18 @@ impl MenuAction {
         }
     }
 
+    /// Map a controller action string ("list", "create", "view", "update", "delete") to a `MenuAction`
+    pub fn from_action(action: &str) -> Option<Self> {
+        match action {
+            "list" => Some(MenuAction::List),
+            "view" => Some(MenuAction::View),
+            "create" => Some(MenuAction::Create),
+            "update" | "edit" => Some(MenuAction::Edit),
+            "delete" => Some(MenuAction::Delete),
+            _ => None,
+        }
+    }
+
     pub fn to_path(&self, base_path: &str) -> String {
         match self {
             MenuAction::List => base_path.to_string(),
@@ -1,6 +1,7 @@
 // adminx/src/middleware/role_guard.rs - Fixed version
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
     Error, HttpMessage, web,
 };
 use futures_util::future::LocalBoxFuture;
@@ -11,13 +12,15 @@ use std::{
 use actix_session::SessionExt;
 use crate::utils::{
     auth::{
-        extract_claims_from_session
+        session_claims,
+        unauthenticated_response,
     },
     structs::{
         RoleGuard
     },
 };
 use crate::configs::initializer::AdminxConfig;
+use crate::error::AdminxError;
 use tracing::{info, warn};
 
 impl<S, B> Transform<S, ServiceRequest> for RoleGuard
@@ -73,7 +76,7 @@ where
                     actix_web::error::ErrorInternalServerError("AdminX config not found")
                 })?;
 
-            match extract_claims_from_session(&session, config.as_ref()).await {
+            match session_claims(&session, config.as_ref()) {
                 Ok(claims) => {
                     let user_roles: HashSet<String> = {
                         let mut roles = claims.roles.clone();
@@ -94,21 +97,23 @@ where
                     } else {
                         warn!("🚫 Access denied to {} for {} - insufficient roles (user: {:?}, required: {:?})", 
                               claims.email, uri, user_roles, allowed_roles);
-                        return Err(actix_web::error::ErrorForbidden(format!(
+                        return Err(AdminxError::Forbidden(format!(
                             "Access denied. Required roles: {:?}, User roles: {:?}", 
                             allowed_roles, user_roles
-                        )));
+                        )).into());
                     }
                 }
                 Err(auth_error) => {
                     warn!("🔐 Authentication failed for request: {} - {:?}", uri, auth_error);
-                    return Err(actix_web::error::ErrorUnauthorized("Authentication required"));
+                    let response = unauthenticated_response(auth_error, req.request());
+                    Err(InternalError::from_response("Authentication required", response).into())
                 }
             }
         })
     }
 }
 
+
 // Helper functions for common role checks
 impl RoleGuard {
     /// Create a role guard that allows only admins
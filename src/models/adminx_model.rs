@@ -1,8 +1,10 @@
 // adminx/src/models/adminx_model.rs
 use serde::{Deserialize, Serialize};
-use mongodb::bson::{doc, oid::ObjectId, DateTime as BsonDateTime};
-use bcrypt::verify;
+use mongodb::bson::{doc, oid::ObjectId, Bson, DateTime as BsonDateTime};
+use crate::utils::password::{hash_password, needs_rehash, verify_password, PasswordHashing};
 use anyhow::Result;
+use crate::utils::mount::admin_path;
+use crate::filters::{parse_query, FilterOptions};
 
 use crate::{
     utils::{
@@ -12,7 +14,9 @@ use crate::{
         auth::{
             AdminxStatus
         },
-        jwt::create_jwt_token,
+        jwt::create_jwt_token_with_roles,
+        password::check_password,
+        mailer::{Email, Mailer},
     },
     configs::initializer::AdminxConfig,
 };
@@ -28,13 +32,97 @@ pub struct AdminxUser {
     pub status: AdminxStatus,
     pub created_at: BsonDateTime,
     pub updated_at: BsonDateTime,
+    /// Whether the admin confirmed ownership of `email`. Records created
+    /// before verification existed are treated as verified.
+    #[serde(default = "default_email_verified")]
+    pub email_verified: bool,
+    /// Pending email verification token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_token: Option<String>,
+    /// SHA-256 (hex) of the pending single-use password reset token; the token
+    /// itself is only ever emailed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reset_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reset_token_expires_at: Option<BsonDateTime>,
+    /// Roles the admin signs in with, the first being the primary one. Admins
+    /// without any sign in as `admin`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
 }
 
+fn default_email_verified() -> bool {
+    true
+}
+
+/// Random, URL-safe token used for email verification links
+pub fn generate_verification_token() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
+
+/// What is stored for a password reset token, so a leaked database can't be used to reset passwords
+pub fn reset_token_digest(token: &str) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Message sent to an admin so they can confirm their email address
+pub fn verification_email(to: &str, token: &str) -> Email {
+    Email {
+        to: to.to_string(),
+        subject: "Verify your AdminX email address".to_string(),
+        body: format!(
+            "Confirm your email address by sending this token to POST {}:\n\n{}",
+            admin_path("/api/verify"),
+            token
+        ),
+    }
+}
+
+/// Message carrying a password reset link
+pub fn password_reset_email(to: &str, token: &str) -> Email {
+    Email {
+        to: to.to_string(),
+        subject: "Reset your AdminX password".to_string(),
+        body: format!(
+            "Someone asked to reset your AdminX password. Open {} to choose a new one.\n\nIf this wasn't you, ignore this email.",
+            admin_path(&format!("/reset-password/{}", token))
+        ),
+    }
+}
+
+/// Why a password reset could not be completed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordResetError {
+    InvalidToken,
+    ExpiredToken,
+    WeakPassword(String),
+    Database(String),
+}
+
+impl std::fmt::Display for PasswordResetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PasswordResetError::InvalidToken => f.write_str("This reset link is invalid or has already been used"),
+            PasswordResetError::ExpiredToken => f.write_str("This reset link has expired"),
+            PasswordResetError::WeakPassword(reason) => f.write_str(reason),
+            PasswordResetError::Database(e) => write!(f, "Failed to reset password: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PasswordResetError {}
+
 impl AdminxUser {
     pub fn verify_password(&self, plain: &str) -> bool {
-        verify(plain, &self.password).unwrap_or(false)
+        verify_password(plain, &self.password)
     }
     
+    /// Primary role of this user's sessions
+    pub fn session_role(&self) -> &str {
+        self.roles.first().map(String::as_str).unwrap_or("admin")
+    }
+
     /// Create a JWT token for this user
     pub fn create_session_token(&self, config: &AdminxConfig) -> Result<String, Box<dyn std::error::Error>> {
         let admin_id = self.id.as_ref()
@@ -42,7 +130,7 @@ impl AdminxUser {
             .to_string();
         
         // Convert anyhow::Error to Box<dyn std::error::Error>
-        create_jwt_token(&admin_id, &self.email, "admin", config)
+        create_jwt_token_with_roles(&admin_id, &self.email, self.session_role(), self.roles.clone(), config)
             .map_err(|e| {
                 let error: Box<dyn std::error::Error> = Box::new(std::io::Error::new(
                     std::io::ErrorKind::Other,
@@ -63,7 +151,7 @@ impl AdminxUser {
             .to_string();
         
         // Convert anyhow::Error to Box<dyn std::error::Error>
-        crate::utils::jwt::create_jwt_token_with_roles(
+        create_jwt_token_with_roles(
             &admin_id, 
             &self.email, 
             "admin", 
@@ -121,6 +209,52 @@ impl AdminxUser {
             status: self.status.clone(),
             created_at: self.created_at,
             updated_at: self.updated_at,
+            email_verified: self.email_verified,
+        }
+    }
+
+    /// Mark the email as unverified and issue a fresh verification token
+    pub fn start_email_verification(&mut self) -> String {
+        let token = generate_verification_token();
+        self.email_verified = false;
+        self.verification_token = Some(token.clone());
+        token
+    }
+
+    /// Issue a password reset token valid for `ttl` from `now`; only its digest is kept
+    pub fn start_password_reset(&mut self, ttl: std::time::Duration, now: BsonDateTime) -> String {
+        let token = generate_verification_token();
+        let expires_at = now.timestamp_millis() + ttl.as_millis() as i64;
+        self.reset_token = Some(reset_token_digest(&token));
+        self.reset_token_expires_at = Some(BsonDateTime::from_millis(expires_at));
+        token
+    }
+
+    /// Consume a password reset token as of `now`. Tokens are single-use.
+    pub fn consume_reset_token(&mut self, token: &str, now: BsonDateTime) -> Result<(), PasswordResetError> {
+        match &self.reset_token {
+            Some(pending) if !token.is_empty() && *pending == reset_token_digest(token) => {}
+            _ => return Err(PasswordResetError::InvalidToken),
+        }
+        match self.reset_token_expires_at {
+            Some(expires_at) if expires_at > now => {}
+            _ => return Err(PasswordResetError::ExpiredToken),
+        }
+
+        self.reset_token = None;
+        self.reset_token_expires_at = None;
+        Ok(())
+    }
+
+    /// Consume `token`, marking the email verified if it matches the pending token
+    pub fn verify_email(&mut self, token: &str) -> bool {
+        match &self.verification_token {
+            Some(pending) if !token.is_empty() && pending == token => {
+                self.email_verified = true;
+                self.verification_token = None;
+                true
+            }
+            _ => false,
         }
     }
 
@@ -135,9 +269,7 @@ impl AdminxUser {
         if email.trim().is_empty() {
             return Err("Email cannot be empty".into());
         }
-        if plain_password.len() < 8 {
-            return Err("Password must be at least 8 characters long".into());
-        }
+        check_password(&plain_password)?;
         
         // Check if user already exists
         if get_admin_by_email(&email).await.is_some() {
@@ -145,7 +277,7 @@ impl AdminxUser {
         }
 
         // Hash the password
-        let hashed_password = bcrypt::hash(plain_password, bcrypt::DEFAULT_COST)
+        let hashed_password = hash_password(&plain_password)
             .map_err(|e| format!("Failed to hash password: {}", e))?;
 
         let now = BsonDateTime::now();
@@ -158,6 +290,11 @@ impl AdminxUser {
             status: AdminxStatus::Active,
             created_at: now,
             updated_at: now,
+            email_verified: true,
+            verification_token: None,
+            reset_token: None,
+            reset_token_expires_at: None,
+            roles: Vec::new(),
         };
 
         // Insert into database
@@ -176,14 +313,52 @@ impl AdminxUser {
         email: String,
         plain_password: String,
         status: AdminxStatus,
+    ) -> Result<ObjectId, Box<dyn std::error::Error>> {
+        Self::insert_new_user(username, email, plain_password, status, Vec::new(), None).await
+    }
+
+    /// Create a new AdminX user who signs in with `roles` (see `AdminxUser::roles`)
+    pub async fn create_new_user_with_roles(
+        username: String,
+        email: String,
+        plain_password: String,
+        status: AdminxStatus,
+        roles: Vec<String>,
+    ) -> Result<ObjectId, Box<dyn std::error::Error>> {
+        Self::insert_new_user(username, email, plain_password, status, roles, None).await
+    }
+
+    /// Create a user whose email must be confirmed, and send them the verification token
+    pub async fn create_unverified_user(
+        username: String,
+        email: String,
+        plain_password: String,
+        status: AdminxStatus,
+        mailer: &dyn Mailer,
+    ) -> Result<ObjectId, Box<dyn std::error::Error>> {
+        let token = generate_verification_token();
+        let id = Self::insert_new_user(username, email.clone(), plain_password, status, Vec::new(), Some(token.clone())).await?;
+
+        if let Err(e) = mailer.send(verification_email(&email.to_lowercase(), &token)).await {
+            tracing::error!("Failed to send verification email to {}: {}", email, e);
+        }
+
+        Ok(id)
+    }
+
+    async fn insert_new_user(
+        username: String,
+        email: String,
+        plain_password: String,
+        status: AdminxStatus,
+        roles: Vec<String>,
+        verification_token: Option<String>,
     ) -> Result<ObjectId, Box<dyn std::error::Error>> {
         // Validate inputs
         if email.trim().is_empty() {
             return Err("Email cannot be empty".into());
         }
-        if plain_password.len() < 8 {
-            return Err("Password must be at least 8 characters long".into());
-        }
+        check_password(&plain_password)?;
         
         // Check if user already exists
         if get_admin_by_email(&email).await.is_some() {
@@ -191,7 +366,7 @@ impl AdminxUser {
         }
 
         // Hash the password
-        let hashed_password = bcrypt::hash(plain_password, bcrypt::DEFAULT_COST)
+        let hashed_password = hash_password(&plain_password)
             .map_err(|e| format!("Failed to hash password: {}", e))?;
 
         let now = BsonDateTime::now();
@@ -204,6 +379,11 @@ impl AdminxUser {
             status,
             created_at: now,
             updated_at: now,
+            email_verified: verification_token.is_none(),
+            verification_token,
+            reset_token: None,
+            reset_token_expires_at: None,
+            roles,
         };
 
         // Insert into database
@@ -247,12 +427,10 @@ impl AdminxUser {
         }
 
         // Validate new password
-        if new_password.len() < 8 {
-            return Err("New password must be at least 8 characters long".into());
-        }
+        check_password(new_password)?;
 
         // Hash new password
-        let hashed_password = bcrypt::hash(new_password, bcrypt::DEFAULT_COST)
+        let hashed_password = hash_password(new_password)
             .map_err(|e| format!("Failed to hash password: {}", e))?;
 
         // Update in database
@@ -363,6 +541,8 @@ pub struct AdminxUserPublic {
     pub status: AdminxStatus,
     pub created_at: BsonDateTime,
     pub updated_at: BsonDateTime,
+    #[serde(default = "default_email_verified")]
+    pub email_verified: bool,
 }
 
 // Database operations
@@ -428,6 +608,325 @@ pub async fn get_all_admins(include_deleted: bool) -> Result<Vec<AdminxUser>, mo
     Ok(users)
 }
 
+/// Filtering, sorting and paging options for listing admins
+#[derive(Debug, Clone, Default)]
+pub struct AdminListQuery {
+    /// Case-insensitive substring matched against email and username
+    pub search: Option<String>,
+    pub status: Option<AdminxStatus>,
+    pub include_deleted: bool,
+    pub limit: Option<i64>,
+    pub offset: Option<u64>,
+    /// Field to sort by, prefixed with `-` for descending (e.g. `-created_at`)
+    pub sort: Option<String>,
+    /// Filters, sort and paging from a web-style query string, see `with_query`
+    pub filters: Option<FilterOptions>,
+}
+
+/// Fields `AdminListQuery::sort` may reference
+pub const ADMIN_SORT_FIELDS: &[&str] = &["email", "username", "status", "created_at", "updated_at"];
+
+/// Fields a `with_query` string may filter on
+pub const ADMIN_FILTER_FIELDS: &[&str] = &["email", "username", "status", "delete", "created_at", "updated_at"];
+
+impl AdminListQuery {
+    /// Add the filters of a query string such as `status=active&sort=-created_at`, parsed
+    /// by `parse_query` exactly as the web list parses its URL query
+    pub fn with_query(mut self, query: &str) -> Result<Self> {
+        let filters = parse_query(query);
+        let sort_fields = filters.sort.iter().flat_map(|sort| sort.keys());
+        for field in filters.filter.keys() {
+            if !ADMIN_FILTER_FIELDS.contains(&field.as_str()) {
+                anyhow::bail!("Cannot filter by '{}'. Available: {}", field, ADMIN_FILTER_FIELDS.join(", "));
+            }
+        }
+        for field in sort_fields {
+            if !ADMIN_SORT_FIELDS.contains(&field.as_str()) {
+                anyhow::bail!("Cannot sort by '{}'. Available: {}", field, ADMIN_SORT_FIELDS.join(", "));
+            }
+        }
+        admin_filter_document(&filters.filter)?;
+        self.filters = Some(filters);
+        Ok(self)
+    }
+
+    pub fn filter(&self) -> mongodb::bson::Document {
+        let mut filter = if self.include_deleted {
+            doc! {}
+        } else {
+            doc! { "delete": false }
+        };
+
+        if let Some(search) = self.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            let pattern = regex::escape(search);
+            filter.insert("$or", vec![
+                doc! { "email": { "$regex": &pattern, "$options": "i" } },
+                doc! { "username": { "$regex": &pattern, "$options": "i" } },
+            ]);
+        }
+
+        if let Some(status) = &self.status {
+            if let Ok(status) = mongodb::bson::to_bson(status) {
+                filter.insert("status", status);
+            }
+        }
+
+        if let Some(filters) = &self.filters {
+            filter.extend(admin_filter_document(&filters.filter).unwrap_or_else(|_| filters.filter.clone()));
+        }
+
+        filter
+    }
+
+    /// Explicit `sort`, `limit` and `offset` win over those of the query string, which
+    /// only pages the list when it sets `page` or `per_page`
+    pub fn find_options(&self) -> Result<mongodb::options::FindOptions> {
+        let query_sort = self.filters.as_ref().and_then(|filters| filters.sort.clone());
+        let query_paging = self.filters.as_ref().filter(|filters| filters.paged);
+        let sort = match self.sort.as_deref() {
+            Some(sort) => {
+                let (field, direction) = match sort.strip_prefix('-') {
+                    Some(field) => (field, -1),
+                    None => (sort, 1),
+                };
+                if !ADMIN_SORT_FIELDS.contains(&field) {
+                    anyhow::bail!("Cannot sort by '{}'. Available: {}", field, ADMIN_SORT_FIELDS.join(", "));
+                }
+                doc! { field: direction }
+            }
+            None => query_sort.unwrap_or_else(|| doc! { "created_at": 1 }),
+        };
+
+        Ok(mongodb::options::FindOptions::builder()
+            .sort(sort)
+            .skip(self.offset.or(query_paging.map(|filters| filters.skip)))
+            .limit(self.limit.or(query_paging.map(|filters| filters.limit as i64)))
+            .build())
+    }
+}
+
+/// A `parse_query` filter with values typed as the admin fields are stored: `delete` as
+/// a bool and the timestamps as dates, given in RFC 3339 (`2024-01-31T00:00:00Z`)
+fn admin_filter_document(filter: &mongodb::bson::Document) -> Result<mongodb::bson::Document> {
+    filter.iter()
+        .map(|(field, value)| Ok((field.clone(), admin_filter_value(field, value.clone())?)))
+        .collect()
+}
+
+fn admin_filter_value(field: &str, value: Bson) -> Result<Bson> {
+    match (field, value) {
+        // Operators (`$gte`, `$in`, ...) apply to each of their operands
+        (_, Bson::Document(operators)) => operators.into_iter()
+            .map(|(op, operand)| Ok((op, admin_filter_value(field, operand)?)))
+            .collect::<Result<_>>()
+            .map(Bson::Document),
+        (_, Bson::Array(values)) => values.into_iter()
+            .map(|value| admin_filter_value(field, value))
+            .collect::<Result<_>>()
+            .map(Bson::Array),
+        ("delete", Bson::Boolean(flag)) => Ok(Bson::Boolean(flag)),
+        ("delete", Bson::String(flag)) => flag.parse().map(Bson::Boolean)
+            .map_err(|_| anyhow::anyhow!("'delete' takes true or false, not '{}'", flag)),
+        ("created_at" | "updated_at", Bson::String(date)) => BsonDateTime::parse_rfc3339_str(&date).map(Bson::DateTime)
+            .map_err(|_| anyhow::anyhow!("'{}' takes an RFC 3339 timestamp such as 2024-01-31T00:00:00Z, not '{}'", field, date)),
+        ("delete" | "created_at" | "updated_at", other) => {
+            anyhow::bail!("'{}' cannot be compared with {}", field, other)
+        }
+        (_, other) => Ok(other),
+    }
+}
+
+/// List admins matching `query`, filtered and paged by the database
+pub async fn find_admins(query: &AdminListQuery) -> Result<Vec<AdminxUser>> {
+    let db = get_adminx_database();
+    let collection = db.collection::<AdminxUser>("adminxs");
+
+    let mut cursor = collection.find(query.filter(), query.find_options()?).await?;
+    let mut users = Vec::new();
+
+    use futures::stream::TryStreamExt;
+    while let Some(user) = cursor.try_next().await? {
+        users.push(user);
+    }
+
+    Ok(users)
+}
+
+/// Consume an email verification token. Returns `false` when no admin holds the token.
+pub async fn verify_admin_email(token: &str) -> Result<bool, mongodb::error::Error> {
+    if token.is_empty() {
+        return Ok(false);
+    }
+
+    let db = get_adminx_database();
+    let collection = db.collection::<AdminxUser>("adminxs");
+
+    let mut user = match collection.find_one(doc! { "verification_token": token, "delete": false }, None).await? {
+        Some(user) => user,
+        None => return Ok(false),
+    };
+    if !user.verify_email(token) {
+        return Ok(false);
+    }
+
+    let result = collection.update_one(
+        doc! { "_id": user.id, "verification_token": token },
+        doc! {
+            "$set": { "email_verified": true, "updated_at": BsonDateTime::now() },
+            "$unset": { "verification_token": "" }
+        },
+        None,
+    ).await?;
+
+    Ok(result.modified_count > 0)
+}
+
+/// Mark the admin owning `email` unverified and send them a fresh verification token.
+/// Returns `false` when there is no such admin.
+pub async fn send_email_verification(email: &str, mailer: &dyn Mailer) -> Result<bool, mongodb::error::Error> {
+    let mut user = match get_admin_by_email(email).await {
+        Some(user) => user,
+        None => return Ok(false),
+    };
+    let token = user.start_email_verification();
+
+    let collection = get_adminx_database().collection::<AdminxUser>("adminxs");
+    collection.update_one(
+        doc! { "_id": user.id },
+        doc! { "$set": {
+            "email_verified": false,
+            "verification_token": &token,
+            "updated_at": BsonDateTime::now(),
+        } },
+        None,
+    ).await?;
+
+    if let Err(e) = mailer.send(verification_email(&user.email, &token)).await {
+        tracing::error!("Failed to send verification email to {}: {}", user.email, e);
+    }
+    Ok(true)
+}
+
+/// Store a reset token, valid for `ttl` from `now`, for the active admin owning `email`.
+/// Returns `None` when there is no such admin so callers can stay silent about it.
+pub async fn request_password_reset(
+    email: &str,
+    ttl: std::time::Duration,
+    now: BsonDateTime,
+) -> Result<Option<(AdminxUser, String)>, mongodb::error::Error> {
+    let mut user = match get_admin_by_email(email).await {
+        Some(user) if user.is_active() => user,
+        _ => return Ok(None),
+    };
+    let token = user.start_password_reset(ttl, now);
+
+    let db = get_adminx_database();
+    let collection = db.collection::<AdminxUser>("adminxs");
+    collection.update_one(
+        doc! { "_id": user.id },
+        doc! { "$set": {
+            "reset_token": &user.reset_token,
+            "reset_token_expires_at": user.reset_token_expires_at,
+        } },
+        None,
+    ).await?;
+
+    Ok(Some((user, token)))
+}
+
+/// Look up the admin holding a pending reset token
+pub async fn find_admin_by_reset_token(token: &str) -> Option<AdminxUser> {
+    if token.is_empty() {
+        return None;
+    }
+    let db = get_adminx_database();
+    let collection = db.collection::<AdminxUser>("adminxs");
+    collection
+        .find_one(doc! { "reset_token": reset_token_digest(token), "delete": false }, None)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!("Database error while looking up reset token: {}", e);
+            None
+        })
+}
+
+/// Set a new password using a reset token, consuming the token
+pub async fn reset_password_with_token(token: &str, new_password: &str, now: BsonDateTime) -> Result<(), PasswordResetError> {
+    check_password(new_password).map_err(PasswordResetError::WeakPassword)?;
+
+    let mut user = find_admin_by_reset_token(token)
+        .await
+        .ok_or(PasswordResetError::InvalidToken)?;
+    user.consume_reset_token(token, now)?;
+
+    let hashed_password = hash_password(new_password)
+        .map_err(PasswordResetError::Database)?;
+
+    let db = get_adminx_database();
+    let collection = db.collection::<AdminxUser>("adminxs");
+    // Matching on the token as well keeps concurrent submissions single-use
+    let result = collection.update_one(
+        doc! { "_id": user.id, "reset_token": reset_token_digest(token) },
+        doc! {
+            "$set": { "password": hashed_password, "updated_at": now },
+            "$unset": { "reset_token": "", "reset_token_expires_at": "" }
+        },
+        None,
+    ).await
+    .map_err(|e| PasswordResetError::Database(e.to_string()))?;
+
+    if result.modified_count == 0 {
+        return Err(PasswordResetError::InvalidToken);
+    }
+    Ok(())
+}
+
+/// Persist a new password hash for an admin
+pub async fn store_password_hash(id: ObjectId, hash: String) -> Result<(), String> {
+    let db = get_adminx_database();
+    let collection = db.collection::<AdminxUser>("adminxs");
+    collection.update_one(
+        doc! { "_id": id },
+        doc! { "$set": { "password": hash, "updated_at": BsonDateTime::now() } },
+        None,
+    ).await
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// After a successful login, replace a hash made with weaker parameters (or another
+/// algorithm) than `hashing`. Best-effort: failures are logged and never block the login.
+/// Returns the stored hash when an upgrade happened.
+pub async fn rehash_password_if_needed<F, Fut>(
+    user: &AdminxUser,
+    plain_password: &str,
+    hashing: &PasswordHashing,
+    store: F,
+) -> Option<String>
+where
+    F: FnOnce(ObjectId, String) -> Fut,
+    Fut: std::future::Future<Output = Result<(), String>>,
+{
+    if !needs_rehash(&user.password, hashing) {
+        return None;
+    }
+    let id = user.id?;
+    let hash = hashing
+        .hash(plain_password)
+        .map_err(|e| tracing::warn!("Failed to rehash password for {}: {}", user.email, e))
+        .ok()?;
+    match store(id, hash.clone()).await {
+        Ok(()) => {
+            tracing::info!("Upgraded password hash for {}", user.email);
+            Some(hash)
+        }
+        Err(e) => {
+            tracing::warn!("Failed to store upgraded password hash for {}: {}", user.email, e);
+            None
+        }
+    }
+}
+
 pub async fn count_active_admins() -> Result<u64, mongodb::error::Error> {
     let db = get_adminx_database();
     let collection = db.collection::<AdminxUser>("adminxs");
@@ -475,4 +974,302 @@ pub async fn update_admin_status(id: &ObjectId, status: AdminxStatus) -> Result<
     ).await?;
     
     Ok(result.modified_count > 0)
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_query_searches_email_and_username_with_limit() {
+        let query = AdminListQuery {
+            search: Some("foo".to_string()),
+            limit: Some(2),
+            ..Default::default()
+        };
+
+        let filter = query.filter();
+        assert!(!filter.get_bool("delete").unwrap());
+        let or = filter.get_array("$or").unwrap();
+        assert_eq!(or.len(), 2);
+        assert_eq!(or[0].as_document().unwrap().get_document("email").unwrap().get_str("$regex").unwrap(), "foo");
+
+        let options = query.find_options().unwrap();
+        assert_eq!(options.limit, Some(2));
+        assert_eq!(options.skip, None);
+    }
+
+    #[test]
+    fn list_query_escapes_search_and_validates_sort() {
+        let query = AdminListQuery {
+            search: Some("a.b+".to_string()),
+            status: Some(AdminxStatus::Suspended),
+            include_deleted: true,
+            offset: Some(20),
+            sort: Some("-email".to_string()),
+            ..Default::default()
+        };
+
+        let filter = query.filter();
+        assert!(filter.get("delete").is_none());
+        assert_eq!(filter.get_str("status").unwrap(), "suspended");
+        let or = filter.get_array("$or").unwrap();
+        assert_eq!(or[1].as_document().unwrap().get_document("username").unwrap().get_str("$regex").unwrap(), r"a\.b\+");
+
+        let options = query.find_options().unwrap();
+        assert_eq!(options.sort, Some(doc! { "email": -1 }));
+        assert_eq!(options.skip, Some(20));
+
+        let bad = AdminListQuery { sort: Some("password".to_string()), ..Default::default() };
+        assert!(bad.find_options().is_err());
+    }
+
+    #[test]
+    fn list_query_applies_query_string_filters() {
+        let query = AdminListQuery::default()
+            .with_query("status=suspended&created_at__gte=2024-01-31T00:00:00Z&sort=-email&page=2&per_page=10")
+            .unwrap();
+        let since = BsonDateTime::parse_rfc3339_str("2024-01-31T00:00:00Z").unwrap();
+        assert_eq!(query.filter(), doc! { "delete": false, "status": "suspended", "created_at": { "$gte": since } });
+
+        let options = query.find_options().unwrap();
+        assert_eq!(options.sort, Some(doc! { "email": -1 }));
+        assert_eq!((options.skip, options.limit), (Some(10), Some(10)));
+
+        let explicit = AdminListQuery { limit: Some(3), sort: Some("username".to_string()), ..query };
+        let options = explicit.find_options().unwrap();
+        assert_eq!(options.sort, Some(doc! { "username": 1 }));
+        assert_eq!(options.limit, Some(3));
+
+        // Without page or per_page the query leaves the list unpaged, as without --query
+        let unpaged = AdminListQuery::default().with_query("status=active").unwrap().find_options().unwrap();
+        assert_eq!((unpaged.skip, unpaged.limit), (None, None));
+
+        assert!(AdminListQuery::default().with_query("password=x").is_err());
+        assert!(AdminListQuery::default().with_query("sort=password").is_err());
+    }
+
+    #[test]
+    fn list_query_types_delete_and_timestamp_filters() {
+        let deleted = AdminListQuery::default().with_query("delete=true").unwrap();
+        assert_eq!(deleted.filter(), doc! { "delete": true });
+
+        let range = AdminListQuery::default()
+            .with_query("updated_at__gte=2024-01-01T00:00:00Z&updated_at__lte=2024-02-01T00:00:00Z")
+            .unwrap();
+        let from = BsonDateTime::parse_rfc3339_str("2024-01-01T00:00:00Z").unwrap();
+        let to = BsonDateTime::parse_rfc3339_str("2024-02-01T00:00:00Z").unwrap();
+        assert_eq!(range.filter(), doc! { "delete": false, "updated_at": { "$gte": from, "$lte": to } });
+
+        for query in ["delete=yes", "created_at__gte=5", "updated_at=yesterday"] {
+            assert!(AdminListQuery::default().with_query(query).is_err(), "{}", query);
+        }
+    }
+
+    fn unverified_user() -> AdminxUser {
+        AdminxUser {
+            id: Some(ObjectId::new()),
+            username: "new".to_string(),
+            email: "new@example.com".to_string(),
+            password: String::new(),
+            delete: false,
+            status: AdminxStatus::Active,
+            created_at: BsonDateTime::now(),
+            updated_at: BsonDateTime::now(),
+            email_verified: true,
+            verification_token: None,
+            reset_token: None,
+            reset_token_expires_at: None,
+            roles: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn sessions_carry_the_stored_roles() {
+        let config = crate::utils::jwt::tests::test_config();
+        let mut user = unverified_user();
+        assert_eq!(user.session_role(), "admin");
+
+        user.roles = vec!["editor".to_string(), "viewer".to_string()];
+        let token = user.create_session_token(&config).unwrap();
+        let claims = crate::utils::jwt::decode_jwt_token(&token, &config).unwrap();
+        assert_eq!(claims.role, "editor");
+        assert_eq!(claims.roles, vec!["editor".to_string(), "viewer".to_string()]);
+    }
+
+    #[test]
+    fn verification_tokens_are_long_and_unique() {
+        let first = generate_verification_token();
+        let second = generate_verification_token();
+        assert_eq!(first.len(), 64);
+        assert!(first.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(first, second);
+
+        let email = verification_email("new@example.com", &first);
+        assert_eq!(email.to, "new@example.com");
+        assert!(email.body.contains(&first));
+    }
+
+    #[test]
+    fn verify_email_consumes_matching_token() {
+        let mut user = unverified_user();
+        let token = user.start_email_verification();
+        assert!(!user.email_verified);
+
+        assert!(user.verify_email(&token));
+        assert!(user.email_verified);
+        assert_eq!(user.verification_token, None);
+
+        // The token is single-use
+        assert!(!user.verify_email(&token));
+    }
+
+    #[actix_rt::test]
+    async fn login_upgrades_low_cost_hash() {
+        let mut user = unverified_user();
+        user.password = PasswordHashing::Bcrypt { cost: 4 }.hash("correct horse").unwrap();
+        assert!(user.verify_password("correct horse"));
+
+        let stored = std::sync::Mutex::new(None);
+        let current = PasswordHashing::Bcrypt { cost: 5 };
+        let upgraded = rehash_password_if_needed(&user, "correct horse", &current, |id, hash| {
+            *stored.lock().unwrap() = Some((id, hash));
+            async { Ok(()) }
+        })
+        .await
+        .unwrap();
+
+        let (id, hash) = stored.lock().unwrap().clone().unwrap();
+        assert_eq!(Some(id), user.id);
+        assert_eq!(hash, upgraded);
+        assert!(hash.starts_with("$2b$05$"));
+        user.password = hash;
+        assert!(user.verify_password("correct horse"));
+
+        // Already current: nothing is written
+        let again = rehash_password_if_needed(&user, "correct horse", &current, |_, _| async {
+            Err("should not be called".to_string())
+        })
+        .await;
+        assert_eq!(again, None);
+    }
+
+    #[test]
+    fn verify_email_rejects_invalid_token() {
+        let mut user = unverified_user();
+        user.start_email_verification();
+
+        assert!(!user.verify_email("not-the-token"));
+        assert!(!user.verify_email(""));
+        assert!(!user.email_verified);
+        assert!(user.verification_token.is_some());
+    }
+
+    #[test]
+    fn legacy_records_default_to_verified() {
+        let user: AdminxUser = mongodb::bson::from_document(doc! {
+            "username": "legacy",
+            "email": "legacy@example.com",
+            "password": "hash",
+            "delete": false,
+            "status": "active",
+            "created_at": BsonDateTime::now(),
+            "updated_at": BsonDateTime::now(),
+        }).unwrap();
+        assert!(user.email_verified);
+        assert_eq!(user.verification_token, None);
+    }
+
+    #[test]
+    fn reset_token_happy_path() {
+        let mut user = unverified_user();
+        let token = user.start_password_reset(std::time::Duration::from_secs(3600), BsonDateTime::now());
+        // Only the digest is stored
+        assert_eq!(user.reset_token, Some(reset_token_digest(&token)));
+        assert_ne!(user.reset_token.as_deref(), Some(token.as_str()));
+
+        assert_eq!(user.consume_reset_token(&token, BsonDateTime::now()), Ok(()));
+        assert_eq!(user.reset_token, None);
+        assert_eq!(user.reset_token_expires_at, None);
+        assert!(password_reset_email("new@example.com", &token).body.contains(&token));
+    }
+
+    #[test]
+    fn reset_token_expires() {
+        let mut user = unverified_user();
+        let now = BsonDateTime::from_millis(1_700_000_000_000);
+        let token = user.start_password_reset(std::time::Duration::from_secs(60), now);
+        let later = BsonDateTime::from_millis(now.timestamp_millis() + 61_000);
+
+        assert_eq!(user.consume_reset_token(&token, later), Err(PasswordResetError::ExpiredToken));
+    }
+
+    #[test]
+    fn reset_token_is_single_use() {
+        let mut user = unverified_user();
+        let token = user.start_password_reset(std::time::Duration::from_secs(3600), BsonDateTime::now());
+
+        assert_eq!(user.consume_reset_token(&token, BsonDateTime::now()), Ok(()));
+        assert_eq!(user.consume_reset_token(&token, BsonDateTime::now()), Err(PasswordResetError::InvalidToken));
+        assert_eq!(user.consume_reset_token("", BsonDateTime::now()), Err(PasswordResetError::InvalidToken));
+    }
+
+    /// Captures sent emails instead of delivering them
+    #[derive(Default)]
+    struct Outbox(std::sync::Mutex<Vec<Email>>);
+
+    #[async_trait::async_trait]
+    impl Mailer for Outbox {
+        async fn send(&self, email: Email) -> Result<(), String> {
+            self.0.lock().unwrap().push(email);
+            Ok(())
+        }
+    }
+
+    #[actix_rt::test]
+    #[ignore = "needs a MongoDB at ADMINX_TEST_MONGODB_URL"]
+    async fn unverified_user_signs_in_after_verifying() {
+        use crate::configs::initializer::{get_adminx_session_middleware, AdminxConfig};
+        use crate::controllers::auth_controller::{api_login_action, verify_email_action};
+        use crate::utils::database::{initiate_database, initiate_mongo_client};
+        use actix_web::{http::StatusCode, test::{call_service, init_service, TestRequest}, web, App};
+
+        let url = std::env::var("ADMINX_TEST_MONGODB_URL").expect("ADMINX_TEST_MONGODB_URL");
+        let db_name = format!("adminx_test_{}", uuid::Uuid::new_v4().simple());
+        initiate_database(initiate_mongo_client(&url, &db_name).await);
+
+        let outbox = Outbox::default();
+        let password = "Correct-horse-battery-9";
+        AdminxUser::create_unverified_user("editor".into(), "Editor@example.com".into(), password.into(), AdminxStatus::Active, &outbox)
+            .await
+            .unwrap();
+        let email = outbox.0.lock().unwrap().pop().expect("verification email");
+        assert_eq!(email.to, "editor@example.com");
+        let token = email.body.lines().last().unwrap().trim().to_string();
+
+        let config = AdminxConfig { require_email_verification: true, ..crate::utils::jwt::tests::test_config() };
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/api/login", web::post().to(api_login_action))
+                .route("/api/verify", web::post().to(verify_email_action))
+        ).await;
+        let login = || TestRequest::post()
+            .uri("/api/login")
+            .set_json(serde_json::json!({ "email": "editor@example.com", "password": password }))
+            .to_request();
+
+        assert_eq!(call_service(&app, login()).await.status(), StatusCode::FORBIDDEN);
+
+        let verify = |token: &str| TestRequest::post().uri("/api/verify").set_json(serde_json::json!({ "token": token })).to_request();
+        assert_eq!(call_service(&app, verify(&token)).await.status(), StatusCode::OK);
+        // Tokens are single-use
+        assert_eq!(call_service(&app, verify(&token)).await.status(), StatusCode::BAD_REQUEST);
+
+        assert_eq!(call_service(&app, login()).await.status(), StatusCode::OK);
+
+        get_adminx_database().drop(None).await.unwrap();
+    }
+}
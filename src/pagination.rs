@@ -6,4 +6,98 @@ pub struct PaginatedResponse<T: Serialize> {
     pub total: u64,
     pub page: u64,
     pub per_page: u64,
+    pub total_pages: u64,
+    pub has_next: bool,
+    pub has_prev: bool,
+}
+
+impl<T: Serialize> PaginatedResponse<T> {
+    pub fn new(data: Vec<T>, total: u64, page: u64, per_page: u64) -> Self {
+        let total_pages = total_pages(total, per_page);
+        Self {
+            data,
+            total,
+            page,
+            per_page,
+            total_pages,
+            has_next: page < total_pages,
+            has_prev: page > 1,
+        }
+    }
+}
+
+// Pagination math shared by the list view, the JSON API and exports.
+// A per_page of zero means "no limit": everything is on a single page.
+
+/// Number of pages needed for `total` items; an empty list is still one (empty) page
+pub fn total_pages(total: u64, per_page: u64) -> u64 {
+    if per_page > 0 { total.div_ceil(per_page).max(1) } else { 1 }
+}
+
+/// Documents to skip to reach the 1-based `page`; page 0 is treated as the first page
+pub fn page_offset(page: u64, per_page: u64) -> u64 {
+    page.saturating_sub(1).saturating_mul(per_page)
+}
+
+/// 1-based page containing the document at `offset`
+pub fn page_for_offset(offset: u64, per_page: u64) -> u64 {
+    offset.checked_div(per_page).map_or(1, |page| page + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_collection_is_a_single_page() {
+        let response = PaginatedResponse::<u64>::new(vec![], 0, 1, 10);
+        assert_eq!(response.total_pages, 1);
+        assert!(!response.has_next);
+        assert!(!response.has_prev);
+        assert_eq!(total_pages(0, 0), total_pages(0, 10));
+    }
+
+    #[test]
+    fn exact_multiple_does_not_add_a_page() {
+        let first = PaginatedResponse::new(vec![1; 10], 20, 1, 10);
+        assert_eq!(first.total_pages, 2);
+        assert!(first.has_next);
+        assert!(!first.has_prev);
+
+        let last = PaginatedResponse::new(vec![1; 10], 20, 2, 10);
+        assert!(!last.has_next);
+        assert!(last.has_prev);
+
+        assert_eq!(total_pages(21, 10), 3);
+    }
+
+    #[test]
+    fn serializes_navigation_fields() {
+        let json = serde_json::to_value(PaginatedResponse::new(vec!["a"], 3, 2, 1)).unwrap();
+        assert_eq!(json["total_pages"], 3);
+        assert_eq!(json["has_next"], true);
+        assert_eq!(json["has_prev"], true);
+    }
+
+    #[test]
+    fn zero_per_page_puts_everything_on_one_page() {
+        assert_eq!(total_pages(25, 0), 1);
+        assert_eq!(page_offset(3, 0), 0);
+        assert_eq!(page_for_offset(40, 0), 1);
+    }
+
+    #[test]
+    fn one_per_page_gives_a_page_per_item() {
+        assert_eq!(total_pages(25, 1), 25);
+        assert_eq!(page_offset(3, 1), 2);
+        assert_eq!(page_for_offset(2, 1), 3);
+    }
+
+    #[test]
+    fn per_page_larger_than_total_is_a_single_page() {
+        assert_eq!(total_pages(7, 50), 1);
+        assert_eq!(page_offset(1, 50), 0);
+        assert_eq!(page_for_offset(0, 50), 1);
+        assert_eq!(page_offset(0, 50), 0);
+    }
 }
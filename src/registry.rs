@@ -1,69 +1,155 @@
 // crates/adminx/src/registry.rs
 use crate::resource::AdmixResource;
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 use lazy_static::lazy_static;
 use crate::menu::{MenuItem};
-use std::collections::HashMap;
+use crate::utils::rbac::enabled_action_names;
+use serde::Serialize;
 
 lazy_static! {
-    static ref RESOURCE_REGISTRY: RwLock<Vec<Box<dyn AdmixResource>>> = RwLock::new(vec![]);
+    static ref RESOURCE_REGISTRY: RwLock<Vec<Arc<dyn AdmixResource>>> = RwLock::new(vec![]);
+}
+
+/// Serializes tests that mutate the global registry
+#[cfg(test)]
+pub(crate) static REGISTRY_TEST_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Registers a resource for the length of a test and unregisters it on drop, even if the test panics
+#[cfg(test)]
+pub(crate) struct TestRegistration(&'static str);
+
+#[cfg(test)]
+impl TestRegistration {
+    pub(crate) fn new(resource: Box<dyn AdmixResource>) -> Self {
+        let _guard = REGISTRY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let base_path = resource.base_path();
+        register_resource(resource);
+        TestRegistration(base_path)
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestRegistration {
+    fn drop(&mut self) {
+        let _guard = REGISTRY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        unregister_resource(self.0);
+    }
 }
 
 /// Register a resource globally
 pub fn register_resource(resource: Box<dyn AdmixResource>) {
-    RESOURCE_REGISTRY.write().unwrap().push(resource);
+    RESOURCE_REGISTRY.write().unwrap().push(Arc::from(resource));
+}
+
+/// Remove the resource registered at `base_path`. Returns false if none was registered.
+pub fn unregister_resource(base_path: &str) -> bool {
+    let mut registry = RESOURCE_REGISTRY.write().unwrap();
+    let before = registry.len();
+    registry.retain(|r| r.base_path() != base_path);
+    registry.len() != before
+}
+
+/// Replace the resource sharing this resource's `base_path`, or register it if there is none.
+/// Returns true if an existing resource was replaced.
+pub fn replace_resource(resource: Box<dyn AdmixResource>) -> bool {
+    let mut registry = RESOURCE_REGISTRY.write().unwrap();
+    match registry.iter_mut().find(|r| r.base_path() == resource.base_path()) {
+        Some(existing) => {
+            *existing = Arc::from(resource);
+            true
+        }
+        None => {
+            registry.push(Arc::from(resource));
+            false
+        }
+    }
 }
 
-pub fn all_resources() -> Vec<Box<dyn AdmixResource>> {
+/// Look up a registered resource by `base_path`
+pub fn get_resource(base_path: &str) -> Option<Arc<dyn AdmixResource>> {
     RESOURCE_REGISTRY
         .read()
         .unwrap()
         .iter()
-        .map(|r| r.clone_box())
+        .find(|r| r.base_path() == base_path)
+        .cloned()
+}
+
+/// Shared handles to all registered resources (cheap `Arc` clones, not `clone_box`)
+pub fn all_resources() -> Vec<Arc<dyn AdmixResource>> {
+    RESOURCE_REGISTRY
+        .read()
+        .unwrap()
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// What a registered resource supports, as listed by `GET /adminx/api/resources`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ResourceCapabilities {
+    pub name: &'static str,
+    pub base_path: &'static str,
+    pub allowed_roles: Vec<String>,
+    pub supports_file_upload: bool,
+    pub allowed_actions: Vec<&'static str>,
+    pub has_form_structure: bool,
+    pub has_list_structure: bool,
+    pub has_view_structure: bool,
+}
+
+impl ResourceCapabilities {
+    pub fn of(resource: &dyn AdmixResource) -> Self {
+        Self {
+            name: resource.resource_name(),
+            base_path: resource.base_path(),
+            allowed_roles: resource.allowed_roles(),
+            supports_file_upload: resource.supports_file_upload(),
+            allowed_actions: enabled_action_names(resource),
+            has_form_structure: resource.form_structure().is_some(),
+            has_list_structure: resource.list_structure().is_some(),
+            has_view_structure: resource.view_structure().is_some(),
+        }
+    }
+}
+
+/// Capabilities of every registered resource, in registration order
+pub fn resource_capabilities() -> Vec<ResourceCapabilities> {
+    RESOURCE_REGISTRY
+        .read()
+        .unwrap()
+        .iter()
+        .map(|resource| ResourceCapabilities::of(resource.as_ref()))
         .collect()
 }
 
 /// Collect all the menus from registered resources and group them properly
 pub fn get_registered_menus() -> Vec<MenuItem> {
     let resources = RESOURCE_REGISTRY.read().unwrap();
-    let mut grouped_menus: HashMap<String, Vec<MenuItem>> = HashMap::new();
-    let mut ungrouped_menus: Vec<MenuItem> = Vec::new();
-
-    // Group resources by their menu_group
-    for resource in resources.iter() {
-        if let Some(menu_item) = resource.generate_menu() {
-            if let Some(group_name) = resource.menu_group() {
-                // Add to grouped menus
-                grouped_menus
-                    .entry(group_name.to_string())
-                    .or_insert_with(Vec::new)
-                    .push(menu_item);
-            } else {
-                // Add to ungrouped menus (top-level)
-                ungrouped_menus.push(menu_item);
-            }
-        }
-    }
+    let entries = resources
+        .iter()
+        .filter(|resource| resource.show_in_menu() && !resource.api_only())
+        .filter_map(|resource| resource.generate_menu().map(|item| (resource.menu_group(), item)))
+        .collect();
+
+    build_menu_tree(entries)
+}
 
+/// Build the menu tree from `(menu_group, item)` pairs. A group may be a path
+/// like `"Settings/Billing"`, producing one nested parent menu per segment.
+pub fn build_menu_tree(entries: Vec<(Option<&str>, MenuItem)>) -> Vec<MenuItem> {
     let mut final_menus = Vec::new();
 
-    // Create parent menus for groups with multiple resources
-    for (group_name, mut children) in grouped_menus {
-        // Sort children by title
-        children.sort_by(|a, b| a.title.cmp(&b.title));
-        
-        let parent_menu = MenuItem {
-            title: group_name,
-            path: String::new(), // Non-clickable parent
-            icon: Some("folder".to_string()),
-            order: Some(5), // Groups appear before ungrouped items
-            children: Some(children),
-        };
-        final_menus.push(parent_menu);
+    for (group, menu_item) in entries {
+        let segments: Vec<&str> = group
+            .map(|g| g.split('/').map(str::trim).filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        insert_menu_item(&mut final_menus, &segments, menu_item);
     }
 
-    // Add ungrouped menus (resources without menu_group)
-    final_menus.extend(ungrouped_menus);
+    for menu in final_menus.iter_mut() {
+        sort_children(menu);
+    }
 
     // Sort final menus by order and then by title
     final_menus.sort_by(|a, b| {
@@ -78,6 +164,45 @@ pub fn get_registered_menus() -> Vec<MenuItem> {
     final_menus
 }
 
+// Walk (or create) the group parents for `segments` and push `item` into the innermost one
+fn insert_menu_item(menus: &mut Vec<MenuItem>, segments: &[&str], item: MenuItem) {
+    let Some((group_name, rest)) = segments.split_first() else {
+        menus.push(item);
+        return;
+    };
+
+    let index = match menus.iter().position(|m| is_group(m) && m.title == *group_name) {
+        Some(index) => index,
+        None => {
+            menus.push(MenuItem {
+                title: group_name.to_string(),
+                path: String::new(), // Non-clickable parent
+                icon: Some("folder".to_string()),
+                order: Some(5), // Groups appear before ungrouped items
+                children: Some(Vec::new()),
+            });
+            menus.len() - 1
+        }
+    };
+
+    let children = menus[index].children.get_or_insert_with(Vec::new);
+    insert_menu_item(children, rest, item);
+}
+
+fn is_group(menu: &MenuItem) -> bool {
+    menu.path.is_empty() && menu.children.is_some()
+}
+
+// Sort children by title at every level
+fn sort_children(menu: &mut MenuItem) {
+    if let Some(children) = menu.children.as_mut() {
+        children.sort_by(|a, b| a.title.cmp(&b.title));
+        for child in children.iter_mut() {
+            sort_children(child);
+        }
+    }
+}
+
 /// Clear all registered resources (useful for testing)
 pub fn clear_registry() {
     RESOURCE_REGISTRY.write().unwrap().clear();
@@ -86,4 +211,164 @@ pub fn clear_registry() {
 /// Get count of registered resources
 pub fn resource_count() -> usize {
     RESOURCE_REGISTRY.read().unwrap().len()
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestResource;
+    use mongodb::{bson::Document, Collection};
+
+    fn registered_paths() -> Vec<&'static str> {
+        all_resources().iter().map(|r| r.base_path()).collect()
+    }
+
+    fn leaf(title: &str, path: &str) -> MenuItem {
+        MenuItem {
+            title: title.to_string(),
+            path: path.to_string(),
+            icon: None,
+            order: Some(10),
+            children: None,
+        }
+    }
+
+    #[test]
+    fn test_two_segment_group_builds_nested_parents() {
+        let menus = build_menu_tree(vec![
+            (Some("Settings/Billing"), leaf("Plans", "plans")),
+            (Some("Settings/Billing"), leaf("Invoices", "invoices")),
+            (Some("Settings"), leaf("General", "general")),
+            (None, leaf("Users", "users")),
+        ]);
+
+        assert_eq!(menus.len(), 2);
+        let settings = &menus[0];
+        assert_eq!(settings.title, "Settings");
+
+        let settings_children = settings.children.as_ref().unwrap();
+        assert_eq!(settings_children.len(), 2);
+        let billing = &settings_children[0];
+        assert_eq!(billing.title, "Billing");
+        assert_eq!(settings_children[1].title, "General");
+
+        let billing_children = billing.children.as_ref().unwrap();
+        let titles: Vec<&str> = billing_children.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["Invoices", "Plans"]);
+
+        assert_eq!(menus[1].title, "Users");
+    }
+
+    #[test]
+    fn test_single_level_group_is_unchanged() {
+        let menus = build_menu_tree(vec![
+            (Some("Content"), leaf("Posts", "posts")),
+            (Some("Content"), leaf("Pages", "pages")),
+        ]);
+
+        assert_eq!(menus.len(), 1);
+        let children = menus[0].children.as_ref().unwrap();
+        assert_eq!(children.len(), 2);
+        assert!(children.iter().all(|c| c.children.is_none()));
+    }
+
+    #[test]
+    fn test_unregister_resource() {
+        let _guard = REGISTRY_TEST_LOCK.lock().unwrap();
+        let before = resource_count();
+
+        register_resource(Box::new(TestResource::named("Orders", "registry_orders")));
+        register_resource(Box::new(TestResource::named("Refunds", "registry_refunds")));
+        assert_eq!(resource_count(), before + 2);
+
+        assert!(unregister_resource("registry_orders"));
+        assert!(!unregister_resource("registry_orders"));
+        assert_eq!(resource_count(), before + 1);
+
+        let paths = registered_paths();
+        assert!(!paths.contains(&"registry_orders"));
+        assert!(paths.contains(&"registry_refunds"));
+
+        unregister_resource("registry_refunds");
+        assert_eq!(resource_count(), before);
+    }
+
+    #[test]
+    fn test_replace_and_get_resource() {
+        let _guard = REGISTRY_TEST_LOCK.lock().unwrap();
+        let before = resource_count();
+
+        assert!(!replace_resource(Box::new(TestResource::named("Coupons", "registry_coupons"))));
+        assert!(replace_resource(Box::new(TestResource::named("Vouchers", "registry_coupons"))));
+        assert_eq!(resource_count(), before + 1);
+
+        let resource = get_resource("registry_coupons").unwrap();
+        assert_eq!(resource.resource_name(), "Vouchers");
+        assert!(get_resource("registry_missing").is_none());
+
+        unregister_resource("registry_coupons");
+    }
+
+    static CLONE_COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    struct CountingResource;
+
+    impl AdmixResource for CountingResource {
+        fn new() -> Self { CountingResource }
+        fn resource_name(&self) -> &'static str { "Counting" }
+        fn base_path(&self) -> &'static str { "registry_counting" }
+        fn collection_name(&self) -> &'static str { "registry_counting" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> {
+            CLONE_COUNT.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Box::new(CountingResource)
+        }
+    }
+
+    #[test]
+    fn test_reads_do_not_deep_clone() {
+        let _guard = REGISTRY_TEST_LOCK.lock().unwrap();
+        register_resource(Box::new(CountingResource));
+
+        let first = get_resource("registry_counting").unwrap();
+        let _ = all_resources();
+        let _ = get_registered_menus();
+        let second = get_resource("registry_counting").unwrap();
+
+        assert_eq!(CLONE_COUNT.load(std::sync::atomic::Ordering::SeqCst), 0);
+        assert!(Arc::ptr_eq(&first, &second));
+
+        unregister_resource("registry_counting");
+    }
+
+    struct IconResource;
+
+    impl AdmixResource for IconResource {
+        fn new() -> Self { IconResource }
+        fn resource_name(&self) -> &'static str { "Invoices" }
+        fn base_path(&self) -> &'static str { "invoices" }
+        fn collection_name(&self) -> &'static str { "invoices" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(IconResource) }
+
+        fn menu_icon(&self) -> Option<&'static str> {
+            Some("receipt")
+        }
+
+        fn menu_order(&self) -> Option<usize> {
+            Some(1)
+        }
+    }
+
+    #[test]
+    fn test_menu_icon_and_order_overrides() {
+        let item = IconResource.generate_menu().unwrap();
+        assert_eq!(item.icon.as_deref(), Some("receipt"));
+        assert_eq!(item.order, Some(1));
+
+        let default_item = TestResource::named("Orders", "orders").generate_menu().unwrap();
+        assert_eq!(default_item.icon.as_deref(), Some("users"));
+        assert_eq!(default_item.order, Some(10));
+    }
+}
@@ -4,15 +4,38 @@ use async_trait::async_trait;
 use futures::future::BoxFuture;
 use serde_json::{json, Value};
 use crate::menu::{MenuItem, MenuAction};
-use crate::actions::CustomAction;
+use crate::actions::{CustomAction, RowAction};
 use crate::nested::AdmixNestedResource;
 use crate::error::AdminxError;
 use crate::filters::parse_query;
-use crate::pagination::PaginatedResponse;
-use mongodb::{Collection, bson::{doc, oid::ObjectId, Document}};
+use crate::pagination::{page_offset, PaginatedResponse};
+use mongodb::{Collection, bson::{doc, oid::ObjectId, Document}, options::ReadPreference};
 use futures::TryStreamExt;
 use std::collections::HashMap;
-use crate::helpers::resource_helper::convert_form_data_to_json;
+use crate::helpers::resource_helper::{
+    bson_to_json_value, convert_form_data_to_json, created_response, create_fields, permitted_fields, default_list_structure, hide_soft_deleted, query_error, read_count_options, read_find_options,
+    saved_response_body, soft_delete_update, submitted_version, update_result_response, validate_partial_update,
+    versioned_update, VERSION_FIELD,
+};
+use crate::helpers::form_helper::{extract_field_constraints, extract_field_types, validate_field_constraints, FieldConstraints};
+use crate::helpers::rich_text::sanitize_rich_text_fields;
+use crate::helpers::image_helper::ImageVariant;
+use crate::helpers::file_cleanup::{local_file_paths, remove_local_files};
+use crate::helpers::indexes::{duplicate_key_response, is_duplicate_key, IndexSpec};
+use crate::utils::clock::request_clock;
+use crate::helpers::list_query::ListQuery;
+use crate::helpers::redact::redact_sensitive;
+use crate::helpers::downloads::csv_download::CsvOptions;
+use crate::utils::rbac::{read_projection, roles_from_request, writable_keys};
+use crate::utils::structs::Claims;
+use crate::utils::constants::{
+    DEFAULT_JSON_BODY_LIMIT, DEFAULT_MAX_SEARCH_LENGTH, DEFAULT_MIN_SEARCH_LENGTH, DEFAULT_PDF_MAX_ROWS, DEFAULT_SEARCH_FIELDS, DEFAULT_SENSITIVE_FIELDS,
+    MAX_LIMIT, MAX_UPLOAD_FILES,
+};
+use crate::utils::database::resolve_database;
+use crate::utils::timezone::request_timezone;
+use crate::utils::auth::request_actor;
+use actix_web::HttpMessage;
 
 #[async_trait]
 pub trait AdmixResource: Send + Sync {
@@ -23,18 +46,81 @@ pub trait AdmixResource: Send + Sync {
     fn resource_name(&self) -> &'static str;
     fn base_path(&self) -> &'static str;
     fn collection_name(&self) -> &'static str; 
-    fn get_collection(&self) -> Collection<Document>;
     fn clone_box(&self) -> Box<dyn AdmixResource>;
 
+    /// Collection backing this resource. Defaults to `collection_override()`, else
+    /// `collection_name()` in the database selected by `database_name()`.
+    fn get_collection(&self) -> Collection<Document> {
+        self.collection_override().unwrap_or_else(|| {
+            resolve_database(self.database_name())
+                .unwrap_or_else(|e| panic!("No database for resource {}: {}", self.resource_name(), e))
+                .collection::<Document>(self.collection_name())
+        })
+    }
+
+    /// `get_collection()`, or an error when neither an override, the named database
+    /// nor the AdminX database is set up, so the request fails with a 500 instead of
+    /// panicking. The built-in data access goes through this.
+    fn try_get_collection(&self) -> Result<Collection<Document>, AdminxError> {
+        if self.collection_override().is_none() {
+            if let Err(e) = resolve_database(self.database_name()) {
+                tracing::error!("No database configured for resource {}: {}", self.resource_name(), e);
+                return Err(e);
+            }
+        }
+        Ok(self.get_collection())
+    }
+
+    /// A collection this instance is bound to instead of resolving one, e.g. in a
+    /// throwaway test database. Keep it on the resource so each instance (and each
+    /// test) has its own.
+    fn collection_override(&self) -> Option<Collection<Document>> {
+        None
+    }
+
+    /// Name of a database registered with `initiate_named_database`.
+    /// `None` (default) uses the global AdminX database.
+    fn database_name(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Read preference for list and export queries. Return e.g.
+    /// `ReadPreference::SecondaryPreferred` to serve them from replicas.
+    fn read_preference(&self) -> ReadPreference {
+        ReadPreference::Primary
+    }
+
+    /// Server-side time limit (`maxTimeMS`) for list, count and export queries. Queries
+    /// running longer are aborted and answered with a 504. `None` sets no limit.
+    fn query_timeout(&self) -> Option<std::time::Duration> {
+        None
+    }
+
     // ===========================
     // CONFIGURATION (Optional - with defaults)
     // ===========================
 
     /// Optional parent/super menu name to group this resource under.
+    /// Use `/` to nest groups, e.g. `"Settings/Billing"`.
     fn menu_group(&self) -> Option<&'static str> {
         None
     }
 
+    /// Menu icon name (default: "image" for upload resources, otherwise "users")
+    fn menu_icon(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Menu position; lower values appear first (default: 10)
+    fn menu_order(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether this resource appears in the menu. Hidden resources keep their routes.
+    fn show_in_menu(&self) -> bool {
+        true
+    }
+
     /// Menu label for this resource (default: same as resource_name)
     fn menu(&self) -> &'static str {
         self.resource_name()
@@ -48,6 +134,33 @@ pub trait AdmixResource: Send + Sync {
         json!({})
     }
 
+    /// Fields whose values are logged as `***`. A key matches when it contains one of
+    /// these, ignoring case (`api_token` matches `token`).
+    fn sensitive_fields(&self) -> Vec<&'static str> {
+        DEFAULT_SENSITIVE_FIELDS.to_vec()
+    }
+
+    /// `(max, window)`: at most `max` writes (POST/PUT/PATCH/DELETE) per `window` to this
+    /// resource from one admin, and from one IP. Further writes get 429 with `Retry-After`.
+    fn write_rate_limit(&self) -> Option<(u32, std::time::Duration)> {
+        None
+    }
+
+    /// Whether `action` needs the admin to have re-entered their password through
+    /// `POST /api/auth/reauth` within `AdminxConfig::reauth_window`. Every write asks:
+    /// `"create"`, `"update"` (also patch and bulk status; import asks both), `"delete"`
+    /// (also restore), and custom and row actions by name.
+    fn requires_reauth(&self, _action: &str) -> bool {
+        false
+    }
+
+    /// Field-level permissions per role, e.g.
+    /// `{"editor": {"read": ["title", "status"], "write": ["title"]}}`.
+    /// Roles without an entry (or without a "read"/"write" list) are unrestricted.
+    fn field_permissions(&self) -> Value {
+        json!({})
+    }
+
     fn visible_fields_for_role(&self, _roles: &[String]) -> Vec<String> {
         vec![]
     }
@@ -56,10 +169,27 @@ pub trait AdmixResource: Send + Sync {
         vec![]
     }
 
+    /// Run the custom action `action` in a MongoDB transaction (replica set required):
+    /// its handler finds the session with `action_transaction(&req)`, and the writes
+    /// commit on a 2xx response and are aborted otherwise
+    fn action_in_transaction(&self, _action: &str) -> bool {
+        false
+    }
+
     fn custom_actions(&self) -> Vec<CustomAction> {
         vec![]
     }
 
+    /// Buttons shown on every list row, each calling its handler for that document
+    fn row_actions(&self) -> Vec<RowAction> {
+        vec![]
+    }
+
+    /// Values `POST /bulk-status` may set the `status` field to; `None` accepts any
+    fn status_values(&self) -> Option<Vec<&'static str>> {
+        None
+    }
+
     fn allowed_actions(&self) -> Option<Vec<MenuAction>> {
         None // None means all actions are allowed
     }
@@ -69,7 +199,23 @@ pub trait AdmixResource: Send + Sync {
     }
 
     fn readonly_keys(&self) -> Vec<&'static str> {
-        vec!["_id", "created_at", "updated_at"]
+        let mut keys = vec!["_id"];
+        if let Some((created, updated)) = self.timestamps() {
+            keys.extend([created, updated]);
+        }
+        keys
+    }
+
+    /// Serve only the JSON API (`/api`, `/api/{id}` and custom actions under it), with
+    /// no HTML pages and no menu entry, for resources nobody edits through the UI
+    fn api_only(&self) -> bool {
+        false
+    }
+
+    /// Serve `GET /events`, a server-sent events stream of document changes for live
+    /// list updates. Needs MongoDB change streams (a replica set); off by default.
+    fn live_updates(&self) -> bool {
+        false
     }
 
     // ===========================
@@ -86,6 +232,24 @@ pub trait AdmixResource: Send + Sync {
         10 * 1024 * 1024 // 10MB
     }
     
+    /// Files a single multipart request may carry (default: `MAX_UPLOAD_FILES`).
+    /// One more is rejected with 400 before it is buffered.
+    fn max_files(&self) -> usize {
+        MAX_UPLOAD_FILES
+    }
+
+    /// Cap on the whole multipart request (default: `max_file_size()` × `max_files()`).
+    /// Larger uploads are rejected with 413 before they are buffered.
+    fn max_upload_total_bytes(&self) -> usize {
+        self.max_file_size().saturating_mul(self.max_files())
+    }
+    
+    /// Cap on JSON bodies sent to the resource's API routes (create, update, patch and
+    /// custom actions). Larger bodies are rejected with a 400 naming the limit.
+    fn max_json_body_bytes(&self) -> usize {
+        DEFAULT_JSON_BODY_LIMIT
+    }
+    
     /// Allowed file extensions
     fn allowed_file_extensions(&self) -> Vec<&'static str> {
         vec!["jpg", "jpeg", "png", "gif", "webp"]
@@ -95,6 +259,47 @@ pub trait AdmixResource: Send + Sync {
     fn file_upload_config(&self) -> Option<Value> {
         None
     }
+
+    /// Resized copies to generate for uploaded images, e.g. `vec![("thumb", 200, 200)]`.
+    /// Used by `helpers::image_helper::process_image_upload`.
+    fn image_variants(&self) -> Vec<ImageVariant> {
+        vec![]
+    }
+
+    /// Indexes `helpers::indexes::ensure_indexes` creates. Mark unique ones `partial()`
+    /// on soft-deleting resources so deleted records don't block re-creating their values.
+    fn indexes(&self) -> Vec<IndexSpec> {
+        vec![]
+    }
+
+    /// Fields holding the stored location of an uploaded file
+    fn file_fields(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    /// Directory local uploads are stored under. The default `cleanup_files` only
+    /// deletes files inside it; without one, only relative paths below the working
+    /// directory are removed.
+    fn upload_root(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    /// Remove the files `doc` references once it has been hard deleted (soft deletes
+    /// keep them). The default deletes the local files named by `file_fields()` and
+    /// their `<field>_<variant>` image variants that lie under `upload_root()`;
+    /// override it for S3 or other storage.
+    fn cleanup_files(&self, doc: &Document) -> BoxFuture<'static, ()> {
+        let variants = self.image_variants();
+        let fields: Vec<String> = self.file_fields()
+            .into_iter()
+            .flat_map(|field| {
+                std::iter::once(field.to_string())
+                    .chain(variants.iter().map(move |(variant, _, _)| format!("{}_{}", field, variant)))
+            })
+            .collect();
+        let paths = local_file_paths(doc, &fields);
+        Box::pin(remove_local_files(paths, self.upload_root()))
+    }
     
     /* -----------------------------------------------------------
     START - Image specific resource
@@ -111,44 +316,81 @@ pub trait AdmixResource: Send + Sync {
 
 fn create(&self, _req: &HttpRequest, payload: Value) -> BoxFuture<'static, HttpResponse> {
     // Extract everything we need BEFORE the async block
-    let collection = self.get_collection();
-    let permitted = self.permit_keys().into_iter().collect::<std::collections::HashSet<_>>();
+    let collection = self.try_get_collection();
+    let permitted = writable_keys(self, &roles_from_request(_req)).into_iter().collect::<std::collections::HashSet<_>>();
     let resource_name = self.resource_name().to_string();
+    let field_types = self.field_types();
+    let soft_delete_field = self.soft_delete_field();
+    let timestamps = self.timestamps();
+    let full_document = self.return_full_document();
+    let projection = read_projection(self, &roles_from_request(_req));
+    let base_path = self.base_path();
+    let sensitive = self.sensitive_fields();
+    let actor = request_actor(_req);
+    let permit_keys = self.permit_keys();
+    let defaults: serde_json::Map<String, Value> = self.default_values()
+        .into_iter()
+        .filter(|(key, _)| permit_keys.contains(&key.as_str()))
+        .collect();
+    let clock = request_clock(_req);
+    let indexes = self.indexes();
+    let mut clean_map = create_fields(&payload, &permitted, &defaults);
+    let validation = self.validate(&clean_map);
     
     Box::pin(async move {
         // Now _req is not captured in this async block
-        tracing::info!("Default create implementation for resource: {} with payload: {:?}", resource_name, payload);
-        
-        let mut clean_map = serde_json::Map::new();
-        if let Value::Object(map) = payload {
-            for (key, value) in map {
-                if permitted.contains(key.as_str()) {
-                    clean_map.insert(key, value);
-                }
-            }
+        tracing::info!("Default create implementation for resource: {} by {} with payload: {:?}", resource_name, actor, redact_sensitive(&payload, &sensitive));
+        if let Err(e) = validation {
+            tracing::warn!("Rejected create for {}: {}", resource_name, e);
+            return e.error_response();
         }
+        let collection = match collection {
+            Ok(collection) => collection,
+            Err(e) => return e.error_response(),
+        };
+        
+        sanitize_rich_text_fields(&mut clean_map, &field_types);
 
-        let now = mongodb::bson::DateTime::now();
-        clean_map.insert("created_at".to_string(), json!(now));
-        clean_map.insert("updated_at".to_string(), json!(now));
+        if let Some((created, updated)) = timestamps {
+            let now = clock.bson_now();
+            clean_map.insert(created.to_string(), json!(now));
+            clean_map.insert(updated.to_string(), json!(now));
+        }
 
-        if permitted.contains("deleted") && !clean_map.contains_key("deleted") {
-            clean_map.insert("deleted".to_string(), json!(false));
+        if let Some(field) = soft_delete_field {
+            clean_map.entry(field).or_insert(json!(false));
         }
 
-        tracing::debug!("Cleaned payload for {}: {:?}", resource_name, clean_map);
+        tracing::debug!("Cleaned payload for {}: {:?}", resource_name, redact_sensitive(&serde_json::Value::Object(clean_map.clone()), &sensitive));
 
         match mongodb::bson::to_document(&Value::Object(clean_map)) {
             Ok(document) => {
-                match collection.insert_one(document, None).await {
+                match collection.insert_one(&document, None).await {
                     Ok(insert_result) => {
                         tracing::info!("Document created successfully for {}: {:?}", resource_name, insert_result.inserted_id);
-                        HttpResponse::Created().json(json!({
+                        let document = if full_document {
+                            let options = mongodb::options::FindOneOptions::builder()
+                                .projection(projection)
+                                .build();
+                            collection
+                                .find_one(doc! { "_id": insert_result.inserted_id.clone() }, options)
+                                .await
+                                .unwrap_or_else(|e| {
+                                    tracing::warn!("Created document for {} but could not read it back: {}", resource_name, e);
+                                    None
+                                })
+                        } else {
+                            None
+                        };
+                        created_response(base_path, &insert_result.inserted_id, saved_response_body(json!({
                             "success": true,
                             "message": format!("{} created successfully", resource_name),
                             "id": insert_result.inserted_id
-                        }))
+                        }), document))
                     },
+                    Err(e) if is_duplicate_key(&e) => {
+                        duplicate_key_response(&resource_name, base_path, soft_delete_field, &collection, &indexes, &document).await
+                    }
                     Err(e) => {
                         tracing::error!("Error inserting document for {}: {}", resource_name, e);
                         AdminxError::InternalError.error_response()
@@ -165,27 +407,56 @@ fn create(&self, _req: &HttpRequest, payload: Value) -> BoxFuture<'static, HttpR
 
 fn update(&self, _req: &HttpRequest, id: String, payload: Value) -> BoxFuture<'static, HttpResponse> {
     // Extract everything we need BEFORE the async block
-    let collection = self.get_collection();
-    let permitted = self.permit_keys().into_iter().collect::<std::collections::HashSet<_>>();
+    let collection = self.try_get_collection();
+    let permitted = writable_keys(self, &roles_from_request(_req)).into_iter().collect::<std::collections::HashSet<_>>();
     let resource_name = self.resource_name().to_string();
+    let field_types = self.field_types();
+    let versioning = self.uses_versioning();
+    let updated_field = self.timestamps().map(|(_, updated)| updated);
+    let full_document = self.return_full_document();
+    let projection = read_projection(self, &roles_from_request(_req));
+    let sensitive = self.sensitive_fields();
+    let actor = request_actor(_req);
+    let clock = request_clock(_req);
+    let validation = self.validate(&permitted_fields(&payload, &permitted));
     
     Box::pin(async move {
         // Now _req is not captured in this async block
-        tracing::info!("Default update implementation for resource: {} with id: {} and payload: {:?}", 
-                     resource_name, id, payload);
+        tracing::info!("Default update implementation for resource: {} by {} with id: {} and payload: {:?}", 
+                     resource_name, actor, id, redact_sensitive(&payload, &sensitive));
+        if let Err(e) = validation {
+            tracing::warn!("Rejected update for {} {}: {}", resource_name, id, e);
+            return e.error_response();
+        }
+        let collection = match collection {
+            Ok(collection) => collection,
+            Err(e) => return e.error_response(),
+        };
+
+        let version = if versioning {
+            match submitted_version(&payload) {
+                Some(version) => Some(version),
+                None => return AdminxError::BadRequest("Missing or invalid version".into()).error_response(),
+            }
+        } else {
+            None
+        };
         
         match ObjectId::parse_str(&id) {
             Ok(oid) => {
                 let mut clean_map = serde_json::Map::new();
                 if let Value::Object(map) = payload {
                     for (key, value) in map {
-                        if permitted.contains(key.as_str()) {
+                        if permitted.contains(key.as_str()) && !(versioning && key == VERSION_FIELD) {
                             clean_map.insert(key, value);
                         }
                     }
                 }
+                sanitize_rich_text_fields(&mut clean_map, &field_types);
 
-                clean_map.insert("updated_at".to_string(), json!(mongodb::bson::DateTime::now()));
+                if let Some(updated) = updated_field {
+                    clean_map.insert(updated.to_string(), json!(clock.bson_now()));
+                }
 
                 let bson_payload: Document = match mongodb::bson::to_document(&Value::Object(clean_map)) {
                     Ok(doc) => doc,
@@ -195,22 +466,34 @@ fn update(&self, _req: &HttpRequest, id: String, payload: Value) -> BoxFuture<'s
                     }
                 };
 
-                let update_doc = doc! { "$set": bson_payload };
+                let (filter, update_doc) = match version {
+                    Some(version) => versioned_update(oid, bson_payload, version),
+                    None => (doc! { "_id": oid }, doc! { "$set": bson_payload }),
+                };
 
-                match collection.update_one(doc! { "_id": oid }, update_doc, None).await {
+                match collection.update_one(filter, update_doc, None).await {
                     Ok(result) => {
-                        if result.modified_count > 0 {
-                            tracing::info!("Document {} updated successfully for {}", id, resource_name);
-                            HttpResponse::Ok().json(json!({
-                                "success": true,
-                                "message": format!("{} updated successfully", resource_name),
-                                "modified_count": result.modified_count
-                            }))
+                        let document = if full_document && result.matched_count > 0 {
+                            let options = mongodb::options::FindOneOptions::builder().projection(projection).build();
+                            match collection.find_one(doc! { "_id": oid }, options).await {
+                                Ok(document) => document,
+                                Err(e) => {
+                                    tracing::error!("Error reading back document {} for {}: {}", id, resource_name, e);
+                                    return AdminxError::InternalError.error_response();
+                                }
+                            }
                         } else {
-                            tracing::warn!("No document found to update with id: {} for {}", id, resource_name);
-                            AdminxError::NotFound.error_response()
-                        }
-                    },
+                            None
+                        };
+                        update_result_response(
+                            &resource_name,
+                            &id,
+                            version.is_some(),
+                            result.matched_count,
+                            result.modified_count,
+                            document,
+                        )
+                    }
                     Err(e) => {
                         tracing::error!("Error updating document {} for {}: {}", id, resource_name, e);
                         AdminxError::InternalError.error_response()
@@ -233,6 +516,7 @@ fn create_with_files(
     files: std::collections::HashMap<String, (String, Vec<u8>)>,
 ) -> futures::future::BoxFuture<'static, actix_web::HttpResponse> {
     let resource = self.clone_box();
+    let claims = _req.extensions().get::<Claims>().cloned();
 
     Box::pin(async move {
         // 1) पहले फाइल अपलोड प्रोसेस कर लें
@@ -253,11 +537,14 @@ fn create_with_files(
         }
 
         // 2) form_data → JSON
-        let json_payload = convert_form_data_to_json(form_data);
+        let json_payload = convert_form_data_to_json(form_data, &resource.field_types(), &resource.readonly_keys());
 
         // 3) ⬇️ HttpRequest को inner scope में बनाइए; future निकालिए; फिर outer में await कीजिए
         let fut = {
             let test_req = actix_web::test::TestRequest::default().to_http_request();
+            if let Some(claims) = claims {
+                test_req.extensions_mut().insert(claims);
+            }
             resource.create(&test_req, json_payload)
         };
 
@@ -274,6 +561,7 @@ fn update_with_files(
     files: std::collections::HashMap<String, (String, Vec<u8>)>,
 ) -> futures::future::BoxFuture<'static, actix_web::HttpResponse> {
     let resource = self.clone_box();
+    let claims = _req.extensions().get::<Claims>().cloned();
 
     Box::pin(async move {
         for (field_name, (filename, file_data)) in files {
@@ -294,10 +582,13 @@ fn update_with_files(
             }
         }
 
-        let json_payload = convert_form_data_to_json(form_data);
+        let json_payload = convert_form_data_to_json(form_data, &resource.field_types(), &resource.readonly_keys());
 
         let fut = {
             let test_req = actix_web::test::TestRequest::default().to_http_request();
+            if let Some(claims) = claims {
+                test_req.extensions_mut().insert(claims);
+            }
             resource.update(&test_req, id, json_payload)
         };
 
@@ -454,61 +745,291 @@ fn update_with_files(
         None // Override to customize list view
     }
 
+    /// Text of an unfiltered list with no rows, in place of "No <resource> found."
+    fn empty_state_message(&self) -> Option<String> {
+        None
+    }
+
     fn view_structure(&self) -> Option<Value> {
         None // Override to customize detail view
     }
 
+    /// Related data for the view page of document `id` (e.g. an order's line items),
+    /// available to the template as `extras`. Object keys holding arrays of objects are
+    /// shown as tables under the record. `Null` adds nothing.
+    async fn view_extras(&self, _id: &str, _claims: &Claims) -> Value {
+        Value::Null
+    }
+
     fn filters(&self) -> Option<Value> {
         None // Override to add search/filter functionality
     }
 
+    /// Load related data for a whole list page in one go (e.g. a single `$in` query)
+    /// instead of per row. Called once per page with every document on it; object
+    /// values are merged into the row of the document with the matching `_id`.
+    async fn prefetch(&self, _docs: &[Document]) -> HashMap<ObjectId, Value> {
+        HashMap::new()
+    }
+
+    /// Value of a `list_structure()` column marked `"computed": true`, which is not a
+    /// stored field (e.g. a full name from `first_name` and `last_name`). `None` renders
+    /// a blank cell.
+    fn compute_column(&self, _field: &str, _doc: &Document) -> Option<Value> {
+        None
+    }
+
+    fn field_types(&self) -> HashMap<String, String> {
+        // Field name -> type ("number", "boolean", ...) used to coerce list filters
+        self.form_structure()
+            .map(|form| extract_field_types(&form))
+            .unwrap_or_default()
+    }
+
+    /// `min`, `max`, `minlength`, `maxlength` and `pattern` per field, read from
+    /// `form_structure()`. The forms render them as input attributes.
+    fn field_constraints(&self) -> HashMap<String, FieldConstraints> {
+        self.form_structure()
+            .map(|form| extract_field_constraints(&form))
+            .unwrap_or_default()
+    }
+
+    /// Check the writable fields of a create, update or inline edit before they are
+    /// saved. Defaults to `field_constraints()`; return `AdminxError::Validation` to
+    /// answer 422 with per-field messages.
+    fn validate(&self, fields: &serde_json::Map<String, Value>) -> Result<(), AdminxError> {
+        validate_field_constraints(fields, &self.field_constraints())
+    }
+
+    /// Field and direction (`1` ascending, `-1` descending) lists and exports are
+    /// sorted by when no `?sort=` is given. `None` sorts newest first by `created_at`.
+    fn default_sort(&self) -> Option<(String, i32)> {
+        None
+    }
+
+    /// Fields `?sort=` may order by; anything else is ignored in favour of the default
+    /// sort. Defaults to the `list_structure()` columns marked `"sortable": true`.
+    fn sortable_fields(&self) -> Vec<String> {
+        let structure = self.list_structure().unwrap_or_else(|| default_list_structure(self));
+        structure
+            .get("columns")
+            .and_then(|columns| columns.as_array())
+            .map(|columns| {
+                columns
+                    .iter()
+                    .filter(|column| column.get("sortable").and_then(|s| s.as_bool()).unwrap_or(false))
+                    .filter_map(|column| column.get("field").and_then(|f| f.as_str()).map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn default_per_page(&self) -> u64 {
+        10 // Items per page on the list view when the URL doesn't set per_page
+    }
+
+    fn max_per_page(&self) -> u64 {
+        MAX_LIMIT // Upper bound for per_page, including URL overrides
+    }
+
+    fn max_pdf_rows(&self) -> u64 {
+        DEFAULT_PDF_MAX_ROWS // PDF exports are built in memory; rows past this are dropped
+    }
+
+    /// Delimiter and line ending of CSV exports; `?delimiter=` and `?line_ending=`
+    /// override them per download
+    fn csv_options(&self) -> CsvOptions {
+        CsvOptions::default()
+    }
+
+    /// Fields the global `search` parameter matches. Defaults to the usual text
+    /// fields (`name`, `email`, `title`, ...) that are in `permit_keys()`.
+    fn searchable_fields(&self) -> Vec<&'static str> {
+        let permitted = self.permit_keys();
+        DEFAULT_SEARCH_FIELDS.iter().copied().filter(|field| permitted.contains(field)).collect()
+    }
+
+    fn max_search_length(&self) -> usize {
+        DEFAULT_MAX_SEARCH_LENGTH // Longer search/text filter terms are rejected with 400
+    }
+
+    fn min_search_length(&self) -> usize {
+        DEFAULT_MIN_SEARCH_LENGTH // Shorter search/text filter terms are ignored
+    }
+
+    /// Opt into optimistic concurrency: updates must send the `version` they were
+    /// based on and fail with a conflict if the document changed in the meantime
+    fn uses_versioning(&self) -> bool {
+        false
+    }
+
+    /// Boolean field marking a document deleted. When `Some`, delete sets it, lists hide
+    /// documents where it is `true`, and restore clears it; `None` means delete removes
+    /// the document. Defaults to `deleted` when that field is in `permit_keys()`.
+    fn soft_delete_field(&self) -> Option<&'static str> {
+        self.permit_keys().into_iter().find(|key| *key == "deleted")
+    }
+
+    /// `(created, updated)` timestamp field names. Create sets both, update, patch and
+    /// soft delete bump the second, and lists, sorting and exports read them under these
+    /// names. `None` leaves timestamps entirely to the application.
+    fn timestamps(&self) -> Option<(&'static str, &'static str)> {
+        Some(("created_at", "updated_at"))
+    }
+
+    /// Have `create` and `update` respond with the stored document under `data`,
+    /// server-set fields such as `created_at` included, so API clients need no follow-up GET
+    fn return_full_document(&self) -> bool {
+        true
+    }
+
+    /// Values the default `create` stores for fields the submission leaves out, e.g.
+    /// `status: "pending"`. They still have to be `permit_keys()`, but are stored even
+    /// when the creating role may not write the field.
+    fn default_values(&self) -> serde_json::Map<String, Value> {
+        serde_json::Map::new()
+    }
+
     // ===========================
     // ENHANCED CRUD IMPLEMENTATIONS
     // ===========================
     
     fn list(&self, _req: &HttpRequest, query: String) -> BoxFuture<'static, HttpResponse> {
-        let collection = self.get_collection();
+        let collection = self.try_get_collection();
         let resource_name = self.resource_name().to_string();
+        let projection = read_projection(self, &roles_from_request(_req));
+        let count_options = read_count_options(self);
+        let mut find_options = read_find_options(self);
+        let soft_delete_field = self.soft_delete_field();
+        let list_query = ListQuery::parse(&query);
+        let sort = list_query.sort_document(self);
+        let page = list_query.page;
+        let per_page = list_query.per_page_for(self);
         
         Box::pin(async move {
+            let collection = match collection {
+                Ok(collection) => collection,
+                Err(e) => return e.error_response(),
+            };
             tracing::info!("Default list implementation for resource: {}", resource_name);
             
-            let opts = parse_query(&query);
+            let mut opts = parse_query(&query);
+            hide_soft_deleted(&mut opts.filter, soft_delete_field);
             
-            let total = match collection.count_documents(opts.filter.clone(), None).await {
+            let total = match collection.count_documents(opts.filter.clone(), count_options).await {
                 Ok(count) => count,
-                Err(e) => {
-                    tracing::error!("Error counting documents for {}: {}", resource_name, e);
-                    return AdminxError::InternalError.error_response();
-                }
+                Err(e) => return query_error(&resource_name, &e).error_response(),
             };
             
-            let mut find_options = mongodb::options::FindOptions::default();
-            find_options.skip = Some(opts.skip);
-            find_options.limit = Some(opts.limit as i64);
-            if let Some(sort) = opts.sort {
-                find_options.sort = Some(sort);
-            }
+            find_options.skip = Some(page_offset(page, per_page));
+            find_options.limit = Some(per_page as i64);
+            find_options.projection = projection;
+            find_options.sort = Some(sort);
             
             match collection.find(opts.filter, find_options).await {
                 Ok(mut cursor) => {
                     let mut documents = Vec::new();
-                    while let Some(doc) = cursor.try_next().await.unwrap_or(None) {
-                        documents.push(doc);
+                    loop {
+                        match cursor.try_next().await {
+                            Ok(Some(doc)) => documents.push(doc),
+                            Ok(None) => break,
+                            Err(e) => return query_error(&resource_name, &e).error_response(),
+                        }
                     }
 
                     tracing::info!("Found {} documents for {} out of {} total", 
                                  documents.len(), resource_name, total);
                     
-                    HttpResponse::Ok().json(PaginatedResponse {
-                        data: documents,
+                    HttpResponse::Ok().json(PaginatedResponse::new(
+                        documents,
                         total,
-                        page: (opts.skip / opts.limit) + 1,
-                        per_page: opts.limit,
-                    })
+                        page,
+                        per_page,
+                    ))
+                }
+                Err(e) => query_error(&resource_name, &e).error_response(),
+            }
+        })
+    }
+
+    /// Partially update a document (used by inline list editing). Only the given fields are
+    /// set; read-only or non-writable fields reject the request. Responds with the updated values.
+    fn patch(&self, _req: &HttpRequest, id: String, payload: Value) -> BoxFuture<'static, HttpResponse> {
+        let collection = self.try_get_collection();
+        let resource_name = self.resource_name().to_string();
+        let validated = validate_partial_update(self, &roles_from_request(_req), payload)
+            .and_then(|fields| self.validate(&fields).map(|_| fields));
+        let versioning = self.uses_versioning();
+        let field_types = self.field_types();
+        let updated_field = self.timestamps().map(|(_, updated)| updated);
+        let tz = request_timezone(_req);
+        let clock = request_clock(_req);
+
+        Box::pin(async move {
+            let fields = match validated {
+                Ok(fields) => fields,
+                Err(e) => {
+                    tracing::warn!("Rejected partial update for {} {}: {}", resource_name, id, e);
+                    return e.error_response();
+                }
+            };
+            let collection = match collection {
+                Ok(collection) => collection,
+                Err(e) => return e.error_response(),
+            };
+
+            let oid = match ObjectId::parse_str(&id) {
+                Ok(oid) => oid,
+                Err(e) => {
+                    tracing::error!("Invalid ObjectId {} for {}: {}", id, resource_name, e);
+                    return AdminxError::BadRequest("Invalid ID format".into()).error_response();
+                }
+            };
+
+            let field_names: Vec<String> = fields.keys().cloned().collect();
+            let mut set_map = fields;
+            sanitize_rich_text_fields(&mut set_map, &field_types);
+            if let Some(updated) = updated_field {
+                set_map.insert(updated.to_string(), json!(clock.bson_now()));
+            }
+
+            let set_doc = match mongodb::bson::to_document(&Value::Object(set_map)) {
+                Ok(doc) => doc,
+                Err(e) => {
+                    tracing::error!("Error converting payload to BSON for {}: {}", resource_name, e);
+                    return AdminxError::BadRequest("Invalid payload format".into()).error_response();
+                }
+            };
+
+            let options = mongodb::options::FindOneAndUpdateOptions::builder()
+                .return_document(mongodb::options::ReturnDocument::After)
+                .build();
+
+            let mut update_doc = doc! { "$set": set_doc };
+            if versioning {
+                // Inline edits don't carry a version but still invalidate open edit forms
+                update_doc.insert("$inc", doc! { VERSION_FIELD: 1 });
+            }
+
+            match collection.find_one_and_update(doc! { "_id": oid }, update_doc, options).await {
+                Ok(Some(updated)) => {
+                    let values: serde_json::Map<String, Value> = field_names
+                        .into_iter()
+                        .map(|name| {
+                            let value = updated.get(&name).map(|value| bson_to_json_value(value, tz)).unwrap_or(Value::Null);
+                            (name, value)
+                        })
+                        .collect();
+                    tracing::info!("Document {} partially updated for {}", id, resource_name);
+                    HttpResponse::Ok().json(json!({
+                        "success": true,
+                        "id": id,
+                        "fields": values
+                    }))
                 }
+                Ok(None) => AdminxError::NotFound.error_response(),
                 Err(e) => {
-                    tracing::error!("Error executing find query for {}: {}", resource_name, e);
+                    tracing::error!("Error patching document {} for {}: {}", id, resource_name, e);
                     AdminxError::InternalError.error_response()
                 }
             }
@@ -516,15 +1037,23 @@ fn update_with_files(
     }
 
     fn get(&self, _req: &HttpRequest, id: String) -> BoxFuture<'static, HttpResponse> {
-        let collection = self.get_collection();
+        let collection = self.try_get_collection();
         let resource_name = self.resource_name().to_string();
+        let projection = read_projection(self, &roles_from_request(_req));
         
         Box::pin(async move {
+            let collection = match collection {
+                Ok(collection) => collection,
+                Err(e) => return e.error_response(),
+            };
             tracing::info!("Default get implementation for resource: {} with id: {}", resource_name, id);
             
             match ObjectId::parse_str(&id) {
                 Ok(oid) => {
-                    match collection.find_one(doc! { "_id": oid }, None).await {
+                    let find_options = mongodb::options::FindOneOptions::builder()
+                        .projection(projection)
+                        .build();
+                    match collection.find_one(doc! { "_id": oid }, find_options).await {
                         Ok(Some(document)) => {
                             tracing::info!("Found document with id: {} for resource: {}", id, resource_name);
                             HttpResponse::Ok().json(document)
@@ -664,23 +1193,26 @@ fn update_with_files(
 
     /// Enhanced delete with soft delete support
     fn delete(&self, _req: &HttpRequest, id: String) -> BoxFuture<'static, HttpResponse> {
-        let collection = self.get_collection();
+        let collection = self.try_get_collection();
         let resource_name = self.resource_name().to_string();
-        let permitted = self.permit_keys().into_iter().collect::<std::collections::HashSet<_>>();
+        let soft_delete_field = self.soft_delete_field();
+        let updated_field = self.timestamps().map(|(_, updated)| updated);
+        let actor = request_actor(_req);
+        let clock = request_clock(_req);
+        let resource = self.clone_box();
         
         Box::pin(async move {
-            tracing::info!("Default delete implementation for resource: {} with id: {}", resource_name, id);
+            let collection = match collection {
+                Ok(collection) => collection,
+                Err(e) => return e.error_response(),
+            };
+            tracing::info!("Default delete implementation for resource: {} by {} with id: {}", resource_name, actor, id);
             
             match ObjectId::parse_str(&id) {
                 Ok(oid) => {
-                    // If resource supports soft delete (has "deleted" in permitted keys), use soft delete
-                    if permitted.contains("deleted") {
-                        let update_doc = doc! { 
-                            "$set": {
-                                "deleted": true,
-                                "updated_at": mongodb::bson::DateTime::now()
-                            }
-                        };
+                    // Resources with a soft-delete field keep the document and flag it
+                    if let Some(field) = soft_delete_field {
+                        let update_doc = soft_delete_update(field, true, updated_field, clock.bson_now());
                         
                         match collection.update_one(doc! { "_id": oid }, update_doc, None).await {
                             Ok(result) => {
@@ -703,21 +1235,21 @@ fn update_with_files(
                             }
                         }
                     } else {
-                        // Hard delete
-                        match collection.delete_one(doc! { "_id": oid }, None).await {
-                            Ok(result) => {
-                                if result.deleted_count > 0 {
-                                    tracing::info!("Document {} hard deleted successfully for {}", id, resource_name);
-                                    HttpResponse::Ok().json(json!({
-                                        "success": true,
-                                        "message": format!("{} deleted successfully", resource_name),
-                                        "soft_delete": false,
-                                        "deleted_count": result.deleted_count
-                                    }))
-                                } else {
-                                    tracing::warn!("No document found to hard delete with id: {} for {}", id, resource_name);
-                                    AdminxError::NotFound.error_response()
-                                }
+                        // Hard delete, then remove the files the document referenced
+                        match collection.find_one_and_delete(doc! { "_id": oid }, None).await {
+                            Ok(Some(deleted)) => {
+                                tracing::info!("Document {} hard deleted successfully for {}", id, resource_name);
+                                resource.cleanup_files(&deleted).await;
+                                HttpResponse::Ok().json(json!({
+                                    "success": true,
+                                    "message": format!("{} deleted successfully", resource_name),
+                                    "soft_delete": false,
+                                    "deleted_count": 1
+                                }))
+                            },
+                            Ok(None) => {
+                                tracing::warn!("No document found to hard delete with id: {} for {}", id, resource_name);
+                                AdminxError::NotFound.error_response()
                             },
                             Err(e) => {
                                 tracing::error!("Error hard deleting document {} for {}: {}", id, resource_name, e);
@@ -734,6 +1266,58 @@ fn update_with_files(
         })
     }
 
+
+    /// Undo a soft delete by clearing `soft_delete_field()`. Resources without one reply 400.
+    fn restore(&self, _req: &HttpRequest, id: String) -> BoxFuture<'static, HttpResponse> {
+        let resource_name = self.resource_name().to_string();
+        let Some(field) = self.soft_delete_field() else {
+            return Box::pin(async move {
+                tracing::warn!("Restore requested for {} which does not soft delete", resource_name);
+                AdminxError::BadRequest(format!("{} does not support restore", resource_name)).error_response()
+            });
+        };
+        let oid = match ObjectId::parse_str(&id) {
+            Ok(oid) => oid,
+            Err(e) => {
+                tracing::error!("Invalid ObjectId {} for {}: {}", id, resource_name, e);
+                return Box::pin(async { AdminxError::BadRequest("Invalid ID format".into()).error_response() });
+            }
+        };
+        let collection = self.try_get_collection();
+        let updated_field = self.timestamps().map(|(_, updated)| updated);
+        let actor = request_actor(_req);
+        let clock = request_clock(_req);
+
+        Box::pin(async move {
+            let collection = match collection {
+                Ok(collection) => collection,
+                Err(e) => return e.error_response(),
+            };
+            match collection.update_one(doc! { "_id": oid, field: true }, soft_delete_update(field, false, updated_field, clock.bson_now()), None).await {
+                Ok(result) if result.modified_count > 0 => {
+                    tracing::info!("Document {} restored for {} by {}", id, resource_name, actor);
+                    HttpResponse::Ok().json(json!({
+                        "success": true,
+                        "message": format!("{} restored successfully", resource_name),
+                        "modified_count": result.modified_count
+                    }))
+                }
+                Ok(_) => {
+                    tracing::warn!("No deleted document found to restore with id: {} for {}", id, resource_name);
+                    AdminxError::NotFound.error_response()
+                }
+                // A live record took its unique values while it was deleted
+                Err(e) if is_duplicate_key(&e) => {
+                    tracing::warn!("Restoring {} for {} clashes with a live record: {}", id, resource_name, e);
+                    AdminxError::Conflict(format!("Another {} record now uses this record's unique values", resource_name)).error_response()
+                }
+                Err(e) => {
+                    tracing::error!("Error restoring document {} for {}: {}", id, resource_name, e);
+                    AdminxError::InternalError.error_response()
+                }
+            }
+        })
+    }
     
     // ===========================
     // MENU GENERATION
@@ -742,8 +1326,12 @@ fn update_with_files(
         Some(MenuItem {
             title: self.menu().to_string(),
             path: self.base_path().to_string(),
-            icon: Some(if self.supports_file_upload() { "image".to_string() } else { "users".to_string() }),
-            order: Some(10),
+            icon: Some(match self.menu_icon() {
+                Some(icon) => icon.to_string(),
+                None if self.supports_file_upload() => "image".to_string(),
+                None => "users".to_string(),
+            }),
+            order: Some(self.menu_order().unwrap_or(10)),
             children: None,
         })
     }
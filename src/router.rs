@@ -1,5 +1,11 @@
 // crates/adminx/src/router.rs - Complete Fixed Version
-use actix_web::{web, Scope};
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceFactory, ServiceRequest, ServiceResponse},
+    http::StatusCode,
+    middleware::{from_fn, Compress, Condition, ErrorHandlers},
+    web, Scope,
+};
 use tracing::{info, warn};
 use crate::registry::all_resources;
 use crate::controllers::{
@@ -13,9 +19,25 @@ use crate::controllers::auth_controller::{
     logout_action, 
     dashboard_view,
     profile_view,
+    change_password_form,
+    change_password_action,
+    profile_edit_form,
+    profile_edit_action,
     api_login_action,
+    api_refresh_token,
+    api_reauth_action,
+    verify_email_action,
+    reset_password_request_form,
+    reset_password_request_action,
+    reset_password_form,
+    reset_password_action,
     check_auth_status
 };
+use crate::controllers::dashboard_controller::resources_api;
+use crate::helpers::error_pages::{not_found_page, server_error_page};
+use crate::configs::initializer::AdminxConfig;
+use crate::utils::mount::{configured_base_mount, scope_base_mount};
+use crate::utils::database::get_adminx_config;
 use crate::utils::{
     structs::{
         RoleGuard
@@ -23,10 +45,45 @@ use crate::utils::{
 };
 
 
-pub fn register_all_admix_routes() -> Scope {
-    info!("🔧 Starting AdminX route registration...");
+/// All AdminX routes under the globally configured mount (`/adminx` by default)
+pub fn register_all_admix_routes() -> Scope<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    let compress = get_adminx_config().is_none_or(|config| config.compress_responses);
+    mounted_admix_routes(configured_base_mount(), compress)
+}
+
+/// All AdminX routes under `config.base_mount`
+pub fn register_all_admix_routes_with(config: &AdminxConfig) -> Scope<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    mounted_admix_routes(config.base_mount.clone(), config.compress_responses)
+}
+
+fn mounted_admix_routes(mount: String, compress: bool) -> Scope<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    info!("🔧 Starting AdminX route registration under {}...", mount);
     
-    let mut scope = web::scope("/adminx")
+    let mut scope = web::scope(&mount)
         // ===========================
         // AUTHENTICATION ROUTES
         // ===========================
@@ -34,6 +91,10 @@ pub fn register_all_admix_routes() -> Scope {
         .route("/login", web::post().to(login_action))
         .route("/logout", web::get().to(logout_action))     // FIXED: Added GET support
         .route("/logout", web::post().to(logout_action))    // Keep POST support too
+        .route("/reset-password", web::get().to(reset_password_request_form))
+        .route("/reset-password", web::post().to(reset_password_request_action))
+        .route("/reset-password/{token}", web::get().to(reset_password_form))
+        .route("/reset-password/{token}", web::post().to(reset_password_action))
         
         // ===========================
         // DASHBOARD ROUTES
@@ -46,12 +107,24 @@ pub fn register_all_admix_routes() -> Scope {
         // PROFILE ROUTES
         // ===========================
         .route("/profile", web::get().to(profile_view))
+        .route("/profile/password", web::get().to(change_password_form))
+        .route("/profile/password", web::post().to(change_password_action))
+        .route("/profile/edit", web::get().to(profile_edit_form))
+        .route("/profile/edit", web::post().to(profile_edit_action))
         
         // ===========================
         // API ROUTES
         // ===========================
         .route("/api/login", web::post().to(api_login_action))
-        .route("/api/auth/status", web::get().to(check_auth_status));
+        .route("/api/auth/status", web::get().to(check_auth_status))
+        .route("/api/auth/refresh", web::post().to(api_refresh_token))
+        .route("/api/auth/reauth", web::post().to(api_reauth_action))
+        .route("/api/verify", web::post().to(verify_email_action))
+        .service(
+            web::resource("/api/resources")
+                .wrap(RoleGuard::admin_only())
+                .route(web::get().to(resources_api))
+        );
 
     // Debug: Check if we have any resources
     let resources = all_resources();
@@ -59,7 +132,6 @@ pub fn register_all_admix_routes() -> Scope {
     
     if resources.is_empty() {
         warn!("⚠️  No resources found! Make sure you've called register_resource() before starting the server.");
-        return scope;
     }
 
     // Register resource routes with role guards
@@ -73,33 +145,42 @@ pub fn register_all_admix_routes() -> Scope {
         
         // Create the resource scope with the base path
         let resource_scope = web::scope(&format!("/{}", base_path))
-            .service(register_admix_resource_routes(resource))
+            .service(register_admix_resource_routes(resource.clone_box()))
             .wrap(RoleGuard { allowed_roles });
         
         scope = scope.service(resource_scope);
         
         info!("✅ Successfully registered resource: '{}'", resource_name);
         info!("🌐 Available URLs:");
-        info!("   - GET  /adminx/{}/list", base_path);
-        info!("   - GET  /adminx/{}/new", base_path);
-        info!("   - GET  /adminx/{}/view/{{id}}", base_path);
-        info!("   - GET  /adminx/{}/edit/{{id}}", base_path);
-        info!("   - GET  /adminx/{} (API list)", base_path);
-        info!("   - POST /adminx/{} (API create)", base_path);
-        info!("   - GET  /adminx/{}/{{id}} (API get)", base_path);
-        info!("   - PUT  /adminx/{}/{{id}} (API update)", base_path);
-        info!("   - DELETE /adminx/{}/{{id}} (API delete)", base_path);
+        if !resource.api_only() {
+            info!("   - GET  {}/{}/list", mount, base_path);
+            info!("   - GET  {}/{}/new", mount, base_path);
+            info!("   - GET  {}/{}/view/{{id}}", mount, base_path);
+            info!("   - GET  {}/{}/edit/{{id}}", mount, base_path);
+        }
+        info!("   - GET  {}/{} (API list)", mount, base_path);
+        info!("   - POST {}/{} (API create)", mount, base_path);
+        info!("   - GET  {}/{}/{{id}} (API get)", mount, base_path);
+        info!("   - PUT  {}/{}/{{id}} (API update)", mount, base_path);
+        info!("   - DELETE {}/{}/{{id}} (API delete)", mount, base_path);
     }
     
     info!("🎉 AdminX route registration completed!");
+
+    // Styled pages for unmatched paths and internal errors; links and redirects use the mount
     scope
+        .default_service(web::to(not_found_page))
+        .wrap(ErrorHandlers::new().handler(StatusCode::INTERNAL_SERVER_ERROR, server_error_page))
+        // Responses that set their own Content-Encoding (gzipped exports, event streams) are left alone
+        .wrap(Condition::new(compress, Compress::default()))
+        .wrap(from_fn(move |req, next| scope_base_mount(mount.clone(), req, next)))
 }
 
 // Alternative version without middleware (for testing)
 pub fn register_all_admix_routes_debug() -> Scope {
     info!("🔧 Starting AdminX route registration (DEBUG MODE - NO AUTH)...");
     
-    let mut scope = web::scope("/adminx")
+    let mut scope = web::scope(&configured_base_mount())
         // ===========================
         // AUTHENTICATION ROUTES (DEBUG)
         // ===========================
@@ -107,6 +188,10 @@ pub fn register_all_admix_routes_debug() -> Scope {
         .route("/login", web::post().to(login_action))
         .route("/logout", web::get().to(logout_action))     // FIXED: Added GET support
         .route("/logout", web::post().to(logout_action))    // Keep POST support too
+        .route("/reset-password", web::get().to(reset_password_request_form))
+        .route("/reset-password", web::post().to(reset_password_request_action))
+        .route("/reset-password/{token}", web::get().to(reset_password_form))
+        .route("/reset-password/{token}", web::post().to(reset_password_action))
         
         // ===========================
         // DASHBOARD ROUTES (DEBUG)
@@ -119,12 +204,19 @@ pub fn register_all_admix_routes_debug() -> Scope {
         // PROFILE ROUTES (DEBUG)
         // ===========================
         .route("/profile", web::get().to(profile_view))
+        .route("/profile/password", web::get().to(change_password_form))
+        .route("/profile/password", web::post().to(change_password_action))
+        .route("/profile/edit", web::get().to(profile_edit_form))
+        .route("/profile/edit", web::post().to(profile_edit_action))
         
         // ===========================
         // API ROUTES (DEBUG)
         // ===========================
         .route("/api/login", web::post().to(api_login_action))
-        .route("/api/auth/status", web::get().to(check_auth_status));
+        .route("/api/auth/status", web::get().to(check_auth_status))
+        .route("/api/auth/refresh", web::post().to(api_refresh_token))
+        .route("/api/auth/reauth", web::post().to(api_reauth_action))
+        .route("/api/verify", web::post().to(verify_email_action));
 
     // Debug: Check if we have any resources
     let resources = all_resources();
@@ -144,7 +236,7 @@ pub fn register_all_admix_routes_debug() -> Scope {
         
         // Create the resource scope with the base path - NO MIDDLEWARE
         let resource_scope = web::scope(&format!("/{}", base_path))
-            .service(register_admix_resource_routes(resource));
+            .service(register_admix_resource_routes(resource.clone_box()));
         
         scope = scope.service(resource_scope);
         
@@ -157,24 +249,35 @@ pub fn register_all_admix_routes_debug() -> Scope {
 
 // Helper function to register auth routes only (for separate registration)
 pub fn register_auth_routes_only() -> Scope {
-    web::scope("/adminx")
+    web::scope(&configured_base_mount())
         .route("/login", web::get().to(login_form))
         .route("/login", web::post().to(login_action))
         .route("/logout", web::get().to(logout_action))
         .route("/logout", web::post().to(logout_action))
+        .route("/reset-password", web::get().to(reset_password_request_form))
+        .route("/reset-password", web::post().to(reset_password_request_action))
+        .route("/reset-password/{token}", web::get().to(reset_password_form))
+        .route("/reset-password/{token}", web::post().to(reset_password_action))
         .route("", web::get().to(dashboard_view))
         .route("/", web::get().to(dashboard_view))
         .route("/dashboard", web::get().to(dashboard_view))
         .route("/profile", web::get().to(profile_view))
+        .route("/profile/password", web::get().to(change_password_form))
+        .route("/profile/password", web::post().to(change_password_action))
+        .route("/profile/edit", web::get().to(profile_edit_form))
+        .route("/profile/edit", web::post().to(profile_edit_action))
         .route("/api/login", web::post().to(api_login_action))
         .route("/api/auth/status", web::get().to(check_auth_status))
+        .route("/api/auth/refresh", web::post().to(api_refresh_token))
+        .route("/api/auth/reauth", web::post().to(api_reauth_action))
+        .route("/api/verify", web::post().to(verify_email_action))
 }
 
 // Helper function to register only resource routes (for separate registration)
 pub fn register_resource_routes_only() -> Scope {
     info!("🔧 Starting AdminX resource-only route registration...");
     
-    let mut scope = web::scope("/adminx");
+    let mut scope = web::scope(&configured_base_mount());
     let resources = all_resources();
     
     info!("📋 Found {} resources to register", resources.len());
@@ -187,7 +290,7 @@ pub fn register_resource_routes_only() -> Scope {
         info!("📝 Registering resource: '{}' at path: '{}'", resource_name, base_path);
         
         let resource_scope = web::scope(&format!("/{}", base_path))
-            .service(register_admix_resource_routes(resource))
+            .service(register_admix_resource_routes(resource.clone_box()))
             .wrap(RoleGuard { allowed_roles });
         
         scope = scope.service(resource_scope);
@@ -204,7 +307,7 @@ pub fn register_all_admix_routes_enhanced() -> Scope {
     info!("🔧 Starting Enhanced AdminX route registration...");
     
     // First register auth routes
-    let mut scope = web::scope("/adminx")
+    let mut scope = web::scope(&configured_base_mount())
         // Auth routes with better organization
         .service(
             web::scope("/auth")
@@ -219,16 +322,27 @@ pub fn register_all_admix_routes_enhanced() -> Scope {
         .route("/", web::get().to(dashboard_view))
         .route("/dashboard", web::get().to(dashboard_view))
         .route("/profile", web::get().to(profile_view))
+        .route("/profile/password", web::get().to(change_password_form))
+        .route("/profile/password", web::post().to(change_password_action))
+        .route("/profile/edit", web::get().to(profile_edit_form))
+        .route("/profile/edit", web::post().to(profile_edit_action))
         // Legacy auth routes (for backward compatibility)
         .route("/login", web::get().to(login_form))
         .route("/login", web::post().to(login_action))
         .route("/logout", web::get().to(logout_action))
         .route("/logout", web::post().to(logout_action))
+        .route("/reset-password", web::get().to(reset_password_request_form))
+        .route("/reset-password", web::post().to(reset_password_request_action))
+        .route("/reset-password/{token}", web::get().to(reset_password_form))
+        .route("/reset-password/{token}", web::post().to(reset_password_action))
         // API routes
         .service(
             web::scope("/api")
                 .route("/login", web::post().to(api_login_action))
                 .route("/auth/status", web::get().to(check_auth_status))
+                .route("/auth/refresh", web::post().to(api_refresh_token))
+                .route("/auth/reauth", web::post().to(api_reauth_action))
+                .route("/verify", web::post().to(verify_email_action))
         );
 
     // Register resources
@@ -249,7 +363,7 @@ pub fn register_all_admix_routes_enhanced() -> Scope {
               resource_name, base_path, allowed_roles);
         
         let resource_scope = web::scope(&format!("/{}", base_path))
-            .service(register_admix_resource_routes(resource))
+            .service(register_admix_resource_routes(resource.clone_box()))
             .wrap(RoleGuard { allowed_roles });
         
         scope = scope.service(resource_scope);
@@ -257,4 +371,238 @@ pub fn register_all_admix_routes_enhanced() -> Scope {
     
     info!("🎉 Enhanced AdminX route registration completed!");
     scope
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test::{call_service, init_service, read_body, try_call_service, TestRequest}, App};
+    use crate::registry::{get_registered_menus, TestRegistration};
+    use crate::test_support::{login_cookie, login_route, TestResource};
+
+    fn hidden_reports() -> TestResource {
+        TestResource::named("Hidden Reports", "hidden_reports").hidden_from_menu()
+    }
+
+    #[actix_rt::test]
+    async fn test_hidden_resource_keeps_routes() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let _registration = TestRegistration::new(Box::new(hidden_reports()));
+
+        assert!(get_registered_menus().iter().all(|m| m.path != "hidden_reports"));
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(register_all_admix_routes_with(&config))
+        ).await;
+
+        let cookie = login_cookie(&app, "admin").await;
+        let resp = call_service(&app, TestRequest::get().uri("/adminx/hidden_reports/list").cookie(cookie).to_request()).await;
+
+        // Left out of the menu, but its routes are still mounted
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn test_unknown_path_renders_styled_404() {
+        let app = init_service(App::new().service(register_all_admix_routes())).await;
+        let resp = call_service(&app, TestRequest::get().uri("/adminx/nope").to_request()).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let body = String::from_utf8(read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains("Page Not Found"));
+        assert!(body.contains("<html"));
+    }
+
+    fn uploads() -> TestResource {
+        TestResource::named("Uploads", "introspected_uploads")
+            .with_file_upload()
+            .form(serde_json::json!({ "groups": [] }))
+            .actions(vec![crate::menu::MenuAction::List, crate::menu::MenuAction::Create])
+    }
+
+    #[actix_rt::test]
+    async fn test_resources_api_lists_capabilities_for_admins() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let _registration = TestRegistration::new(Box::new(uploads()));
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(register_all_admix_routes())
+        ).await;
+
+        let cookie = login_cookie(&app, "admin").await;
+        let resp = call_service(&app, TestRequest::get().uri("/adminx/api/resources").cookie(cookie).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(&read_body(resp).await).unwrap();
+        let uploads = body["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["base_path"] == "introspected_uploads")
+            .unwrap();
+        assert_eq!(uploads["name"], "Uploads");
+        assert_eq!(uploads["allowed_roles"], serde_json::json!(["admin"]));
+        assert_eq!(uploads["supports_file_upload"], true);
+        assert_eq!(uploads["allowed_actions"], serde_json::json!(["list", "create"]));
+        assert_eq!(uploads["has_form_structure"], true);
+        assert_eq!(uploads["has_list_structure"], false);
+
+        let cookie = login_cookie(&app, "viewer").await;
+        let status = match try_call_service(&app, TestRequest::get().uri("/adminx/api/resources").cookie(cookie).to_request()).await {
+            Ok(resp) => resp.status(),
+            Err(err) => err.as_response_error().status_code(),
+        };
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[actix_rt::test]
+    async fn test_custom_base_mount_drives_routes_redirects_and_links() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config().with_base_mount("/admin/");
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .route("/login-as/{role}", login_route())
+                .service(register_all_admix_routes_with(&config))
+        ).await;
+
+        let resp = call_service(&app, TestRequest::get().uri("/admin/profile").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        assert_eq!(resp.headers().get("Location").unwrap(), "/admin/login");
+
+        let resp = call_service(&app, TestRequest::get().uri("/admin/login").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let html = String::from_utf8(read_body(resp).await.to_vec()).unwrap();
+        assert!(html.contains(r#"action="/admin/login""#));
+        assert!(html.contains(r#"href="/admin/reset-password""#));
+        assert!(!html.contains(r#""/adminx"#));
+
+        let resp = call_service(&app, TestRequest::post().uri("/admin/logout").to_request()).await;
+        assert_eq!(resp.headers().get("Location").unwrap(), "/admin/login");
+
+        let cookie = login_cookie(&app, "admin").await;
+        let resp = call_service(&app, TestRequest::get().uri("/admin/login").cookie(cookie).to_request()).await;
+        assert_eq!(resp.headers().get("Location").unwrap(), "/admin");
+
+        let resp = call_service(&app, TestRequest::get().uri("/adminx/login").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn test_login_page_never_reflects_a_hostile_next() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+
+        let config = test_config();
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .service(register_all_admix_routes_with(&config))
+        ).await;
+
+        let hostile = urlencoding::encode(r#"/adminx/"><script>alert(1)</script>"#);
+        let resp = call_service(&app, TestRequest::get().uri(&format!("/adminx/login?next={}", hostile)).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let html = String::from_utf8(read_body(resp).await.to_vec()).unwrap();
+        assert!(!html.contains("<script>alert(1)"));
+        assert!(!html.contains(r#"name="next""#));
+
+        // Safe targets are kept, escaped
+        let next = urlencoding::encode("/adminx/posts/list?page=2&per_page=5");
+        let resp = call_service(&app, TestRequest::get().uri(&format!("/adminx/login?next={}", next)).to_request()).await;
+        let html = String::from_utf8(read_body(resp).await.to_vec()).unwrap();
+        assert!(html.contains(r#"name="next" value="&#x2F;adminx&#x2F;posts&#x2F;list?page=2&amp;per_page=5""#));
+    }
+
+    #[actix_rt::test]
+    async fn test_login_page_shows_configured_branding() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::branding::Branding;
+        use crate::utils::jwt::tests::test_config;
+
+        let branding = Branding::new("Acme Ops")
+            .with_logo_url("https://cdn.example.com/acme.png")
+            .with_primary_color("#ff6600");
+        let config = test_config().with_branding(branding);
+        let app = init_service(
+            App::new()
+                .wrap(get_adminx_session_middleware(&config))
+                .app_data(web::Data::new(config.clone()))
+                .service(register_all_admix_routes_with(&config))
+        ).await;
+
+        let resp = call_service(&app, TestRequest::get().uri("/adminx/login").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let html = String::from_utf8(read_body(resp).await.to_vec()).unwrap();
+        assert!(html.contains("<title>Acme Ops Login</title>"));
+        assert!(html.contains(r#"<img src="https:&#x2F;&#x2F;cdn.example.com&#x2F;acme.png" alt="Acme Ops Logo""#));
+        assert!(html.contains("background: #ff6600"));
+        assert!(!html.contains("AdminX"));
+
+        // Colors go into inline styles, so anything but a hex color is dropped
+        assert_eq!(Branding::default().with_primary_color("red;}</style>").primary_color, None);
+        assert_eq!(Branding::default().app_name, "AdminX");
+    }
+
+    fn ledger() -> TestResource {
+        TestResource::named("Ledger", "compressed_ledger")
+    }
+
+    #[actix_rt::test]
+    async fn test_list_page_is_gzipped_when_accepted() {
+        use crate::configs::initializer::get_adminx_session_middleware;
+        use crate::utils::jwt::tests::test_config;
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        let _registration = TestRegistration::new(Box::new(ledger()));
+
+        for compress in [true, false] {
+            let config = test_config().with_response_compression(compress);
+            let app = init_service(
+                App::new()
+                    .wrap(get_adminx_session_middleware(&config))
+                    .app_data(web::Data::new(config.clone()))
+                    .route("/login-as/{role}", login_route())
+                    .service(register_all_admix_routes_with(&config))
+            ).await;
+
+            let cookie = login_cookie(&app, "admin").await;
+            let resp = call_service(&app, TestRequest::get()
+                .uri("/adminx/compressed_ledger/list")
+                .insert_header(("Accept-Encoding", "gzip"))
+                .cookie(cookie)
+                .to_request()).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            let encoding = resp.headers().get("Content-Encoding").map(|v| v.to_str().unwrap().to_string());
+            let body = read_body(resp).await;
+            if compress {
+                assert_eq!(encoding.as_deref(), Some("gzip"));
+                let mut html = String::new();
+                GzDecoder::new(&body[..]).read_to_string(&mut html).unwrap();
+                assert!(html.contains("<html"));
+            } else {
+                assert_eq!(encoding, None);
+                assert!(String::from_utf8(body.to_vec()).unwrap().contains("<html"));
+            }
+        }
+    }
+}
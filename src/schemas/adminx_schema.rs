@@ -1,7 +1,8 @@
 // adminx/src/schemas/adminx_schema.rs
+use convert_case::{Case, Casing};
 use schemars::gen::SchemaGenerator;
 use schemars::{schema::RootSchema, JsonSchema};
-use schemars::schema::{InstanceType, Schema};
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
 use serde_json::Value;
 
 /// Field struct used to render forms
@@ -11,52 +12,119 @@ pub struct Field {
     pub label: String,
     pub value: String,
     pub field_type: String,                // e.g., "text", "select", "date"
-    pub options: Option<Vec<String>>,     // for enums or dropdowns
+    pub options: Option<Vec<FieldOption>>, // for enums or dropdowns
+    pub required: bool,                    // listed in the schema's `required`
+    pub default: Option<String>,           // schema `default`, prefilled on new forms
+    #[serde(rename = "help_text")]
+    pub help: Option<String>,              // schema `description` (doc comment)
+    // schema `minimum`/`maximum`, `minLength`/`maxLength` and `pattern`, rendered as input attributes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minlength: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maxlength: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+/// One choice of a select field
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct FieldOption {
+    pub value: String,
+    pub label: String,
 }
 
 
 /// Blanket trait to unify schema derivation across models.
-pub trait AdminxSchema: JsonSchema {}
+pub trait AdminxSchema: JsonSchema {
+    /// Form structure generated from the model, for use in `AdmixResource::form_structure`
+    fn admin_form_structure() -> Option<Value>
+    where
+        Self: Sized,
+    {
+        form_structure_from_model::<Self>()
+    }
+}
 /// Implement it for all types that implement `JsonSchema`
 impl<T: JsonSchema> AdminxSchema for T {}
 
 
 
 
-/// Detect field type from schema definition
-fn detect_field_type(schema: &Schema) -> (String, Option<Vec<String>>) {
-    if let Schema::Object(obj) = schema {
-        // Enum detection → dropdown
-        if let Some(enum_values) = &obj.enum_values {
-            let options = enum_values
-                .iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                .collect::<Vec<String>>();
-
-            return ("select".to_string(), Some(options));
-        }
+/// Follow `$ref`s and the `anyOf`/`allOf` wrappers schemars emits for
+/// `Option<T>` and documented fields down to the schema describing the value
+fn resolve_schema<'a>(schema: &'a Schema, root: &'a RootSchema) -> Option<&'a SchemaObject> {
+    let Schema::Object(obj) = schema else {
+        return None;
+    };
 
-        // Type detection and name pattern matching
-        if let Some(instance_types) = &obj.instance_type {
-            if instance_types.contains(&InstanceType::Integer) {
-                return ("number".to_string(), None);
-            }
-            if instance_types.contains(&InstanceType::Boolean) {
-                return ("checkbox".to_string(), None);
-            }
-            if instance_types.contains(&InstanceType::String) {
-                // name-based heuristics
-                if let Some(meta) = &obj.metadata {
-                    if let Some(title) = &meta.title {
-                        if title.ends_with("_at") || title.contains("date") {
-                            return ("date".to_string(), None);
-                        }
-                    }
-                }
+    if let Some(reference) = &obj.reference {
+        let name = reference.rsplit('/').next()?;
+        return resolve_schema(root.definitions.get(name)?, root);
+    }
+
+    if let Some(subschemas) = &obj.subschemas {
+        let candidates = subschemas.all_of.iter().chain(subschemas.any_of.iter()).flatten();
+        for candidate in candidates {
+            let is_null = matches!(
+                candidate,
+                Schema::Object(SchemaObject { instance_type: Some(SingleOrVec::Single(t)), .. })
+                    if **t == InstanceType::Null
+            );
+            if !is_null {
+                return resolve_schema(candidate, root);
             }
         }
     }
 
+    Some(obj)
+}
+
+/// Detect field type from schema definition
+fn detect_field_type(name: &str, schema: &Schema, root: &RootSchema) -> (String, Option<Vec<FieldOption>>) {
+    let Some(obj) = resolve_schema(schema, root) else {
+        return ("text".to_string(), None);
+    };
+
+    // Enum detection → dropdown
+    if let Some(enum_values) = &obj.enum_values {
+        let options = enum_values
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(|value| FieldOption {
+                value: value.to_string(),
+                label: value.to_case(Case::Title),
+            })
+            .collect::<Vec<FieldOption>>();
+
+        return ("select".to_string(), Some(options));
+    }
+
+    // Explicit string formats win over name heuristics
+    match obj.format.as_deref() {
+        Some("email") => return ("email".to_string(), None),
+        Some("uri") | Some("url") => return ("url".to_string(), None),
+        Some("date") => return ("date".to_string(), None),
+        Some("date-time") => return ("datetime-local".to_string(), None),
+        _ => {}
+    }
+
+    // Type detection and name pattern matching
+    if let Some(instance_types) = &obj.instance_type {
+        if instance_types.contains(&InstanceType::Integer) || instance_types.contains(&InstanceType::Number) {
+            return ("number".to_string(), None);
+        }
+        if instance_types.contains(&InstanceType::Boolean) {
+            return ("checkbox".to_string(), None);
+        }
+        if instance_types.contains(&InstanceType::String) && (name.ends_with("_at") || name.contains("date")) {
+            return ("date".to_string(), None);
+        }
+    }
+
     ("text".to_string(), None)
 }
 
@@ -65,28 +133,53 @@ pub fn generate_fields_from_model<T: JsonSchema>(record: Option<&Value>) -> Vec<
     let gen = SchemaGenerator::default();
     let schema: RootSchema = gen.into_root_schema_for::<T>();
 
-    let obj_props = match &schema.schema.object {
-        Some(obj) => &obj.properties,
+    let (obj_props, required) = match &schema.schema.object {
+        Some(obj) => (&obj.properties, &obj.required),
         None => return vec![],
     };
 
     obj_props
         .iter()
-        .map(|(name, schema)| {
+        .map(|(name, prop)| {
             let label = name.replace('_', " ").to_uppercase();
+            let (field_type, options) = detect_field_type(name, prop, &schema);
+
+            // Metadata sits on the property itself, or on the referenced definition
+            let metadata = |pick: fn(&schemars::schema::Metadata) -> Option<Value>| {
+                let own = match prop {
+                    Schema::Object(obj) => obj.metadata.as_deref().and_then(pick),
+                    Schema::Bool(_) => None,
+                };
+                own.or_else(|| resolve_schema(prop, &schema)?.metadata.as_deref().and_then(pick))
+            };
+            let default = metadata(|m| m.default.clone()).map(|v| stringify_json_value(&v));
+            let help = metadata(|m| m.description.clone().map(Value::String))
+                .map(|v| stringify_json_value(&v));
+
+            let resolved = resolve_schema(prop, &schema);
+            let number = resolved.and_then(|s| s.number.as_deref());
+            let string = resolved.and_then(|s| s.string.as_deref());
+
             let value = record
                 .and_then(|r| r.get(name))
                 .map(stringify_json_value)
+                .or_else(|| default.clone())
                 .unwrap_or_default();
 
-            let (field_type, options) = detect_field_type(schema);
-
             Field {
                 name: name.clone(),
                 label,
                 value,
                 field_type,
                 options,
+                required: required.contains(name),
+                default,
+                help,
+                min: number.and_then(|n| n.minimum),
+                max: number.and_then(|n| n.maximum),
+                minlength: string.and_then(|s| s.min_length),
+                maxlength: string.and_then(|s| s.max_length),
+                pattern: string.and_then(|s| s.pattern.clone()),
             }
         })
         .collect()
@@ -119,3 +212,141 @@ fn stringify_json_value(value: &Value) -> String {
         _ => "".to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    #[allow(dead_code)]
+    enum Status {
+        Draft,
+        InReview,
+        Published,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Article {
+        title: String,
+        status: Status,
+        // Optional enums are wrapped in `anyOf`
+        previous_status: Option<Status>,
+        #[schemars(email)]
+        author_email: String,
+        #[schemars(url)]
+        source: Option<String>,
+        published_at: String,
+        views: u32,
+    }
+
+    fn field<'a>(fields: &'a [Field], name: &str) -> &'a Field {
+        fields.iter().find(|f| f.name == name).unwrap()
+    }
+
+    #[test]
+    fn enum_field_becomes_select_with_variants() {
+        let fields = generate_fields_from_model::<Article>(None);
+        let status = field(&fields, "status");
+        assert_eq!(status.field_type, "select");
+        assert_eq!(
+            status.options.as_ref().unwrap(),
+            &vec![
+                FieldOption { value: "draft".into(), label: "Draft".into() },
+                FieldOption { value: "in_review".into(), label: "In Review".into() },
+                FieldOption { value: "published".into(), label: "Published".into() },
+            ]
+        );
+        assert_eq!(field(&fields, "previous_status").field_type, "select");
+    }
+
+    #[test]
+    fn formats_and_names_map_to_input_types() {
+        let fields = generate_fields_from_model::<Article>(None);
+        assert_eq!(field(&fields, "author_email").field_type, "email");
+        assert_eq!(field(&fields, "source").field_type, "url");
+        assert_eq!(field(&fields, "published_at").field_type, "date");
+        assert_eq!(field(&fields, "views").field_type, "number");
+        assert_eq!(field(&fields, "title").field_type, "text");
+    }
+
+    #[test]
+    fn form_structure_is_available_on_the_model() {
+        let form = Article::admin_form_structure().unwrap();
+        let fields = form["groups"][0]["fields"].as_array().unwrap();
+        assert_eq!(fields.len(), 7);
+        assert_eq!(form, form_structure_from_model::<Article>().unwrap());
+    }
+
+    fn default_priority() -> u32 {
+        3
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Ticket {
+        /// Short summary shown in the list
+        subject: String,
+        #[serde(default = "default_priority")]
+        priority: u32,
+        notes: Option<String>,
+    }
+
+    #[test]
+    fn required_fields_come_from_schema() {
+        let fields = generate_fields_from_model::<Ticket>(None);
+        assert!(field(&fields, "subject").required);
+        assert!(!field(&fields, "priority").required);
+        assert!(!field(&fields, "notes").required);
+        assert_eq!(field(&fields, "subject").help.as_deref(), Some("Short summary shown in the list"));
+    }
+
+    #[test]
+    fn defaults_prefill_new_forms_but_not_records() {
+        let fields = generate_fields_from_model::<Ticket>(None);
+        let priority = field(&fields, "priority");
+        assert_eq!(priority.default.as_deref(), Some("3"));
+        assert_eq!(priority.value, "3");
+        assert_eq!(field(&fields, "notes").default, None);
+
+        let record = serde_json::json!({ "priority": 7 });
+        let fields = generate_fields_from_model::<Ticket>(Some(&record));
+        assert_eq!(field(&fields, "priority").value, "7");
+
+        let form = Ticket::admin_form_structure().unwrap();
+        let subject = form["groups"][0]["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["name"] == "subject")
+            .unwrap();
+        assert_eq!(subject["required"], true);
+        assert_eq!(subject["help_text"], "Short summary shown in the list");
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Coupon {
+        #[schemars(length(min = 4, max = 12), regex(pattern = r"[A-Z0-9]+"))]
+        code: String,
+        #[schemars(range(min = 1, max = 90))]
+        percent_off: u8,
+    }
+
+    #[test]
+    fn schema_validation_becomes_field_constraints() {
+        let fields = generate_fields_from_model::<Coupon>(None);
+        let code = field(&fields, "code");
+        assert_eq!((code.minlength, code.maxlength), (Some(4), Some(12)));
+        assert_eq!(code.pattern.as_deref(), Some("[A-Z0-9]+"));
+        let percent_off = field(&fields, "percent_off");
+        assert_eq!((percent_off.min, percent_off.max), (Some(1.0), Some(90.0)));
+
+        let form = Coupon::admin_form_structure().unwrap();
+        let constraints = crate::helpers::form_helper::extract_field_constraints(&form);
+        assert_eq!(constraints["code"].pattern.as_deref(), Some("[A-Z0-9]+"));
+        assert!(form["groups"][0]["fields"][0].get("min").is_none());
+    }
+}
@@ -0,0 +1,147 @@
+// adminx/src/test_support.rs
+//! Fixtures shared by the unit tests
+use actix_session::Session;
+use actix_web::cookie::Cookie;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::test::{call_service, TestRequest};
+use actix_web::{web, HttpResponse, Route};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::configs::initializer::AdminxConfig;
+use crate::menu::MenuAction;
+use crate::resource::AdmixResource;
+use crate::utils::jwt::create_jwt_token_with_expiration;
+
+/// A resource set up with only what a test needs; everything else keeps the trait defaults.
+/// Tests overriding behaviour (handlers, hooks) still implement `AdmixResource` themselves.
+#[derive(Debug, Clone)]
+pub(crate) struct TestResource {
+    name: &'static str,
+    base_path: &'static str,
+    permit_keys: Vec<&'static str>,
+    timestamps: Option<(&'static str, &'static str)>,
+    show_in_menu: bool,
+    supports_file_upload: bool,
+    allowed_actions: Option<Vec<MenuAction>>,
+    role_permissions: Value,
+    status_values: Option<Vec<&'static str>>,
+    form_structure: Option<Value>,
+    list_structure: Option<Value>,
+}
+
+impl TestResource {
+    /// `name` served at `base_path`, stored in a collection of the same name
+    pub(crate) fn named(name: &'static str, base_path: &'static str) -> Self {
+        Self {
+            name,
+            base_path,
+            permit_keys: Vec::new(),
+            timestamps: Some(("created_at", "updated_at")),
+            show_in_menu: true,
+            supports_file_upload: false,
+            allowed_actions: None,
+            role_permissions: Value::Object(Default::default()),
+            status_values: None,
+            form_structure: None,
+            list_structure: None,
+        }
+    }
+
+    pub(crate) fn permit(mut self, keys: &[&'static str]) -> Self {
+        self.permit_keys = keys.to_vec();
+        self
+    }
+
+    pub(crate) fn timestamps(mut self, fields: Option<(&'static str, &'static str)>) -> Self {
+        self.timestamps = fields;
+        self
+    }
+
+    pub(crate) fn hidden_from_menu(mut self) -> Self {
+        self.show_in_menu = false;
+        self
+    }
+
+    pub(crate) fn with_file_upload(mut self) -> Self {
+        self.supports_file_upload = true;
+        self
+    }
+
+    pub(crate) fn actions(mut self, actions: Vec<MenuAction>) -> Self {
+        self.allowed_actions = Some(actions);
+        self
+    }
+
+    /// Per-role action lists, as in `allowed_roles_with_permissions()`
+    pub(crate) fn role_permissions(mut self, permissions: Value) -> Self {
+        self.role_permissions = permissions;
+        self
+    }
+
+    pub(crate) fn statuses(mut self, values: &[&'static str]) -> Self {
+        self.status_values = Some(values.to_vec());
+        self
+    }
+
+    pub(crate) fn form(mut self, structure: Value) -> Self {
+        self.form_structure = Some(structure);
+        self
+    }
+
+    pub(crate) fn list(mut self, structure: Value) -> Self {
+        self.list_structure = Some(structure);
+        self
+    }
+}
+
+impl AdmixResource for TestResource {
+    fn new() -> Self { TestResource::named("Tests", "tests") }
+    fn resource_name(&self) -> &'static str { self.name }
+    fn base_path(&self) -> &'static str { self.base_path }
+    fn collection_name(&self) -> &'static str { self.base_path }
+    fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(self.clone()) }
+    fn permit_keys(&self) -> Vec<&'static str> { self.permit_keys.clone() }
+    fn timestamps(&self) -> Option<(&'static str, &'static str)> { self.timestamps }
+    fn show_in_menu(&self) -> bool { self.show_in_menu }
+    fn supports_file_upload(&self) -> bool { self.supports_file_upload }
+    fn allowed_actions(&self) -> Option<Vec<MenuAction>> { self.allowed_actions.clone() }
+    fn allowed_roles_with_permissions(&self) -> Value { self.role_permissions.clone() }
+    fn status_values(&self) -> Option<Vec<&'static str>> { self.status_values.clone() }
+    fn form_structure(&self) -> Option<Value> { self.form_structure.clone() }
+    fn list_structure(&self) -> Option<Value> { self.list_structure.clone() }
+}
+
+#[derive(Deserialize)]
+struct LoginQuery {
+    id: Option<String>,
+}
+
+/// Handler for `/login-as/{role}`: signs in as `a@example.com` with `role`, as user `1`
+/// unless `?id=` names another. The app needs the session middleware and the config.
+pub(crate) fn login_route() -> Route {
+    web::get().to(|role: web::Path<String>, query: web::Query<LoginQuery>, session: Session, config: web::Data<AdminxConfig>| async move {
+        let id = query.id.as_deref().unwrap_or("1");
+        let token = create_jwt_token_with_expiration(id, "a@example.com", &role, &config, chrono::Duration::hours(1)).unwrap();
+        session.insert("admintoken", token).unwrap();
+        HttpResponse::Ok().finish()
+    })
+}
+
+/// Session cookie for `role` from an app serving `login_route()` at `/login-as/{role}`
+pub(crate) async fn login_cookie<S, B>(app: &S, role: &str) -> Cookie<'static>
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+{
+    login_cookie_as(app, role, "1").await
+}
+
+/// `login_cookie` for user `id`
+pub(crate) async fn login_cookie_as<S, B>(app: &S, role: &str, id: &str) -> Cookie<'static>
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+{
+    let uri = format!("/login-as/{}?id={}", role, id);
+    let login = call_service(app, TestRequest::get().uri(&uri).to_request()).await;
+    login.response().cookies().next().expect("login set no session cookie").into_owned()
+}
@@ -1,16 +1,17 @@
 // adminx/src/utils/auth.rs
 use crate::models::adminx_model::{AdminxUser};
 use crate::configs::initializer::AdminxConfig;
+use crate::error::AdminxError;
 use mongodb::{
     bson::{doc, DateTime as BsonDateTime},
 };
-use bcrypt::{hash, DEFAULT_COST};
 use anyhow::{Result};
 use crate::{custom_error_expression};
 use serde::{Serialize, Deserialize};
-use actix_session::Session;
-use actix_web::{Error, web};
-use jsonwebtoken::{decode, DecodingKey, Validation};
+use actix_session::{Session, SessionExt};
+use actix_web::{dev::Payload, http::header, Error, FromRequest, HttpMessage, HttpRequest, HttpResponse, ResponseError, web};
+use std::future::{ready, Ready};
+use jsonwebtoken::errors::ErrorKind as JwtErrorKind;
 use crate::{
     utils::{
         database::{
@@ -22,27 +23,112 @@ use crate::{
         structs::{
             Claims // ✅ Use Claims from structs.rs, not jwt.rs
         },
+        jwt::{
+            decode_jwt_token
+        },
+        password::{check_password, hash_password},
+        mount::{admin_path, base_mount},
     }
 };
 
+/// Why a session has no usable token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionAuthError {
+    /// Never logged in, or logged out
+    Missing,
+    /// The token was valid but its `exp` has passed
+    Expired,
+    /// Unreadable session or a token that fails verification
+    Invalid,
+}
+
+impl SessionAuthError {
+    fn unauthorized(self) -> AdminxError {
+        AdminxError::Unauthorized(match self {
+            SessionAuthError::Missing => "Missing token in session",
+            SessionAuthError::Expired => "Session expired",
+            SessionAuthError::Invalid => "Invalid token",
+        }.into())
+    }
+}
+
+/// Decode the session token, telling an expired token apart from a missing or bad one
+pub fn session_claims(session: &Session, config: &AdminxConfig) -> Result<Claims, SessionAuthError> {
+    let token = session
+        .get::<String>("admintoken")
+        .map_err(|_| SessionAuthError::Invalid)?
+        .ok_or(SessionAuthError::Missing)?;
+
+    decode_jwt_token(&token, config).map_err(|e| {
+        match e.downcast_ref::<jsonwebtoken::errors::Error>().map(|e| e.kind()) {
+            Some(JwtErrorKind::ExpiredSignature) => SessionAuthError::Expired,
+            _ => SessionAuthError::Invalid,
+        }
+    })
+}
+
 // Updated to use config instead of env::var
 pub async fn extract_claims_from_session(
     session: &Session,
     config: &AdminxConfig,
 ) -> Result<Claims, Error> {
-    let token = session
-        .get::<String>("admintoken")
-        .map_err(|_| actix_web::error::ErrorUnauthorized("Invalid session"))?
-        .ok_or_else(|| actix_web::error::ErrorUnauthorized("Missing token in session"))?;
-    
-    let token_data = decode::<Claims>(
-        &token,
-        &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
-        &Validation::default(),
+    session_claims(session, config).map_err(|e| e.unauthorized().into())
+}
+
+/// Login URL for a failed session check. Expired sessions carry `reason=expired` and,
+/// for GET requests, `next` so the admin lands back where they were.
+pub fn login_redirect_url(error: SessionAuthError, req: &HttpRequest) -> String {
+    let login = admin_path("/login");
+    if error != SessionAuthError::Expired {
+        return login;
+    }
+
+    let mut params = vec![("reason", "expired".to_string())];
+    if req.method() == actix_web::http::Method::GET {
+        let path_and_query = req.uri().path_and_query().map_or_else(base_mount, |pq| pq.as_str().to_string());
+        params.push(("next", path_and_query));
+    }
+    format!(
+        "{}?{}",
+        login,
+        serde_urlencoded::to_string(&params).unwrap_or_else(|_| "reason=expired".to_string())
     )
-    .map_err(|_| actix_web::error::ErrorUnauthorized("Invalid token"))?;
-    
-    Ok(token_data.claims)
+}
+
+/// Redirect to the login page for a failed session check
+pub fn login_redirect(error: SessionAuthError, req: &HttpRequest) -> HttpResponse {
+    HttpResponse::Found()
+        .append_header((header::LOCATION, login_redirect_url(error, req)))
+        .finish()
+}
+
+/// Whether `req` comes from an API client: its path has an `api` segment, or it
+/// accepts JSON but not HTML
+pub fn wants_json(req: &HttpRequest) -> bool {
+    let accept = req.headers().get(header::ACCEPT).and_then(|value| value.to_str().ok()).unwrap_or("");
+    req.path().split('/').any(|segment| segment == "api")
+        || (accept.contains("application/json") && !accept.contains("text/html"))
+}
+
+/// Response to a failed session check: a 401 JSON body for API clients, a login
+/// redirect for browser pages
+pub fn unauthenticated_response(error: SessionAuthError, req: &HttpRequest) -> HttpResponse {
+    if wants_json(req) {
+        error.unauthorized().error_response()
+    } else {
+        login_redirect(error, req)
+    }
+}
+
+/// A `next` target that is safe to redirect to after login: an AdminX path on this host
+/// made only of plain path and query characters (no quotes, angle brackets, spaces,
+/// backslashes or control characters), so it can't break out of an HTML attribute
+pub fn safe_next_path(next: &str) -> Option<&str> {
+    let mount = base_mount();
+    let is_adminx_path = next.strip_prefix(mount.as_str())
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/') || rest.starts_with('?'));
+    let plain = next.chars().all(|c| c.is_ascii_alphanumeric() || "-._~/?&=%+,;:@!$*()#".contains(c));
+    (is_adminx_path && plain).then_some(next)
 }
 
 // Convenience function for extracting claims from request context
@@ -53,6 +139,47 @@ pub async fn extract_claims_from_request(
     extract_claims_from_session(session, config.as_ref()).await
 }
 
+/// Claims of the signed-in admin: the ones `RoleGuard` stored on the request, else
+/// the session's
+pub fn request_claims(req: &HttpRequest) -> Option<Claims> {
+    let guarded = req.extensions().get::<Claims>().cloned();
+    guarded.or_else(|| {
+        let config = req.app_data::<web::Data<AdminxConfig>>()?;
+        session_claims(&req.get_session(), config).ok()
+    })
+}
+
+/// Email of the signed-in admin, to attribute writes to in logs; `anonymous` without one
+pub fn request_actor(req: &HttpRequest) -> String {
+    request_claims(req).map_or_else(|| "anonymous".to_string(), |claims| claims.email)
+}
+
+/// Extractor for the signed-in admin; add `user: AuthenticatedUser` to a handler's
+/// parameters. Requests without a valid session are rejected with 401.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser(pub Claims);
+
+impl std::ops::Deref for AuthenticatedUser {
+    type Target = Claims;
+
+    fn deref(&self) -> &Claims {
+        &self.0
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = Error;
+    type Future = Ready<Result<Self, Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(
+            request_claims(req)
+                .map(AuthenticatedUser)
+                .ok_or_else(|| AdminxError::Unauthorized("Not signed in".into()).into()),
+        )
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum AdminxStatus {
@@ -79,8 +206,11 @@ pub async fn initiate_auth(adminx: NewAdminxUser) -> Result<InitOutcome, actix_w
     let db = get_adminx_database();
     let collection = db.collection::<AdminxUser>("adminxs");
     
+    check_password(&adminx.password)
+        .map_err(|reason| custom_error_expression!(bad_request, 400, reason))?;
+
     let now = BsonDateTime::now();
-    let hashed_pwd = hash(&adminx.password, DEFAULT_COST)
+    let hashed_pwd = hash_password(&adminx.password)
         .map_err(|e| custom_error_expression!(bad_request, 400, format!("Failed to hash password: {e}")))?;
         
     match collection.find_one(doc! { "email": &adminx.email }, None).await {
@@ -112,6 +242,11 @@ pub async fn initiate_auth(adminx: NewAdminxUser) -> Result<InitOutcome, actix_w
                 status: adminx.status,
                 created_at: now,
                 updated_at: now,
+                email_verified: true,
+                verification_token: None,
+                reset_token: None,
+                reset_token_expires_at: None,
+                roles: Vec::new(),
             };
             collection.insert_one(new_user, None)
                 .await
@@ -129,6 +264,11 @@ pub fn validate_session_config(config: &AdminxConfig) -> Result<(), String> {
     if config.jwt_secret.len() < 32 {
         return Err("JWT_SECRET must be at least 32 characters long".to_string());
     }
+
+    if crate::utils::jwt::is_rsa_algorithm(config.jwt_algorithm)
+        && (config.jwt_private_key_path.is_none() || config.jwt_public_key_path.is_none()) {
+        return Err("JWT_PRIVATE_KEY_PATH and JWT_PUBLIC_KEY_PATH are required for RSA algorithms".to_string());
+    }
     
     if !config.session_secret.is_empty() && config.session_secret.len() < 64 {
         return Err("SESSION_SECRET must be at least 64 characters long".to_string());
@@ -138,6 +278,7 @@ pub fn validate_session_config(config: &AdminxConfig) -> Result<(), String> {
 }
 
 // Rate limiting helper (optional enhancement)
+// Prefer `AdminxConfig::rate_limiter`, which is pluggable and evicts expired entries.
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use std::sync::Mutex;
@@ -146,6 +287,7 @@ lazy_static::lazy_static! {
     static ref LOGIN_ATTEMPTS: Mutex<HashMap<String, (u32, Instant)>> = Mutex::new(HashMap::new());
 }
 
+#[deprecated(note = "use `AdminxConfig::rate_limiter` instead")]
 pub fn is_rate_limited(email: &str, max_attempts: u32, window: Duration) -> bool {
     let mut attempts = LOGIN_ATTEMPTS.lock().unwrap();
     let now = Instant::now();
@@ -175,7 +317,62 @@ pub fn is_rate_limited(email: &str, max_attempts: u32, window: Duration) -> bool
     }
 }
 
+#[deprecated(note = "use `AdminxConfig::rate_limiter` instead")]
 pub fn reset_rate_limit(email: &str) {
     let mut attempts = LOGIN_ATTEMPTS.lock().unwrap();
     attempts.remove(email);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::jwt::{create_jwt_token_with_expiration, tests::test_config};
+    use actix_session::SessionExt;
+    use actix_web::test::TestRequest;
+
+    fn redirect_for(token: Option<&str>) -> String {
+        let config = test_config();
+        let req = TestRequest::get().uri("/adminx/posts/list?page=2").to_http_request();
+        let session = req.get_session();
+        if let Some(token) = token {
+            session.insert("admintoken", token).unwrap();
+        }
+        let error = session_claims(&session, &config).unwrap_err();
+        login_redirect_url(error, &req)
+    }
+
+    #[test]
+    fn expired_session_redirects_back_with_reason() {
+        let config = test_config();
+        let token = create_jwt_token_with_expiration("1", "a@example.com", "admin", &config, chrono::Duration::hours(-2)).unwrap();
+
+        assert_eq!(
+            redirect_for(Some(&token)),
+            "/adminx/login?reason=expired&next=%2Fadminx%2Fposts%2Flist%3Fpage%3D2"
+        );
+    }
+
+    #[test]
+    fn missing_or_invalid_token_redirects_to_plain_login() {
+        assert_eq!(redirect_for(None), "/adminx/login");
+        assert_eq!(redirect_for(Some("not.a.jwt")), "/adminx/login");
+
+        let config = test_config();
+        let req = TestRequest::default().to_http_request();
+        req.get_session().insert("admintoken", "not.a.jwt").unwrap();
+        assert_eq!(session_claims(&req.get_session(), &config).unwrap_err(), SessionAuthError::Invalid);
+    }
+
+    #[test]
+    fn next_must_stay_inside_adminx() {
+        assert_eq!(safe_next_path("/adminx/posts/list?page=2"), Some("/adminx/posts/list?page=2"));
+        assert_eq!(safe_next_path("/adminx"), Some("/adminx"));
+        assert_eq!(safe_next_path("https://evil.example"), None);
+        assert_eq!(safe_next_path("//evil.example/adminx"), None);
+        assert_eq!(safe_next_path("/adminxevil"), None);
+        assert_eq!(safe_next_path("/adminx/\\evil"), None);
+        assert_eq!(safe_next_path(r#"/adminx/"><script>alert(1)</script>"#), None);
+        assert_eq!(safe_next_path("/adminx/posts' onfocus='x"), None);
+        assert_eq!(safe_next_path("/adminx/posts\nSet-Cookie: x"), None);
+    }
+}
@@ -0,0 +1,75 @@
+// adminx/src/utils/branding.rs
+use serde::Serialize;
+use std::env;
+use tera::Context;
+
+use crate::utils::database::get_adminx_config;
+
+/// Default `Branding::app_name`
+pub const DEFAULT_APP_NAME: &str = "AdminX";
+
+/// Name, logo and accent color shown on the login page and in the layout,
+/// available to templates as `branding`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Branding {
+    pub app_name: String,
+    pub logo_url: Option<String>,
+    /// Hex color (`#4f46e5`) for the login button and accents
+    pub primary_color: Option<String>,
+}
+
+impl Default for Branding {
+    fn default() -> Self {
+        Branding { app_name: DEFAULT_APP_NAME.to_string(), logo_url: None, primary_color: None }
+    }
+}
+
+impl Branding {
+    pub fn new(app_name: &str) -> Self {
+        Branding { app_name: app_name.to_string(), ..Default::default() }
+    }
+
+    pub fn with_logo_url(mut self, url: &str) -> Self {
+        self.logo_url = Some(url.to_string());
+        self
+    }
+
+    /// Ignored unless it is a hex color, as it is written into inline styles
+    pub fn with_primary_color(mut self, color: &str) -> Self {
+        self.primary_color = is_hex_color(color).then(|| color.to_string());
+        self
+    }
+
+    /// Read `ADMINX_APP_NAME`, `ADMINX_LOGO_URL` and `ADMINX_PRIMARY_COLOR`
+    pub fn from_env() -> Self {
+        let mut branding = Branding::new(
+            &env::var("ADMINX_APP_NAME").unwrap_or_else(|_| DEFAULT_APP_NAME.to_string()),
+        );
+        if let Ok(url) = env::var("ADMINX_LOGO_URL") {
+            branding = branding.with_logo_url(&url);
+        }
+        if let Ok(color) = env::var("ADMINX_PRIMARY_COLOR") {
+            branding = branding.with_primary_color(&color);
+        }
+        branding
+    }
+
+    /// Add `branding` and `app_name` to a template context
+    pub fn insert_into(&self, ctx: &mut Context) {
+        ctx.insert("branding", self);
+        ctx.insert("app_name", &self.app_name);
+    }
+}
+
+/// `#rgb` or `#rrggbb`
+pub fn is_hex_color(color: &str) -> bool {
+    color.strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// The global `AdminxConfig`'s branding, else the default
+pub fn current_branding() -> Branding {
+    get_adminx_config()
+        .map(|config| config.branding.clone())
+        .unwrap_or_default()
+}
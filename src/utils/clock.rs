@@ -0,0 +1,89 @@
+// adminx/src/utils/clock.rs
+use actix_web::{web, HttpRequest};
+use chrono::{DateTime, Utc};
+use mongodb::bson::DateTime as BsonDateTime;
+use once_cell::sync::Lazy;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::configs::initializer::AdminxConfig;
+use crate::utils::database::get_adminx_config;
+
+/// Source of the current time for token expiry, written timestamps and rate limit
+/// windows. Swap in a `FixedClock` to make time-dependent behaviour deterministic.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// `now()` as a BSON datetime, for documents
+    fn bson_now(&self) -> BsonDateTime {
+        BsonDateTime::from_millis(self.now().timestamp_millis())
+    }
+
+    /// Time since an arbitrary origin, for measuring intervals such as rate limit
+    /// windows. Follows `now()` unless the clock has a source that never goes back.
+    fn monotonic(&self) -> Duration {
+        Duration::from_millis(self.now().timestamp_millis().max(0) as u64)
+    }
+}
+
+impl fmt::Debug for dyn Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Clock")
+    }
+}
+
+/// The real time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+static PROCESS_START: Lazy<Instant> = Lazy::new(Instant::now);
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    /// Measured with `Instant`, so wall clock adjustments don't stretch or cut intervals
+    fn monotonic(&self) -> Duration {
+        PROCESS_START.elapsed()
+    }
+}
+
+/// A clock that only moves when told to
+#[derive(Debug)]
+pub struct FixedClock(Mutex<DateTime<Utc>>);
+
+impl FixedClock {
+    pub fn new(at: DateTime<Utc>) -> Self {
+        FixedClock(Mutex::new(at))
+    }
+
+    pub fn set(&self, at: DateTime<Utc>) {
+        *self.0.lock().unwrap() = at;
+    }
+
+    pub fn advance(&self, by: chrono::Duration) {
+        *self.0.lock().unwrap() += by;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap()
+    }
+}
+
+/// The global `AdminxConfig`'s clock, else the system clock
+pub fn current_clock() -> Arc<dyn Clock> {
+    get_adminx_config()
+        .map(|config| Arc::clone(&config.clock))
+        .unwrap_or_else(|| Arc::new(SystemClock))
+}
+
+/// The clock of the `AdminxConfig` serving `req`, else `current_clock()`
+pub fn request_clock(req: &HttpRequest) -> Arc<dyn Clock> {
+    req.app_data::<web::Data<AdminxConfig>>()
+        .map(|config| Arc::clone(&config.clock))
+        .unwrap_or_else(current_clock)
+}
@@ -1,6 +1,58 @@
 // adminx/src/utils/constants.rs
 
+/// Path AdminX is served under unless `AdminxConfig::base_mount` says otherwise
+pub const DEFAULT_BASE_MOUNT: &str = "/adminx";
+
 pub const DEFAULT_PAGE: u64 = 1;
 pub const DEFAULT_LIMIT: u64 = 25;
 pub const DEFAULT_PER_PAGE: u64 = 25;
 pub const MAX_LIMIT: u64 = 100;
+
+/// Default `max_files()`: files a single multipart upload may carry
+pub const MAX_UPLOAD_FILES: usize = 5;
+
+/// Default cap on JSON request bodies sent to a resource's API (actix's own default)
+pub const DEFAULT_JSON_BODY_LIMIT: usize = 256 * 1024;
+
+/// Default row cap for PDF exports, which are rendered in memory
+pub const DEFAULT_PDF_MAX_ROWS: u64 = 500;
+
+/// Default bounds, in characters, for `search` and text filter terms
+pub const DEFAULT_MAX_SEARCH_LENGTH: usize = 128;
+pub const DEFAULT_MIN_SEARCH_LENGTH: usize = 1;
+
+/// Fields the global `search` parameter matches, narrowed to a resource's `permit_keys()`
+pub const DEFAULT_SEARCH_FIELDS: &[&str] = &["name", "email", "username", "key", "title", "description"];
+
+/// Field names whose values are masked in logs (matched case-insensitively as substrings)
+pub const DEFAULT_SENSITIVE_FIELDS: &[&str] = &["password", "token", "secret"];
+
+/// Collection holding audit log entries; the dashboard shows recent activity when it exists
+pub const AUDIT_LOG_COLLECTION: &str = "adminx_audit_logs";
+
+/// Collection tracking background export jobs
+pub const EXPORT_JOBS_COLLECTION: &str = "adminx_export_jobs";
+
+/// How long export jobs and their files are kept after the job last changed
+pub const EXPORT_JOB_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// Export jobs persist their progress every this many rows
+pub const EXPORT_JOB_PROGRESS_INTERVAL: u64 = 1000;
+
+/// Session key holding when the admin last re-entered their password (unix seconds)
+pub const REAUTH_SESSION_KEY: &str = "reauth_at";
+
+/// Deepest `a[b][c]` / `a.b.c` form key turned into nested JSON; deeper keys are dropped
+pub const MAX_FORM_NESTING_DEPTH: usize = 4;
+
+/// Most records one `POST /{resource}/bulk-status` may change
+pub const MAX_BULK_STATUS_IDS: usize = 1000;
+
+/// Documents written per round trip by `POST /{resource}/import`
+pub const IMPORT_BATCH_SIZE: usize = 500;
+
+/// Collection remembering processed `Idempotency-Key`s for creates
+pub const IDEMPOTENCY_KEYS_COLLECTION: &str = "adminx_idempotency_keys";
+
+/// How long a processed idempotency key is replayed before it may be reused
+pub const IDEMPOTENCY_KEY_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
@@ -3,13 +3,19 @@ use mongodb::{Client, options::ClientOptions, Database};
 use log::{info};
 use once_cell::sync::OnceCell;
 use crate::configs::initializer::AdminxConfig;
+use crate::error::AdminxError;
 use anyhow::{Result, Context};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use lazy_static::lazy_static;
 
 pub static ADMINX_DATABASE: OnceCell<Database> = OnceCell::new();
 pub static ADMINX_CONFIG: OnceCell<Arc<AdminxConfig>> = OnceCell::new();
 
-
+lazy_static! {
+    /// Additional databases resources can opt into via `AdmixResource::database_name`
+    static ref ADMINX_DATABASES: RwLock<HashMap<String, Database>> = RwLock::new(HashMap::new());
+}
 
 
 pub async fn initiate_mongo_client(mongo_uri: &str, mongo_database_name: &str) -> Database {
@@ -39,6 +45,47 @@ pub fn get_adminx_database() -> &'static Database {
         .expect("ADMINX_DATABASE has not been initialized. Call initiate_database(db) first.")
 }
 
+/// The AdminX database, or an internal error (logged) when `initiate_database`
+/// was never called
+pub fn try_get_adminx_database() -> Result<&'static Database, AdminxError> {
+    ADMINX_DATABASE.get().ok_or_else(|| {
+        tracing::error!("ADMINX_DATABASE has not been initialized. Call initiate_database(db) first.");
+        AdminxError::InternalError
+    })
+}
+
+/// Register an additional database under `name` (e.g. a tenant database or a read replica).
+/// Registering the same name again replaces the previous database.
+pub fn initiate_named_database(name: &str, db: Database) {
+    ADMINX_DATABASES.write().unwrap().insert(name.to_string(), db);
+}
+
+/// Register several named databases at once
+pub fn initiate_databases<I>(databases: I)
+where
+    I: IntoIterator<Item = (String, Database)>,
+{
+    ADMINX_DATABASES.write().unwrap().extend(databases);
+}
+
+/// Look up a database registered with `initiate_named_database`
+pub fn get_named_database(name: &str) -> Option<Database> {
+    ADMINX_DATABASES.read().unwrap().get(name).cloned()
+}
+
+/// Resolve the database for a resource: the database registered under `name`, or the
+/// global AdminX database when `name` is `None`. A `name` that was never registered is
+/// an error rather than a silent fallback to the global database.
+pub fn resolve_database(name: Option<&str>) -> Result<Database, AdminxError> {
+    match name {
+        Some(name) => get_named_database(name).ok_or_else(|| {
+            tracing::error!("Database '{}' is not registered. Call initiate_named_database first.", name);
+            AdminxError::UnknownDatabase(name.to_string())
+        }),
+        None => try_get_adminx_database().cloned(),
+    }
+}
+
 // Optional: Store config globally if needed by database operations
 pub fn set_adminx_config(config: AdminxConfig) {
     ADMINX_CONFIG.set(Arc::new(config)).ok();
@@ -84,4 +131,248 @@ pub fn validate_database_config() -> Result<()> {
     // This is just an example - modify based on your needs
     tracing::info!("Database validation completed");
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resource::AdmixResource;
+    use mongodb::{bson::Document, Collection};
+
+    macro_rules! db_resource {
+        ($name:ident, $db:expr) => {
+            #[derive(Clone)]
+            struct $name;
+
+            impl AdmixResource for $name {
+                fn new() -> Self { $name }
+                fn resource_name(&self) -> &'static str { stringify!($name) }
+                fn base_path(&self) -> &'static str { "events" }
+                fn collection_name(&self) -> &'static str { "events" }
+                fn database_name(&self) -> Option<&'static str> { $db }
+                fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(self.clone()) }
+            }
+        };
+    }
+
+    db_resource!(TenantEvents, Some("tenant_a"));
+    db_resource!(ArchiveEvents, Some("archive"));
+
+    #[actix_rt::test]
+    async fn resources_resolve_their_own_databases() {
+        let client = Client::with_uri_str("mongodb://localhost:27017").await.unwrap();
+        initiate_databases(vec![
+            ("tenant_a".to_string(), client.database("tenant_a_db")),
+            ("archive".to_string(), client.database("archive_db")),
+        ]);
+
+        let tenant: Collection<Document> = TenantEvents.get_collection();
+        let archive: Collection<Document> = ArchiveEvents.get_collection();
+        assert_eq!(tenant.namespace().db, "tenant_a_db");
+        assert_eq!(archive.namespace().db, "archive_db");
+        assert_eq!(tenant.name(), "events");
+
+        initiate_named_database("archive", client.database("archive_v2"));
+        assert_eq!(ArchiveEvents.get_collection().namespace().db, "archive_v2");
+        assert!(get_named_database("missing").is_none());
+    }
+
+    #[derive(Clone)]
+    struct Notes(Option<Collection<Document>>);
+
+    impl AdmixResource for Notes {
+        fn new() -> Self { Notes(None) }
+        fn resource_name(&self) -> &'static str { "Notes" }
+        fn base_path(&self) -> &'static str { "notes" }
+        fn collection_name(&self) -> &'static str { "notes" }
+        fn collection_override(&self) -> Option<Collection<Document>> { self.0.clone() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(self.clone()) }
+        fn permit_keys(&self) -> Vec<&'static str> { vec!["title", "status"] }
+        fn default_values(&self) -> serde_json::Map<String, serde_json::Value> {
+            serde_json::json!({ "status": "pending" }).as_object().unwrap().clone()
+        }
+    }
+
+    #[actix_rt::test]
+    async fn injected_collection_wins_over_databases() {
+        let client = Client::with_uri_str("mongodb://localhost:27017").await.unwrap();
+        let notes = Notes(Some(client.database("ephemeral_db").collection("notes_v2")));
+
+        let collection = notes.try_get_collection().unwrap();
+        assert_eq!(collection.namespace().db, "ephemeral_db");
+        assert_eq!(collection.name(), "notes_v2");
+        // Other instances are unaffected
+        assert!(Notes(None).try_get_collection().is_err());
+    }
+
+    /// A throwaway database on the server at `ADMINX_TEST_MONGODB_URL`
+    async fn test_database() -> Database {
+        let url = std::env::var("ADMINX_TEST_MONGODB_URL").expect("ADMINX_TEST_MONGODB_URL");
+        let client = Client::with_uri_str(&url).await.unwrap();
+        client.database(&format!("adminx_test_{}", uuid::Uuid::new_v4().simple()))
+    }
+
+    /// Default CRUD against a throwaway database
+    #[actix_rt::test]
+    #[ignore = "needs a MongoDB at ADMINX_TEST_MONGODB_URL"]
+    async fn default_crud_against_injected_collection() {
+        use actix_web::{body::to_bytes, http::StatusCode, test::TestRequest};
+        use serde_json::{json, Value};
+
+        let db = test_database().await;
+        let notes = Notes(Some(db.collection("notes")));
+
+        async fn body(response: actix_web::HttpResponse) -> Value {
+            serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap()
+        }
+
+        let req = TestRequest::default().to_http_request();
+        let created = notes.create(&req, json!({ "title": "First", "secret": "x" })).await;
+        assert_eq!(created.status(), StatusCode::CREATED);
+        let created = body(created).await;
+        assert_eq!(created["data"]["title"], "First");
+        assert_eq!(created["data"]["status"], "pending");
+        assert!(created["data"].get("secret").is_none());
+
+        let done = body(notes.create(&req, json!({ "title": "Done", "status": "done" })).await).await;
+        assert_eq!(done["data"]["status"], "done");
+        let id = created["id"]["$oid"].as_str().unwrap().to_string();
+
+        let updated = body(notes.update(&req, id.clone(), json!({ "title": "Second" })).await).await;
+        assert_eq!(updated["data"]["title"], "Second");
+
+        assert_eq!(notes.get(&req, id.clone()).await.status(), StatusCode::OK);
+        assert!(notes.delete(&req, id.clone()).await.status().is_success());
+        assert_eq!(notes.get(&req, id).await.status(), StatusCode::NOT_FOUND);
+
+        db.drop(None).await.unwrap();
+    }
+
+    #[derive(Clone)]
+    struct Members { partial: bool, collection: Option<Collection<Document>> }
+
+    impl AdmixResource for Members {
+        fn new() -> Self { Members { partial: true, collection: None } }
+        fn resource_name(&self) -> &'static str { "Members" }
+        fn base_path(&self) -> &'static str { "members" }
+        fn collection_name(&self) -> &'static str { "members" }
+        fn collection_override(&self) -> Option<Collection<Document>> { self.collection.clone() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(self.clone()) }
+        fn permit_keys(&self) -> Vec<&'static str> { vec!["email", "deleted"] }
+        fn indexes(&self) -> Vec<crate::helpers::indexes::IndexSpec> {
+            let email = crate::helpers::indexes::IndexSpec::unique(mongodb::bson::doc! { "email": 1 });
+            vec![if self.partial { email.partial() } else { email }]
+        }
+    }
+
+    /// Soft-deleted records and unique indexes
+    #[actix_rt::test]
+    #[ignore = "needs a MongoDB at ADMINX_TEST_MONGODB_URL"]
+    async fn partial_unique_index_allows_recreating_a_soft_deleted_email() {
+        use crate::helpers::indexes::ensure_indexes;
+        use actix_web::{body::to_bytes, http::StatusCode, test::TestRequest};
+        use serde_json::{json, Value};
+
+        let req = TestRequest::default().to_http_request();
+        async fn body(response: actix_web::HttpResponse) -> Value {
+            serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap()
+        }
+
+        for partial in [true, false] {
+            let db = test_database().await;
+            let members = Members { partial, collection: Some(db.collection("members")) };
+            // Written before soft deletes were enabled, so it has no `deleted` field
+            db.collection::<Document>("members").insert_one(mongodb::bson::doc! { "email": "legacy@example.com" }, None).await.unwrap();
+            ensure_indexes(&members).await.unwrap();
+            let legacy = members.create(&req, json!({ "email": "legacy@example.com" })).await;
+            assert_eq!(legacy.status(), StatusCode::CONFLICT);
+
+            let created = body(members.create(&req, json!({ "email": "a@example.com" })).await).await;
+            let id = created["id"]["$oid"].as_str().unwrap().to_string();
+            assert!(members.delete(&req, id.clone()).await.status().is_success());
+
+            let again = members.create(&req, json!({ "email": "a@example.com" })).await;
+            if partial {
+                assert_eq!(again.status(), StatusCode::CREATED);
+                // The live record now owns the email, so the old one can't come back
+                assert_eq!(members.restore(&req, id).await.status(), StatusCode::CONFLICT);
+            } else {
+                assert_eq!(again.status(), StatusCode::CONFLICT);
+                let conflict = body(again).await;
+                assert_eq!(conflict["restore_id"], id.as_str());
+                assert_eq!(conflict["restore_url"], format!("/adminx/members/{}/restore", id));
+            }
+
+            db.drop(None).await.unwrap();
+        }
+    }
+
+    #[derive(Clone)]
+    struct Attachments { root: std::path::PathBuf, collection: Option<Collection<Document>> }
+
+    impl AdmixResource for Attachments {
+        fn new() -> Self { Attachments { root: Default::default(), collection: None } }
+        fn resource_name(&self) -> &'static str { "Attachments" }
+        fn base_path(&self) -> &'static str { "attachments" }
+        fn collection_name(&self) -> &'static str { "attachments" }
+        fn collection_override(&self) -> Option<Collection<Document>> { self.collection.clone() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(self.clone()) }
+        fn file_fields(&self) -> Vec<&'static str> { vec!["file"] }
+        fn upload_root(&self) -> Option<std::path::PathBuf> { Some(self.root.clone()) }
+    }
+
+    /// Hard delete removes the stored file
+    #[actix_rt::test]
+    #[ignore = "needs a MongoDB at ADMINX_TEST_MONGODB_URL"]
+    async fn hard_delete_removes_the_stored_file() {
+        use actix_web::test::TestRequest;
+
+        let db = test_database().await;
+        let collection = db.collection::<Document>("attachments");
+
+        let dir = tempfile::tempdir().unwrap();
+        let stored = dir.path().join("report.pdf");
+        std::fs::write(&stored, b"%PDF").unwrap();
+        let inserted = collection.insert_one(mongodb::bson::doc! { "file": stored.to_str().unwrap() }, None).await.unwrap();
+        let id = inserted.inserted_id.as_object_id().unwrap().to_hex();
+
+        let req = TestRequest::default().to_http_request();
+        let attachments = Attachments { root: dir.path().to_path_buf(), collection: Some(collection) };
+        assert!(attachments.delete(&req, id).await.status().is_success());
+        assert!(!stored.exists());
+
+        db.drop(None).await.unwrap();
+    }
+
+    #[derive(Clone)]
+    struct Unconfigured;
+
+    impl AdmixResource for Unconfigured {
+        fn new() -> Self { Unconfigured }
+        fn resource_name(&self) -> &'static str { "Unconfigured" }
+        fn base_path(&self) -> &'static str { "unconfigured" }
+        fn collection_name(&self) -> &'static str { "unconfigured" }
+        fn database_name(&self) -> Option<&'static str> { Some("never_registered") }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(Unconfigured) }
+    }
+
+    #[actix_rt::test]
+    async fn data_access_without_a_database_is_an_error() {
+        use actix_web::{http::StatusCode, test::TestRequest};
+
+        // No test initiates the global database; this must not be the first panic in a worker
+        assert!(ADMINX_DATABASE.get().is_none());
+        assert!(matches!(try_get_adminx_database(), Err(AdminxError::InternalError)));
+        assert!(matches!(resolve_database(None), Err(AdminxError::InternalError)));
+        // A named database that was never registered is reported by name, never replaced by the default
+        assert!(matches!(resolve_database(Some("never_registered")), Err(AdminxError::UnknownDatabase(name)) if name == "never_registered"));
+        assert!(matches!(Unconfigured.try_get_collection(), Err(AdminxError::UnknownDatabase(name)) if name == "never_registered"));
+
+        let req = TestRequest::default().to_http_request();
+        let id = mongodb::bson::oid::ObjectId::new().to_hex();
+        let response = Unconfigured.get(&req, id).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let response = Unconfigured.list(&req, String::new()).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
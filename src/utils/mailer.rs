@@ -0,0 +1,40 @@
+// adminx/src/utils/mailer.rs
+use async_trait::async_trait;
+use std::fmt;
+use tracing::info;
+
+/// A plain-text message sent by AdminX (verification links, password resets, ...)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Pluggable mail transport used by the auth flows.
+///
+/// AdminX does not ship an SMTP client; implement this trait on top of
+/// whichever provider the host application already uses.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> Result<(), String>;
+}
+
+impl fmt::Debug for dyn Mailer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Mailer")
+    }
+}
+
+/// Default mailer that only logs who a message is for. Bodies carry verification and
+/// reset tokens, so they are never logged; configure a real `Mailer` to deliver them.
+#[derive(Debug, Default, Clone)]
+pub struct LoggingMailer;
+
+#[async_trait]
+impl Mailer for LoggingMailer {
+    async fn send(&self, email: Email) -> Result<(), String> {
+        info!(to = %email.to, subject = %email.subject, "📧 Email not delivered: no mailer configured");
+        Ok(())
+    }
+}
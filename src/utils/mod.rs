@@ -4,4 +4,12 @@ pub mod ubson;
 pub mod database;
 pub mod jwt;
 pub mod structs;
-pub mod constants;
\ No newline at end of file
+pub mod constants;
+pub mod rate_limit;
+pub mod password;
+pub mod mailer;
+pub mod timezone;
+pub mod mount;
+pub mod reauth;
+pub mod clock;
+pub mod branding;
\ No newline at end of file
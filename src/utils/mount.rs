@@ -0,0 +1,78 @@
+// adminx/src/utils/mount.rs
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+};
+use std::future::Future;
+
+use crate::utils::constants::DEFAULT_BASE_MOUNT;
+use crate::utils::database::get_adminx_config;
+
+tokio::task_local! {
+    /// Mount of the AdminX scope serving the current request
+    static REQUEST_BASE_MOUNT: String;
+}
+
+/// `/admin`, `admin/` and `/admin/` all become `/admin`; a blank mount falls back to `/adminx`
+pub fn normalize_base_mount(mount: &str) -> String {
+    let trimmed = mount.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        return DEFAULT_BASE_MOUNT.to_string();
+    }
+    format!("/{}", trimmed)
+}
+
+/// The mount from the global `AdminxConfig`, else `/adminx`
+pub fn configured_base_mount() -> String {
+    get_adminx_config()
+        .map(|config| config.base_mount.clone())
+        .unwrap_or_else(|| DEFAULT_BASE_MOUNT.to_string())
+}
+
+/// Mount to build links and redirects with: the one the current request came in
+/// under, else the configured one
+pub fn base_mount() -> String {
+    REQUEST_BASE_MOUNT
+        .try_with(|mount| mount.clone())
+        .unwrap_or_else(|_| configured_base_mount())
+}
+
+/// `path` under the current mount, e.g. `admin_path("/login")`
+pub fn admin_path(path: &str) -> String {
+    format!("{}{}", base_mount(), path)
+}
+
+/// Run `future` with `mount` as the current request's mount
+pub async fn with_base_mount<F: Future>(mount: String, future: F) -> F::Output {
+    REQUEST_BASE_MOUNT.scope(mount, future).await
+}
+
+/// Scope middleware making `mount` the mount for everything the request runs
+pub async fn scope_base_mount<B: MessageBody>(
+    mount: String,
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<B>, actix_web::Error> {
+    with_base_mount(mount, next.call(req)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mounts_are_normalized() {
+        assert_eq!(normalize_base_mount("/admin"), "/admin");
+        assert_eq!(normalize_base_mount("admin/"), "/admin");
+        assert_eq!(normalize_base_mount(" /tools/admin/ "), "/tools/admin");
+        assert_eq!(normalize_base_mount("/"), "/adminx");
+    }
+
+    #[actix_rt::test]
+    async fn request_mount_wins_over_the_default() {
+        assert_eq!(admin_path("/login"), "/adminx/login");
+        let inside = with_base_mount("/admin".to_string(), async { admin_path("/login") }).await;
+        assert_eq!(inside, "/admin/login");
+    }
+}
@@ -0,0 +1,319 @@
+// adminx/src/utils/password.rs
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params};
+use serde::Serialize;
+use std::env;
+use std::fmt;
+use tracing::warn;
+
+use crate::utils::database::get_adminx_config;
+
+/// Default minimum password length
+pub const DEFAULT_MIN_LENGTH: usize = 8;
+
+/// Rules a password must satisfy before it is hashed and stored.
+///
+/// Only the length check is enabled by default; the character class
+/// requirements are opt-in via `AdminxConfig` / environment variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: DEFAULT_MIN_LENGTH,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Build a policy from `ADMINX_PASSWORD_MIN_LENGTH`, `ADMINX_PASSWORD_REQUIRE_MIXED_CASE`,
+    /// `ADMINX_PASSWORD_REQUIRE_DIGIT` and `ADMINX_PASSWORD_REQUIRE_SYMBOL`
+    pub fn from_env() -> Self {
+        let flag = |name: &str| {
+            env::var(name)
+                .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+                .unwrap_or(false)
+        };
+        let mixed_case = flag("ADMINX_PASSWORD_REQUIRE_MIXED_CASE");
+
+        Self {
+            min_length: env::var("ADMINX_PASSWORD_MIN_LENGTH")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_MIN_LENGTH),
+            require_uppercase: mixed_case,
+            require_lowercase: mixed_case,
+            require_digit: flag("ADMINX_PASSWORD_REQUIRE_DIGIT"),
+            require_symbol: flag("ADMINX_PASSWORD_REQUIRE_SYMBOL"),
+        }
+    }
+}
+
+/// A single rule a password failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum PasswordViolation {
+    TooShort { min_length: usize },
+    MissingUppercase,
+    MissingLowercase,
+    MissingDigit,
+    MissingSymbol,
+}
+
+impl fmt::Display for PasswordViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordViolation::TooShort { min_length } => {
+                write!(f, "must be at least {} characters long", min_length)
+            }
+            PasswordViolation::MissingUppercase => f.write_str("must contain an uppercase letter"),
+            PasswordViolation::MissingLowercase => f.write_str("must contain a lowercase letter"),
+            PasswordViolation::MissingDigit => f.write_str("must contain a digit"),
+            PasswordViolation::MissingSymbol => f.write_str("must contain a symbol"),
+        }
+    }
+}
+
+/// How new password hashes are produced. Verification accepts either format,
+/// so switching algorithms keeps existing accounts working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordHashing {
+    /// bcrypt with the given cost (4..=31)
+    Bcrypt { cost: u32 },
+    /// Argon2id with the crate's recommended parameters
+    Argon2,
+}
+
+impl Default for PasswordHashing {
+    fn default() -> Self {
+        PasswordHashing::Bcrypt { cost: bcrypt::DEFAULT_COST }
+    }
+}
+
+impl PasswordHashing {
+    /// Read `ADMINX_PASSWORD_HASHER` (`bcrypt` or `argon2`) and `ADMINX_BCRYPT_COST`
+    pub fn from_env() -> Self {
+        match env::var("ADMINX_PASSWORD_HASHER").map(|v| v.to_lowercase()).as_deref() {
+            Ok("argon2") => PasswordHashing::Argon2,
+            _ => PasswordHashing::Bcrypt {
+                cost: bcrypt_cost(env::var("ADMINX_BCRYPT_COST").ok().as_deref()),
+            },
+        }
+    }
+
+    /// Hash `password` with this algorithm
+    pub fn hash(&self, password: &str) -> Result<String, String> {
+        match self {
+            PasswordHashing::Bcrypt { cost } => bcrypt::hash(password, *cost).map_err(|e| e.to_string()),
+            PasswordHashing::Argon2 => {
+                let salt = SaltString::generate(&mut OsRng);
+                Argon2::default()
+                    .hash_password(password.as_bytes(), &salt)
+                    .map(|hash| hash.to_string())
+                    .map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+/// `ADMINX_BCRYPT_COST` if it is a cost bcrypt accepts (4..=31), else the default
+fn bcrypt_cost(value: Option<&str>) -> u32 {
+    let Some(value) = value else {
+        return bcrypt::DEFAULT_COST;
+    };
+    match value.trim().parse() {
+        Ok(cost) if (4..=31).contains(&cost) => cost,
+        _ => {
+            warn!("ADMINX_BCRYPT_COST must be between 4 and 31, got {:?}; using {}", value, bcrypt::DEFAULT_COST);
+            bcrypt::DEFAULT_COST
+        }
+    }
+}
+
+/// The hashing from the global `AdminxConfig`, falling back to the environment
+pub fn current_password_hashing() -> PasswordHashing {
+    get_adminx_config()
+        .map(|config| config.password_hashing)
+        .unwrap_or_else(PasswordHashing::from_env)
+}
+
+/// Hash a password with the configured algorithm
+pub fn hash_password(password: &str) -> Result<String, String> {
+    current_password_hashing().hash(password)
+}
+
+/// Check `password` against a stored bcrypt or Argon2 hash
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    if hash.starts_with("$argon2") {
+        PasswordHash::new(hash)
+            .map(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+            .unwrap_or(false)
+    } else {
+        bcrypt::verify(password, hash).unwrap_or(false)
+    }
+}
+
+/// Whether a stored hash is weaker than (or a different algorithm from) `hashing`,
+/// so it should be replaced after the next successful login
+pub fn needs_rehash(hash: &str, hashing: &PasswordHashing) -> bool {
+    match hashing {
+        PasswordHashing::Bcrypt { cost } => {
+            // `$2b$12$...`: the cost is the second field
+            let stored_cost = hash
+                .strip_prefix('$')
+                .and_then(|rest| rest.split('$').nth(1))
+                .and_then(|cost| cost.parse::<u32>().ok());
+            !hash.starts_with("$2") || stored_cost.is_none_or(|stored| stored < *cost)
+        }
+        PasswordHashing::Argon2 => {
+            let Ok(parsed) = PasswordHash::new(hash) else {
+                return true;
+            };
+            let Ok(params) = Params::try_from(&parsed) else {
+                return true;
+            };
+            let current = Params::default();
+            parsed.algorithm != Algorithm::Argon2id.ident()
+                || params.m_cost() < current.m_cost()
+                || params.t_cost() < current.t_cost()
+                || params.p_cost() < current.p_cost()
+        }
+    }
+}
+
+/// Check `password` against `policy`, returning every rule it fails
+pub fn validate_password(password: &str, policy: &PasswordPolicy) -> Result<(), Vec<PasswordViolation>> {
+    let mut violations = Vec::new();
+
+    if password.chars().count() < policy.min_length {
+        violations.push(PasswordViolation::TooShort { min_length: policy.min_length });
+    }
+    if policy.require_uppercase && !password.chars().any(char::is_uppercase) {
+        violations.push(PasswordViolation::MissingUppercase);
+    }
+    if policy.require_lowercase && !password.chars().any(char::is_lowercase) {
+        violations.push(PasswordViolation::MissingLowercase);
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        violations.push(PasswordViolation::MissingDigit);
+    }
+    if policy.require_symbol && !password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace()) {
+        violations.push(PasswordViolation::MissingSymbol);
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+/// Join violations into a single human readable sentence
+pub fn describe_violations(violations: &[PasswordViolation]) -> String {
+    let reasons: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+    format!("Password {}", reasons.join(", "))
+}
+
+/// The policy from the global `AdminxConfig`, falling back to the environment
+/// when no config has been registered (e.g. in the CLI)
+pub fn current_password_policy() -> PasswordPolicy {
+    get_adminx_config()
+        .map(|config| config.password_policy.clone())
+        .unwrap_or_else(PasswordPolicy::from_env)
+}
+
+/// Validate against the current policy, mapping failures to a readable message
+pub fn check_password(password: &str) -> Result<(), String> {
+    validate_password(password, &current_password_policy())
+        .map_err(|violations| describe_violations(&violations))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strict() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 10,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: true,
+        }
+    }
+
+    #[test]
+    fn accepts_password_meeting_every_rule() {
+        assert_eq!(validate_password("Sup3r-secret", &strict()), Ok(()));
+        assert_eq!(validate_password("plainpassword", &PasswordPolicy::default()), Ok(()));
+    }
+
+    #[test]
+    fn reports_each_failing_rule() {
+        let policy = strict();
+        assert_eq!(
+            validate_password("Sh0rt!", &policy),
+            Err(vec![PasswordViolation::TooShort { min_length: 10 }])
+        );
+        assert_eq!(validate_password("sup3r-secret", &policy), Err(vec![PasswordViolation::MissingUppercase]));
+        assert_eq!(validate_password("SUP3R-SECRET", &policy), Err(vec![PasswordViolation::MissingLowercase]));
+        assert_eq!(validate_password("Super-secret", &policy), Err(vec![PasswordViolation::MissingDigit]));
+        assert_eq!(validate_password("Sup3rsecret", &policy), Err(vec![PasswordViolation::MissingSymbol]));
+    }
+
+    #[test]
+    fn verifies_bcrypt_and_argon2_hashes() {
+        let bcrypt_hash = PasswordHashing::Bcrypt { cost: 4 }.hash("correct horse").unwrap();
+        let argon2_hash = PasswordHashing::Argon2.hash("correct horse").unwrap();
+        assert!(bcrypt_hash.starts_with("$2"));
+        assert!(argon2_hash.starts_with("$argon2id$"));
+
+        for hash in [&bcrypt_hash, &argon2_hash] {
+            assert!(verify_password("correct horse", hash));
+            assert!(!verify_password("wrong horse", hash));
+        }
+        assert!(!verify_password("correct horse", "not-a-hash"));
+    }
+
+    #[test]
+    fn weaker_or_foreign_hashes_need_rehash() {
+        let cost_4 = PasswordHashing::Bcrypt { cost: 4 }.hash("pw").unwrap();
+        let argon2 = PasswordHashing::Argon2.hash("pw").unwrap();
+
+        assert!(needs_rehash(&cost_4, &PasswordHashing::Bcrypt { cost: 10 }));
+        assert!(!needs_rehash(&cost_4, &PasswordHashing::Bcrypt { cost: 4 }));
+        assert!(needs_rehash(&argon2, &PasswordHashing::Bcrypt { cost: 4 }));
+        assert!(needs_rehash(&cost_4, &PasswordHashing::Argon2));
+        assert!(!needs_rehash(&argon2, &PasswordHashing::Argon2));
+    }
+
+    #[test]
+    fn collects_multiple_violations_into_one_message() {
+        let violations = validate_password("abc", &strict()).unwrap_err();
+        assert_eq!(violations.len(), 4);
+        assert_eq!(
+            describe_violations(&violations),
+            "Password must be at least 10 characters long, must contain an uppercase letter, must contain a digit, must contain a symbol"
+        );
+    }
+
+    #[test]
+    fn out_of_range_bcrypt_costs_fall_back_to_the_default() {
+        assert_eq!(bcrypt_cost(Some("10")), 10);
+        assert_eq!(bcrypt_cost(None), bcrypt::DEFAULT_COST);
+        assert_eq!(bcrypt_cost(Some("3")), bcrypt::DEFAULT_COST);
+        assert_eq!(bcrypt_cost(Some("32")), bcrypt::DEFAULT_COST);
+        assert_eq!(bcrypt_cost(Some("fast")), bcrypt::DEFAULT_COST);
+    }
+}
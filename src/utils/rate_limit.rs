@@ -0,0 +1,269 @@
+// adminx/src/utils/rate_limit.rs
+use actix_web::HttpRequest;
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::utils::clock::{Clock, SystemClock};
+
+/// Default login attempts allowed per window
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+/// Default rate limit window (15 minutes)
+pub const DEFAULT_WINDOW: Duration = Duration::from_secs(900);
+
+/// Pluggable rate limiter used by the auth handlers.
+///
+/// Implement this trait to back rate limiting with a shared store (e.g. Redis)
+/// so limits hold across multiple nodes.
+pub trait RateLimiter: Send + Sync {
+    /// Record an attempt for `key` and return `true` if the key is over its limit.
+    fn check(&self, key: &str) -> bool;
+
+    /// Forget all attempts recorded for `key` (e.g. after a successful login).
+    fn reset(&self, key: &str);
+
+    /// How long until `key` may try again, when the limiter can tell. Used for `Retry-After`.
+    fn retry_after(&self, _key: &str) -> Option<Duration> {
+        None
+    }
+}
+
+impl fmt::Debug for dyn RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RateLimiter")
+    }
+}
+
+/// In-memory fixed-window rate limiter.
+///
+/// Expired entries are evicted periodically (at most once per window) so the
+/// map doesn't grow without bound.
+pub struct InMemoryRateLimiter {
+    max_attempts: u32,
+    window: Duration,
+    clock: Arc<dyn Clock>,
+    /// Attempts per key and when the last one was made, on the clock's monotonic time
+    attempts: Mutex<HashMap<String, (u32, Duration)>>,
+    last_eviction: Mutex<Duration>,
+}
+
+impl InMemoryRateLimiter {
+    pub fn new(max_attempts: u32, window: Duration) -> Self {
+        Self::with_clock(max_attempts, window, Arc::new(SystemClock))
+    }
+
+    /// A limiter whose windows follow `clock` instead of the system time
+    pub fn with_clock(max_attempts: u32, window: Duration, clock: Arc<dyn Clock>) -> Self {
+        let now = clock.monotonic();
+        Self {
+            max_attempts,
+            window,
+            clock,
+            attempts: Mutex::new(HashMap::new()),
+            last_eviction: Mutex::new(now),
+        }
+    }
+
+    /// Time since `earlier` on this limiter's clock
+    fn elapsed_since(&self, earlier: Duration) -> Duration {
+        self.clock.monotonic().saturating_sub(earlier)
+    }
+
+    /// Remove all entries whose window has elapsed
+    pub fn evict_expired(&self) {
+        let now = self.clock.monotonic();
+        let window = self.window;
+        self.attempts
+            .lock()
+            .unwrap()
+            .retain(|_, (_, last_attempt)| now.saturating_sub(*last_attempt) <= window);
+        *self.last_eviction.lock().unwrap() = now;
+    }
+
+    /// Number of keys currently tracked
+    pub fn tracked_keys(&self) -> usize {
+        self.attempts.lock().unwrap().len()
+    }
+
+    fn maybe_evict(&self) {
+        let last_eviction = *self.last_eviction.lock().unwrap();
+        let due = self.elapsed_since(last_eviction) > self.window;
+        if due {
+            self.evict_expired();
+        }
+    }
+}
+
+impl Default for InMemoryRateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_ATTEMPTS, DEFAULT_WINDOW)
+    }
+}
+
+impl RateLimiter for InMemoryRateLimiter {
+    fn check(&self, key: &str) -> bool {
+        self.maybe_evict();
+
+        let mut attempts = self.attempts.lock().unwrap();
+        let now = self.clock.monotonic();
+
+        match attempts.get_mut(key) {
+            Some((count, last_attempt)) => {
+                if self.elapsed_since(*last_attempt) > self.window {
+                    // Reset counter if outside window
+                    *count = 1;
+                    *last_attempt = now;
+                    false
+                } else if *count >= self.max_attempts {
+                    // Still within rate limit
+                    true
+                } else {
+                    *count += 1;
+                    *last_attempt = now;
+                    false
+                }
+            }
+            None => {
+                attempts.insert(key.to_string(), (1, now));
+                false
+            }
+        }
+    }
+
+    fn reset(&self, key: &str) {
+        self.attempts.lock().unwrap().remove(key);
+    }
+
+    fn retry_after(&self, key: &str) -> Option<Duration> {
+        let attempts = self.attempts.lock().unwrap();
+        let (_, last_attempt) = attempts.get(key)?;
+        Some(self.window.saturating_sub(self.elapsed_since(*last_attempt)))
+    }
+}
+
+/// The IP to rate limit `req` by: the peer address, or the forwarded client address
+/// when the peer is one of `trusted_proxies`
+pub fn client_ip(req: &HttpRequest, trusted_proxies: &[IpAddr]) -> Option<String> {
+    let peer = req.peer_addr()?.ip();
+    if trusted_proxies.contains(&peer) {
+        if let Some(forwarded) = req.connection_info().realip_remote_addr() {
+            return Some(forwarded.to_string());
+        }
+    }
+    Some(peer.to_string())
+}
+
+/// Comma-separated IPs, skipping (and warning about) entries that don't parse
+pub fn parse_trusted_proxies(value: &str) -> Vec<IpAddr> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            entry.parse()
+                .map_err(|_| tracing::warn!("Ignoring invalid trusted proxy address: {}", entry))
+                .ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::FixedClock;
+    use chrono::Utc;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_in_memory_limits_after_max_attempts() {
+        let limiter = InMemoryRateLimiter::new(2, Duration::from_secs(60));
+        assert!(!limiter.check("a@example.com"));
+        assert!(!limiter.check("a@example.com"));
+        assert!(limiter.check("a@example.com"));
+
+        let retry_after = limiter.retry_after("a@example.com").unwrap();
+        assert!(retry_after > Duration::from_secs(55) && retry_after <= Duration::from_secs(60));
+        assert_eq!(limiter.retry_after("b@example.com"), None);
+
+        limiter.reset("a@example.com");
+        assert!(!limiter.check("a@example.com"));
+    }
+
+    #[test]
+    fn test_in_memory_evicts_expired_entries() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let limiter = InMemoryRateLimiter::with_clock(5, Duration::from_secs(60), clock.clone());
+        limiter.check("a@example.com");
+        limiter.check("b@example.com");
+        assert_eq!(limiter.tracked_keys(), 2);
+
+        clock.advance(chrono::Duration::seconds(61));
+
+        // The next check triggers a sweep of everything older than the window
+        limiter.check("c@example.com");
+        assert_eq!(limiter.tracked_keys(), 1);
+    }
+
+    #[test]
+    fn test_in_memory_window_follows_the_clock() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let limiter = InMemoryRateLimiter::with_clock(1, Duration::from_secs(60), clock.clone());
+        assert!(!limiter.check("a@example.com"));
+        assert!(limiter.check("a@example.com"));
+
+        clock.advance(chrono::Duration::seconds(45));
+        assert_eq!(limiter.retry_after("a@example.com"), Some(Duration::from_secs(15)));
+
+        // Exactly at the window's end the key is still limited, a second later it is not
+        clock.advance(chrono::Duration::seconds(15));
+        assert!(limiter.check("a@example.com"));
+        clock.advance(chrono::Duration::seconds(1));
+        assert!(!limiter.check("a@example.com"));
+    }
+
+    struct DenyListLimiter {
+        blocked: Mutex<HashSet<String>>,
+    }
+
+    impl RateLimiter for DenyListLimiter {
+        fn check(&self, key: &str) -> bool {
+            self.blocked.lock().unwrap().contains(key)
+        }
+
+        fn reset(&self, key: &str) {
+            self.blocked.lock().unwrap().remove(key);
+        }
+    }
+
+    #[test]
+    fn test_custom_limiter_through_trait_object() {
+        let limiter: Arc<dyn RateLimiter> = Arc::new(DenyListLimiter {
+            blocked: Mutex::new(["blocked@example.com".to_string()].into_iter().collect()),
+        });
+
+        assert!(limiter.check("blocked@example.com"));
+        assert!(!limiter.check("ok@example.com"));
+
+        limiter.reset("blocked@example.com");
+        assert!(!limiter.check("blocked@example.com"));
+    }
+
+    #[test]
+    fn forwarded_addresses_are_only_believed_from_trusted_proxies() {
+        use actix_web::test::TestRequest;
+
+        let req = |peer: &str| TestRequest::default()
+            .peer_addr(peer.parse().unwrap())
+            .insert_header(("X-Forwarded-For", "203.0.113.9"))
+            .to_http_request();
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+
+        assert_eq!(client_ip(&req("198.51.100.7:4000"), &[]).as_deref(), Some("198.51.100.7"));
+        assert_eq!(client_ip(&req("198.51.100.7:4000"), &[proxy]).as_deref(), Some("198.51.100.7"));
+        assert_eq!(client_ip(&req("10.0.0.1:4000"), &[proxy]).as_deref(), Some("203.0.113.9"));
+
+        assert_eq!(parse_trusted_proxies(" 10.0.0.1, nope,::1 "), vec![proxy, "::1".parse::<IpAddr>().unwrap()]);
+    }
+}
@@ -1,6 +1,11 @@
 // src/utils/rbac.rs
+use actix_web::{HttpMessage, HttpRequest};
+use mongodb::bson::{doc, Document};
+use serde_json::Value;
+use crate::actions::RowAction;
 use crate::menu::MenuAction;
 use crate::resource::AdmixResource;
+use crate::utils::structs::Claims;
 
 pub fn has_permission(resource: &dyn AdmixResource, roles: &[String], action: MenuAction) -> bool {
     let permissions = resource.allowed_roles_with_permissions();
@@ -20,3 +25,266 @@ pub fn has_permission(resource: &dyn AdmixResource, roles: &[String], action: Me
     false
 }
 
+/// Whether `action` is enabled for `resource` at all (see `AdmixResource::allowed_actions`)
+pub fn action_enabled(resource: &dyn AdmixResource, action: MenuAction) -> bool {
+    match resource.allowed_actions() {
+        Some(actions) => actions.contains(&action),
+        None => true,
+    }
+}
+
+/// Names of the enabled actions, as exposed to templates via `allowed_actions`
+pub fn enabled_action_names(resource: &dyn AdmixResource) -> Vec<&'static str> {
+    [MenuAction::List, MenuAction::View, MenuAction::Create, MenuAction::Edit, MenuAction::Delete]
+        .into_iter()
+        .filter(|action| action_enabled(resource, action.clone()))
+        .map(|action| action.as_str())
+        .collect()
+}
+
+/// Whether `roles` may perform `action` on `resource`. Uses `has_permission` when the
+/// resource declares `allowed_roles_with_permissions()`, otherwise falls back to `allowed_roles()`.
+pub fn can_perform_action(resource: &dyn AdmixResource, roles: &[String], action: &str) -> bool {
+    let has_fine_grained = resource.allowed_roles_with_permissions()
+        .as_object()
+        .is_some_and(|perms| !perms.is_empty());
+
+    if has_fine_grained {
+        return MenuAction::from_action(action)
+            .is_some_and(|action| has_permission(resource, roles, action));
+    }
+
+    let allowed_roles = resource.allowed_roles();
+    roles.iter().any(|role| allowed_roles.contains(role))
+}
+
+/// Whether `roles` may see and run a row action: its own `roles` when set,
+/// otherwise the resource's `edit` permission
+pub fn can_run_row_action(resource: &dyn AdmixResource, roles: &[String], action: &RowAction) -> bool {
+    if action.roles.is_empty() {
+        can_perform_action(resource, roles, "edit")
+    } else {
+        roles.iter().any(|role| action.roles.contains(&role.as_str()))
+    }
+}
+
+/// Row actions `roles` may see on the list view
+pub fn visible_row_actions(resource: &dyn AdmixResource, roles: &[String]) -> Vec<RowAction> {
+    resource.row_actions()
+        .into_iter()
+        .filter(|action| can_run_row_action(resource, roles, action))
+        .collect()
+}
+
+/// Whether any of `roles` may see `field` (see `AdmixResource::field_permissions`)
+pub fn can_read_field<R: AdmixResource + ?Sized>(resource: &R, roles: &[String], field: &str) -> bool {
+    field_allowed(&resource.field_permissions(), roles, "read", field)
+}
+
+/// Whether any of `roles` may set `field` on create/update
+pub fn can_write_field<R: AdmixResource + ?Sized>(resource: &R, roles: &[String], field: &str) -> bool {
+    field_allowed(&resource.field_permissions(), roles, "write", field)
+}
+
+/// `permit_keys()` narrowed to the fields `roles` may read
+pub fn readable_keys<R: AdmixResource + ?Sized>(resource: &R, roles: &[String]) -> Vec<&'static str> {
+    let rules = resource.field_permissions();
+    resource.permit_keys()
+        .into_iter()
+        .filter(|key| field_allowed(&rules, roles, "read", key))
+        .collect()
+}
+
+/// `permit_keys()` narrowed to the fields `roles` may write
+pub fn writable_keys<R: AdmixResource + ?Sized>(resource: &R, roles: &[String]) -> Vec<&'static str> {
+    let rules = resource.field_permissions();
+    resource.permit_keys()
+        .into_iter()
+        .filter(|key| field_allowed(&rules, roles, "write", key))
+        .collect()
+}
+
+/// Drop every top-level key of `payload` that `roles` may not write
+pub fn filter_writable_fields<R: AdmixResource + ?Sized>(resource: &R, roles: &[String], payload: Value) -> Value {
+    let rules = resource.field_permissions();
+    match payload {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(key, _)| field_allowed(&rules, roles, "write", key))
+                .collect()
+        ),
+        other => other,
+    }
+}
+
+/// MongoDB projection limiting documents to the fields `roles` may read.
+/// Returns `None` when the roles are not restricted.
+pub fn read_projection<R: AdmixResource + ?Sized>(resource: &R, roles: &[String]) -> Option<Document> {
+    let rules = resource.field_permissions();
+    let mut projection = doc! { "_id": 1 };
+
+    for role in roles {
+        match rules.get(role).and_then(|r| r.get("read")).and_then(|f| f.as_array()) {
+            Some(fields) => {
+                for field in fields.iter().filter_map(|f| f.as_str()) {
+                    if field == "*" {
+                        return None;
+                    }
+                    projection.insert(field, 1);
+                }
+            }
+            // A role without read rules sees everything
+            None => return None,
+        }
+    }
+
+    if roles.is_empty() {
+        None
+    } else {
+        Some(projection)
+    }
+}
+
+/// Roles of the authenticated user, as stored in the request extensions by `RoleGuard`
+pub fn roles_from_request(req: &HttpRequest) -> Vec<String> {
+    req.extensions()
+        .get::<Claims>()
+        .map(|claims| {
+            let mut roles = claims.roles.clone();
+            if !roles.contains(&claims.role) {
+                roles.push(claims.role.clone());
+            }
+            roles
+        })
+        .unwrap_or_default()
+}
+
+// A field is allowed if any role grants it; roles without rules for `access` are unrestricted
+fn field_allowed(rules: &Value, roles: &[String], access: &str, field: &str) -> bool {
+    if roles.is_empty() {
+        return true;
+    }
+
+    roles.iter().any(|role| {
+        match rules.get(role).and_then(|r| r.get(access)).and_then(|f| f.as_array()) {
+            Some(fields) => fields.iter().any(|f| f == field || f == "*"),
+            None => true,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::Collection;
+    use serde_json::json;
+
+    struct PostResource;
+
+    impl AdmixResource for PostResource {
+        fn new() -> Self { PostResource }
+        fn resource_name(&self) -> &'static str { "Posts" }
+        fn base_path(&self) -> &'static str { "posts" }
+        fn collection_name(&self) -> &'static str { "posts" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(PostResource) }
+
+        fn permit_keys(&self) -> Vec<&'static str> {
+            vec!["title", "body", "status"]
+        }
+
+        fn field_permissions(&self) -> Value {
+            json!({
+                "editor": {
+                    "read": ["title", "body", "status"],
+                    "write": ["title", "body"]
+                },
+                "viewer": {
+                    "read": ["title"]
+                }
+            })
+        }
+    }
+
+    struct AuditLogResource;
+
+    impl AdmixResource for AuditLogResource {
+        fn new() -> Self { AuditLogResource }
+        fn resource_name(&self) -> &'static str { "Audit Logs" }
+        fn base_path(&self) -> &'static str { "audit_logs" }
+        fn collection_name(&self) -> &'static str { "audit_logs" }
+        fn get_collection(&self) -> Collection<Document> { unimplemented!() }
+        fn clone_box(&self) -> Box<dyn AdmixResource> { Box::new(AuditLogResource) }
+
+        fn allowed_roles(&self) -> Vec<String> {
+            vec!["admin".to_string(), "auditor".to_string()]
+        }
+
+        fn allowed_roles_with_permissions(&self) -> Value {
+            json!({
+                "admin": ["list", "view", "create", "edit", "delete"],
+                "auditor": ["list", "view"]
+            })
+        }
+    }
+
+    fn roles(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_editor_can_write_title_but_not_status() {
+        let resource = PostResource;
+        let editor = roles(&["editor"]);
+
+        assert!(can_write_field(&resource, &editor, "title"));
+        assert!(!can_write_field(&resource, &editor, "status"));
+        assert!(can_read_field(&resource, &editor, "status"));
+        assert_eq!(writable_keys(&resource, &editor), vec!["title", "body"]);
+
+        let payload = filter_writable_fields(&resource, &editor, json!({
+            "title": "Hello",
+            "status": "published"
+        }));
+        assert_eq!(payload, json!({ "title": "Hello" }));
+    }
+
+    #[test]
+    fn test_unlisted_roles_are_unrestricted() {
+        let resource = PostResource;
+        let admin = roles(&["admin"]);
+
+        assert!(can_write_field(&resource, &admin, "status"));
+        assert!(read_projection(&resource, &admin).is_none());
+        assert!(read_projection(&resource, &roles(&["admin", "viewer"])).is_none());
+    }
+
+    #[test]
+    fn test_read_set_feeds_projection() {
+        let resource = PostResource;
+        let viewer = roles(&["viewer"]);
+
+        assert_eq!(readable_keys(&resource, &viewer), vec!["title"]);
+        assert_eq!(read_projection(&resource, &viewer), Some(doc! { "_id": 1, "title": 1 }));
+    }
+
+    #[test]
+    fn test_action_permissions_are_enforced() {
+        let resource = AuditLogResource;
+        let auditor = roles(&["auditor"]);
+
+        assert!(can_perform_action(&resource, &auditor, "list"));
+        assert!(can_perform_action(&resource, &auditor, "view"));
+        assert!(!can_perform_action(&resource, &auditor, "update"));
+        assert!(!can_perform_action(&resource, &auditor, "delete"));
+        assert!(can_perform_action(&resource, &roles(&["admin"]), "delete"));
+    }
+
+    #[test]
+    fn test_falls_back_to_allowed_roles() {
+        let resource = PostResource;
+
+        assert!(can_perform_action(&resource, &roles(&["admin"]), "delete"));
+        assert!(!can_perform_action(&resource, &roles(&["editor"]), "list"));
+    }
+}
@@ -0,0 +1,72 @@
+// adminx/src/utils/reauth.rs
+use actix_session::Session;
+use actix_web::HttpResponse;
+use std::time::Duration;
+use tracing::{error, warn};
+
+use crate::configs::initializer::AdminxConfig;
+use crate::resource::AdmixResource;
+use crate::utils::clock::Clock;
+use crate::utils::constants::REAUTH_SESSION_KEY;
+use crate::utils::mount::admin_path;
+
+/// Record that the session just proved its password, as of `clock`
+pub fn stamp_reauth(session: &Session, clock: &dyn Clock) {
+    if let Err(err) = session.insert(REAUTH_SESSION_KEY, clock.now().timestamp()) {
+        error!("Failed to record re-authentication: {}", err);
+    }
+}
+
+/// Whether the session re-entered its password within `window` of `clock`'s now
+pub fn has_recent_reauth(session: &Session, window: Duration, clock: &dyn Clock) -> bool {
+    match session.get::<i64>(REAUTH_SESSION_KEY) {
+        Ok(Some(reauth_at)) => clock.now().timestamp() - reauth_at <= window.as_secs() as i64,
+        _ => false,
+    }
+}
+
+/// Block `action` on `resource` when it `requires_reauth()` and the session has no
+/// recent password re-entry; the 403 body points at the reauth endpoint
+pub fn check_reauth(
+    session: &Session,
+    config: &AdminxConfig,
+    resource: &dyn AdmixResource,
+    action: &str,
+) -> Result<(), HttpResponse> {
+    if !resource.requires_reauth(action) || has_recent_reauth(session, config.reauth_window, config.clock.as_ref()) {
+        return Ok(());
+    }
+    warn!("{} action on resource {} needs a recent re-authentication", action, resource.resource_name());
+    Err(HttpResponse::Forbidden().json(serde_json::json!({
+        "code": "REAUTH_REQUIRED",
+        "error": "Re-authentication required",
+        "reauth_required": true,
+        "reauth_url": admin_path("/api/auth/reauth"),
+        "action": action,
+        "resource": resource.resource_name()
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_session::SessionExt;
+    use actix_web::test::TestRequest;
+    use crate::utils::clock::FixedClock;
+
+    #[test]
+    fn reauth_expires_after_the_window() {
+        let clock = FixedClock::new(chrono::Utc::now());
+        let window = Duration::from_secs(300);
+        let req = TestRequest::default().to_http_request();
+        let session = req.get_session();
+        assert!(!has_recent_reauth(&session, window, &clock));
+
+        stamp_reauth(&session, &clock);
+        clock.advance(chrono::Duration::seconds(300));
+        assert!(has_recent_reauth(&session, window, &clock));
+
+        clock.advance(chrono::Duration::seconds(1));
+        assert!(!has_recent_reauth(&session, window, &clock));
+    }
+}
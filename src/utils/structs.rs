@@ -8,12 +8,62 @@ pub struct Claims {
     pub email: String,       // Email address
     pub role: String,        // Primary role (e.g., "admin")
     pub roles: Vec<String>,  // Additional roles for fine-grained permissions
+    pub iss: String,         // Issuer
+    pub aud: String,         // Audience
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoginForm {
     pub email: String,
     pub password: String,
+    /// Page to return to after signing in
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+/// Query of `GET /adminx/login`, set when a session expired
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LoginQuery {
+    pub reason: Option<String>,
+    pub next: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasswordChangeForm {
+    pub current_password: String,
+    pub new_password: String,
+    pub confirm_password: String,
+    /// End the current session once the password has changed
+    #[serde(default)]
+    pub sign_out: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasswordResetRequestForm {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasswordResetForm {
+    pub new_password: String,
+    pub confirm_password: String,
+}
+
+/// Body of `POST /adminx/api/auth/reauth`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReauthRequest {
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfileEditForm {
+    pub username: String,
+    pub email: String,
 }
 
 #[derive(Debug, Clone)]
@@ -179,6 +229,13 @@ impl ValidationErrors {
     }
 }
 
+// Breadcrumb trail entry for resource pages
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Breadcrumb {
+    pub label: String,
+    pub url: String,
+}
+
 // Flash message support
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FlashMessage {
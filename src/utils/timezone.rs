@@ -0,0 +1,88 @@
+// adminx/src/utils/timezone.rs
+use actix_session::SessionExt;
+use actix_web::HttpRequest;
+use chrono_tz::Tz;
+use std::env;
+
+use crate::utils::database::get_adminx_config;
+
+/// Session key holding a per-admin IANA timezone that overrides `AdminxConfig::display_timezone`
+pub const DISPLAY_TIMEZONE_SESSION_KEY: &str = "display_timezone";
+
+/// Format used for datetimes in the list and detail views
+pub const DISPLAY_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Parse an IANA timezone name such as `Europe/Berlin`
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.trim().parse().ok()
+}
+
+/// The zone from `ADMINX_DISPLAY_TIMEZONE`, UTC when unset or unknown
+pub fn timezone_from_env() -> Tz {
+    env::var("ADMINX_DISPLAY_TIMEZONE")
+        .ok()
+        .and_then(|name| parse_timezone(&name))
+        .unwrap_or(Tz::UTC)
+}
+
+/// The zone from the global `AdminxConfig`, falling back to the environment
+/// when no config has been registered (e.g. in the CLI)
+pub fn configured_timezone() -> Tz {
+    get_adminx_config()
+        .map(|config| config.display_timezone)
+        .unwrap_or_else(timezone_from_env)
+}
+
+/// Zone to display datetimes in for this request: the admin's session override,
+/// else the configured default
+pub fn request_timezone(req: &HttpRequest) -> Tz {
+    req.get_session()
+        .get::<String>(DISPLAY_TIMEZONE_SESSION_KEY)
+        .ok()
+        .flatten()
+        .and_then(|name| parse_timezone(&name))
+        .unwrap_or_else(configured_timezone)
+}
+
+/// Format a stored (UTC) BSON datetime in `tz` with `pattern`
+pub fn format_datetime(datetime: &mongodb::bson::DateTime, tz: Tz, pattern: &str) -> Option<String> {
+    chrono::DateTime::from_timestamp_millis(datetime.timestamp_millis())
+        .map(|utc| utc.with_timezone(&tz).format(pattern).to_string())
+}
+
+/// RFC 3339 rendering of a stored datetime in `tz`, offset included
+pub fn datetime_rfc3339(datetime: &mongodb::bson::DateTime, tz: Tz) -> Option<String> {
+    chrono::DateTime::from_timestamp_millis(datetime.timestamp_millis())
+        .map(|utc| utc.with_timezone(&tz).to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    // 2024-07-01 12:00:00 UTC
+    fn instant() -> mongodb::bson::DateTime {
+        mongodb::bson::DateTime::from_millis(1_719_835_200_000)
+    }
+
+    #[test]
+    fn formats_utc_instant_in_configured_zones() {
+        let new_york = parse_timezone("America/New_York").unwrap();
+        let kolkata = parse_timezone("Asia/Kolkata").unwrap();
+
+        assert_eq!(format_datetime(&instant(), Tz::UTC, DISPLAY_DATETIME_FORMAT).unwrap(), "2024-07-01 12:00:00");
+        assert_eq!(format_datetime(&instant(), new_york, DISPLAY_DATETIME_FORMAT).unwrap(), "2024-07-01 08:00:00");
+        assert_eq!(format_datetime(&instant(), kolkata, DISPLAY_DATETIME_FORMAT).unwrap(), "2024-07-01 17:30:00");
+        assert_eq!(datetime_rfc3339(&instant(), kolkata).unwrap(), "2024-07-01T17:30:00+05:30");
+    }
+
+    #[test]
+    fn session_override_wins_over_default() {
+        assert_eq!(parse_timezone("Not/AZone"), None);
+
+        let req = TestRequest::default().to_http_request();
+        req.get_session().insert(DISPLAY_TIMEZONE_SESSION_KEY, "Europe/Berlin").unwrap();
+        assert_eq!(request_timezone(&req), chrono_tz::Europe::Berlin);
+    }
+}
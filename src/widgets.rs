@@ -0,0 +1,194 @@
+// crates/adminx/src/widgets.rs
+use async_trait::async_trait;
+use futures::future::join_all;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+
+use crate::controllers::dashboard_controller::{count_visible_documents, visible_documents_filter};
+use crate::registry::get_resource;
+use crate::utils::mount::base_mount;
+use crate::utils::structs::Claims;
+
+lazy_static! {
+    static ref WIDGET_REGISTRY: RwLock<Vec<Arc<dyn DashboardWidget>>> = RwLock::new(vec![]);
+}
+
+/// What a widget knows about the dashboard request
+pub struct WidgetContext {
+    /// The admin viewing the dashboard
+    pub claims: Claims,
+}
+
+impl WidgetContext {
+    /// Primary and additional roles of the viewing admin
+    pub fn roles(&self) -> Vec<String> {
+        let mut roles = self.claims.roles.clone();
+        if !roles.contains(&self.claims.role) {
+            roles.push(self.claims.role.clone());
+        }
+        roles
+    }
+}
+
+/// A card rendered on the dashboard. Implement it in the host application and
+/// `register_widget` it at startup; widgets render in registration order.
+#[async_trait]
+pub trait DashboardWidget: Send + Sync {
+    /// Card heading
+    fn title(&self) -> String;
+
+    /// HTML fragment for the card body. It is inserted unescaped, so escape any
+    /// user data (e.g. with `tera::escape_html`).
+    async fn render(&self, ctx: &WidgetContext) -> String;
+
+    /// Roles that see this widget
+    fn allowed_roles(&self) -> Vec<String> {
+        vec!["admin".to_string()]
+    }
+}
+
+/// A widget as handed to the stats template
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedWidget {
+    pub title: String,
+    pub html: String,
+}
+
+/// Register a widget globally
+pub fn register_widget(widget: Box<dyn DashboardWidget>) {
+    WIDGET_REGISTRY.write().unwrap().push(Arc::from(widget));
+}
+
+/// Remove all registered widgets (useful for testing)
+pub fn clear_widgets() {
+    WIDGET_REGISTRY.write().unwrap().clear();
+}
+
+/// Render every widget `claims` may see, concurrently, keeping registration order
+pub async fn render_widgets(claims: &Claims) -> Vec<RenderedWidget> {
+    let ctx = WidgetContext { claims: claims.clone() };
+    let roles = ctx.roles();
+    let widgets: Vec<Arc<dyn DashboardWidget>> = WIDGET_REGISTRY
+        .read()
+        .unwrap()
+        .iter()
+        .filter(|widget| widget.allowed_roles().iter().any(|role| roles.contains(role)))
+        .cloned()
+        .collect();
+
+    join_all(widgets.iter().map(|widget| {
+        let ctx = &ctx;
+        async move {
+            RenderedWidget {
+                title: widget.title(),
+                html: widget.render(ctx).await,
+            }
+        }
+    }))
+    .await
+}
+
+/// Sample widget: a card with the number of visible documents in a registered resource
+pub struct CountCardWidget {
+    pub title: String,
+    /// `base_path` of the resource to count
+    pub resource_path: String,
+}
+
+impl CountCardWidget {
+    pub fn new(title: &str, resource_path: &str) -> Self {
+        Self { title: title.to_string(), resource_path: resource_path.to_string() }
+    }
+}
+
+#[async_trait]
+impl DashboardWidget for CountCardWidget {
+    fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    async fn render(&self, _ctx: &WidgetContext) -> String {
+        let count = match get_resource(&self.resource_path) {
+            Some(resource) => {
+                let filter = visible_documents_filter(resource.as_ref());
+                count_visible_documents(resource, filter)
+                    .await
+                    .map(|count| count.to_string())
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("Count card failed for {}: {}", self.resource_path, e);
+                        "&mdash;".to_string()
+                    })
+            }
+            None => "&mdash;".to_string(),
+        };
+
+        format!(
+            r#"<a href="{}/{}/list" class="text-3xl font-semibold text-gray-900 dark:text-white">{}</a>"#,
+            tera::escape_html(&base_mount()),
+            tera::escape_html(&self.resource_path),
+            count
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::dashboard_controller::insert_stats;
+    use crate::helpers::template_helper::ADMINX_TEMPLATES;
+    use tera::Context;
+
+    struct GreetingWidget;
+
+    #[async_trait]
+    impl DashboardWidget for GreetingWidget {
+        fn title(&self) -> String {
+            "Greeting".to_string()
+        }
+
+        async fn render(&self, ctx: &WidgetContext) -> String {
+            format!("<p class=\"greeting\">Hello {}</p>", tera::escape_html(&ctx.claims.email))
+        }
+
+        fn allowed_roles(&self) -> Vec<String> {
+            vec!["analyst".to_string()]
+        }
+    }
+
+    fn claims(role: &str) -> Claims {
+        Claims {
+            sub: "1".to_string(),
+            exp: 0,
+            email: "ana@example.com".to_string(),
+            role: role.to_string(),
+            roles: vec![],
+            iss: "adminx".to_string(),
+            aud: "adminx".to_string(),
+        }
+    }
+
+    async fn render_dashboard(claims: &Claims) -> String {
+        let mut ctx = Context::new();
+        ctx.insert("menus", &Vec::<String>::new());
+        ctx.insert("current_user", claims);
+        insert_stats(&mut ctx, &[], Some(1), &[]);
+        ctx.insert("widgets", &render_widgets(claims).await);
+        ADMINX_TEMPLATES.render("stats.html.tera", &ctx).unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn registered_widget_renders_for_authorized_user() {
+        clear_widgets();
+        register_widget(Box::new(GreetingWidget));
+
+        let html = render_dashboard(&claims("analyst")).await;
+        assert!(html.contains("Greeting"));
+        assert!(html.contains("<p class=\"greeting\">Hello ana@example.com</p>"));
+
+        let html = render_dashboard(&claims("admin")).await;
+        assert!(!html.contains("class=\"greeting\""));
+
+        clear_widgets();
+    }
+}